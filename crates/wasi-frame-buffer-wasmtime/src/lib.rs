@@ -7,7 +7,9 @@ use wasmtime::component::Resource;
 use wasmtime_wasi::WasiView;
 
 use crate::wasi::webgpu::frame_buffer;
-use wasi_graphics_context_wasmtime::{DisplayApi, DrawApi, GraphicsContext, GraphicsContextBuffer};
+use wasi_graphics_context_wasmtime::{
+    DisplayApi, DrawApi, Frame, GraphicsContext, GraphicsContextBuffer,
+};
 
 wasmtime::component::bindgen!({
     path: "../../wit/",
@@ -24,18 +26,35 @@ wasmtime::component::bindgen!({
 
 pub struct FBSurface {
     pub(crate) surface: Option<softbuffer::Surface<DisplayHandle<'static>, WindowHandle<'static>>>,
+    // softbuffer doesn't expose the size it was resized to.
+    size: (u32, u32),
 }
 // TODO: actually ensure safety
 unsafe impl Send for FBSurface {}
 unsafe impl Sync for FBSurface {}
+impl Default for FBSurface {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FBSurface {
     pub fn new() -> Self {
-        Self { surface: None }
+        Self {
+            surface: None,
+            size: (0, 0),
+        }
     }
 }
 
 // TODO: can we avoid the Mutex here?
 pub struct FBSurfaceArc(pub Arc<Mutex<FBSurface>>);
+impl Default for FBSurfaceArc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FBSurfaceArc {
     pub fn new() -> Self {
         FBSurfaceArc(Arc::new(Mutex::new(FBSurface::new())))
@@ -50,9 +69,13 @@ impl DrawApi for FBSurfaceArc {
         self.0.lock().unwrap().present()
     }
 
-    fn display_api_ready(&mut self, display_api: &Box<dyn DisplayApi + Send + Sync>) {
+    fn display_api_ready(&mut self, display_api: &(dyn DisplayApi + Send + Sync)) {
         self.0.lock().unwrap().display_api_ready(display_api)
     }
+
+    fn capture_frame(&mut self) -> Option<Frame> {
+        self.0.lock().unwrap().capture_frame()
+    }
 }
 
 // impl Surface {
@@ -85,7 +108,7 @@ impl DrawApi for FBSurface {
         Ok(())
     }
 
-    fn display_api_ready(&mut self, display: &Box<dyn DisplayApi + Send + Sync>) {
+    fn display_api_ready(&mut self, display: &(dyn DisplayApi + Send + Sync)) {
         let context = softbuffer::Context::new(display.display_handle().unwrap()).unwrap();
         let surface = softbuffer::Surface::new(&context, display.window_handle().unwrap()).unwrap();
 
@@ -94,17 +117,29 @@ impl DrawApi for FBSurface {
             unsafe { mem::transmute(surface) };
 
         // softbuffer requires setting the size before presenting.
-        let _ = surface.resize(
-            display
-                .width()
-                .try_into()
-                .unwrap_or(NonZeroU32::new(1).unwrap()),
-            display
-                .height()
-                .try_into()
-                .unwrap_or(NonZeroU32::new(1).unwrap()),
-        );
+        let width = display.width().try_into().unwrap_or(NonZeroU32::MIN);
+        let height = display.height().try_into().unwrap_or(NonZeroU32::MIN);
+        let _ = surface.resize(width, height);
         self.surface = Some(surface);
+        self.size = (width.get(), height.get());
+    }
+
+    fn capture_frame(&mut self) -> Option<Frame> {
+        let (width, height) = self.size;
+        let buffer = self.surface.as_mut()?.buffer_mut().ok()?;
+        // softbuffer pixels are 0RGB.
+        let data = buffer
+            .iter()
+            .flat_map(|pixel| {
+                let [_, r, g, b] = pixel.to_be_bytes();
+                [r, g, b, 255]
+            })
+            .collect();
+        Some(Frame {
+            width,
+            height,
+            data,
+        })
     }
 }
 
@@ -115,9 +150,17 @@ pub struct FBBuffer {
 // TODO: ensure safety
 unsafe impl Send for FBBuffer {}
 unsafe impl Sync for FBBuffer {}
+impl FBBuffer {
+    /// Runs `f` with the pixels, as `0RGB` u32s in rows. `None` once the buffer was dropped.
+    pub fn with_pixels<R>(&self, f: impl FnOnce(&[u32]) -> R) -> Option<R> {
+        self.buffer.lock().unwrap().as_deref().map(f)
+    }
+}
+
 impl From<softbuffer::Buffer<'static, GraphicsContext, GraphicsContext>> for FBBuffer {
     fn from(buffer: softbuffer::Buffer<'static, GraphicsContext, GraphicsContext>) -> Self {
         FBBuffer {
+            #[allow(clippy::arc_with_non_send_sync)]
             buffer: Arc::new(Mutex::new(Some(buffer))),
         }
     }
@@ -158,8 +201,16 @@ impl frame_buffer::HostSurface for dyn WasiFrameBufferView + '_ {
         graphics_context.connect_draw_api(Box::new(surface));
     }
 
-    fn drop(&mut self, _rep: Resource<FBSurfaceArc>) -> wasmtime::Result<()> {
-        todo!()
+    fn get_read_buffer(&mut self, surface: Resource<FBSurfaceArc>) -> Option<Vec<u32>> {
+        let surface = Arc::clone(&self.table().get(&surface).unwrap().0);
+        let mut surface = surface.lock().unwrap();
+        // Holding the lock keeps a present from swapping buffers halfway through the copy.
+        surface.surface.as_mut()?.fetch().ok()
+    }
+
+    fn drop(&mut self, surface: Resource<FBSurfaceArc>) -> wasmtime::Result<()> {
+        self.table().delete(surface)?;
+        Ok(())
     }
 }
 
@@ -193,7 +244,7 @@ impl frame_buffer::HostFrameBuffer for dyn WasiFrameBufferView + '_ {
 
     fn set(&mut self, buffer: Resource<FBBuffer>, i: u32, val: u32) {
         let buffer = self.table().get_mut(&buffer).unwrap();
-        buffer.buffer.lock().unwrap().as_mut().unwrap()[i as usize] = val as u32;
+        buffer.buffer.lock().unwrap().as_mut().unwrap()[i as usize] = val;
     }
 
     fn drop(&mut self, frame_buffer: Resource<FBBuffer>) -> wasmtime::Result<()> {
@@ -0,0 +1,363 @@
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc, Mutex},
+};
+#[cfg(feature = "gilrs")]
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use async_broadcast::{Receiver, TrySendError};
+#[cfg(feature = "gilrs")]
+use gilrs::{
+    ff::{BaseEffect, BaseEffectType, EffectBuilder, Replay, Ticks},
+    EventType, GamepadId as GilrsId, Gilrs, GilrsBuilder,
+};
+
+#[cfg(feature = "gilrs")]
+use crate::{Axis, AxisState, Button, ButtonState};
+use crate::{ConnectionEvent, GamepadId, GamepadInfo, GamepadState, RumbleError};
+
+/// How long the gilrs thread waits for input before checking for rumble requests.
+#[cfg(feature = "gilrs")]
+const POLL_INTERVAL: Duration = Duration::from_millis(8);
+
+/// Connection events kept for listeners that fall behind. Past this, the oldest are dropped.
+const CONNECTION_EVENT_CAPACITY: usize = 5;
+
+#[cfg(feature = "gilrs")]
+const BUTTONS: [(gilrs::Button, Button); 19] = [
+    (gilrs::Button::South, Button::South),
+    (gilrs::Button::East, Button::East),
+    (gilrs::Button::North, Button::North),
+    (gilrs::Button::West, Button::West),
+    (gilrs::Button::C, Button::C),
+    (gilrs::Button::Z, Button::Z),
+    (gilrs::Button::LeftTrigger, Button::LeftTrigger),
+    (gilrs::Button::LeftTrigger2, Button::LeftTrigger2),
+    (gilrs::Button::RightTrigger, Button::RightTrigger),
+    (gilrs::Button::RightTrigger2, Button::RightTrigger2),
+    (gilrs::Button::Select, Button::Select),
+    (gilrs::Button::Start, Button::Start),
+    (gilrs::Button::Mode, Button::Mode),
+    (gilrs::Button::LeftThumb, Button::LeftThumb),
+    (gilrs::Button::RightThumb, Button::RightThumb),
+    (gilrs::Button::DPadUp, Button::DPadUp),
+    (gilrs::Button::DPadDown, Button::DPadDown),
+    (gilrs::Button::DPadLeft, Button::DPadLeft),
+    (gilrs::Button::DPadRight, Button::DPadRight),
+];
+
+#[cfg(feature = "gilrs")]
+const AXES: [(gilrs::Axis, Axis); 8] = [
+    (gilrs::Axis::LeftStickX, Axis::LeftStickX),
+    (gilrs::Axis::LeftStickY, Axis::LeftStickY),
+    (gilrs::Axis::LeftZ, Axis::LeftZ),
+    (gilrs::Axis::RightStickX, Axis::RightStickX),
+    (gilrs::Axis::RightStickY, Axis::RightStickY),
+    (gilrs::Axis::RightZ, Axis::RightZ),
+    (gilrs::Axis::DPadX, Axis::DPadX),
+    (gilrs::Axis::DPadY, Axis::DPadY),
+];
+
+// Only the gilrs thread reads requests.
+#[cfg_attr(not(feature = "gilrs"), allow(dead_code))]
+struct RumbleRequest {
+    id: GamepadId,
+    strong_magnitude: f32,
+    weak_magnitude: f32,
+    duration_ms: u32,
+    response: oneshot::Sender<Result<(), RumbleError>>,
+}
+
+/// Gamepad input, read by gilrs on a dedicated thread. Needs the `gilrs` feature, without it there are never any
+/// gamepads.
+#[derive(Debug)]
+pub struct Gamepads {
+    connected: Arc<Mutex<HashMap<GamepadId, GamepadStateSnapshot>>>,
+    connection_sender: async_broadcast::Sender<ConnectionEvent>,
+    // Keeping inactive receiver to keep channel open.
+    _connection_receiver: async_broadcast::InactiveReceiver<ConnectionEvent>,
+    rumble_sender: mpsc::Sender<RumbleRequest>,
+}
+
+type GamepadStateSnapshot = (GamepadInfo, GamepadState);
+
+impl Gamepads {
+    /// Starts listening for gamepads. Fails if the platform's gamepad API isn't available.
+    #[cfg(feature = "gilrs")]
+    pub fn new() -> wasmtime::Result<Self> {
+        let (gamepads, rumble_receiver) = Self::without_thread();
+        let connected = Arc::clone(&gamepads.connected);
+        let connection_sender = gamepads.connection_sender.clone();
+
+        // Gilrs isn't Send on every platform, so it's created on the thread that polls it.
+        let (init_sender, init_receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // Default filters apply dead zones, raw values are left for the guest to filter.
+            let gilrs = match GilrsBuilder::new().with_default_filters(false).build() {
+                Ok(gilrs) => gilrs,
+                // Platforms without gamepad support get a gilrs that never reports any.
+                Err(gilrs::Error::NotImplemented(gilrs)) => gilrs,
+                Err(e) => {
+                    init_sender.send(Err(e.to_string())).unwrap();
+                    return;
+                }
+            };
+            init_sender.send(Ok(())).unwrap();
+            poll_loop(gilrs, connected, connection_sender, rumble_receiver);
+        });
+        init_receiver
+            .recv()
+            .unwrap()
+            .map_err(wasmtime::Error::msg)?;
+        Ok(gamepads)
+    }
+
+    /// Always fails, reading gamepads needs the `gilrs` feature.
+    #[cfg(not(feature = "gilrs"))]
+    pub fn new() -> wasmtime::Result<Self> {
+        Err(wasmtime::Error::msg(
+            "wasi-gamepad-wasmtime was built without the gilrs feature",
+        ))
+    }
+
+    /// Gamepads that never connect, for when [`Gamepads::new`] fails. Rumbling fails with
+    /// [`RumbleError::Disconnected`].
+    pub fn unavailable() -> Self {
+        Self::without_thread().0
+    }
+
+    fn without_thread() -> (Self, mpsc::Receiver<RumbleRequest>) {
+        let (mut connection_sender, connection_receiver) =
+            async_broadcast::broadcast(CONNECTION_EVENT_CAPACITY);
+        connection_sender.set_overflow(true);
+        let (rumble_sender, rumble_receiver) = mpsc::channel();
+        let gamepads = Self {
+            connected: Arc::new(Mutex::new(HashMap::new())),
+            connection_sender,
+            _connection_receiver: connection_receiver.deactivate(),
+            rumble_sender,
+        };
+        (gamepads, rumble_receiver)
+    }
+
+    pub fn connected(&self) -> Vec<GamepadInfo> {
+        let connected = self.connected.lock().unwrap();
+        let mut gamepads: Vec<_> = connected.values().map(|(info, _)| info.clone()).collect();
+        gamepads.sort_by_key(|info| info.id);
+        gamepads
+    }
+
+    pub fn state(&self, id: GamepadId) -> Option<GamepadState> {
+        let connected = self.connected.lock().unwrap();
+        connected.get(&id).map(|(_, state)| state.clone())
+    }
+
+    pub async fn rumble(
+        &self,
+        id: GamepadId,
+        strong_magnitude: f32,
+        weak_magnitude: f32,
+        duration_ms: u32,
+    ) -> Result<(), RumbleError> {
+        let (response, receiver) = oneshot::channel();
+        // Only fails without the gilrs thread, when there are no gamepads to rumble.
+        self.rumble_sender
+            .send(RumbleRequest {
+                id,
+                strong_magnitude,
+                weak_magnitude,
+                duration_ms,
+                response,
+            })
+            .map_err(|_| RumbleError::Disconnected)?;
+        receiver.await.map_err(|_| RumbleError::Disconnected)?
+    }
+
+    pub(crate) fn connection_receiver(&self) -> Receiver<ConnectionEvent> {
+        self.connection_sender.new_receiver()
+    }
+}
+
+#[cfg(feature = "gilrs")]
+fn poll_loop(
+    mut gilrs: Gilrs,
+    connected: Arc<Mutex<HashMap<GamepadId, GamepadStateSnapshot>>>,
+    connection_sender: async_broadcast::Sender<ConnectionEvent>,
+    rumble_receiver: mpsc::Receiver<RumbleRequest>,
+) {
+    for (id, _) in gilrs.gamepads() {
+        let snapshot = snapshot(&gilrs, id);
+        connected.lock().unwrap().insert(gamepad_id(id), snapshot);
+    }
+
+    // Effects stop playing when dropped, so they're kept until their duration is over.
+    let mut playing_effects = Vec::new();
+
+    loop {
+        while let Some(event) = gilrs.next_event_blocking(Some(POLL_INTERVAL)) {
+            let id = gamepad_id(event.id);
+            match event.event {
+                EventType::Connected => {
+                    let snapshot = snapshot(&gilrs, event.id);
+                    let info = snapshot.0.clone();
+                    connected.lock().unwrap().insert(id, snapshot);
+                    broadcast(&connection_sender, ConnectionEvent::Connected(info));
+                }
+                EventType::Disconnected => {
+                    connected.lock().unwrap().remove(&id);
+                    broadcast(&connection_sender, ConnectionEvent::Disconnected(id));
+                }
+                EventType::ButtonPressed(..)
+                | EventType::ButtonReleased(..)
+                | EventType::ButtonChanged(..)
+                | EventType::AxisChanged(..) => {
+                    if let Some(entry) = connected.lock().unwrap().get_mut(&id) {
+                        entry.1 = snapshot(&gilrs, event.id).1;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let now = Instant::now();
+        playing_effects.retain(|(_, until)| *until > now);
+
+        while let Ok(request) = rumble_receiver.try_recv() {
+            let result = gilrs
+                .gamepads()
+                .find(|(id, _)| gamepad_id(*id) == request.id)
+                .map(|(id, gamepad)| (id, gamepad.is_ff_supported()));
+            let result = match result {
+                None => Err(RumbleError::Disconnected),
+                Some((_, false)) => Err(RumbleError::Unsupported),
+                Some((id, true)) => {
+                    let scheduling = Replay {
+                        play_for: Ticks::from_ms(request.duration_ms),
+                        ..Default::default()
+                    };
+                    EffectBuilder::new()
+                        .add_effect(BaseEffect {
+                            kind: BaseEffectType::Strong {
+                                magnitude: magnitude(request.strong_magnitude),
+                            },
+                            scheduling,
+                            ..Default::default()
+                        })
+                        .add_effect(BaseEffect {
+                            kind: BaseEffectType::Weak {
+                                magnitude: magnitude(request.weak_magnitude),
+                            },
+                            scheduling,
+                            ..Default::default()
+                        })
+                        .gamepads(&[id])
+                        .finish(&mut gilrs)
+                        .and_then(|effect| {
+                            effect.play()?;
+                            let until = now + Duration::from_millis(request.duration_ms as u64);
+                            playing_effects.push((effect, until));
+                            Ok(())
+                        })
+                        .map_err(|_| RumbleError::Unsupported)
+                }
+            };
+            // The guest may have stopped waiting.
+            let _ = request.response.send(result);
+        }
+    }
+}
+
+#[cfg(feature = "gilrs")]
+fn snapshot(gilrs: &Gilrs, id: GilrsId) -> GamepadStateSnapshot {
+    let gamepad = gilrs.gamepad(id);
+    let info = GamepadInfo {
+        id: gamepad_id(id),
+        name: gamepad.name().to_string(),
+        supports_rumble: gamepad.is_ff_supported(),
+    };
+    let state = GamepadState {
+        buttons: BUTTONS
+            .iter()
+            .filter_map(|(gilrs_button, button)| {
+                let data = gamepad.button_data(*gilrs_button)?;
+                Some(ButtonState {
+                    button: *button,
+                    pressed: data.is_pressed(),
+                    value: data.value(),
+                })
+            })
+            .collect(),
+        axes: AXES
+            .iter()
+            .filter_map(|(gilrs_axis, axis)| {
+                let data = gamepad.axis_data(*gilrs_axis)?;
+                Some(AxisState {
+                    axis: *axis,
+                    value: data.value(),
+                })
+            })
+            .collect(),
+    };
+    (info, state)
+}
+
+#[cfg(feature = "gilrs")]
+fn gamepad_id(id: GilrsId) -> GamepadId {
+    usize::from(id) as GamepadId
+}
+
+#[cfg(feature = "gilrs")]
+fn magnitude(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * u16::MAX as f32) as u16
+}
+
+#[cfg_attr(not(feature = "gilrs"), allow(dead_code))]
+fn broadcast(sender: &async_broadcast::Sender<ConnectionEvent>, event: ConnectionEvent) {
+    match sender.try_broadcast(event) {
+        Ok(None) | Err(TrySendError::Inactive(_)) => {}
+        Ok(Some(dropped)) => {
+            log::warn!("Gamepad connection listeners fell behind, dropped {dropped:?}")
+        }
+        Err(e) => log::warn!("Failed to send a gamepad connection event: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_listeners_miss_the_oldest_events() {
+        let gamepads = Gamepads::unavailable();
+        let mut receiver = gamepads.connection_receiver();
+        for id in 0..CONNECTION_EVENT_CAPACITY as GamepadId + 2 {
+            broadcast(
+                &gamepads.connection_sender,
+                ConnectionEvent::Disconnected(id),
+            );
+        }
+        let mut received = Vec::new();
+        loop {
+            match receiver.try_recv() {
+                Ok(ConnectionEvent::Disconnected(id)) => received.push(id),
+                Err(async_broadcast::TryRecvError::Overflowed(_)) => {}
+                _ => break,
+            }
+        }
+        assert_eq!(
+            received,
+            (2..CONNECTION_EVENT_CAPACITY as GamepadId + 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn unavailable_gamepads_cant_rumble() {
+        let gamepads = Gamepads::unavailable();
+        assert!(gamepads.connected().is_empty());
+        let result = futures::executor::block_on(gamepads.rumble(0, 1.0, 1.0, 100));
+        assert!(matches!(result, Err(RumbleError::Disconnected)));
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::{Arc, Mutex};
+
+use async_broadcast::Receiver;
+use wasmtime::component::Resource;
+use wasmtime_wasi::WasiView;
+
+use crate::wasi::webgpu::gamepad::{self, Pollable};
+
+mod gilrs_backend;
+
+pub use crate::wasi::webgpu::gamepad::{
+    Axis, AxisState, Button, ButtonState, ConnectionEvent, GamepadId, GamepadInfo, GamepadState,
+    RumbleError,
+};
+pub use gilrs_backend::Gamepads;
+
+pub trait WasiGamepadView: WasiView {
+    /// Fails if the host has no gamepads to hand out, which traps the calling guest.
+    fn gamepads(&self) -> wasmtime::Result<Arc<Gamepads>>;
+}
+
+pub fn add_to_linker<T>(l: &mut wasmtime::component::Linker<T>) -> wasmtime::Result<()>
+where
+    T: WasiGamepadView,
+{
+    fn type_annotate<T, F>(val: F) -> F
+    where
+        F: Fn(&mut T) -> &mut dyn WasiGamepadView,
+    {
+        val
+    }
+    let closure = type_annotate::<T, _>(|t| t);
+    wasmtime_wasi::bindings::io::poll::add_to_linker_get_host(l, closure)?;
+    wasmtime_wasi::bindings::io::streams::add_to_linker_get_host(l, closure)?;
+    add_only_gamepad_to_linker(l)?;
+    Ok(())
+}
+
+pub fn add_only_gamepad_to_linker<T>(l: &mut wasmtime::component::Linker<T>) -> wasmtime::Result<()>
+where
+    T: WasiGamepadView,
+{
+    fn type_annotate<T, F>(val: F) -> F
+    where
+        F: Fn(&mut T) -> &mut dyn WasiGamepadView,
+    {
+        val
+    }
+    let closure = type_annotate::<T, _>(|t| t);
+    wasi::webgpu::gamepad::add_to_linker_get_host(l, closure)?;
+    Ok(())
+}
+
+wasmtime::component::bindgen!({
+    path: "../../wit/",
+    world: "example",
+    async: {
+        only_imports: [
+            "poll",
+            "rumble",
+        ],
+    },
+    // Only fail when the host isn't set up for gamepads.
+    trappable_imports: [
+        "gamepads",
+        "get-state",
+        "rumble",
+        "listener",
+    ],
+    with: {
+        "wasi:io": wasmtime_wasi::bindings::io,
+        "wasi:webgpu/gamepad/connection-listener": ConnectionListener,
+    },
+});
+
+#[derive(Debug)]
+pub struct ConnectionListener {
+    receiver: Receiver<ConnectionEvent>,
+    data: Mutex<Option<ConnectionEvent>>,
+}
+
+#[async_trait::async_trait]
+impl wasmtime_wasi::Subscribe for ConnectionListener {
+    async fn ready(&mut self) {
+        let event = self.receiver.recv().await.unwrap();
+        *self.data.lock().unwrap() = Some(event);
+    }
+}
+
+#[async_trait::async_trait]
+impl gamepad::Host for dyn WasiGamepadView + '_ {
+    fn gamepads(&mut self) -> wasmtime::Result<Vec<GamepadInfo>> {
+        Ok(WasiGamepadView::gamepads(self)?.connected())
+    }
+
+    fn get_state(&mut self, id: GamepadId) -> wasmtime::Result<Option<GamepadState>> {
+        Ok(WasiGamepadView::gamepads(self)?.state(id))
+    }
+
+    async fn rumble(
+        &mut self,
+        id: GamepadId,
+        strong_magnitude: f32,
+        weak_magnitude: f32,
+        duration_ms: u32,
+    ) -> wasmtime::Result<Result<(), RumbleError>> {
+        Ok(WasiGamepadView::gamepads(self)?
+            .rumble(id, strong_magnitude, weak_magnitude, duration_ms)
+            .await)
+    }
+
+    fn listener(&mut self) -> wasmtime::Result<Resource<ConnectionListener>> {
+        let receiver = WasiGamepadView::gamepads(self)?.connection_receiver();
+        Ok(self.table().push(ConnectionListener {
+            receiver,
+            data: Default::default(),
+        })?)
+    }
+}
+
+impl gamepad::HostConnectionListener for dyn WasiGamepadView + '_ {
+    fn subscribe(&mut self, listener: Resource<ConnectionListener>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.table(), listener).unwrap()
+    }
+    fn get(&mut self, listener: Resource<ConnectionListener>) -> Option<ConnectionEvent> {
+        let listener = self.table().get(&listener).unwrap();
+        listener.data.lock().unwrap().take()
+    }
+    fn drop(&mut self, _self_: Resource<ConnectionListener>) -> wasmtime::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::Parser, punctuated::Punctuated, spanned::Spanned, Expr, ItemFn, MetaNameValue, Token,
+};
+
+/// Exports `start` for the component, calling the annotated `fn run(canvas: Canvas)` with a new canvas.
+///
+/// The canvas size can be set with `#[wasi_gfx::main(width = 800, height = 600)]`.
+#[proc_macro_attribute]
+pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(attr, item) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let run: ItemFn = syn::parse(item)?;
+    let args = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse(attr)?;
+
+    let mut width: Expr = syn::parse_quote!(800);
+    let mut height: Expr = syn::parse_quote!(600);
+    for arg in args {
+        if arg.path.is_ident("width") {
+            width = arg.value;
+        } else if arg.path.is_ident("height") {
+            height = arg.value;
+        } else {
+            return Err(syn::Error::new(
+                arg.path.span(),
+                "expected `width` or `height`",
+            ));
+        }
+    }
+
+    let name = &run.sig.ident;
+    Ok(quote! {
+        #run
+
+        struct __WasiGfxMain;
+
+        impl ::wasi_gfx::bindings::Guest for __WasiGfxMain {
+            fn start() {
+                #name(::wasi_gfx::Canvas::new(#width, #height));
+            }
+        }
+
+        ::wasi_gfx::bindings::export!(__WasiGfxMain with_types_in ::wasi_gfx::bindings);
+    })
+}
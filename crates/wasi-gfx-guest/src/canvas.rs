@@ -0,0 +1,139 @@
+use crate::{animation_frame, frame_buffer, graphics_context, mini_canvas};
+
+/// A canvas with its graphics context.
+///
+/// Draw to it either through [`Canvas::frame_buffer`], or with webgpu after [`WebGpuDevice::connect`](crate::WebGpuDevice::connect), not both.
+pub struct Canvas {
+    canvas: mini_canvas::MiniCanvas,
+    context: graphics_context::GraphicsContext,
+    surface: Option<frame_buffer::Surface>,
+    frames: animation_frame::FrameListener,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32) -> Self {
+        let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
+            height,
+            width,
+            offscreen: false,
+            title: None,
+            resizable: None,
+            decorations: None,
+            fullscreen: None,
+            transparent: None,
+        });
+        let context = graphics_context::GraphicsContext::new();
+        canvas.connect_graphics_context(&context);
+        let frames = animation_frame::listener(&canvas);
+        Self {
+            canvas,
+            context,
+            surface: None,
+            frames,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.canvas.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.canvas.height()
+    }
+
+    /// For event listeners, e.g. `pointer_events::up_listener(canvas.mini_canvas())`.
+    pub fn mini_canvas(&self) -> &mini_canvas::MiniCanvas {
+        &self.canvas
+    }
+
+    pub fn graphics_context(&self) -> &graphics_context::GraphicsContext {
+        &self.context
+    }
+
+    /// Asks the host for another frame. Without it, frames only come when input arrives or the canvas is resized.
+    pub fn request_redraw(&self) {
+        self.canvas.request_redraw();
+    }
+
+    /// Blocks until the host asks for the next frame.
+    pub fn next_frame(&self) -> animation_frame::FrameEvent {
+        self.frames.subscribe().block();
+        self.frames.get().unwrap()
+    }
+
+    /// The buffer to draw the current frame into.
+    pub fn frame_buffer(&mut self) -> FrameBuffer {
+        let context = &self.context;
+        self.surface.get_or_insert_with(|| {
+            let surface = frame_buffer::Surface::new();
+            surface.connect_graphics_context(context);
+            surface
+        });
+        FrameBuffer {
+            buffer: frame_buffer::FrameBuffer::from_graphics_buffer(
+                self.context.get_current_buffer(),
+            ),
+            width: self.width(),
+            height: self.height(),
+        }
+    }
+
+    /// Pixels of the last presented frame, in the same format as [`FrameBuffer`].
+    ///
+    /// `None` before the first [`Canvas::frame_buffer`] call, or if the host can't read the window back.
+    pub fn read_back(&self) -> Option<Vec<u32>> {
+        self.surface.as_ref()?.get_read_buffer()
+    }
+
+    /// Takes the frame, so it can't be drawn into once it's on its way to the screen.
+    pub fn present(&self, frame: FrameBuffer) -> graphics_context::PresentStatus {
+        // Let go of the buffer before the host presents it.
+        drop(frame);
+        self.context.present()
+    }
+}
+
+/// Pixels of a frame, as `0RGB` u32s in rows.
+pub struct FrameBuffer {
+    buffer: frame_buffer::FrameBuffer,
+    width: u32,
+    height: u32,
+}
+
+impl FrameBuffer {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Out of bounds pixels are ignored.
+    pub fn set(&mut self, x: u32, y: u32, color: u32) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+            if index < self.buffer.length() {
+                self.buffer.set(index, color);
+            }
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Option<u32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = y * self.width + x;
+        (index < self.buffer.length()).then(|| self.buffer.get(index))
+    }
+
+    pub fn fill(&mut self, color: u32) {
+        for index in 0..self.buffer.length() {
+            self.buffer.set(index, color);
+        }
+    }
+
+    pub fn raw(&self) -> &frame_buffer::FrameBuffer {
+        &self.buffer
+    }
+}
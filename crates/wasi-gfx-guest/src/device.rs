@@ -0,0 +1,39 @@
+use crate::{graphics_context, webgpu, Canvas};
+
+/// A webgpu device on the default adapter, with its queue.
+pub struct WebGpuDevice {
+    device: webgpu::GpuDevice,
+    queue: webgpu::GpuQueue,
+}
+
+impl WebGpuDevice {
+    /// `None` if the host has no adapter, e.g. without a gpu. Draw through [`Canvas::frame_buffer`] then.
+    pub fn new() -> Option<Self> {
+        let device = webgpu::get_gpu()
+            .request_adapter(None)?
+            .request_device(None);
+        let queue = device.queue();
+        Some(Self { device, queue })
+    }
+
+    pub fn device(&self) -> &webgpu::GpuDevice {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &webgpu::GpuQueue {
+        &self.queue
+    }
+
+    /// Render to `canvas` through [`WebGpuDevice::current_texture`].
+    ///
+    /// Fails if the host's ui thread didn't get to creating the surface in time.
+    pub fn connect(&self, canvas: &Canvas) -> Result<(), graphics_context::TimeoutError> {
+        self.device
+            .connect_graphics_context(canvas.graphics_context())
+    }
+
+    /// The texture to draw the current frame of `canvas` into. Present it with `canvas.graphics_context().present()`.
+    pub fn current_texture(&self, canvas: &Canvas) -> webgpu::GpuTexture {
+        webgpu::GpuTexture::from_graphics_buffer(canvas.graphics_context().get_current_buffer())
+    }
+}
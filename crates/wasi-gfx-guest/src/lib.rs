@@ -0,0 +1,36 @@
+//! Guest side of wasi-gfx, wrapping the generated bindings.
+//!
+//! ```rust,ignore
+//! #[wasi_gfx::main(width = 400, height = 300)]
+//! fn run(mut canvas: wasi_gfx::Canvas) {
+//!     loop {
+//!         // Frames only come on request, or when input arrives.
+//!         canvas.request_redraw();
+//!         canvas.next_frame();
+//!         let mut frame = canvas.frame_buffer();
+//!         frame.fill(0x00_80_80_80);
+//!         canvas.present(frame);
+//!     }
+//! }
+//! ```
+
+/// Raw bindings for the `example:example/example` world, which is what the example runtime runs.
+pub mod bindings {
+    wit_bindgen::generate!({
+        path: "../../wit",
+        world: "example:example/example",
+        pub_export_macro: true,
+    });
+}
+
+mod canvas;
+mod device;
+
+pub use bindings::print;
+pub use bindings::wasi::webgpu::{
+    animation_frame, clipboard, file_drop_events, frame_buffer, gamepad, graphics_context,
+    key_events, mini_canvas, pointer_events, webgpu,
+};
+pub use canvas::{Canvas, FrameBuffer};
+pub use device::WebGpuDevice;
+pub use wasi_gfx_guest_macros::main;
@@ -0,0 +1,480 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use wasi_frame_buffer_wasmtime::WasiFrameBufferView;
+use wasi_graphics_context_wasmtime::{
+    DisplayApi, ExternalSurface, FrameStats, PresentObserver, WasiGraphicsContextView,
+};
+#[cfg(feature = "winit")]
+use wasi_mini_canvas_wasmtime::WasiWinitEventLoopProxy;
+use wasi_mini_canvas_wasmtime::{
+    CanvasQuota, Clipboard, DropPolicy, HeadlessCanvases, MiniCanvas, MiniCanvasDesc, Monitors,
+    WasiMiniCanvasView,
+};
+use wasi_webgpu_wasmtime::{
+    AdapterSelector, DynMainThreadSpawner, GpuQuota, InlineSpawner, Labels, LiveDevices,
+    MainThreadSpawner, Recorder, SharedBuffers, SpawnError, WasiWebGpuObserver, WasiWebGpuView, WebGpuInstanceBuilder,
+};
+use wasmtime_wasi::{HostWallClock, ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
+
+use crate::poll::{DevicePoller, PollMode};
+
+/// Where guest canvases end up.
+#[derive(Clone)]
+pub enum CanvasBackend {
+    #[cfg(feature = "winit")]
+    Winit(WasiWinitEventLoopProxy),
+    Headless(HeadlessCanvases),
+}
+
+/// Host state implementing all of the wasi-gfx view traits.
+///
+/// Use it as the store's data directly, or embed it in your own state and forward the view traits to it.
+///
+/// Devices the guest didn't drop are released along with the context, so a shared instance can outlive many guests.
+pub struct WasiGfxCtx {
+    table: ResourceTable,
+    wasi: WasiCtx,
+    instance: Arc<wgpu_core::global::Global>,
+    adapter: Option<AdapterSelector>,
+    canvas_backend: CanvasBackend,
+    ui_thread: Option<Arc<dyn DynMainThreadSpawner>>,
+    present_observer: Option<Arc<dyn PresentObserver>>,
+    frame_stats: Option<FrameStats>,
+    default_display: Option<ExternalSurface>,
+    drop_policy: DropPolicy,
+    live_devices: LiveDevices,
+    shared_buffers: SharedBuffers,
+    labels: Labels,
+    trace_path: Option<PathBuf>,
+    gpu_observer: Option<Arc<dyn WasiWebGpuObserver>>,
+    gpu_quota: Option<GpuQuota>,
+    recorder: Option<Recorder>,
+    canvas_quota: Option<CanvasQuota>,
+    wall_clock: Option<Arc<dyn HostWallClock + Send + Sync>>,
+    poller: Option<DevicePoller>,
+}
+
+impl WasiGfxCtx {
+    pub fn builder() -> WasiGfxCtxBuilder {
+        WasiGfxCtxBuilder::new()
+    }
+
+    pub fn canvas_backend(&self) -> &CanvasBackend {
+        &self.canvas_backend
+    }
+}
+
+pub struct WasiGfxCtxBuilder {
+    wasi: Option<WasiCtx>,
+    instance: WebGpuInstanceBuilder,
+    shared_instance: Option<Arc<wgpu_core::global::Global>>,
+    adapter: Option<AdapterSelector>,
+    canvas_backend: Option<CanvasBackend>,
+    ui_thread: Option<Arc<dyn DynMainThreadSpawner>>,
+    present_observer: Option<Arc<dyn PresentObserver>>,
+    frame_stats: Option<FrameStats>,
+    default_display: Option<ExternalSurface>,
+    drop_policy: DropPolicy,
+    shared_buffers: Option<SharedBuffers>,
+    trace_path: Option<PathBuf>,
+    gpu_observer: Option<Arc<dyn WasiWebGpuObserver>>,
+    gpu_quota: Option<GpuQuota>,
+    recorder: Option<Recorder>,
+    canvas_quota: Option<CanvasQuota>,
+    wall_clock: Option<Arc<dyn HostWallClock + Send + Sync>>,
+    poll_mode: PollMode,
+}
+
+impl Default for WasiGfxCtxBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WasiGfxCtxBuilder {
+    pub fn new() -> Self {
+        Self {
+            wasi: None,
+            instance: WebGpuInstanceBuilder::new(),
+            shared_instance: None,
+            adapter: None,
+            canvas_backend: None,
+            ui_thread: None,
+            present_observer: None,
+            frame_stats: None,
+            default_display: None,
+            drop_policy: DropPolicy::default(),
+            shared_buffers: None,
+            trace_path: None,
+            gpu_observer: None,
+            gpu_quota: None,
+            recorder: None,
+            canvas_quota: None,
+            wall_clock: None,
+            poll_mode: PollMode::default(),
+        }
+    }
+
+    /// Defaults to a context that only inherits stdio.
+    pub fn wasi(mut self, wasi: WasiCtx) -> Self {
+        self.wasi = Some(wasi);
+        self
+    }
+
+    /// Backends and other instance options.
+    pub fn instance(mut self, instance: WebGpuInstanceBuilder) -> Self {
+        self.instance = instance;
+        self
+    }
+
+    /// Debugging options of the instance, e.g. [`VALIDATION`](wgpu_types::InstanceFlags::VALIDATION) to turn on the
+    /// Vulkan validation layers. Defaults to [`InstanceFlags::from_build_config`](wgpu_types::InstanceFlags::from_build_config).
+    /// Like the rest of [`instance`](Self::instance), ignored with a [`shared_instance`](Self::shared_instance).
+    pub fn instance_flags(mut self, flags: wgpu_types::InstanceFlags) -> Self {
+        self.instance = self.instance.flags(flags);
+        self
+    }
+
+    /// Use an existing instance instead of building one, e.g. to share it between the stores of several components.
+    ///
+    /// Takes precedence over [`instance`](Self::instance).
+    pub fn shared_instance(mut self, instance: Arc<wgpu_core::global::Global>) -> Self {
+        self.shared_instance = Some(instance);
+        self
+    }
+
+    /// Hand this adapter to guests instead of letting wgpu pick one.
+    pub fn adapter(mut self, adapter: AdapterSelector) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
+    /// Open a window for each canvas. Unless a ui thread spawner is set, ui thread work is also done through `proxy`.
+    #[cfg(feature = "winit")]
+    pub fn winit(mut self, proxy: WasiWinitEventLoopProxy) -> Self {
+        self.canvas_backend = Some(CanvasBackend::Winit(proxy));
+        self
+    }
+
+    /// Render canvases offscreen. This is the default.
+    pub fn headless(mut self, canvases: HeadlessCanvases) -> Self {
+        self.canvas_backend = Some(CanvasBackend::Headless(canvases));
+        self
+    }
+
+    /// For embedders with their own event loop, e.g. a `ChannelSpawner`.
+    pub fn ui_thread(mut self, ui_thread: Arc<dyn DynMainThreadSpawner>) -> Self {
+        self.ui_thread = Some(ui_thread);
+        self
+    }
+
+    pub fn present_observer(mut self, observer: Arc<dyn PresentObserver>) -> Self {
+        self.present_observer = Some(observer);
+        self
+    }
+
+    /// Record the guest's presents, see [`WasiGraphicsContextView::frame_stats`].
+    pub fn frame_stats(mut self, frame_stats: FrameStats) -> Self {
+        self.frame_stats = Some(frame_stats);
+        self
+    }
+
+    /// Hand guests asking for the default context a context on this window.
+    pub fn default_display(mut self, surface: ExternalSurface) -> Self {
+        self.default_display = Some(surface);
+        self
+    }
+
+    pub fn drop_policy(mut self, drop_policy: DropPolicy) -> Self {
+        self.drop_policy = drop_policy;
+        self
+    }
+
+    /// Share buffers with the stores of other components built with the same registry.
+    /// Defaults to a registry of its own, so buffers are only shared within the component.
+    pub fn shared_buffers(mut self, shared_buffers: SharedBuffers) -> Self {
+        self.shared_buffers = Some(shared_buffers);
+        self
+    }
+
+    /// Record wgpu api traces of the guest's devices. See [`WasiWebGpuView::trace_path`].
+    pub fn trace_path(mut self, trace_path: impl Into<PathBuf>) -> Self {
+        self.trace_path = Some(trace_path.into());
+        self
+    }
+
+    /// Follow the guest's gpu usage, e.g. with a [`CountingObserver`](wasi_webgpu_wasmtime::CountingObserver).
+    pub fn gpu_observer(mut self, observer: Arc<dyn WasiWebGpuObserver>) -> Self {
+        self.gpu_observer = Some(observer);
+        self
+    }
+
+    /// Limit the buffers and textures the guest can allocate. Give each context its own quota, or they share the limits.
+    pub fn gpu_quota(mut self, quota: GpuQuota) -> Self {
+        self.gpu_quota = Some(quota);
+        self
+    }
+
+    /// Record the guest's webgpu calls. See [`WasiWebGpuView::recorder`].
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Limit the canvases the guest can have open at once.
+    pub fn canvas_quota(mut self, quota: CanvasQuota) -> Self {
+        self.canvas_quota = Some(quota);
+        self
+    }
+
+    /// Time canvas presents with `clock`, see [`WasiMiniCanvasView::wall_clock`]. Pass the clock the guest's wasi
+    /// context has, so frame pacing follows it.
+    pub fn wall_clock(mut self, clock: Arc<dyn HostWallClock + Send + Sync>) -> Self {
+        self.wall_clock = Some(clock);
+        self
+    }
+
+    /// Poll the context's devices in the background, so buffer maps and freed resources don't wait for the guest's
+    /// next submit. Contexts on a [`shared_instance`](Self::shared_instance) are better off with a single
+    /// [`DevicePoller`] for all of them.
+    pub fn poll_mode(mut self, poll_mode: PollMode) -> Self {
+        self.poll_mode = poll_mode;
+        self
+    }
+
+    /// Has to be called within a tokio runtime when polling on an interval.
+    pub fn build(self) -> WasiGfxCtx {
+        let instance = self
+            .shared_instance
+            .unwrap_or_else(|| Arc::new(self.instance.build()));
+        let poller = match self.poll_mode {
+            PollMode::Lazy => None,
+            PollMode::Interval(interval) => {
+                Some(DevicePoller::spawn(Arc::clone(&instance), interval))
+            }
+        };
+        WasiGfxCtx {
+            table: ResourceTable::new(),
+            wasi: self
+                .wasi
+                .unwrap_or_else(|| WasiCtxBuilder::new().inherit_stdio().build()),
+            instance,
+            adapter: self.adapter,
+            canvas_backend: self
+                .canvas_backend
+                .unwrap_or_else(|| CanvasBackend::Headless(HeadlessCanvases::new())),
+            ui_thread: self.ui_thread,
+            present_observer: self.present_observer,
+            frame_stats: self.frame_stats,
+            default_display: self.default_display,
+            drop_policy: self.drop_policy,
+            live_devices: LiveDevices::new(),
+            shared_buffers: self.shared_buffers.unwrap_or_default(),
+            labels: Labels::new(),
+            trace_path: self.trace_path,
+            gpu_observer: self.gpu_observer,
+            gpu_quota: self.gpu_quota,
+            recorder: self.recorder,
+            canvas_quota: self.canvas_quota,
+            wall_clock: self.wall_clock,
+            poller,
+        }
+    }
+}
+
+impl Drop for WasiGfxCtx {
+    fn drop(&mut self) {
+        if let Some(poller) = &self.poller {
+            poller.stop();
+        }
+        // Surfaces in the table go first, while the devices they're configured with are still around.
+        drop(std::mem::replace(&mut self.table, ResourceTable::new()));
+        self.live_devices.release_all(&self.instance);
+    }
+}
+
+impl WasiView for WasiGfxCtx {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi
+    }
+}
+
+impl WasiGraphicsContextView for WasiGfxCtx {
+    fn present_observer(&self) -> Option<Arc<dyn PresentObserver>> {
+        self.present_observer.clone()
+    }
+
+    fn frame_stats(&self) -> Option<FrameStats> {
+        self.frame_stats.clone()
+    }
+
+    fn default_display(&self) -> Option<Box<dyn DisplayApi + Send + Sync>> {
+        self.default_display
+            .clone()
+            .map(|surface| Box::new(surface) as Box<dyn DisplayApi + Send + Sync>)
+    }
+}
+
+impl WasiFrameBufferView for WasiGfxCtx {}
+
+pub struct UiThreadSpawner {
+    ui_thread: Option<Arc<dyn DynMainThreadSpawner>>,
+    canvas_backend: CanvasBackend,
+}
+
+impl MainThreadSpawner for UiThreadSpawner {
+    async fn spawn<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        if let Some(ui_thread) = &self.ui_thread {
+            return ui_thread.spawn(f).await;
+        }
+        match &self.canvas_backend {
+            #[cfg(feature = "winit")]
+            // The spawner has no way to fail, so a shut down loop can only panic here.
+            CanvasBackend::Winit(proxy) => proxy
+                .spawn(f)
+                .await
+                .expect("spawned on the ui thread after shutdown"),
+            // Headless canvases have no ui thread, so closures just run in place.
+            CanvasBackend::Headless(_) => InlineSpawner.spawn(f).await,
+        }
+    }
+
+    /// Has to be awaited within a tokio runtime, like the one guests run on.
+    async fn spawn_with_timeout<F, T>(&self, f: F, timeout: Duration) -> Result<T, SpawnError>
+    where
+        F: FnOnce() -> T + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        tokio::time::timeout(timeout, self.spawn(f))
+            .await
+            .map_err(|_| SpawnError::Timeout(timeout))
+    }
+}
+
+impl WasiWebGpuView for WasiGfxCtx {
+    fn instance(&self) -> Arc<wgpu_core::global::Global> {
+        Arc::clone(&self.instance)
+    }
+
+    fn ui_thread_spawner(&self) -> Box<impl MainThreadSpawner + 'static> {
+        Box::new(UiThreadSpawner {
+            ui_thread: self.ui_thread.clone(),
+            canvas_backend: self.canvas_backend.clone(),
+        })
+    }
+
+    fn adapter_selector(&self) -> Option<AdapterSelector> {
+        self.adapter.clone()
+    }
+
+    fn live_devices(&self) -> Option<LiveDevices> {
+        Some(self.live_devices.clone())
+    }
+
+    fn shared_buffers(&self) -> Option<SharedBuffers> {
+        Some(self.shared_buffers.clone())
+    }
+
+    fn labels(&self) -> Option<Labels> {
+        Some(self.labels.clone())
+    }
+
+    fn trace_path(&self) -> Option<PathBuf> {
+        self.trace_path.clone()
+    }
+
+    fn observer(&self) -> Option<Arc<dyn WasiWebGpuObserver>> {
+        self.gpu_observer.clone()
+    }
+
+    fn quota(&self) -> Option<GpuQuota> {
+        self.gpu_quota.clone()
+    }
+
+    fn recorder(&self) -> Option<Recorder> {
+        self.recorder.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl WasiMiniCanvasView for WasiGfxCtx {
+    async fn create_canvas(&mut self, desc: MiniCanvasDesc) -> MiniCanvas {
+        match &self.canvas_backend {
+            #[cfg(feature = "winit")]
+            CanvasBackend::Winit(proxy) => proxy
+                .create_window(desc)
+                .await
+                .expect("canvas created after the ui thread shut down"),
+            CanvasBackend::Headless(canvases) => canvases.create_canvas(desc),
+        }
+    }
+
+    fn drop_policy(&self) -> DropPolicy {
+        self.drop_policy
+    }
+
+    fn clipboard(&self) -> Option<Arc<dyn Clipboard>> {
+        match &self.canvas_backend {
+            #[cfg(feature = "winit")]
+            CanvasBackend::Winit(proxy) => Some(Arc::new(proxy.clone())),
+            CanvasBackend::Headless(_) => None,
+        }
+    }
+
+    fn monitors(&self) -> Option<Arc<dyn Monitors>> {
+        match &self.canvas_backend {
+            #[cfg(feature = "winit")]
+            CanvasBackend::Winit(proxy) => Some(Arc::new(proxy.clone())),
+            CanvasBackend::Headless(_) => None,
+        }
+    }
+
+    fn canvas_quota(&self) -> Option<CanvasQuota> {
+        self.canvas_quota.clone()
+    }
+
+    fn wall_clock(&self) -> Option<Arc<dyn HostWallClock + Send + Sync>> {
+        self.wall_clock.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasi_webgpu_wasmtime::ChannelSpawner;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn spawns_time_out_while_the_ui_thread_is_blocked() {
+        // The pump never runs, like on a ui thread stuck handling a long event.
+        let (ui_thread, pump) = ChannelSpawner::channel(|| {});
+        let spawner = UiThreadSpawner {
+            ui_thread: Some(Arc::new(ui_thread)),
+            canvas_backend: CanvasBackend::Headless(HeadlessCanvases::new()),
+        };
+        let timeout = Duration::from_millis(10);
+        assert_eq!(
+            spawner.spawn_with_timeout(|| 1, timeout).await,
+            Err(SpawnError::Timeout(timeout))
+        );
+
+        // The closure still runs once the ui thread gets to it, and later spawns go through.
+        assert_eq!(pump.pump(), 1);
+        let spawned = spawner.spawn_with_timeout(|| 2, Duration::from_secs(10));
+        let pumped = async {
+            while pump.pump() == 0 {
+                tokio::task::yield_now().await;
+            }
+        };
+        let (spawned, ()) = tokio::join!(spawned, pumped);
+        assert_eq!(spawned, Ok(2));
+    }
+}
@@ -0,0 +1,57 @@
+use wasi_frame_buffer_wasmtime::WasiFrameBufferView;
+use wasi_graphics_context_wasmtime::WasiGraphicsContextView;
+use wasi_mini_canvas_wasmtime::WasiMiniCanvasView;
+use wasi_webgpu_wasmtime::WasiWebGpuView;
+
+mod ctx;
+mod poll;
+
+pub use ctx::{CanvasBackend, UiThreadSpawner, WasiGfxCtx, WasiGfxCtxBuilder};
+pub use poll::{DevicePoller, PollMode};
+
+pub use wasi_frame_buffer_wasmtime as frame_buffer;
+pub use wasi_graphics_context_wasmtime as graphics_context;
+pub use wasi_mini_canvas_wasmtime as mini_canvas;
+pub use wasi_webgpu_wasmtime as webgpu;
+
+/// Everything a host state needs to implement to provide all of wasi-gfx.
+pub trait WasiGfxView:
+    WasiWebGpuView + WasiFrameBufferView + WasiGraphicsContextView + WasiMiniCanvasView
+{
+}
+
+impl<T> WasiGfxView for T where
+    T: WasiWebGpuView + WasiFrameBufferView + WasiGraphicsContextView + WasiMiniCanvasView
+{
+}
+
+/// Adds webgpu, frame-buffer, graphics-context and mini-canvas, along with the `wasi:io` interfaces they use.
+///
+/// Like the per-interface `add_to_linker` functions, this fails with wasmtime's duplicate definition error
+/// if any of these were already added, unless shadowing is allowed on the linker.
+pub fn add_to_linker<T>(l: &mut wasmtime::component::Linker<T>) -> wasmtime::Result<()>
+where
+    T: WasiGfxView,
+{
+    wasi_webgpu_wasmtime::add_to_linker(l)?;
+    wasi_frame_buffer_wasmtime::add_to_linker(l)?;
+    wasi_graphics_context_wasmtime::add_to_linker(l)?;
+    wasi_mini_canvas_wasmtime::add_to_linker(l)?;
+    Ok(())
+}
+
+/// Like [`add_to_linker`], but also adds the standard wasi cli interfaces.
+///
+/// Requires async support to be enabled on the engine.
+pub fn add_to_linker_with_wasi<T>(l: &mut wasmtime::component::Linker<T>) -> wasmtime::Result<()>
+where
+    T: WasiGfxView,
+{
+    // Includes `wasi:io`, so mini-canvas is added without it.
+    wasmtime_wasi::add_to_linker_async(l)?;
+    wasi_webgpu_wasmtime::add_to_linker(l)?;
+    wasi_frame_buffer_wasmtime::add_to_linker(l)?;
+    wasi_graphics_context_wasmtime::add_to_linker(l)?;
+    wasi_mini_canvas_wasmtime::add_only_mini_canvas_to_linker(l)?;
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+/// When the devices of an instance get polled, which is when wgpu-core runs buffer map callbacks and frees resources
+/// the gpu is done with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PollMode {
+    /// Only when the guest waits on the gpu, e.g. for a buffer map. Mapping can then stall until a later submit.
+    #[default]
+    Lazy,
+    /// Also poll every device of the instance in the background, on this interval.
+    Interval(Duration),
+}
+
+impl PollMode {
+    /// 1 ms, so mapped buffers are ready well within a frame.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(1);
+    /// Shorter intervals are raised to this, since polling without a pause keeps a core busy.
+    pub const MIN_INTERVAL: Duration = Duration::from_micros(100);
+}
+
+/// Polls every device of an instance on a blocking tokio task, until it's stopped or dropped.
+///
+/// Hosts sharing an instance between stores want a single poller for it rather than one per [`WasiGfxCtx`](crate::WasiGfxCtx).
+pub struct DevicePoller {
+    stop: Arc<AtomicBool>,
+    // Held while polling, so stopping can wait for a poll in progress.
+    polling: Arc<Mutex<()>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl DevicePoller {
+    /// Has to be called within a tokio runtime. `interval` is at least [`PollMode::MIN_INTERVAL`].
+    pub fn spawn(instance: Arc<wgpu_core::global::Global>, interval: Duration) -> Self {
+        let interval = interval.max(PollMode::MIN_INTERVAL);
+        let stop = Arc::new(AtomicBool::new(false));
+        let polling = Arc::new(Mutex::new(()));
+        let task = tokio::task::spawn_blocking({
+            let stop = Arc::clone(&stop);
+            let polling = Arc::clone(&polling);
+            move || loop {
+                {
+                    let _polling = polling.lock().unwrap();
+                    if stop.load(Ordering::Acquire) {
+                        return;
+                    }
+                    // Errors, e.g. lost devices, also reach the guest through its own calls.
+                    let _ = instance.poll_all_devices(false);
+                }
+                std::thread::sleep(interval);
+            }
+        });
+        Self {
+            stop,
+            polling,
+            task: Some(task),
+        }
+    }
+
+    /// Stops polling. Once this returns no poll is in progress, so devices can be released.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Release);
+        drop(self.polling.lock());
+    }
+
+    /// Stops polling and waits for the task to end.
+    pub async fn join(mut self) {
+        self.stop();
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+    }
+}
+
+impl Drop for DevicePoller {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::{Arc, Mutex};
+
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, RawDisplayHandle,
+    RawWindowHandle, WindowHandle,
+};
+
+use crate::{DisplayApi, OffscreenTarget};
+
+/// A window the host created and owns, for guests to present into instead of a canvas of their own.
+///
+/// Hand it to the guest through [`WasiGraphicsContextView::default_display`](crate::WasiGraphicsContextView::default_display),
+/// and keep a clone to resize it along with the window. Once the window is about to close, [`invalidate`](Self::invalidate)
+/// it: the guest's presents report `lost` from then on, and its frames go nowhere.
+#[derive(Clone)]
+pub struct ExternalSurface(Arc<ExternalSurfaceInner>);
+
+struct ExternalSurfaceInner {
+    window: RawWindowHandle,
+    display: RawDisplayHandle,
+    state: Mutex<ExternalSurfaceState>,
+}
+
+struct ExternalSurfaceState {
+    width: u32,
+    height: u32,
+    /// Set once invalidated, so draw apis render somewhere that doesn't need the window.
+    closed: Option<OffscreenTarget>,
+}
+
+// The raw handles are only turned back into window and display handles while the surface is valid, which the
+// creator of the surface vouches for.
+unsafe impl Send for ExternalSurfaceInner {}
+unsafe impl Sync for ExternalSurfaceInner {}
+
+impl ExternalSurface {
+    /// # Safety
+    ///
+    /// `window` and `display` must stay valid until [`invalidate`](Self::invalidate) is called, and must be usable
+    /// from the threads guests run on. The guest's surface for the window is released on its next frame after that,
+    /// so destroying the window right after invalidating only relies on the platform tolerating a surface that
+    /// outlives its window for a moment.
+    pub unsafe fn new(
+        window: RawWindowHandle,
+        display: RawDisplayHandle,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self(Arc::new(ExternalSurfaceInner {
+            window,
+            display,
+            state: Mutex::new(ExternalSurfaceState {
+                width,
+                height,
+                closed: None,
+            }),
+        }))
+    }
+
+    /// Call whenever the window is resized. The guest's surface is reconfigured on its next frame.
+    pub fn resize(&self, width: u32, height: u32) {
+        let mut state = self.0.state.lock().unwrap();
+        state.width = width;
+        state.height = height;
+    }
+
+    /// Stops the guest from presenting into the window, e.g. because it's closing. Can't be undone.
+    pub fn invalidate(&self) {
+        let mut state = self.0.state.lock().unwrap();
+        if state.closed.is_none() {
+            state.closed = Some(OffscreenTarget::new(state.width, state.height));
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.0.state.lock().unwrap().closed.is_none()
+    }
+}
+
+impl HasDisplayHandle for ExternalSurface {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        if !self.is_valid() {
+            return Err(HandleError::Unavailable);
+        }
+        // Valid until invalidated, see `new`.
+        Ok(unsafe { DisplayHandle::borrow_raw(self.0.display) })
+    }
+}
+
+impl HasWindowHandle for ExternalSurface {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        if !self.is_valid() {
+            return Err(HandleError::Unavailable);
+        }
+        // Valid until invalidated, see `new`.
+        Ok(unsafe { WindowHandle::borrow_raw(self.0.window) })
+    }
+}
+
+impl DisplayApi for ExternalSurface {
+    fn height(&self) -> u32 {
+        self.0.state.lock().unwrap().height
+    }
+
+    fn width(&self) -> u32 {
+        self.0.state.lock().unwrap().width
+    }
+
+    fn offscreen_target(&self) -> Option<OffscreenTarget> {
+        self.0.state.lock().unwrap().closed.clone()
+    }
+
+    fn closed(&self) -> bool {
+        !self.is_valid()
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use wasmtime_wasi::HostMonotonicClock;
+
+/// Frame times kept for the percentiles, about four seconds at 60 fps.
+const WINDOW: usize = 240;
+
+/// Frame times over the most recent presents.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameTimes {
+    /// Presents since the stats were created, not only the ones in the window.
+    pub frames: u64,
+    pub average: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl FrameTimes {
+    pub fn fps(&self) -> f64 {
+        1.0 / self.average.as_secs_f64()
+    }
+}
+
+/// Records when frames are presented, to tell how fast a guest renders.
+///
+/// Every graphics context of a store presents into the same stats, so guests with several canvases see their
+/// presents interleaved. Clones share the same record.
+#[derive(Clone)]
+pub struct FrameStats(Arc<Mutex<FrameStatsState>>);
+
+struct FrameStatsState {
+    clock: Arc<dyn HostMonotonicClock + Send + Sync>,
+    frames: u64,
+    /// Monotonic clock reading of the last present, in nanoseconds.
+    last_present: Option<u64>,
+    frame_times: VecDeque<u64>,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(InstantClock(Instant::now())))
+    }
+
+    /// Time presents with `clock`, e.g. the monotonic clock the guest sees.
+    pub fn with_clock(clock: Arc<dyn HostMonotonicClock + Send + Sync>) -> Self {
+        Self(Arc::new(Mutex::new(FrameStatsState {
+            clock,
+            frames: 0,
+            last_present: None,
+            frame_times: VecDeque::with_capacity(WINDOW),
+        })))
+    }
+
+    pub(crate) fn presented(&self) {
+        let mut state = self.0.lock().unwrap();
+        let now = state.clock.now();
+        if let Some(last_present) = state.last_present {
+            if state.frame_times.len() == WINDOW {
+                state.frame_times.pop_front();
+            }
+            state
+                .frame_times
+                .push_back(now.saturating_sub(last_present));
+        }
+        state.last_present = Some(now);
+        state.frames += 1;
+    }
+
+    /// `None` until two frames were presented.
+    pub fn frame_times(&self) -> Option<FrameTimes> {
+        let state = self.0.lock().unwrap();
+        if state.frame_times.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = state.frame_times.iter().copied().collect();
+        sorted.sort_unstable();
+        let percentile = |p: usize| {
+            let index = (sorted.len() * p / 100).min(sorted.len() - 1);
+            Duration::from_nanos(sorted[index])
+        };
+        let total: u64 = sorted.iter().sum();
+        Some(FrameTimes {
+            frames: state.frames,
+            average: Duration::from_nanos(total / sorted.len() as u64),
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+            max: Duration::from_nanos(*sorted.last().unwrap()),
+        })
+    }
+}
+
+struct InstantClock(Instant);
+
+impl HostMonotonicClock for InstantClock {
+    fn resolution(&self) -> u64 {
+        1
+    }
+
+    fn now(&self) -> u64 {
+        self.0.elapsed().as_nanos() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+
+    /// Clock the test moves by hand, in milliseconds.
+    #[derive(Default)]
+    struct StepClock(AtomicU64);
+
+    impl StepClock {
+        fn advance(&self, millis: u64) {
+            self.0.fetch_add(millis * 1_000_000, Ordering::Relaxed);
+        }
+    }
+
+    impl HostMonotonicClock for StepClock {
+        fn resolution(&self) -> u64 {
+            1
+        }
+
+        fn now(&self) -> u64 {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn no_frame_times_before_two_presents() {
+        let stats = FrameStats::with_clock(Arc::new(StepClock::default()));
+        assert_eq!(stats.frame_times(), None);
+        stats.presented();
+        assert_eq!(stats.frame_times(), None);
+    }
+
+    #[test]
+    fn accumulates_frame_times() {
+        let clock = Arc::new(StepClock::default());
+        let stats = FrameStats::with_clock(clock.clone());
+        stats.presented();
+        for millis in [10, 20, 30, 40] {
+            clock.advance(millis);
+            stats.presented();
+        }
+        let times = stats.frame_times().unwrap();
+        assert_eq!(times.frames, 5);
+        assert_eq!(times.average, Duration::from_millis(25));
+        assert_eq!(times.p50, Duration::from_millis(30));
+        assert_eq!(times.max, Duration::from_millis(40));
+        assert_eq!(times.fps(), 40.0);
+    }
+
+    #[test]
+    fn keeps_only_the_window() {
+        let clock = Arc::new(StepClock::default());
+        let stats = FrameStats::with_clock(clock.clone());
+        stats.presented();
+        clock.advance(1000);
+        stats.presented();
+        for _ in 0..WINDOW {
+            clock.advance(10);
+            stats.presented();
+        }
+        let times = stats.frame_times().unwrap();
+        assert_eq!(times.frames, WINDOW as u64 + 2);
+        assert_eq!(times.max, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn clones_share_the_record() {
+        let clock = Arc::new(StepClock::default());
+        let stats = FrameStats::with_clock(clock.clone());
+        let other = stats.clone();
+        stats.presented();
+        clock.advance(16);
+        other.presented();
+        assert_eq!(stats.frame_times().unwrap().frames, 2);
+    }
+}
@@ -1,12 +1,25 @@
 use std::any::Any;
+use std::sync::Arc;
 
-use crate::wasi::webgpu::graphics_context::{self, ConfigureContextDesc};
+use crate::wasi::webgpu::graphics_context;
+use crate::wasi::webgpu::graphics_context::FrameStats as GuestFrameStats;
+pub use crate::wasi::webgpu::graphics_context::{
+    ConfigureContextDesc, ContextFormat, PresentStatus, TimeoutError,
+};
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
 };
 use wasmtime::component::Resource;
 use wasmtime_wasi::WasiView;
 
+mod external;
+mod frame_stats;
+mod offscreen;
+
+pub use external::ExternalSurface;
+pub use frame_stats::{FrameStats, FrameTimes};
+pub use offscreen::{Frame, OffscreenTarget};
+
 wasmtime::component::bindgen!({
     path: "../../wit/",
     world: "example",
@@ -20,6 +33,17 @@ wasmtime::component::bindgen!({
 pub struct GraphicsContext {
     draw_api: Option<Box<dyn DrawApi + Send + Sync>>,
     display_api: Option<Box<dyn DisplayApi + Send + Sync>>,
+    present_status: PresentStatus,
+    /// Set by the guest, handed to draw apis connected later too.
+    desc: Option<ConfigureContextDesc>,
+    /// Whether the draw api was moved off the display because it closed.
+    closed: bool,
+}
+
+impl Default for GraphicsContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GraphicsContext {
@@ -27,16 +51,28 @@ impl GraphicsContext {
         Self {
             display_api: None,
             draw_api: None,
+            present_status: PresentStatus::Ok,
+            desc: None,
+            closed: false,
         }
     }
 
-    pub fn configure(&mut self, _desc: ConfigureContextDesc) -> wasmtime::Result<()> {
+    pub fn configure(&mut self, desc: ConfigureContextDesc) -> wasmtime::Result<()> {
+        if let Some(draw_api) = &mut self.draw_api {
+            draw_api.configure(&desc);
+            if let Some(display_api) = &self.display_api {
+                draw_api.display_api_ready(display_api.as_ref());
+                self.present_status = draw_api.present_status();
+            }
+        }
+        self.desc = Some(desc);
         Ok(())
     }
 
     pub fn connect_display_api(&mut self, display_api: Box<dyn DisplayApi + Send + Sync>) {
         if let Some(draw_api) = &mut self.draw_api {
-            draw_api.display_api_ready(&display_api)
+            draw_api.display_api_ready(display_api.as_ref());
+            self.present_status = draw_api.present_status();
         }
         self.display_api = Some(display_api);
     }
@@ -47,11 +83,101 @@ impl GraphicsContext {
     // }
 
     pub fn connect_draw_api(&mut self, mut draw_api: Box<dyn DrawApi + Send + Sync>) {
+        if let Some(desc) = &self.desc {
+            draw_api.configure(desc);
+        }
         if let Some(display_api) = &self.display_api {
-            draw_api.display_api_ready(&*display_api)
+            draw_api.display_api_ready(display_api.as_ref());
+            self.present_status = draw_api.present_status();
         }
         self.draw_api = Some(draw_api);
     }
+
+    pub fn get_current_buffer(&mut self) -> wasmtime::Result<GraphicsContextBuffer> {
+        self.close_if_needed();
+        let draw_api = self.draw_api.as_mut().expect("draw_api not set");
+        let buffer = draw_api.get_current_buffer();
+        self.present_status = draw_api.present_status();
+        if buffer.is_err() && self.reconfigure_if_needed() {
+            // Try again on the fresh surface.
+            let draw_api = self.draw_api.as_mut().unwrap();
+            let buffer = draw_api.get_current_buffer();
+            self.present_status = draw_api.present_status();
+            return buffer;
+        }
+        buffer
+    }
+
+    pub fn present(&mut self) -> PresentStatus {
+        self.present_observed(None)
+    }
+
+    /// Presents, first handing the frame to `observer` if it asked for one.
+    ///
+    /// Presents that fail are logged and reported as `lost`, which also reconfigures the draw api for the next frame.
+    pub fn present_observed(&mut self, observer: Option<&dyn PresentObserver>) -> PresentStatus {
+        if self.close_if_needed() {
+            self.present_status = PresentStatus::Lost;
+            return PresentStatus::Lost;
+        }
+        let draw_api = self.draw_api.as_mut().expect("draw_api not set");
+        if let Some(observer) = observer.filter(|observer| observer.wants_frame()) {
+            observer.frame_presented(draw_api.capture_frame());
+        }
+        self.present_status = match draw_api.present() {
+            Ok(()) => {
+                if let Some(display_api) = &self.display_api {
+                    display_api.presented();
+                }
+                draw_api.present_status()
+            }
+            Err(e) => {
+                log::error!("Failed to present: {e:#}");
+                PresentStatus::Lost
+            }
+        };
+        let status = self.present_status;
+        self.reconfigure_if_needed();
+        status
+    }
+
+    pub fn present_status(&self) -> PresentStatus {
+        self.present_status
+    }
+
+    /// Moves the draw api onto the offscreen target of a display that closed, so the guest can keep drawing.
+    /// Returns whether the display is closed.
+    fn close_if_needed(&mut self) -> bool {
+        if self.closed {
+            return true;
+        }
+        match (&mut self.draw_api, &self.display_api) {
+            (Some(draw_api), Some(display_api)) if display_api.closed() => {
+                draw_api.display_api_ready(display_api.as_ref());
+                self.closed = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Reconfigures the draw api for the current display if the surface became outdated or lost, or creating it
+    /// timed out.
+    fn reconfigure_if_needed(&mut self) -> bool {
+        if !matches!(
+            self.present_status,
+            PresentStatus::Outdated | PresentStatus::Lost | PresentStatus::Timeout
+        ) {
+            return false;
+        }
+        match (&mut self.draw_api, &self.display_api) {
+            (Some(draw_api), Some(display_api)) => {
+                draw_api.display_api_ready(display_api.as_ref());
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 impl HasDisplayHandle for GraphicsContext {
@@ -76,12 +202,48 @@ impl HasWindowHandle for GraphicsContext {
 pub trait DrawApi {
     fn get_current_buffer(&mut self) -> wasmtime::Result<GraphicsContextBuffer>;
     fn present(&mut self) -> wasmtime::Result<()>;
-    fn display_api_ready(&mut self, display_api: &Box<dyn DisplayApi + Send + Sync>);
+    /// Called with the guest's configuration when it configures the context, or when connecting to a context it
+    /// configured before, ahead of the next `display_api_ready`. Draw apis that can't honor it ignore it.
+    fn configure(&mut self, _desc: &ConfigureContextDesc) {}
+    /// Called when the display is first connected, and again when the surface needs to be reconfigured.
+    fn display_api_ready(&mut self, display_api: &(dyn DisplayApi + Send + Sync));
+    /// Status of the last `get_current_buffer` or `present` call.
+    fn present_status(&self) -> PresentStatus {
+        PresentStatus::Ok
+    }
+    /// Reads back the buffer that is about to be presented. `None` if there is none, or it can't be read.
+    fn capture_frame(&mut self) -> Option<Frame> {
+        None
+    }
 }
 
 pub trait DisplayApi: HasDisplayHandle + HasWindowHandle {
     fn height(&self) -> u32;
     fn width(&self) -> u32;
+    /// Set for displays without a window, which draw apis should render into instead of a surface.
+    fn offscreen_target(&self) -> Option<OffscreenTarget> {
+        None
+    }
+    /// Called after each present, e.g. to record when the display last showed a new frame.
+    fn presented(&self) {}
+    /// Whether the window was created with a transparent background, so draw apis should keep the alpha channel.
+    fn transparent(&self) -> bool {
+        false
+    }
+    /// Whether the window went away, e.g. because the host closed it. Presents report `lost` from then on.
+    /// Displays that close should also start returning an [`offscreen_target`](Self::offscreen_target) for draw apis
+    /// to move to.
+    fn closed(&self) -> bool {
+        false
+    }
+}
+
+/// Lets hosts look at presented frames without owning the display.
+pub trait PresentObserver: Send + Sync {
+    /// Whether the next frame should be read back. Reading back stalls the gpu, so this should usually be false.
+    fn wants_frame(&self) -> bool;
+    /// Called right before presenting if `wants_frame` returned true. `None` if the frame couldn't be read back.
+    fn frame_presented(&self, frame: Option<Frame>);
 }
 
 pub struct GraphicsContextBuffer {
@@ -123,9 +285,45 @@ where
     Ok(())
 }
 
-pub trait WasiGraphicsContextView: WasiView {}
+pub trait WasiGraphicsContextView: WasiView {
+    /// Notified of presented frames, e.g. to take screenshots.
+    fn present_observer(&self) -> Option<Arc<dyn PresentObserver>> {
+        None
+    }
+
+    /// Where presents are recorded, for hosts and guests that want to know the frame rate.
+    fn frame_stats(&self) -> Option<FrameStats> {
+        None
+    }
 
-impl graphics_context::Host for dyn WasiGraphicsContextView + '_ {}
+    /// Display handed to guests asking for the default context, e.g. an [`ExternalSurface`] for a window the host
+    /// already has. Called on each request, so guests asking twice get two contexts on the same display.
+    fn default_display(&self) -> Option<Box<dyn DisplayApi + Send + Sync>> {
+        None
+    }
+}
+
+impl graphics_context::Host for dyn WasiGraphicsContextView + '_ {
+    fn get_default_context(&mut self) -> Option<Resource<GraphicsContext>> {
+        let display = self.default_display()?;
+        let mut context = GraphicsContext::new();
+        context.connect_display_api(display);
+        Some(self.table().push(context).unwrap())
+    }
+
+    fn get_frame_stats(&mut self) -> Option<GuestFrameStats> {
+        let times = self.frame_stats()?.frame_times()?;
+        let nanos = |duration: std::time::Duration| duration.as_nanos() as u64;
+        Some(GuestFrameStats {
+            frames: times.frames,
+            average: nanos(times.average),
+            p50: nanos(times.p50),
+            p95: nanos(times.p95),
+            p99: nanos(times.p99),
+            max: nanos(times.max),
+        })
+    }
+}
 
 impl graphics_context::HostGraphicsContext for dyn WasiGraphicsContextView + '_ {
     fn new(&mut self) -> Resource<GraphicsContext> {
@@ -142,20 +340,25 @@ impl graphics_context::HostGraphicsContext for dyn WasiGraphicsContextView + '_
         context: Resource<GraphicsContext>,
     ) -> Resource<GraphicsContextBuffer> {
         let context_kind = self.table().get_mut(&context).unwrap();
-        let next_frame = context_kind
-            .draw_api
-            .as_mut()
-            .expect("draw_api not set")
-            .get_current_buffer()
-            .unwrap();
+        let next_frame = context_kind.get_current_buffer().unwrap();
         let next_frame = self.table().push(next_frame).unwrap();
         next_frame
     }
 
-    fn present(&mut self, context: Resource<GraphicsContext>) {
+    fn present(&mut self, context: Resource<GraphicsContext>) -> PresentStatus {
+        let observer = self.present_observer();
+        let frame_stats = self.frame_stats();
         let context = self.table().get_mut(&context).unwrap();
-        // context.display_api.as_mut().unwrap().present().unwrap();
-        context.draw_api.as_mut().unwrap().present().unwrap();
+        let status = context.present_observed(observer.as_deref());
+        if let Some(frame_stats) = frame_stats {
+            frame_stats.presented();
+        }
+        status
+    }
+
+    fn get_present_status(&mut self, context: Resource<GraphicsContext>) -> PresentStatus {
+        let context = self.table().get(&context).unwrap();
+        context.present_status()
     }
 
     fn drop(&mut self, _graphics_context: Resource<GraphicsContext>) -> wasmtime::Result<()> {
@@ -165,7 +368,36 @@ impl graphics_context::HostGraphicsContext for dyn WasiGraphicsContextView + '_
 }
 
 impl graphics_context::HostGraphicsContextBuffer for dyn WasiGraphicsContextView + '_ {
-    fn drop(&mut self, _rep: Resource<GraphicsContextBuffer>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, buffer: Resource<GraphicsContextBuffer>) -> wasmtime::Result<()> {
+        self.table().delete(buffer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws nowhere, and fails every present.
+    struct FailingDrawApi;
+
+    impl DrawApi for FailingDrawApi {
+        fn get_current_buffer(&mut self) -> wasmtime::Result<GraphicsContextBuffer> {
+            Err(wasmtime::Error::msg("no buffer"))
+        }
+
+        fn present(&mut self) -> wasmtime::Result<()> {
+            Err(wasmtime::Error::msg("the surface went away"))
+        }
+
+        fn display_api_ready(&mut self, _display_api: &(dyn DisplayApi + Send + Sync)) {}
+    }
+
+    #[test]
+    fn failed_presents_report_lost() {
+        let mut context = GraphicsContext::new();
+        context.connect_draw_api(Box::new(FailingDrawApi));
+        assert_eq!(context.present(), PresentStatus::Lost);
+        assert_eq!(context.present_status(), PresentStatus::Lost);
     }
 }
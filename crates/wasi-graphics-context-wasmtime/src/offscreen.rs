@@ -0,0 +1,72 @@
+use std::sync::{Arc, Mutex};
+
+/// A presented frame, as tightly packed RGBA8 rows.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Frame {
+    /// RGBA value of the pixel at `x`, `y`.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.data[i..i + 4].try_into().unwrap()
+    }
+}
+
+/// Shared handle to a display that has no window, and keeps the presented frames in memory instead.
+///
+/// The display side sets the size, the draw side presents into it, and the host reads the frames back.
+#[derive(Clone, Debug)]
+pub struct OffscreenTarget(Arc<Mutex<OffscreenState>>);
+
+#[derive(Debug)]
+struct OffscreenState {
+    width: u32,
+    height: u32,
+    latest_frame: Option<Frame>,
+    frame_count: u64,
+}
+
+impl OffscreenTarget {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self(Arc::new(Mutex::new(OffscreenState {
+            width,
+            height,
+            latest_frame: None,
+            frame_count: 0,
+        })))
+    }
+
+    pub fn width(&self) -> u32 {
+        self.0.lock().unwrap().width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.0.lock().unwrap().height
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        let mut state = self.0.lock().unwrap();
+        state.width = width;
+        state.height = height;
+    }
+
+    pub fn present(&self, frame: Frame) {
+        let mut state = self.0.lock().unwrap();
+        state.latest_frame = Some(frame);
+        state.frame_count += 1;
+    }
+
+    /// The last frame presented, if any.
+    pub fn latest_frame(&self) -> Option<Frame> {
+        self.0.lock().unwrap().latest_frame.clone()
+    }
+
+    /// Number of frames presented so far.
+    pub fn frame_count(&self) -> u64 {
+        self.0.lock().unwrap().frame_count
+    }
+}
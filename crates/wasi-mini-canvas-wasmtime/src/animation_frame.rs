@@ -42,14 +42,14 @@ impl animation_frame::HostFrameListener for dyn WasiMiniCanvasView + '_ {
 
 #[derive(Debug)]
 pub struct AnimationFrameListener {
-    receiver: Receiver<()>,
+    receiver: Receiver<FrameEvent>,
     data: Mutex<Option<FrameEvent>>,
 }
 
 #[async_trait::async_trait]
 impl wasmtime_wasi::Subscribe for AnimationFrameListener {
     async fn ready(&mut self) {
-        let _ = self.receiver.recv().await.unwrap();
-        *self.data.lock().unwrap() = Some(FrameEvent { nothing: false });
+        let event = self.receiver.recv().await.unwrap();
+        *self.data.lock().unwrap() = Some(event);
     }
 }
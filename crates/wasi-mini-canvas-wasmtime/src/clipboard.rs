@@ -0,0 +1,28 @@
+use crate::{
+    wasi::webgpu::clipboard::{self, ClipboardError},
+    WasiMiniCanvasView,
+};
+
+/// Clipboard backend used by guests, see [`WasiMiniCanvasView::clipboard`].
+#[async_trait::async_trait]
+pub trait Clipboard: Send + Sync {
+    async fn read_text(&self) -> Result<Option<String>, ClipboardError>;
+    async fn write_text(&self, text: String) -> Result<(), ClipboardError>;
+}
+
+#[async_trait::async_trait]
+impl clipboard::Host for dyn WasiMiniCanvasView + '_ {
+    async fn read_text(&mut self) -> Result<Option<String>, ClipboardError> {
+        match self.clipboard() {
+            Some(clipboard) => clipboard.read_text().await,
+            None => Err(ClipboardError::Denied),
+        }
+    }
+
+    async fn write_text(&mut self, text: String) -> Result<(), ClipboardError> {
+        match self.clipboard() {
+            Some(clipboard) => clipboard.write_text(text).await,
+            None => Err(ClipboardError::Denied),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::MiniCanvasDesc;
+
+/// Builds a [`MiniCanvasDesc`].
+///
+/// Prefer this over constructing the struct directly, it keeps compiling when new window options are added.
+/// Options that aren't set are left to the host to decide.
+#[derive(Clone, Debug)]
+pub struct MiniCanvasDescBuilder {
+    desc: MiniCanvasDesc,
+}
+
+impl Default for MiniCanvasDescBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MiniCanvasDescBuilder {
+    /// An 800x600 on-screen canvas.
+    pub fn new() -> Self {
+        Self {
+            desc: MiniCanvasDesc {
+                height: 600,
+                width: 800,
+                offscreen: false,
+                title: None,
+                resizable: None,
+                decorations: None,
+                fullscreen: None,
+                transparent: None,
+            },
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.desc.width = width;
+        self.desc.height = height;
+        self
+    }
+
+    pub fn offscreen(mut self, offscreen: bool) -> Self {
+        self.desc.offscreen = offscreen;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.desc.title = Some(title.into());
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.desc.resizable = Some(resizable);
+        self
+    }
+
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.desc.decorations = Some(decorations);
+        self
+    }
+
+    /// Borderless fullscreen on the current monitor.
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.desc.fullscreen = Some(fullscreen);
+        self
+    }
+
+    /// See-through window background, for overlays and desktop widgets.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.desc.transparent = Some(transparent);
+        self
+    }
+
+    pub fn build(self) -> MiniCanvasDesc {
+        self.desc
+    }
+}
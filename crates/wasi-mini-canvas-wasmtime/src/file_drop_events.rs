@@ -0,0 +1,148 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    wasi::webgpu::file_drop_events::{self, DroppedFile, FileDropEvent, Pollable},
+    MiniCanvasArc, WasiMiniCanvasView,
+};
+use async_broadcast::Receiver;
+use wasmtime::component::Resource;
+
+/// What the host hands to the guest when files are dropped onto a canvas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Only deliver the file paths.
+    #[default]
+    PathsOnly,
+    /// Deliver the file paths and an input stream of each file's contents.
+    PathsAndContents,
+}
+
+#[derive(Debug, Clone)]
+pub enum FileDrop {
+    Hovered(Vec<PathBuf>),
+    Dropped(Vec<PathBuf>),
+    HoverCancelled,
+}
+
+#[async_trait::async_trait]
+impl file_drop_events::Host for dyn WasiMiniCanvasView + '_ {
+    async fn listener(
+        &mut self,
+        mini_canvas: Resource<MiniCanvasArc>,
+    ) -> Resource<FileDropListener> {
+        let canvas = &self.table().get(&mini_canvas).unwrap().0;
+        let receiver = canvas.file_drop_sender.new_receiver();
+        self.table()
+            .push(FileDropListener {
+                receiver,
+                data: Default::default(),
+            })
+            .unwrap()
+    }
+}
+
+impl file_drop_events::HostFileDropListener for dyn WasiMiniCanvasView + '_ {
+    fn subscribe(&mut self, file_drop: Resource<FileDropListener>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.table(), file_drop).unwrap()
+    }
+    fn get(&mut self, file_drop: Resource<FileDropListener>) -> Option<FileDropEvent> {
+        let file_drop = self.table().get(&file_drop).unwrap();
+        let event = file_drop.data.lock().unwrap().take()?;
+        Some(match event {
+            FileDrop::Hovered(paths) => {
+                FileDropEvent::Hovered(paths.iter().map(|path| guest_path(path)).collect())
+            }
+            FileDrop::Dropped(paths) => {
+                let drop_policy = self.drop_policy();
+                FileDropEvent::Dropped(
+                    paths
+                        .iter()
+                        .map(|path| {
+                            let contents = match drop_policy {
+                                DropPolicy::PathsOnly => None,
+                                // Files that can't be read are still reported, just without contents.
+                                DropPolicy::PathsAndContents => {
+                                    std::fs::read(path).ok().map(|bytes| {
+                                        let stream = wasmtime_wasi::InputStream::Host(Box::new(
+                                            wasmtime_wasi::pipe::MemoryInputPipe::new(bytes),
+                                        ));
+                                        self.table().push(stream).unwrap()
+                                    })
+                                }
+                            };
+                            DroppedFile {
+                                path: guest_path(path),
+                                contents,
+                            }
+                        })
+                        .collect(),
+                )
+            }
+            FileDrop::HoverCancelled => FileDropEvent::HoverCancelled,
+        })
+    }
+    fn drop(&mut self, _self_: Resource<FileDropListener>) -> wasmtime::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FileDropListener {
+    receiver: Receiver<FileDrop>,
+    data: Mutex<Option<FileDrop>>,
+}
+
+#[async_trait::async_trait]
+impl wasmtime_wasi::Subscribe for FileDropListener {
+    async fn ready(&mut self) {
+        let event = self.receiver.recv().await.unwrap();
+        *self.data.lock().unwrap() = Some(event);
+    }
+}
+
+/// Guests get `/` separated paths, whichever platform the host runs on.
+pub(crate) fn guest_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace(std::path::MAIN_SEPARATOR, "/")
+}
+
+/// Files dropped onto a canvas that the guest hasn't taken with `get-dropped-files` yet.
+#[derive(Debug, Default)]
+pub(crate) struct DroppedFiles(Mutex<Vec<PathBuf>>);
+
+impl DroppedFiles {
+    pub(crate) fn push(&self, paths: &[PathBuf]) {
+        self.0.lock().unwrap().extend_from_slice(paths);
+    }
+
+    pub(crate) fn take(&self) -> Vec<String> {
+        let paths = std::mem::take(&mut *self.0.lock().unwrap());
+        paths.iter().map(|path| guest_path(path)).collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+}
+
+/// The pollable of `file-dropped`. Woken by every file drop event, ready once one of them left files behind.
+#[derive(Debug)]
+pub(crate) struct FileDroppedReady {
+    pub(crate) receiver: Receiver<FileDrop>,
+    pub(crate) dropped: Arc<DroppedFiles>,
+}
+
+#[async_trait::async_trait]
+impl wasmtime_wasi::Subscribe for FileDroppedReady {
+    async fn ready(&mut self) {
+        while self.dropped.is_empty() {
+            // Closed once the canvas is gone, so no more files can arrive.
+            if self.receiver.recv().await.is_err() {
+                return;
+            }
+        }
+    }
+}
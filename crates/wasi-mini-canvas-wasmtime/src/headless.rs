@@ -0,0 +1,177 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+};
+use wasi_graphics_context_wasmtime::{DisplayApi, OffscreenTarget};
+
+use crate::{
+    FileDrop, FrameEvent, KeyEvent, MiniCanvas, MiniCanvasDesc, MiniCanvasProxy, MonitorInfo,
+    PointerEvent, ResizeEvent,
+};
+
+/// Refresh rate of the monitor headless canvases report being on.
+const HEADLESS_REFRESH_RATE_MILLIHERTZ: u32 = 60_000;
+
+/// Creates canvases that aren't backed by a window, for running guests in tests and CI.
+///
+/// Frames presented to these canvases are kept in memory, and events are fed in by the host through [`HeadlessCanvas`].
+#[derive(Clone, Default)]
+pub struct HeadlessCanvases(Arc<Mutex<Vec<HeadlessCanvas>>>);
+
+impl HeadlessCanvases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create_canvas(&self, desc: MiniCanvasDesc) -> MiniCanvas {
+        let target = OffscreenTarget::new(desc.width, desc.height);
+        let monitor_target = target.clone();
+        let canvas = MiniCanvas::new(Box::new(HeadlessWindow(target.clone())))
+            .with_current_monitor(move || {
+                Some(MonitorInfo {
+                    name: Some("headless".into()),
+                    width: monitor_target.width(),
+                    height: monitor_target.height(),
+                    x: 0,
+                    y: 0,
+                    scale_factor: 1.0,
+                    refresh_rate_millihertz: Some(HEADLESS_REFRESH_RATE_MILLIHERTZ),
+                    video_modes: Vec::new(),
+                })
+            });
+        self.0.lock().unwrap().push(HeadlessCanvas {
+            proxy: canvas.proxy(),
+            target,
+            script: Default::default(),
+        });
+        canvas
+    }
+
+    /// All canvases that weren't dropped yet, in creation order.
+    pub fn canvases(&self) -> Vec<HeadlessCanvas> {
+        let mut canvases = self.0.lock().unwrap();
+        // Canvases of a guest that finished go away, while other guests sharing these canvases keep running.
+        canvases.retain(|canvas| !canvas.proxy.is_closed());
+        canvases.clone()
+    }
+}
+
+/// Host side of a headless canvas.
+#[derive(Clone)]
+pub struct HeadlessCanvas {
+    proxy: MiniCanvasProxy,
+    target: OffscreenTarget,
+    script: Arc<Mutex<Script>>,
+}
+
+/// Input a headless canvas can be fed.
+#[derive(Debug, Clone)]
+pub enum CanvasEvent {
+    PointerUp(PointerEvent),
+    PointerDown(PointerEvent),
+    PointerMove(PointerEvent),
+    KeyUp(KeyEvent),
+    KeyDown(KeyEvent),
+    Resize { width: u32, height: u32 },
+    FileDrop(FileDrop),
+}
+
+/// Scripted events, sent right before the animation frame they're scheduled for.
+#[derive(Default)]
+struct Script {
+    /// Animation frames sent so far.
+    frame: u64,
+    /// Sorted by frame.
+    events: VecDeque<(u64, CanvasEvent)>,
+}
+
+impl HeadlessCanvas {
+    /// Sends scripted input and frame events to the guest.
+    pub fn proxy(&self) -> &MiniCanvasProxy {
+        &self.proxy
+    }
+
+    /// Frames presented to this canvas.
+    pub fn target(&self) -> &OffscreenTarget {
+        &self.target
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        self.target.resize(width, height);
+        self.proxy.canvas_resize(ResizeEvent { height, width });
+    }
+
+    pub fn send(&self, event: CanvasEvent) {
+        match event {
+            CanvasEvent::PointerUp(event) => self.proxy.pointer_up(event),
+            CanvasEvent::PointerDown(event) => self.proxy.pointer_down(event),
+            CanvasEvent::PointerMove(event) => self.proxy.pointer_move(event),
+            CanvasEvent::KeyUp(event) => self.proxy.key_up(event),
+            CanvasEvent::KeyDown(event) => self.proxy.key_down(event),
+            CanvasEvent::Resize { width, height } => self.resize(width, height),
+            CanvasEvent::FileDrop(event) => self.proxy.file_drop(event),
+        }
+    }
+
+    /// Queues `events` to be sent by [`Self::animation_frame`], each right before the animation frame with its index.
+    /// Frames are counted from 0, events for frames that were already sent go out with the next one.
+    pub fn script(&self, events: impl IntoIterator<Item = (u64, CanvasEvent)>) {
+        let mut script = self.script.lock().unwrap();
+        script.events.extend(events);
+        script
+            .events
+            .make_contiguous()
+            .sort_by_key(|(frame, _)| *frame);
+    }
+
+    /// Sends the scripted events that are due, then the animation frame itself.
+    pub fn animation_frame(&self, event: FrameEvent) {
+        let due = {
+            let mut script = self.script.lock().unwrap();
+            let frame = script.frame;
+            script.frame += 1;
+            let due = script
+                .events
+                .iter()
+                .take_while(|(at, _)| *at <= frame)
+                .count();
+            script.events.drain(..due).collect::<Vec<_>>()
+        };
+        for (_, event) in due {
+            self.send(event);
+        }
+        self.proxy.animation_frame(event);
+    }
+}
+
+struct HeadlessWindow(OffscreenTarget);
+
+impl HasDisplayHandle for HeadlessWindow {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+}
+
+impl HasWindowHandle for HeadlessWindow {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+}
+
+impl DisplayApi for HeadlessWindow {
+    fn height(&self) -> u32 {
+        self.0.height()
+    }
+
+    fn width(&self) -> u32 {
+        self.0.width()
+    }
+
+    fn offscreen_target(&self) -> Option<OffscreenTarget> {
+        Some(self.0.clone())
+    }
+}
@@ -1,37 +1,103 @@
 use std::{
     fmt::Debug,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
 };
-use wasi_graphics_context_wasmtime::DisplayApi;
+use wasi_graphics_context_wasmtime::{DisplayApi, OffscreenTarget};
 
+use crate::file_drop_events::{DroppedFiles, FileDroppedReady};
 use crate::wasi::webgpu::mini_canvas::{self, GraphicsContext, Pollable};
 use async_broadcast::{Receiver, TrySendError};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use wasmtime::component::Resource;
-use wasmtime_wasi::WasiView;
+use wasmtime_wasi::{HostWallClock, WasiView};
 
 mod animation_frame;
+mod clipboard;
+mod desc;
+mod file_drop_events;
+mod headless;
 mod key_events;
+mod monitors;
 mod pointer_events;
+mod quota;
+mod swap_chain_timing;
 
 #[cfg(feature = "winit")]
 mod winit;
 
 #[cfg(feature = "winit")]
-pub use winit::{create_wasi_winit_event_loop, WasiWinitEventLoop, WasiWinitEventLoopProxy};
+pub use winit::{
+    create_wasi_winit_event_loop, EventLoopStopped, WasiWinitEventLoop, WasiWinitEventLoopProxy,
+};
 
 pub trait HasDisplayAndWindowHandle: HasDisplayHandle + HasWindowHandle {}
 
 impl<T: HasDisplayHandle + HasWindowHandle> HasDisplayAndWindowHandle for T {}
 
+pub use clipboard::Clipboard;
+pub use desc::MiniCanvasDescBuilder;
+pub use file_drop_events::{DropPolicy, FileDrop};
+pub use headless::{CanvasEvent, HeadlessCanvas, HeadlessCanvases};
+pub use monitors::Monitors;
+pub use quota::CanvasQuota;
+
 pub use crate::wasi::webgpu::{
+    animation_frame::FrameEvent,
+    clipboard::ClipboardError,
     key_events::KeyEvent,
-    mini_canvas::{CreateDesc as MiniCanvasDesc, ResizeEvent},
-    pointer_events::PointerEvent,
+    mini_canvas::{
+        CanvasSize, CreateDesc as MiniCanvasDesc, CursorGrabMode, FullscreenMode, MonitorInfo,
+        ResizeEvent, VideoMode,
+    },
+    pointer_events::{MouseDelta, PointerEvent},
 };
 
+#[async_trait::async_trait]
 pub trait WasiMiniCanvasView: WasiView {
-    fn create_canvas(&self, desc: MiniCanvasDesc) -> MiniCanvas;
+    /// Called from the executor, so this shouldn't block. See [`create_canvas_on_thread`] for blocking implementations.
+    async fn create_canvas(&mut self, desc: MiniCanvasDesc) -> MiniCanvas;
+
+    /// Whether dropped files are delivered as paths only, or with their contents as well.
+    fn drop_policy(&self) -> DropPolicy {
+        DropPolicy::PathsOnly
+    }
+
+    /// Clipboard guests can read from and write to. `None` denies access.
+    fn clipboard(&self) -> Option<Arc<dyn Clipboard>> {
+        None
+    }
+
+    /// Source of the monitor list. `None` reports no monitors.
+    fn monitors(&self) -> Option<Arc<dyn Monitors>> {
+        None
+    }
+
+    /// Limit on the canvases the guest can have open. `None` allows any number.
+    fn canvas_quota(&self) -> Option<CanvasQuota> {
+        None
+    }
+
+    /// Clock that present times and frame targets of new canvases are read from, e.g. the guest's own wall clock.
+    /// `None` uses the system clock.
+    fn wall_clock(&self) -> Option<Arc<dyn HostWallClock + Send + Sync>> {
+        None
+    }
+}
+
+/// Runs a blocking canvas constructor on its own thread, for [`WasiMiniCanvasView::create_canvas`] implementations
+/// that can't be made async.
+pub async fn create_canvas_on_thread<F>(create: F) -> MiniCanvas
+where
+    F: FnOnce() -> MiniCanvas + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(create());
+    });
+    receiver.await.expect("canvas constructor panicked")
 }
 
 pub fn add_to_linker<T>(l: &mut wasmtime::component::Linker<T>) -> wasmtime::Result<()>
@@ -66,8 +132,11 @@ where
     let closure = type_annotate::<T, _>(|t| t);
     wasi::webgpu::mini_canvas::add_to_linker_get_host(l, closure)?;
     wasi::webgpu::animation_frame::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::swap_chain_timing::add_to_linker_get_host(l, closure)?;
     wasi::webgpu::pointer_events::add_to_linker_get_host(l, closure)?;
     wasi::webgpu::key_events::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::file_drop_events::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::clipboard::add_to_linker_get_host(l, closure)?;
     Ok(())
 }
 
@@ -81,17 +150,24 @@ wasmtime::component::bindgen!({
             "down-listener",
             "move-listener",
             "listener",
+            "read-text",
+            "write-text",
+            "available-monitors",
+            "[constructor]mini-canvas",
             // "resize-listener",
         ],
     },
+    trappable_imports: ["[constructor]mini-canvas"],
     with: {
         "wasi:io": wasmtime_wasi::bindings::io,
         "wasi:webgpu/pointer-events/pointer-up-listener": pointer_events::PointerUpListener,
         "wasi:webgpu/pointer-events/pointer-down-listener": pointer_events::PointerDownListener,
         "wasi:webgpu/pointer-events/pointer-move-listener": pointer_events::PointerMoveListener,
+        "wasi:webgpu/pointer-events/raw-mouse-delta-listener": pointer_events::RawMouseDeltaListener,
         "wasi:webgpu/key-events/key-up-listener": key_events::KeyUpListener,
         "wasi:webgpu/key-events/key-down-listener": key_events::KeyDownListener,
         "wasi:webgpu/animation-frame/frame-listener": animation_frame::AnimationFrameListener,
+        "wasi:webgpu/file-drop-events/file-drop-listener": file_drop_events::FileDropListener,
         "wasi:webgpu/graphics-context": wasi_graphics_context_wasmtime,
         "wasi:webgpu/mini-canvas/mini-canvas": MiniCanvasArc,
         "wasi:webgpu/mini-canvas/resize-listener": ResizeListener,
@@ -100,6 +176,17 @@ wasmtime::component::bindgen!({
 
 pub struct MiniCanvas {
     pub window: Box<dyn DisplayApi + Send + Sync + 'static>,
+    current_monitor: Option<Box<dyn Fn() -> Option<MonitorInfo> + Send + Sync>>,
+    cursor_grab: Option<Box<dyn Fn(CursorGrabMode) -> bool + Send + Sync>>,
+    cursor_position: Option<Box<dyn Fn(f64, f64) -> bool + Send + Sync>>,
+    fullscreen: Option<Box<dyn Fn(FullscreenMode) -> bool + Send + Sync>>,
+    min_size: Option<Box<dyn Fn(Option<CanvasSize>) + Send + Sync>>,
+    max_size: Option<Box<dyn Fn(Option<CanvasSize>) + Send + Sync>>,
+    request_redraw: Option<Box<dyn Fn() + Send + Sync>>,
+    dropped_files: Arc<DroppedFiles>,
+    clock: Arc<dyn HostWallClock + Send + Sync>,
+    /// Nanoseconds since the unix epoch, 0 until the first present.
+    last_present: AtomicU64,
 
     // Keeping inactive receivers to keep channels open.
     // See https://docs.rs/async-broadcast/0.7.1/async_broadcast/struct.InactiveReceiver.html
@@ -109,25 +196,41 @@ pub struct MiniCanvas {
     _pointer_down_receiver: async_broadcast::InactiveReceiver<PointerEvent>,
     pointer_move_sender: async_broadcast::Sender<PointerEvent>,
     _pointer_move_receiver: async_broadcast::InactiveReceiver<PointerEvent>,
+    raw_mouse_delta_sender: async_broadcast::Sender<MouseDelta>,
+    _raw_mouse_delta_receiver: async_broadcast::InactiveReceiver<MouseDelta>,
     key_up_sender: async_broadcast::Sender<KeyEvent>,
     _key_up_receiver: async_broadcast::InactiveReceiver<KeyEvent>,
     key_down_sender: async_broadcast::Sender<KeyEvent>,
     _key_down_receiver: async_broadcast::InactiveReceiver<KeyEvent>,
     canvas_resize_sender: async_broadcast::Sender<ResizeEvent>,
     _canvas_resize_receiver: async_broadcast::InactiveReceiver<ResizeEvent>,
-    frame_sender: async_broadcast::Sender<()>,
-    _frame_receiver: async_broadcast::InactiveReceiver<()>,
+    frame_sender: async_broadcast::Sender<FrameEvent>,
+    _frame_receiver: async_broadcast::InactiveReceiver<FrameEvent>,
+    file_drop_sender: async_broadcast::Sender<FileDrop>,
+    _file_drop_receiver: async_broadcast::InactiveReceiver<FileDrop>,
 }
 impl Debug for MiniCanvas {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MiniCanvas")
             .field("window", &"<Boxed window>")
+            .field("current_monitor", &"<Boxed fn>")
+            .field("cursor_grab", &"<Boxed fn>")
+            .field("cursor_position", &"<Boxed fn>")
+            .field("fullscreen", &"<Boxed fn>")
+            .field("min_size", &"<Boxed fn>")
+            .field("max_size", &"<Boxed fn>")
+            .field("request_redraw", &"<Boxed fn>")
+            .field("dropped_files", &self.dropped_files)
+            .field("clock", &"<Arc clock>")
+            .field("last_present", &self.last_present)
             .field("pointer_up_sender", &self.pointer_up_sender)
             .field("_pointer_up_receiver", &self._pointer_up_receiver)
             .field("pointer_down_sender", &self.pointer_down_sender)
             .field("_pointer_down_receiver", &self._pointer_down_receiver)
             .field("pointer_move_sender", &self.pointer_move_sender)
             .field("_pointer_move_receiver", &self._pointer_move_receiver)
+            .field("raw_mouse_delta_sender", &self.raw_mouse_delta_sender)
+            .field("_raw_mouse_delta_receiver", &self._raw_mouse_delta_receiver)
             .field("key_up_sender", &self.key_up_sender)
             .field("_key_up_receiver", &self._key_up_receiver)
             .field("key_down_sender", &self.key_down_sender)
@@ -136,6 +239,8 @@ impl Debug for MiniCanvas {
             .field("_canvas_resize_receiver", &self._canvas_resize_receiver)
             .field("frame_sender", &self.frame_sender)
             .field("_frame_receiver", &self._frame_receiver)
+            .field("file_drop_sender", &self.file_drop_sender)
+            .field("_file_drop_receiver", &self._file_drop_receiver)
             .finish()
     }
 }
@@ -148,6 +253,9 @@ impl MiniCanvas {
         let pointer_down_receiver = pointer_down_receiver.deactivate();
         let (pointer_move_sender, pointer_move_receiver) = async_broadcast::broadcast(5);
         let pointer_move_receiver = pointer_move_receiver.deactivate();
+        // Mice can report motion far more often than guests poll, listeners sum up what piled up.
+        let (raw_mouse_delta_sender, raw_mouse_delta_receiver) = async_broadcast::broadcast(64);
+        let raw_mouse_delta_receiver = raw_mouse_delta_receiver.deactivate();
         let (key_up_sender, key_up_receiver) = async_broadcast::broadcast(5);
         let key_up_receiver = key_up_receiver.deactivate();
         let (key_down_sender, key_down_receiver) = async_broadcast::broadcast(5);
@@ -156,14 +264,28 @@ impl MiniCanvas {
         let canvas_resize_receiver = canvas_resize_receiver.deactivate();
         let (frame_sender, frame_receiver) = async_broadcast::broadcast(1);
         let frame_receiver = frame_receiver.deactivate();
+        let (file_drop_sender, file_drop_receiver) = async_broadcast::broadcast(5);
+        let file_drop_receiver = file_drop_receiver.deactivate();
         Self {
             window,
+            current_monitor: None,
+            cursor_grab: None,
+            cursor_position: None,
+            fullscreen: None,
+            min_size: None,
+            max_size: None,
+            request_redraw: None,
+            dropped_files: Default::default(),
+            clock: Arc::new(swap_chain_timing::SystemClock),
+            last_present: AtomicU64::new(0),
             pointer_up_sender,
             _pointer_up_receiver: pointer_up_receiver,
             pointer_down_sender,
             _pointer_down_receiver: pointer_down_receiver,
             pointer_move_sender,
             _pointer_move_receiver: pointer_move_receiver,
+            raw_mouse_delta_sender,
+            _raw_mouse_delta_receiver: raw_mouse_delta_receiver,
             key_up_sender,
             _key_up_receiver: key_up_receiver,
             key_down_sender,
@@ -172,18 +294,135 @@ impl MiniCanvas {
             _canvas_resize_receiver: canvas_resize_receiver,
             frame_sender,
             _frame_receiver: frame_receiver,
+            file_drop_sender,
+            _file_drop_receiver: file_drop_receiver,
+        }
+    }
+
+    /// Lets guests query which monitor this canvas is on.
+    pub fn with_current_monitor(
+        mut self,
+        current_monitor: impl Fn() -> Option<MonitorInfo> + Send + Sync + 'static,
+    ) -> Self {
+        self.current_monitor = Some(Box::new(current_monitor));
+        self
+    }
+
+    pub fn current_monitor(&self) -> Option<MonitorInfo> {
+        self.current_monitor.as_ref().and_then(|f| f())
+    }
+
+    /// Lets guests grab the cursor. `set_grab` returns whether the platform could grab it in that mode.
+    pub fn with_cursor_grab(
+        mut self,
+        set_grab: impl Fn(CursorGrabMode) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.cursor_grab = Some(Box::new(set_grab));
+        self
+    }
+
+    /// Fails on canvases that weren't given a way to grab the cursor, like headless ones.
+    pub fn set_cursor_grab_mode(&self, mode: CursorGrabMode) -> bool {
+        self.cursor_grab.as_ref().is_some_and(|f| f(mode))
+    }
+
+    /// Lets guests move the cursor. `set_position` returns whether the platform could move it.
+    pub fn with_cursor_position(
+        mut self,
+        set_position: impl Fn(f64, f64) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.cursor_position = Some(Box::new(set_position));
+        self
+    }
+
+    /// Fails on canvases that weren't given a way to move the cursor, like headless ones.
+    pub fn set_cursor_position(&self, x: f64, y: f64) -> bool {
+        self.cursor_position.as_ref().is_some_and(|f| f(x, y))
+    }
+
+    /// Lets guests switch fullscreen modes. `set_fullscreen` returns whether the platform could switch to the mode.
+    pub fn with_fullscreen(
+        mut self,
+        set_fullscreen: impl Fn(FullscreenMode) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.fullscreen = Some(Box::new(set_fullscreen));
+        self
+    }
+
+    /// Fails on canvases that weren't given a way to go fullscreen, like headless ones.
+    pub fn set_fullscreen(&self, mode: FullscreenMode) -> bool {
+        self.fullscreen.as_ref().is_some_and(|f| f(mode))
+    }
+
+    /// Lets guests limit how far users can resize the canvas. Both are called with `None` to clear the limit.
+    pub fn with_size_limits(
+        mut self,
+        set_min_size: impl Fn(Option<CanvasSize>) + Send + Sync + 'static,
+        set_max_size: impl Fn(Option<CanvasSize>) + Send + Sync + 'static,
+    ) -> Self {
+        self.min_size = Some(Box::new(set_min_size));
+        self.max_size = Some(Box::new(set_max_size));
+        self
+    }
+
+    /// Ignored on canvases that weren't given a way to limit their size, like headless ones.
+    pub fn set_min_size(&self, size: Option<CanvasSize>) {
+        if let Some(f) = &self.min_size {
+            f(size);
+        }
+    }
+
+    /// Ignored on canvases that weren't given a way to limit their size, like headless ones.
+    pub fn set_max_size(&self, size: Option<CanvasSize>) {
+        if let Some(f) = &self.max_size {
+            f(size);
         }
     }
 
+    /// Lets guests ask for an animation frame, for backends that only send frames on demand.
+    pub fn with_request_redraw(
+        mut self,
+        request_redraw: impl Fn() + Send + Sync + 'static,
+    ) -> Self {
+        self.request_redraw = Some(Box::new(request_redraw));
+        self
+    }
+
+    /// Ignored on canvases that get frames regardless, like headless ones, which the host sends frames to.
+    pub fn request_redraw(&self) {
+        if let Some(f) = &self.request_redraw {
+            f();
+        }
+    }
+
+    /// Time presents with `clock` instead of the system clock.
+    pub fn with_wall_clock(mut self, clock: Arc<dyn HostWallClock + Send + Sync>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Nanoseconds since the unix epoch, by the canvas's clock.
+    pub(crate) fn now(&self) -> u64 {
+        self.clock.now().as_nanos() as u64
+    }
+
+    /// When this canvas last presented, in nanoseconds since the unix epoch. 0 if it hasn't presented yet.
+    pub fn last_present_time(&self) -> u64 {
+        self.last_present.load(Ordering::Relaxed)
+    }
+
     pub fn proxy(&self) -> MiniCanvasProxy {
         MiniCanvasProxy {
             pointer_up_sender: self.pointer_up_sender.clone(),
             pointer_down_sender: self.pointer_down_sender.clone(),
             pointer_move_sender: self.pointer_move_sender.clone(),
+            raw_mouse_delta_sender: self.raw_mouse_delta_sender.clone(),
             key_up_sender: self.key_up_sender.clone(),
             key_down_sender: self.key_down_sender.clone(),
             canvas_resize_sender: self.canvas_resize_sender.clone(),
             frame_sender: self.frame_sender.clone(),
+            file_drop_sender: self.file_drop_sender.clone(),
+            dropped_files: Arc::clone(&self.dropped_files),
         }
     }
 }
@@ -193,10 +432,13 @@ pub struct MiniCanvasProxy {
     pointer_up_sender: async_broadcast::Sender<PointerEvent>,
     pointer_down_sender: async_broadcast::Sender<PointerEvent>,
     pointer_move_sender: async_broadcast::Sender<PointerEvent>,
+    raw_mouse_delta_sender: async_broadcast::Sender<MouseDelta>,
     key_up_sender: async_broadcast::Sender<KeyEvent>,
     key_down_sender: async_broadcast::Sender<KeyEvent>,
     canvas_resize_sender: async_broadcast::Sender<ResizeEvent>,
-    frame_sender: async_broadcast::Sender<()>,
+    frame_sender: async_broadcast::Sender<FrameEvent>,
+    file_drop_sender: async_broadcast::Sender<FileDrop>,
+    dropped_files: Arc<DroppedFiles>,
 }
 
 impl MiniCanvasProxy {
@@ -209,6 +451,9 @@ impl MiniCanvasProxy {
     pub fn pointer_move(&self, event: PointerEvent) {
         unwrap_unless_inactive_or_full(self.pointer_move_sender.try_broadcast(event));
     }
+    pub fn raw_mouse_delta(&self, event: MouseDelta) {
+        unwrap_unless_inactive_or_full(self.raw_mouse_delta_sender.try_broadcast(event));
+    }
     pub fn key_up(&self, event: KeyEvent) {
         unwrap_unless_inactive(self.key_up_sender.try_broadcast(event));
     }
@@ -218,22 +463,33 @@ impl MiniCanvasProxy {
     pub fn canvas_resize(&self, event: ResizeEvent) {
         unwrap_unless_inactive(self.canvas_resize_sender.try_broadcast(event));
     }
-    pub fn animation_frame(&self) {
-        unwrap_unless_inactive_or_full(self.frame_sender.try_broadcast(()));
+    pub fn animation_frame(&self, event: FrameEvent) {
+        unwrap_unless_inactive_or_full(self.frame_sender.try_broadcast(event));
+    }
+    pub fn file_drop(&self, event: FileDrop) {
+        // Kept for `get-dropped-files` before waking its pollables.
+        if let FileDrop::Dropped(paths) = &event {
+            self.dropped_files.push(paths);
+        }
+        unwrap_unless_inactive(self.file_drop_sender.try_broadcast(event));
+    }
+    /// Whether the canvas and everything the guest subscribed to on it were dropped.
+    pub fn is_closed(&self) -> bool {
+        self.frame_sender.is_closed()
     }
 }
 
 impl HasDisplayHandle for MiniCanvas {
     fn display_handle(
         &self,
-    ) -> Result<raw_window_handle::DisplayHandle, raw_window_handle::HandleError> {
+    ) -> Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError> {
         self.window.display_handle()
     }
 }
 impl HasWindowHandle for MiniCanvas {
     fn window_handle(
         &self,
-    ) -> Result<raw_window_handle::WindowHandle, raw_window_handle::HandleError> {
+    ) -> Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
         self.window.window_handle()
     }
 }
@@ -246,6 +502,18 @@ impl DisplayApi for MiniCanvas {
     fn width(&self) -> u32 {
         self.window.width()
     }
+
+    fn offscreen_target(&self) -> Option<OffscreenTarget> {
+        self.window.offscreen_target()
+    }
+
+    fn transparent(&self) -> bool {
+        self.window.transparent()
+    }
+
+    fn presented(&self) {
+        self.last_present.store(self.now(), Ordering::Relaxed);
+    }
 }
 
 // TODO: instead of Arc, maybe have a global list of windows and ids? That ways it's same as webgpu, but might be harder to handle? Would likely also require a Mutex.
@@ -275,22 +543,30 @@ impl DisplayApi for MiniCanvasArc {
     fn width(&self) -> u32 {
         self.0.width()
     }
+
+    fn offscreen_target(&self) -> Option<OffscreenTarget> {
+        self.0.offscreen_target()
+    }
+
+    fn transparent(&self) -> bool {
+        self.0.transparent()
+    }
+
+    fn presented(&self) {
+        self.0.presented()
+    }
 }
 
 fn unwrap_unless_inactive<T>(res: Result<Option<T>, TrySendError<T>>) {
-    if let Err(e) = &res {
-        if let TrySendError::Inactive(_) = e {
-            return;
-        }
+    if let Err(TrySendError::Inactive(_)) = &res {
+        return;
     }
     res.unwrap();
 }
 
 fn unwrap_unless_inactive_or_full<T>(res: Result<Option<T>, TrySendError<T>>) {
-    if let Err(e) = &res {
-        if matches!(e, TrySendError::Inactive(_) | TrySendError::Full(_)) {
-            return;
-        }
+    if let Err(TrySendError::Inactive(_) | TrySendError::Full(_)) = &res {
+        return;
     }
     res.unwrap();
 }
@@ -310,14 +586,22 @@ impl wasmtime_wasi::Subscribe for ResizeListener {
 }
 
 // wasmtime
-impl mini_canvas::Host for dyn WasiMiniCanvasView + '_ {}
-
 #[async_trait::async_trait]
 impl mini_canvas::HostMiniCanvas for dyn WasiMiniCanvasView + '_ {
-    fn new(&mut self, desc: MiniCanvasDesc) -> Resource<MiniCanvasArc> {
-        let canvas = self.create_canvas(desc);
+    async fn new(&mut self, desc: MiniCanvasDesc) -> wasmtime::Result<Resource<MiniCanvasArc>> {
+        if let Some(quota) = self.canvas_quota() {
+            if let Err(max) = quota.acquire() {
+                return Err(wasmtime::Error::msg(format!(
+                    "canvas quota of {max} open canvases reached"
+                )));
+            }
+        }
+        let mut canvas = self.create_canvas(desc).await;
+        if let Some(clock) = self.wall_clock() {
+            canvas = canvas.with_wall_clock(clock);
+        }
         let mini_canvas = MiniCanvasArc(Arc::new(canvas));
-        self.table().push(mini_canvas).unwrap()
+        Ok(self.table().push(mini_canvas)?)
     }
 
     fn connect_graphics_context(
@@ -355,7 +639,76 @@ impl mini_canvas::HostMiniCanvas for dyn WasiMiniCanvasView + '_ {
         mini_canvas.width()
     }
 
-    fn drop(&mut self, _self_: Resource<MiniCanvasArc>) -> wasmtime::Result<()> {
+    fn current_monitor(&mut self, mini_canvas: Resource<MiniCanvasArc>) -> Option<MonitorInfo> {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.current_monitor()
+    }
+
+    fn set_cursor_grab_mode(
+        &mut self,
+        mini_canvas: Resource<MiniCanvasArc>,
+        mode: CursorGrabMode,
+    ) -> bool {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.set_cursor_grab_mode(mode)
+    }
+
+    fn set_cursor_position(
+        &mut self,
+        mini_canvas: Resource<MiniCanvasArc>,
+        x: f64,
+        y: f64,
+    ) -> bool {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.set_cursor_position(x, y)
+    }
+
+    fn set_fullscreen(
+        &mut self,
+        mini_canvas: Resource<MiniCanvasArc>,
+        mode: FullscreenMode,
+    ) -> bool {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.set_fullscreen(mode)
+    }
+
+    fn set_min_size(&mut self, mini_canvas: Resource<MiniCanvasArc>, size: Option<CanvasSize>) {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.set_min_size(size)
+    }
+
+    fn set_max_size(&mut self, mini_canvas: Resource<MiniCanvasArc>, size: Option<CanvasSize>) {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.set_max_size(size)
+    }
+
+    fn request_redraw(&mut self, mini_canvas: Resource<MiniCanvasArc>) {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.request_redraw()
+    }
+
+    fn file_dropped(&mut self, mini_canvas: Resource<MiniCanvasArc>) -> Resource<Pollable> {
+        let canvas = &self.table().get(&mini_canvas).unwrap().0;
+        let ready = FileDroppedReady {
+            receiver: canvas.file_drop_sender.new_receiver(),
+            dropped: Arc::clone(&canvas.dropped_files),
+        };
+        let ready = self.table().push(ready).unwrap();
+        // Owned by the pollable, which removes it from the table along with itself.
+        wasmtime_wasi::subscribe(self.table(), ready).unwrap()
+    }
+
+    fn get_dropped_files(&mut self, mini_canvas: Resource<MiniCanvasArc>) -> Vec<String> {
+        let mini_canvas = self.table().get(&mini_canvas).unwrap();
+        mini_canvas.0.dropped_files.take()
+    }
+
+    fn drop(&mut self, mini_canvas: Resource<MiniCanvasArc>) -> wasmtime::Result<()> {
+        // Graphics contexts hold their own reference, so this doesn't pull the canvas out from under them.
+        self.table().delete(mini_canvas)?;
+        if let Some(quota) = self.canvas_quota() {
+            quota.release();
+        }
         Ok(())
     }
 }
@@ -0,0 +1,20 @@
+use crate::{
+    wasi::webgpu::mini_canvas::{self, MonitorInfo},
+    WasiMiniCanvasView,
+};
+
+/// Monitor enumeration backend, see [`WasiMiniCanvasView::monitors`].
+#[async_trait::async_trait]
+pub trait Monitors: Send + Sync {
+    async fn available_monitors(&self) -> Vec<MonitorInfo>;
+}
+
+#[async_trait::async_trait]
+impl mini_canvas::Host for dyn WasiMiniCanvasView + '_ {
+    async fn available_monitors(&mut self) -> Vec<MonitorInfo> {
+        match self.monitors() {
+            Some(monitors) => monitors.available_monitors().await,
+            None => Vec::new(),
+        }
+    }
+}
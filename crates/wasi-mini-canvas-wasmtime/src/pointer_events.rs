@@ -1,7 +1,7 @@
 use std::sync::{Arc, Mutex};
 
 use crate::{
-    wasi::webgpu::pointer_events::{self, PointerEvent, Pollable},
+    wasi::webgpu::pointer_events::{self, MouseDelta, PointerEvent, Pollable},
     MiniCanvasArc, WasiMiniCanvasView,
 };
 use async_broadcast::Receiver;
@@ -50,6 +50,20 @@ impl pointer_events::Host for dyn WasiMiniCanvasView + '_ {
             })
             .unwrap()
     }
+
+    fn raw_mouse_delta(
+        &mut self,
+        mini_canvas: Resource<MiniCanvasArc>,
+    ) -> Resource<RawMouseDeltaListener> {
+        let canvas = &self.table().get(&mini_canvas).unwrap().0;
+        let receiver = canvas.raw_mouse_delta_sender.new_receiver();
+        self.table()
+            .push(RawMouseDeltaListener {
+                receiver,
+                data: None,
+            })
+            .unwrap()
+    }
 }
 
 impl pointer_events::HostPointerUpListener for dyn WasiMiniCanvasView + '_ {
@@ -132,3 +146,44 @@ impl wasmtime_wasi::Subscribe for PointerMoveListener {
         *self.data.lock().unwrap() = Some(event);
     }
 }
+
+impl pointer_events::HostRawMouseDeltaListener for dyn WasiMiniCanvasView + '_ {
+    fn subscribe(&mut self, raw_delta: Resource<RawMouseDeltaListener>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.table(), raw_delta).unwrap()
+    }
+    fn get(&mut self, raw_delta: Resource<RawMouseDeltaListener>) -> Option<MouseDelta> {
+        let raw_delta = self.table().get_mut(&raw_delta).unwrap();
+        while let Ok(delta) = raw_delta.receiver.try_recv() {
+            raw_delta.add(delta);
+        }
+        raw_delta.data.take()
+    }
+    fn drop(&mut self, _self_: Resource<RawMouseDeltaListener>) -> wasmtime::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RawMouseDeltaListener {
+    receiver: Receiver<MouseDelta>,
+    data: Option<MouseDelta>,
+}
+
+impl RawMouseDeltaListener {
+    fn add(&mut self, delta: MouseDelta) {
+        let sum = self.data.get_or_insert(MouseDelta { x: 0.0, y: 0.0 });
+        sum.x += delta.x;
+        sum.y += delta.y;
+    }
+}
+
+#[async_trait::async_trait]
+impl wasmtime_wasi::Subscribe for RawMouseDeltaListener {
+    async fn ready(&mut self) {
+        if self.data.is_some() {
+            return;
+        }
+        let delta = self.receiver.recv().await.unwrap();
+        self.add(delta);
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::{Arc, Mutex};
+
+/// Caps how many canvases a guest can have open at once.
+///
+/// Constructing a canvas past the limit traps, since canvases have no way to report errors to the guest. Dropping a
+/// canvas frees its slot. Give each store its own quota, handles clone the same count.
+#[derive(Clone, Default)]
+pub struct CanvasQuota(Arc<Mutex<CanvasQuotaState>>);
+
+#[derive(Default)]
+struct CanvasQuotaState {
+    max_canvases: Option<usize>,
+    live: usize,
+}
+
+impl CanvasQuota {
+    /// `None` leaves the count unlimited, while still keeping track of it.
+    pub fn new(max_canvases: Option<usize>) -> Self {
+        let quota = Self::default();
+        quota.set_max_canvases(max_canvases);
+        quota
+    }
+
+    pub fn max_canvases(&self) -> Option<usize> {
+        self.0.lock().unwrap().max_canvases
+    }
+
+    /// Lowering the limit below the canvases already open doesn't close any, it only fails further ones.
+    pub fn set_max_canvases(&self, max_canvases: Option<usize>) {
+        self.0.lock().unwrap().max_canvases = max_canvases;
+    }
+
+    /// Canvases the guest currently has open.
+    pub fn live(&self) -> usize {
+        self.0.lock().unwrap().live
+    }
+
+    pub(crate) fn acquire(&self) -> Result<(), usize> {
+        let mut state = self.0.lock().unwrap();
+        match state.max_canvases {
+            Some(max) if state.live >= max => Err(max),
+            _ => {
+                state.live += 1;
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn release(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.live = state.live.saturating_sub(1);
+    }
+}
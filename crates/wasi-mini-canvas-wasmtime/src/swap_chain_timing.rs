@@ -0,0 +1,89 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{
+    wasi::webgpu::swap_chain_timing::{self, RefreshRate},
+    MiniCanvasArc, WasiMiniCanvasView,
+};
+use wasmtime::component::Resource;
+use wasmtime_wasi::HostWallClock;
+
+/// Used when the monitor doesn't report a refresh rate.
+const DEFAULT_REFRESH_RATE: RefreshRate = 60_000;
+
+/// Canvases time presents with this, unless the host gives them a clock, see [`crate::MiniCanvas::with_wall_clock`].
+pub(crate) struct SystemClock;
+
+impl HostWallClock for SystemClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+impl swap_chain_timing::Host for dyn WasiMiniCanvasView + '_ {
+    fn get_refresh_rate(&mut self, canvas: Resource<MiniCanvasArc>) -> RefreshRate {
+        let canvas = &self.table().get(&canvas).unwrap().0;
+        refresh_rate(canvas)
+    }
+
+    fn get_last_present_time(&mut self, canvas: Resource<MiniCanvasArc>) -> u64 {
+        let canvas = &self.table().get(&canvas).unwrap().0;
+        canvas.last_present_time()
+    }
+
+    fn next_frame_target_time(&mut self, canvas: Resource<MiniCanvasArc>) -> u64 {
+        let canvas = &self.table().get(&canvas).unwrap().0;
+        let interval = 1_000_000_000_000 / refresh_rate(canvas) as u64;
+        next_target(canvas.last_present_time(), interval, canvas.now())
+    }
+}
+
+fn refresh_rate(canvas: &crate::MiniCanvas) -> RefreshRate {
+    canvas
+        .current_monitor()
+        .and_then(|monitor| monitor.refresh_rate_millihertz)
+        .filter(|&millihertz| millihertz > 0)
+        .unwrap_or(DEFAULT_REFRESH_RATE)
+}
+
+/// First whole interval after `last_present` that's still ahead of `now`.
+fn next_target(last_present: u64, interval: u64, now: u64) -> u64 {
+    if last_present == 0 {
+        return now + interval;
+    }
+    let next = last_present + interval;
+    if next > now {
+        return next;
+    }
+    let missed = (now - next) / interval + 1;
+    next + missed * interval
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_first_target_is_an_interval_from_now() {
+        assert_eq!(next_target(0, 10, 105), 115);
+    }
+
+    #[test]
+    fn targets_are_an_interval_after_the_last_present() {
+        assert_eq!(next_target(100, 10, 100), 110);
+        assert_eq!(next_target(100, 10, 109), 110);
+    }
+
+    #[test]
+    fn missed_intervals_are_skipped() {
+        // Right on the target counts as missed, it's too late to present for it.
+        assert_eq!(next_target(100, 10, 110), 120);
+        assert_eq!(next_target(100, 10, 111), 120);
+        assert_eq!(next_target(100, 10, 135), 140);
+    }
+}
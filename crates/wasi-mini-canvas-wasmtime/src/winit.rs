@@ -1,31 +1,63 @@
 use std::{
     any::Any,
+    cell::RefCell,
     collections::HashMap,
     fmt::Debug,
+    path::PathBuf,
     sync::{Arc, Mutex},
     thread::{self, sleep},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use crate::{MiniCanvas, MiniCanvasDesc, MiniCanvasProxy};
+use crate::{
+    CanvasSize, Clipboard, ClipboardError, CursorGrabMode, FileDrop, FrameEvent, FullscreenMode,
+    MiniCanvas, MiniCanvasDesc, MiniCanvasProxy, MonitorInfo, Monitors, VideoMode,
+};
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
 };
 use wasi_graphics_context_wasmtime::DisplayApi;
 use winit::{
     application::ApplicationHandler,
-    dpi::Size,
-    event::{ElementState, WindowEvent},
-    event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy},
+    dpi::{PhysicalPosition, Size},
+    event::{DeviceEvent, DeviceId, ElementState, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
     keyboard::ModifiersState,
-    window::{Window, WindowAttributes, WindowId},
+    monitor::{MonitorHandle, VideoModeHandle},
+    window::{self as winit_window, Fullscreen, Window, WindowAttributes, WindowId},
 };
 
+/// Used when the monitor doesn't report a refresh rate.
+const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// Frame interval while a window is minimized or occluded, so guest logic still advances.
+const HIDDEN_FRAME_INTERVAL: Duration = Duration::from_millis(250);
+
+struct FrameTimer {
+    proxy: MiniCanvasProxy,
+    interval: Duration,
+    hidden: bool,
+    next_frame: Instant,
+    /// Whether the canvas asked for a frame, or got input to react to, since its last frame.
+    requested: bool,
+}
+
+impl FrameTimer {
+    fn current_interval(&self) -> Duration {
+        if self.hidden {
+            HIDDEN_FRAME_INTERVAL
+        } else {
+            self.interval
+        }
+    }
+}
+
 pub fn create_wasi_winit_event_loop() -> (WasiWinitEventLoop, WasiWinitEventLoopProxy) {
     let event_loop = WasiWinitEventLoop {
         event_loop: winit::event_loop::EventLoop::<MainThreadAction>::with_user_event()
             .build()
             .unwrap(),
+        reuse_windows: false,
+        continuous_frames: false,
     };
     let message_sender = WasiWinitEventLoopProxy {
         proxy: event_loop.event_loop.create_proxy(),
@@ -35,25 +67,74 @@ pub fn create_wasi_winit_event_loop() -> (WasiWinitEventLoop, WasiWinitEventLoop
 
 pub struct WasiWinitEventLoop {
     event_loop: EventLoop<MainThreadAction>,
+    reuse_windows: bool,
+    continuous_frames: bool,
 }
 
 impl WasiWinitEventLoop {
+    /// Keep the windows of dropped canvases open, and hand them to the next canvases created instead of opening new ones.
+    /// Lets hosts restart a guest without its window closing and reopening, e.g. when reloading it.
+    ///
+    /// A reused window keeps its size and position, only its title is taken from the new canvas.
+    pub fn reuse_windows(mut self, reuse_windows: bool) -> Self {
+        self.reuse_windows = reuse_windows;
+        self
+    }
+
+    /// Send every canvas animation frames at the display's rate, whether it asked for them or not. For guests that
+    /// predate `request-redraw` and expect frames to keep coming.
+    ///
+    /// By default canvases only get a frame when they're created, when they call `request-redraw`, and when input or a
+    /// resize arrives, so static guests don't render for nothing.
+    pub fn continuous_frames(mut self, continuous_frames: bool) -> Self {
+        self.continuous_frames = continuous_frames;
+        self
+    }
+
     /// This has to be run on the main thread.
-    /// This call will block the thread.
+    /// This call will block the thread until [`WasiWinitEventLoopProxy::shutdown`] is called.
     pub fn run(self) {
-        let proxies: Arc<Mutex<HashMap<WindowId, MiniCanvasProxy>>> = Default::default();
+        let frame_timers: Arc<Mutex<HashMap<WindowId, FrameTimer>>> = Default::default();
 
         {
-            let proxies = Arc::clone(&proxies);
+            let frame_timers = Arc::clone(&frame_timers);
+            let start = Instant::now();
+            let continuous_frames = self.continuous_frames;
+            let proxy = self.event_loop.create_proxy();
             thread::spawn(move || loop {
-                for (_, proxy) in proxies.lock().unwrap().iter() {
-                    proxy.animation_frame();
+                let now = Instant::now();
+                let mut next_wake = now + DEFAULT_FRAME_INTERVAL;
+                let mut frame_timers = frame_timers.lock().unwrap();
+                let timer_count = frame_timers.len();
+                frame_timers.retain(|_, timer| !timer.proxy.is_closed());
+                if frame_timers.len() < timer_count {
+                    // Fails once the loop shut down, when there are no windows left to close.
+                    let _ = proxy.send_event(MainThreadAction::CanvasDropped);
+                }
+                for (_, timer) in frame_timers.iter_mut() {
+                    if !(timer.requested || continuous_frames) {
+                        continue;
+                    }
+                    if timer.next_frame <= now {
+                        timer.proxy.animation_frame(FrameEvent {
+                            timestamp: (now - start).as_nanos() as u64,
+                        });
+                        timer.requested = false;
+                        timer.next_frame = if continuous_frames {
+                            (timer.next_frame + timer.current_interval()).max(now)
+                        } else {
+                            // Frames after idling are still an interval apart.
+                            now + timer.current_interval()
+                        };
+                    }
+                    next_wake = next_wake.min(timer.next_frame);
                 }
-                sleep(Duration::from_millis(16));
+                drop(frame_timers);
+                sleep(next_wake.saturating_duration_since(Instant::now()));
             });
         }
 
-        struct MyWindow(pub Window);
+        struct MyWindow(pub Arc<Window>, bool);
         impl HasDisplayHandle for MyWindow {
             fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
                 self.0.display_handle()
@@ -72,6 +153,10 @@ impl WasiWinitEventLoop {
             fn width(&self) -> u32 {
                 self.0.inner_size().width
             }
+
+            fn transparent(&self) -> bool {
+                self.1
+            }
         }
 
         #[derive(Default)]
@@ -79,7 +164,71 @@ impl WasiWinitEventLoop {
             pointer_pos: HashMap<WindowId, (f64, f64)>,
             modifiers: HashMap<WindowId, ModifiersState>,
             proxies: HashMap<WindowId, MiniCanvasProxy>,
-            arc_proxies: Arc<Mutex<HashMap<WindowId, MiniCanvasProxy>>>,
+            // Along with whether the window was created transparent.
+            windows: HashMap<WindowId, (Arc<Window>, bool)>,
+            reuse_windows: bool,
+            // Windows of dropped canvases, kept open when reusing windows.
+            spare_windows: Vec<(Arc<Window>, bool)>,
+            frame_timers: Arc<Mutex<HashMap<WindowId, FrameTimer>>>,
+            // winit reports files one at a time, these are flushed as one event in about_to_wait.
+            hovered_files: HashMap<WindowId, Vec<PathBuf>>,
+            dropped_files: HashMap<WindowId, Vec<PathBuf>>,
+            // Raw mouse motion isn't tied to a window, it goes to the focused one.
+            focused: Option<WindowId>,
+        }
+
+        impl App {
+            /// Closes the windows of canvases the guest dropped, e.g. after its store went away.
+            /// When reusing windows, they're kept as spares instead.
+            fn close_dropped_canvases(&mut self) {
+                let closed: Vec<WindowId> = self
+                    .proxies
+                    .iter()
+                    .filter(|(_, proxy)| proxy.is_closed())
+                    .map(|(window_id, _)| *window_id)
+                    .collect();
+                for window_id in closed {
+                    self.proxies.remove(&window_id);
+                    self.frame_timers.lock().unwrap().remove(&window_id);
+                    let window = self.windows.remove(&window_id);
+                    match window {
+                        // Spare windows still get events, so their pointer and modifier state is kept.
+                        Some(window) if self.reuse_windows => {
+                            set_cursor_grab(&window.0, CursorGrabMode::None);
+                            window.0.set_fullscreen(None);
+                            window.0.set_min_inner_size(None::<Size>);
+                            window.0.set_max_inner_size(None::<Size>);
+                            self.spare_windows.push(window);
+                        }
+                        _ => {
+                            self.pointer_pos.remove(&window_id);
+                            self.modifiers.remove(&window_id);
+                        }
+                    }
+                }
+            }
+
+            fn take_spare_window(&mut self, transparent: bool) -> Option<Arc<Window>> {
+                let index = self
+                    .spare_windows
+                    .iter()
+                    .position(|(_, spare_transparent)| *spare_transparent == transparent)?;
+                Some(self.spare_windows.swap_remove(index).0)
+            }
+
+            /// Sends the canvas a frame once its frame interval allows, even if it renders on demand.
+            fn request_frame(&mut self, window_id: WindowId) {
+                if let Some(timer) = self.frame_timers.lock().unwrap().get_mut(&window_id) {
+                    timer.requested = true;
+                }
+            }
+
+            fn set_hidden(&mut self, window_id: WindowId, hidden: bool) {
+                if let Some(timer) = self.frame_timers.lock().unwrap().get_mut(&window_id) {
+                    timer.hidden = hidden;
+                    timer.next_frame = Instant::now() + timer.current_interval();
+                }
+            }
         }
 
         impl ApplicationHandler<MainThreadAction> for App {
@@ -90,26 +239,115 @@ impl WasiWinitEventLoop {
             fn user_event(&mut self, event_loop: &ActiveEventLoop, event: MainThreadAction) {
                 match event {
                     MainThreadAction::CreateWindow(desc, response_channel) => {
-                        let window_options = WindowAttributes::default().with_inner_size(
-                            Size::Logical((desc.width as f64, desc.height as f64).into()),
-                        );
-                        let window = event_loop.create_window(window_options).unwrap();
-                        // TODO: remove when window is drooped.
+                        self.close_dropped_canvases();
+                        // Transparency can't be changed after the window is created.
+                        let transparent = desc.transparent == Some(true);
+                        let window = match self.take_spare_window(transparent) {
+                            Some(window) => {
+                                if let Some(title) = &desc.title {
+                                    window.set_title(title);
+                                }
+                                if desc.fullscreen == Some(true) {
+                                    window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+                                }
+                                window
+                            }
+                            None => {
+                                Arc::new(event_loop.create_window(window_attributes(desc)).unwrap())
+                            }
+                        };
                         self.pointer_pos.insert(window.id(), (0.0, 0.0));
                         let window_id = window.id();
+                        let frame_interval = window
+                            .current_monitor()
+                            .and_then(|monitor| monitor.refresh_rate_millihertz())
+                            .map(|millihertz| Duration::from_secs_f64(1000.0 / millihertz as f64))
+                            .unwrap_or(DEFAULT_FRAME_INTERVAL);
 
-                        let canvas = MiniCanvas::new(Box::new(MyWindow(window)));
+                        self.windows
+                            .insert(window_id, (Arc::clone(&window), transparent));
+                        let canvas =
+                            MiniCanvas::new(Box::new(MyWindow(Arc::clone(&window), transparent)))
+                                .with_current_monitor({
+                                    let window = Arc::clone(&window);
+                                    move || {
+                                        window
+                                            .current_monitor()
+                                            .map(|monitor| monitor_info(&monitor))
+                                    }
+                                })
+                                .with_cursor_grab({
+                                    let window = Arc::clone(&window);
+                                    move |mode| set_cursor_grab(&window, mode)
+                                })
+                                .with_cursor_position({
+                                    let window = Arc::clone(&window);
+                                    // Physical, like the positions of pointer events. Fails on e.g. Wayland and iOS.
+                                    move |x, y| {
+                                        window
+                                            .set_cursor_position(PhysicalPosition::new(x, y))
+                                            .is_ok()
+                                    }
+                                })
+                                .with_fullscreen({
+                                    let window = Arc::clone(&window);
+                                    move |mode| set_fullscreen(&window, mode)
+                                })
+                                .with_request_redraw({
+                                    let window = Arc::clone(&window);
+                                    move || window.request_redraw()
+                                })
+                                .with_size_limits(
+                                    {
+                                        let window = Arc::clone(&window);
+                                        move |size| {
+                                            window.set_min_inner_size(size.map(logical_size))
+                                        }
+                                    },
+                                    move |size| window.set_max_inner_size(size.map(logical_size)),
+                                );
 
                         self.proxies.insert(window_id, canvas.proxy());
-                        self.arc_proxies
-                            .lock()
-                            .unwrap()
-                            .insert(window_id, canvas.proxy());
+                        self.frame_timers.lock().unwrap().insert(
+                            window_id,
+                            FrameTimer {
+                                proxy: canvas.proxy(),
+                                interval: frame_interval,
+                                hidden: false,
+                                next_frame: Instant::now(),
+                                // The first frame draws the canvas' initial contents.
+                                requested: true,
+                            },
+                        );
 
-                        response_channel.send(canvas).unwrap();
+                        // The guest may have been torn down while waiting for its canvas, e.g. when it's reloaded.
+                        if response_channel.send(canvas).is_err() {
+                            self.close_dropped_canvases();
+                        }
                     }
                     MainThreadAction::Spawn(f, res) => {
-                        res.send(f()).unwrap();
+                        // Nobody waiting for the result anymore is fine, the guest was torn down.
+                        let _ = res.send(f());
+                    }
+                    MainThreadAction::AvailableMonitors(res) => {
+                        let monitors = event_loop
+                            .available_monitors()
+                            .map(|monitor| monitor_info(&monitor))
+                            .collect();
+                        let _ = res.send(monitors);
+                    }
+                    MainThreadAction::CanvasDropped => {
+                        self.close_dropped_canvases();
+                    }
+                    MainThreadAction::Shutdown => {
+                        // Canvases the guest still holds keep their window alive, so they're hidden instead.
+                        for (_, (window, _)) in self.windows.drain() {
+                            window.set_visible(false);
+                        }
+                        self.spare_windows.clear();
+                        self.frame_timers.lock().unwrap().clear();
+                        self.proxies.clear();
+                        event_loop.exit();
                     }
                 }
             }
@@ -120,6 +358,20 @@ impl WasiWinitEventLoop {
                 window_id: WindowId,
                 event: WindowEvent,
             ) {
+                // Guests rendering on demand get a frame to show what the input changed.
+                if matches!(
+                    event,
+                    WindowEvent::RedrawRequested
+                        | WindowEvent::Resized(_)
+                        | WindowEvent::CursorMoved { .. }
+                        | WindowEvent::MouseInput { .. }
+                        | WindowEvent::KeyboardInput { .. }
+                        | WindowEvent::HoveredFile(_)
+                        | WindowEvent::DroppedFile(_)
+                        | WindowEvent::HoveredFileCancelled
+                ) {
+                    self.request_frame(window_id);
+                }
                 match event {
                     WindowEvent::CursorMoved { position, .. } => {
                         self.pointer_pos
@@ -179,7 +431,31 @@ impl WasiWinitEventLoop {
                             }
                         }
                     }
+                    WindowEvent::HoveredFile(path) => {
+                        self.hovered_files.entry(window_id).or_default().push(path);
+                    }
+                    WindowEvent::DroppedFile(path) => {
+                        self.dropped_files.entry(window_id).or_default().push(path);
+                    }
+                    WindowEvent::HoveredFileCancelled => {
+                        self.hovered_files.remove(&window_id);
+                        if let Some(proxy) = self.proxies.get(&window_id) {
+                            proxy.file_drop(FileDrop::HoverCancelled);
+                        }
+                    }
+                    WindowEvent::Focused(focused) => {
+                        if focused {
+                            self.focused = Some(window_id);
+                        } else if self.focused == Some(window_id) {
+                            self.focused = None;
+                        }
+                    }
+                    WindowEvent::Occluded(occluded) => {
+                        self.set_hidden(window_id, occluded);
+                    }
                     WindowEvent::Resized(new_size) => {
+                        // Some platforms report minimizing as a resize to 0x0.
+                        self.set_hidden(window_id, new_size.width == 0 || new_size.height == 0);
                         if let Some(proxy) = self.proxies.get(&window_id) {
                             proxy.canvas_resize(crate::ResizeEvent {
                                 height: new_size.height,
@@ -190,29 +466,149 @@ impl WasiWinitEventLoop {
                     _ => {}
                 }
             }
+
+            fn device_event(
+                &mut self,
+                _event_loop: &ActiveEventLoop,
+                _device_id: DeviceId,
+                event: DeviceEvent,
+            ) {
+                if let DeviceEvent::MouseMotion { delta: (x, y) } = event {
+                    let Some(window_id) = self.focused else {
+                        return;
+                    };
+                    if let Some(proxy) = self.proxies.get(&window_id) {
+                        proxy.raw_mouse_delta(crate::MouseDelta { x, y });
+                    }
+                    self.request_frame(window_id);
+                }
+            }
+
+            fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+                self.close_dropped_canvases();
+                for (window_id, paths) in self.hovered_files.drain() {
+                    if let Some(proxy) = self.proxies.get(&window_id) {
+                        proxy.file_drop(FileDrop::Hovered(paths));
+                    }
+                }
+                for (window_id, paths) in self.dropped_files.drain() {
+                    if let Some(proxy) = self.proxies.get(&window_id) {
+                        proxy.file_drop(FileDrop::Dropped(paths));
+                    }
+                }
+            }
         }
 
-        let mut app = App::default();
-        app.arc_proxies = Arc::clone(&proxies);
+        let mut app = App {
+            frame_timers: Arc::clone(&frame_timers),
+            reuse_windows: self.reuse_windows,
+            ..Default::default()
+        };
+        // Nothing is drawn from the event loop itself, frames go out from the timer thread when canvases want them.
+        self.event_loop.set_control_flow(ControlFlow::Wait);
         self.event_loop.run_app(&mut app).unwrap();
     }
 }
 
+fn set_cursor_grab(window: &Window, mode: CursorGrabMode) -> bool {
+    let grab_mode = match mode {
+        CursorGrabMode::None => winit_window::CursorGrabMode::None,
+        CursorGrabMode::Confined => winit_window::CursorGrabMode::Confined,
+        CursorGrabMode::Locked => winit_window::CursorGrabMode::Locked,
+    };
+    if window.set_cursor_grab(grab_mode).is_err() {
+        return false;
+    }
+    window.set_cursor_visible(mode != CursorGrabMode::Locked);
+    true
+}
+
+fn set_fullscreen(window: &Window, mode: FullscreenMode) -> bool {
+    let fullscreen = match mode {
+        FullscreenMode::Windowed => None,
+        FullscreenMode::Borderless => Some(Fullscreen::Borderless(window.current_monitor())),
+        FullscreenMode::Exclusive(mode) => {
+            // The monitor the window is on goes first, so it doesn't jump to another monitor with the same mode.
+            let video_mode = window
+                .current_monitor()
+                .into_iter()
+                .chain(window.available_monitors())
+                .flat_map(|monitor| monitor.video_modes())
+                .find(|candidate| {
+                    let candidate = video_mode(candidate);
+                    candidate.width == mode.width
+                        && candidate.height == mode.height
+                        && candidate.bit_depth == mode.bit_depth
+                        && candidate.refresh_rate_millihertz == mode.refresh_rate_millihertz
+                });
+            match video_mode {
+                Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                None => return false,
+            }
+        }
+    };
+    window.set_fullscreen(fullscreen);
+    true
+}
+
+/// Canvas sizes are logical, like the size canvases are created with.
+fn logical_size(size: CanvasSize) -> Size {
+    Size::Logical((size.width as f64, size.height as f64).into())
+}
+
+fn window_attributes(desc: MiniCanvasDesc) -> WindowAttributes {
+    let mut window_options = WindowAttributes::default().with_inner_size(Size::Logical(
+        (desc.width as f64, desc.height as f64).into(),
+    ));
+    if let Some(title) = desc.title {
+        window_options = window_options.with_title(title);
+    }
+    if let Some(resizable) = desc.resizable {
+        window_options = window_options.with_resizable(resizable);
+    }
+    if let Some(decorations) = desc.decorations {
+        window_options = window_options.with_decorations(decorations);
+    }
+    if desc.fullscreen == Some(true) {
+        window_options = window_options.with_fullscreen(Some(Fullscreen::Borderless(None)));
+    }
+    if desc.transparent == Some(true) {
+        window_options = window_options.with_transparent(true);
+    }
+    window_options
+}
+
+/// Returned by [`WasiWinitEventLoopProxy`] calls made after the event loop was shut down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventLoopStopped;
+
+impl std::fmt::Display for EventLoopStopped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the winit event loop was shut down")
+    }
+}
+
+impl std::error::Error for EventLoopStopped {}
+
 #[derive(Clone)]
 pub struct WasiWinitEventLoopProxy {
     proxy: EventLoopProxy<MainThreadAction>,
 }
 
 impl WasiWinitEventLoopProxy {
-    pub async fn create_window(&self, desc: MiniCanvasDesc) -> MiniCanvas {
+    pub async fn create_window(
+        &self,
+        desc: MiniCanvasDesc,
+    ) -> Result<MiniCanvas, EventLoopStopped> {
         let (sender, receiver) = oneshot::channel();
         self.proxy
             .send_event(MainThreadAction::CreateWindow(desc, sender))
-            .unwrap();
-        receiver.await.unwrap()
+            .map_err(|_| EventLoopStopped)?;
+        receiver.await.map_err(|_| EventLoopStopped)
     }
 
-    pub async fn spawn<F, T>(&self, f: F) -> T
+    /// Runs `f` on the main thread. Fails if the event loop shut down before `f` got to run.
+    pub async fn spawn<F, T>(&self, f: F) -> Result<T, EventLoopStopped>
     where
         F: FnOnce() -> T + Send + Sync + 'static,
         T: Send + Sync + 'static,
@@ -224,17 +620,113 @@ impl WasiWinitEventLoopProxy {
         let (sender, receiver) = oneshot::channel();
         self.proxy
             .send_event(MainThreadAction::Spawn(boxed, sender))
-            .unwrap();
-        *receiver.await.unwrap().downcast().unwrap()
+            .map_err(|_| EventLoopStopped)?;
+        // Actions still queued when the loop exits are dropped along with their sender.
+        let res = receiver.await.map_err(|_| EventLoopStopped)?;
+        Ok(*res.downcast().unwrap())
+    }
+
+    /// Hides the remaining windows and makes [`WasiWinitEventLoop::run`] return, after running the
+    /// closures spawned before this call. Calls made after it fail with [`EventLoopStopped`].
+    /// Does nothing if the loop already shut down.
+    pub fn shutdown(&self) {
+        let _ = self.proxy.send_event(MainThreadAction::Shutdown);
+    }
+}
+
+thread_local! {
+    // Kept alive for the lifetime of the main thread, since on some platforms the
+    // clipboard contents are lost when the owning `arboard::Clipboard` is dropped.
+    static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+}
+
+fn with_clipboard<T>(
+    f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+) -> Result<T, arboard::Error> {
+    CLIPBOARD.with_borrow_mut(|clipboard| {
+        if clipboard.is_none() {
+            *clipboard = Some(arboard::Clipboard::new()?);
+        }
+        f(clipboard.as_mut().unwrap())
+    })
+}
+
+/// Clipboard access has to happen on the main thread on many platforms.
+#[async_trait::async_trait]
+impl Clipboard for WasiWinitEventLoopProxy {
+    async fn read_text(&self) -> Result<Option<String>, ClipboardError> {
+        self.spawn(|| match with_clipboard(|clipboard| clipboard.get_text()) {
+            Ok(text) => Ok(Some(text)),
+            Err(arboard::Error::ContentNotAvailable) => Ok(None),
+            Err(_) => Err(ClipboardError::Unavailable),
+        })
+        .await
+        .unwrap_or(Err(ClipboardError::Unavailable))
+    }
+
+    async fn write_text(&self, text: String) -> Result<(), ClipboardError> {
+        self.spawn(|| {
+            with_clipboard(|clipboard| clipboard.set_text(text))
+                .map_err(|_| ClipboardError::Unavailable)
+        })
+        .await
+        .unwrap_or(Err(ClipboardError::Unavailable))
+    }
+}
+
+fn monitor_info(monitor: &MonitorHandle) -> MonitorInfo {
+    let size = monitor.size();
+    let position = monitor.position();
+    MonitorInfo {
+        name: monitor.name(),
+        width: size.width,
+        height: size.height,
+        x: position.x,
+        y: position.y,
+        scale_factor: monitor.scale_factor(),
+        refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+        video_modes: monitor
+            .video_modes()
+            .map(|mode| video_mode(&mode))
+            .collect(),
+    }
+}
+
+fn video_mode(mode: &VideoModeHandle) -> VideoMode {
+    let size = mode.size();
+    VideoMode {
+        width: size.width,
+        height: size.height,
+        bit_depth: mode.bit_depth(),
+        refresh_rate_millihertz: mode.refresh_rate_millihertz(),
+    }
+}
+
+#[async_trait::async_trait]
+impl Monitors for WasiWinitEventLoopProxy {
+    async fn available_monitors(&self) -> Vec<MonitorInfo> {
+        let (sender, receiver) = oneshot::channel();
+        if self
+            .proxy
+            .send_event(MainThreadAction::AvailableMonitors(sender))
+            .is_err()
+        {
+            return Vec::new();
+        }
+        receiver.await.unwrap_or_default()
     }
 }
 
 enum MainThreadAction {
     CreateWindow(MiniCanvasDesc, oneshot::Sender<MiniCanvas>),
+    AvailableMonitors(oneshot::Sender<Vec<MonitorInfo>>),
     Spawn(
         Box<dyn FnOnce() -> Box<dyn Any + Send + Sync> + Send + Sync>,
         oneshot::Sender<Box<dyn Any + Send + Sync>>,
     ),
+    /// Sent when a canvas was dropped, so its window closes without waiting for other events to wake the loop.
+    CanvasDropped,
+    Shutdown,
 }
 
 impl Debug for MainThreadAction {
@@ -245,7 +737,12 @@ impl Debug for MainThreadAction {
                 .field(arg0)
                 .field(arg1)
                 .finish(),
+            Self::AvailableMonitors(arg0) => {
+                f.debug_tuple("AvailableMonitors").field(arg0).finish()
+            }
             Self::Spawn(_, _) => f.debug_tuple("Spawn").finish(),
+            Self::CanvasDropped => f.debug_tuple("CanvasDropped").finish(),
+            Self::Shutdown => f.debug_tuple("Shutdown").finish(),
         }
     }
 }
@@ -4,80 +4,213 @@ impl From<wgpu_types::TextureFormat> for webgpu::GpuTextureFormat {
     fn from(value: wgpu_types::TextureFormat) -> Self {
         match value {
             wgpu_types::TextureFormat::Bgra8UnormSrgb => webgpu::GpuTextureFormat::Bgra8unormSrgb,
-            wgpu_types::TextureFormat::R8Unorm => todo!(),
-            wgpu_types::TextureFormat::R8Snorm => todo!(),
-            wgpu_types::TextureFormat::R8Uint => todo!(),
-            wgpu_types::TextureFormat::R8Sint => todo!(),
-            wgpu_types::TextureFormat::R16Uint => todo!(),
-            wgpu_types::TextureFormat::R16Sint => todo!(),
-            wgpu_types::TextureFormat::R16Unorm => todo!(),
-            wgpu_types::TextureFormat::R16Snorm => todo!(),
-            wgpu_types::TextureFormat::R16Float => todo!(),
-            wgpu_types::TextureFormat::Rg8Unorm => todo!(),
-            wgpu_types::TextureFormat::Rg8Snorm => todo!(),
-            wgpu_types::TextureFormat::Rg8Uint => todo!(),
-            wgpu_types::TextureFormat::Rg8Sint => todo!(),
-            wgpu_types::TextureFormat::R32Uint => todo!(),
-            wgpu_types::TextureFormat::R32Sint => todo!(),
-            wgpu_types::TextureFormat::R32Float => todo!(),
-            wgpu_types::TextureFormat::Rg16Uint => todo!(),
-            wgpu_types::TextureFormat::Rg16Sint => todo!(),
-            wgpu_types::TextureFormat::Rg16Unorm => todo!(),
-            wgpu_types::TextureFormat::Rg16Snorm => todo!(),
-            wgpu_types::TextureFormat::Rg16Float => todo!(),
-            wgpu_types::TextureFormat::Rgba8Unorm => todo!(),
-            wgpu_types::TextureFormat::Rgba8UnormSrgb => todo!(),
-            wgpu_types::TextureFormat::Rgba8Snorm => todo!(),
-            wgpu_types::TextureFormat::Rgba8Uint => todo!(),
-            wgpu_types::TextureFormat::Rgba8Sint => todo!(),
-            wgpu_types::TextureFormat::Bgra8Unorm => todo!(),
-            wgpu_types::TextureFormat::Rgb9e5Ufloat => todo!(),
-            wgpu_types::TextureFormat::Rgb10a2Uint => todo!(),
-            wgpu_types::TextureFormat::Rgb10a2Unorm => todo!(),
-            wgpu_types::TextureFormat::Rg11b10Float => todo!(),
-            wgpu_types::TextureFormat::Rg32Uint => todo!(),
-            wgpu_types::TextureFormat::Rg32Sint => todo!(),
-            wgpu_types::TextureFormat::Rg32Float => todo!(),
-            wgpu_types::TextureFormat::Rgba16Uint => todo!(),
-            wgpu_types::TextureFormat::Rgba16Sint => todo!(),
-            wgpu_types::TextureFormat::Rgba16Unorm => todo!(),
-            wgpu_types::TextureFormat::Rgba16Snorm => todo!(),
-            wgpu_types::TextureFormat::Rgba16Float => todo!(),
-            wgpu_types::TextureFormat::Rgba32Uint => todo!(),
-            wgpu_types::TextureFormat::Rgba32Sint => todo!(),
-            wgpu_types::TextureFormat::Rgba32Float => todo!(),
-            wgpu_types::TextureFormat::Stencil8 => todo!(),
-            wgpu_types::TextureFormat::Depth16Unorm => todo!(),
-            wgpu_types::TextureFormat::Depth24Plus => todo!(),
-            wgpu_types::TextureFormat::Depth24PlusStencil8 => todo!(),
-            wgpu_types::TextureFormat::Depth32Float => todo!(),
-            wgpu_types::TextureFormat::Depth32FloatStencil8 => todo!(),
-            wgpu_types::TextureFormat::Bc1RgbaUnorm => todo!(),
-            wgpu_types::TextureFormat::Bc1RgbaUnormSrgb => todo!(),
-            wgpu_types::TextureFormat::Bc2RgbaUnorm => todo!(),
-            wgpu_types::TextureFormat::Bc2RgbaUnormSrgb => todo!(),
-            wgpu_types::TextureFormat::Bc3RgbaUnorm => todo!(),
-            wgpu_types::TextureFormat::Bc3RgbaUnormSrgb => todo!(),
-            wgpu_types::TextureFormat::Bc4RUnorm => todo!(),
-            wgpu_types::TextureFormat::Bc4RSnorm => todo!(),
-            wgpu_types::TextureFormat::Bc5RgUnorm => todo!(),
-            wgpu_types::TextureFormat::Bc5RgSnorm => todo!(),
-            wgpu_types::TextureFormat::Bc6hRgbUfloat => todo!(),
-            wgpu_types::TextureFormat::Bc6hRgbFloat => todo!(),
-            wgpu_types::TextureFormat::Bc7RgbaUnorm => todo!(),
-            wgpu_types::TextureFormat::Bc7RgbaUnormSrgb => todo!(),
-            wgpu_types::TextureFormat::Etc2Rgb8Unorm => todo!(),
-            wgpu_types::TextureFormat::Etc2Rgb8UnormSrgb => todo!(),
-            wgpu_types::TextureFormat::Etc2Rgb8A1Unorm => todo!(),
-            wgpu_types::TextureFormat::Etc2Rgb8A1UnormSrgb => todo!(),
-            wgpu_types::TextureFormat::Etc2Rgba8Unorm => todo!(),
-            wgpu_types::TextureFormat::Etc2Rgba8UnormSrgb => todo!(),
-            wgpu_types::TextureFormat::EacR11Unorm => todo!(),
-            wgpu_types::TextureFormat::EacR11Snorm => todo!(),
-            wgpu_types::TextureFormat::EacRg11Unorm => todo!(),
-            wgpu_types::TextureFormat::EacRg11Snorm => todo!(),
-            wgpu_types::TextureFormat::Astc { .. } => todo!(),
-            wgpu_types::TextureFormat::NV12 => todo!(),
+            wgpu_types::TextureFormat::R8Unorm => webgpu::GpuTextureFormat::R8unorm,
+            wgpu_types::TextureFormat::R8Snorm => webgpu::GpuTextureFormat::R8snorm,
+            wgpu_types::TextureFormat::R8Uint => webgpu::GpuTextureFormat::R8uint,
+            wgpu_types::TextureFormat::R8Sint => webgpu::GpuTextureFormat::R8sint,
+            wgpu_types::TextureFormat::R16Uint => webgpu::GpuTextureFormat::R16uint,
+            wgpu_types::TextureFormat::R16Sint => webgpu::GpuTextureFormat::R16sint,
+            wgpu_types::TextureFormat::R16Unorm => {
+                unreachable!("{value:?} isn't a webgpu format, so no texture has it")
+            }
+            wgpu_types::TextureFormat::R16Snorm => {
+                unreachable!("{value:?} isn't a webgpu format, so no texture has it")
+            }
+            wgpu_types::TextureFormat::R16Float => webgpu::GpuTextureFormat::R16float,
+            wgpu_types::TextureFormat::Rg8Unorm => webgpu::GpuTextureFormat::Rg8unorm,
+            wgpu_types::TextureFormat::Rg8Snorm => webgpu::GpuTextureFormat::Rg8snorm,
+            wgpu_types::TextureFormat::Rg8Uint => webgpu::GpuTextureFormat::Rg8uint,
+            wgpu_types::TextureFormat::Rg8Sint => webgpu::GpuTextureFormat::Rg8sint,
+            wgpu_types::TextureFormat::R32Uint => webgpu::GpuTextureFormat::R32uint,
+            wgpu_types::TextureFormat::R32Sint => webgpu::GpuTextureFormat::R32sint,
+            wgpu_types::TextureFormat::R32Float => webgpu::GpuTextureFormat::R32float,
+            wgpu_types::TextureFormat::Rg16Uint => webgpu::GpuTextureFormat::Rg16uint,
+            wgpu_types::TextureFormat::Rg16Sint => webgpu::GpuTextureFormat::Rg16sint,
+            wgpu_types::TextureFormat::Rg16Unorm => {
+                unreachable!("{value:?} isn't a webgpu format, so no texture has it")
+            }
+            wgpu_types::TextureFormat::Rg16Snorm => {
+                unreachable!("{value:?} isn't a webgpu format, so no texture has it")
+            }
+            wgpu_types::TextureFormat::Rg16Float => webgpu::GpuTextureFormat::Rg16float,
+            wgpu_types::TextureFormat::Rgba8Unorm => webgpu::GpuTextureFormat::Rgba8unorm,
+            wgpu_types::TextureFormat::Rgba8UnormSrgb => webgpu::GpuTextureFormat::Rgba8unormSrgb,
+            wgpu_types::TextureFormat::Rgba8Snorm => webgpu::GpuTextureFormat::Rgba8snorm,
+            wgpu_types::TextureFormat::Rgba8Uint => webgpu::GpuTextureFormat::Rgba8uint,
+            wgpu_types::TextureFormat::Rgba8Sint => webgpu::GpuTextureFormat::Rgba8sint,
+            wgpu_types::TextureFormat::Bgra8Unorm => webgpu::GpuTextureFormat::Bgra8unorm,
+            wgpu_types::TextureFormat::Rgb9e5Ufloat => webgpu::GpuTextureFormat::Rgb9e5ufloat,
+            wgpu_types::TextureFormat::Rgb10a2Uint => webgpu::GpuTextureFormat::Rgb10a2uint,
+            wgpu_types::TextureFormat::Rgb10a2Unorm => webgpu::GpuTextureFormat::Rgb10a2unorm,
+            wgpu_types::TextureFormat::Rg11b10Float => webgpu::GpuTextureFormat::Rg11b10ufloat,
+            wgpu_types::TextureFormat::Rg32Uint => webgpu::GpuTextureFormat::Rg32uint,
+            wgpu_types::TextureFormat::Rg32Sint => webgpu::GpuTextureFormat::Rg32sint,
+            wgpu_types::TextureFormat::Rg32Float => webgpu::GpuTextureFormat::Rg32float,
+            wgpu_types::TextureFormat::Rgba16Uint => webgpu::GpuTextureFormat::Rgba16uint,
+            wgpu_types::TextureFormat::Rgba16Sint => webgpu::GpuTextureFormat::Rgba16sint,
+            wgpu_types::TextureFormat::Rgba16Unorm => {
+                unreachable!("{value:?} isn't a webgpu format, so no texture has it")
+            }
+            wgpu_types::TextureFormat::Rgba16Snorm => {
+                unreachable!("{value:?} isn't a webgpu format, so no texture has it")
+            }
+            wgpu_types::TextureFormat::Rgba16Float => webgpu::GpuTextureFormat::Rgba16float,
+            wgpu_types::TextureFormat::Rgba32Uint => webgpu::GpuTextureFormat::Rgba32uint,
+            wgpu_types::TextureFormat::Rgba32Sint => webgpu::GpuTextureFormat::Rgba32sint,
+            wgpu_types::TextureFormat::Rgba32Float => webgpu::GpuTextureFormat::Rgba32float,
+            wgpu_types::TextureFormat::Stencil8 => webgpu::GpuTextureFormat::Stencil8,
+            wgpu_types::TextureFormat::Depth16Unorm => webgpu::GpuTextureFormat::Depth16unorm,
+            wgpu_types::TextureFormat::Depth24Plus => webgpu::GpuTextureFormat::Depth24plus,
+            wgpu_types::TextureFormat::Depth24PlusStencil8 => {
+                webgpu::GpuTextureFormat::Depth24plusStencil8
+            }
+            wgpu_types::TextureFormat::Depth32Float => webgpu::GpuTextureFormat::Depth32float,
+            wgpu_types::TextureFormat::Depth32FloatStencil8 => {
+                webgpu::GpuTextureFormat::Depth32floatStencil8
+            }
+            wgpu_types::TextureFormat::Bc1RgbaUnorm => webgpu::GpuTextureFormat::Bc1RgbaUnorm,
+            wgpu_types::TextureFormat::Bc1RgbaUnormSrgb => {
+                webgpu::GpuTextureFormat::Bc1RgbaUnormSrgb
+            }
+            wgpu_types::TextureFormat::Bc2RgbaUnorm => webgpu::GpuTextureFormat::Bc2RgbaUnorm,
+            wgpu_types::TextureFormat::Bc2RgbaUnormSrgb => {
+                webgpu::GpuTextureFormat::Bc2RgbaUnormSrgb
+            }
+            wgpu_types::TextureFormat::Bc3RgbaUnorm => webgpu::GpuTextureFormat::Bc3RgbaUnorm,
+            wgpu_types::TextureFormat::Bc3RgbaUnormSrgb => {
+                webgpu::GpuTextureFormat::Bc3RgbaUnormSrgb
+            }
+            wgpu_types::TextureFormat::Bc4RUnorm => webgpu::GpuTextureFormat::Bc4RUnorm,
+            wgpu_types::TextureFormat::Bc4RSnorm => webgpu::GpuTextureFormat::Bc4RSnorm,
+            wgpu_types::TextureFormat::Bc5RgUnorm => webgpu::GpuTextureFormat::Bc5RgUnorm,
+            wgpu_types::TextureFormat::Bc5RgSnorm => webgpu::GpuTextureFormat::Bc5RgSnorm,
+            wgpu_types::TextureFormat::Bc6hRgbUfloat => webgpu::GpuTextureFormat::Bc6hRgbUfloat,
+            wgpu_types::TextureFormat::Bc6hRgbFloat => webgpu::GpuTextureFormat::Bc6hRgbFloat,
+            wgpu_types::TextureFormat::Bc7RgbaUnorm => webgpu::GpuTextureFormat::Bc7RgbaUnorm,
+            wgpu_types::TextureFormat::Bc7RgbaUnormSrgb => {
+                webgpu::GpuTextureFormat::Bc7RgbaUnormSrgb
+            }
+            wgpu_types::TextureFormat::Etc2Rgb8Unorm => webgpu::GpuTextureFormat::Etc2Rgb8unorm,
+            wgpu_types::TextureFormat::Etc2Rgb8UnormSrgb => {
+                webgpu::GpuTextureFormat::Etc2Rgb8unormSrgb
+            }
+            wgpu_types::TextureFormat::Etc2Rgb8A1Unorm => webgpu::GpuTextureFormat::Etc2Rgb8a1unorm,
+            wgpu_types::TextureFormat::Etc2Rgb8A1UnormSrgb => {
+                webgpu::GpuTextureFormat::Etc2Rgb8a1unormSrgb
+            }
+            wgpu_types::TextureFormat::Etc2Rgba8Unorm => webgpu::GpuTextureFormat::Etc2Rgba8unorm,
+            wgpu_types::TextureFormat::Etc2Rgba8UnormSrgb => {
+                webgpu::GpuTextureFormat::Etc2Rgba8unormSrgb
+            }
+            wgpu_types::TextureFormat::EacR11Unorm => webgpu::GpuTextureFormat::EacR11unorm,
+            wgpu_types::TextureFormat::EacR11Snorm => webgpu::GpuTextureFormat::EacR11snorm,
+            wgpu_types::TextureFormat::EacRg11Unorm => webgpu::GpuTextureFormat::EacRg11unorm,
+            wgpu_types::TextureFormat::EacRg11Snorm => webgpu::GpuTextureFormat::EacRg11snorm,
+            // WebGPU has no HDR ASTC formats, so those are only ever the host's. Their blocks are laid out like
+            // the unorm ones.
+            wgpu_types::TextureFormat::Astc { block, channel } => match (block, channel) {
+                (
+                    wgpu_types::AstcBlock::B4x4,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc4x4Unorm,
+                (wgpu_types::AstcBlock::B4x4, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc4x4UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B5x4,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc5x4Unorm,
+                (wgpu_types::AstcBlock::B5x4, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc5x4UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B5x5,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc5x5Unorm,
+                (wgpu_types::AstcBlock::B5x5, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc5x5UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B6x5,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc6x5Unorm,
+                (wgpu_types::AstcBlock::B6x5, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc6x5UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B6x6,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc6x6Unorm,
+                (wgpu_types::AstcBlock::B6x6, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc6x6UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B8x5,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc8x5Unorm,
+                (wgpu_types::AstcBlock::B8x5, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc8x5UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B8x6,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc8x6Unorm,
+                (wgpu_types::AstcBlock::B8x6, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc8x6UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B8x8,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc8x8Unorm,
+                (wgpu_types::AstcBlock::B8x8, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc8x8UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B10x5,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc10x5Unorm,
+                (wgpu_types::AstcBlock::B10x5, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc10x5UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B10x6,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc10x6Unorm,
+                (wgpu_types::AstcBlock::B10x6, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc10x6UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B10x8,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc10x8Unorm,
+                (wgpu_types::AstcBlock::B10x8, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc10x8UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B10x10,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc10x10Unorm,
+                (wgpu_types::AstcBlock::B10x10, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc10x10UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B12x10,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc12x10Unorm,
+                (wgpu_types::AstcBlock::B12x10, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc12x10UnormSrgb
+                }
+                (
+                    wgpu_types::AstcBlock::B12x12,
+                    wgpu_types::AstcChannel::Unorm | wgpu_types::AstcChannel::Hdr,
+                ) => webgpu::GpuTextureFormat::Astc12x12Unorm,
+                (wgpu_types::AstcBlock::B12x12, wgpu_types::AstcChannel::UnormSrgb) => {
+                    webgpu::GpuTextureFormat::Astc12x12UnormSrgb
+                }
+            },
+            wgpu_types::TextureFormat::NV12 => {
+                unreachable!("{value:?} isn't a webgpu format, so no texture has it")
+            }
         }
     }
 }
@@ -170,34 +303,118 @@ impl From<webgpu::GpuTextureFormat> for wgpu_types::TextureFormat {
             webgpu::GpuTextureFormat::EacR11snorm => wgpu_types::TextureFormat::EacR11Snorm,
             webgpu::GpuTextureFormat::EacRg11unorm => wgpu_types::TextureFormat::EacRg11Unorm,
             webgpu::GpuTextureFormat::EacRg11snorm => wgpu_types::TextureFormat::EacRg11Snorm,
-            webgpu::GpuTextureFormat::Astc4x4Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc4x4UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc5x4Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc5x4UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc5x5Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc5x5UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc6x5Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc6x5UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc6x6Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc6x6UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc8x5Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc8x5UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc8x6Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc8x6UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc8x8Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc8x8UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc10x5Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc10x5UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc10x6Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc10x6UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc10x8Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc10x8UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc10x10Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc10x10UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc12x10Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc12x10UnormSrgb => todo!(),
-            webgpu::GpuTextureFormat::Astc12x12Unorm => todo!(),
-            webgpu::GpuTextureFormat::Astc12x12UnormSrgb => todo!(),
+            webgpu::GpuTextureFormat::Astc4x4Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B4x4,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc4x4UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B4x4,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc5x4Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B5x4,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc5x4UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B5x4,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc5x5Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B5x5,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc5x5UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B5x5,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc6x5Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B6x5,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc6x5UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B6x5,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc6x6Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B6x6,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc6x6UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B6x6,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc8x5Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B8x5,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc8x5UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B8x5,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc8x6Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B8x6,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc8x6UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B8x6,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc8x8Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B8x8,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc8x8UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B8x8,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc10x5Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x5,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc10x5UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x5,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc10x6Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x6,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc10x6UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x6,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc10x8Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x8,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc10x8UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x8,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc10x10Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x10,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc10x10UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B10x10,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc12x10Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B12x10,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc12x10UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B12x10,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
+            webgpu::GpuTextureFormat::Astc12x12Unorm => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B12x12,
+                channel: wgpu_types::AstcChannel::Unorm,
+            },
+            webgpu::GpuTextureFormat::Astc12x12UnormSrgb => wgpu_types::TextureFormat::Astc {
+                block: wgpu_types::AstcBlock::B12x12,
+                channel: wgpu_types::AstcChannel::UnormSrgb,
+            },
         }
     }
 }
@@ -234,6 +451,16 @@ impl From<wgpu_types::PrimitiveTopology> for webgpu::GpuPrimitiveTopology {
     }
 }
 
+impl From<wgpu_types::TextureDimension> for webgpu::GpuTextureDimension {
+    fn from(value: wgpu_types::TextureDimension) -> Self {
+        match value {
+            wgpu_types::TextureDimension::D1 => webgpu::GpuTextureDimension::OneD,
+            wgpu_types::TextureDimension::D2 => webgpu::GpuTextureDimension::TwoD,
+            wgpu_types::TextureDimension::D3 => webgpu::GpuTextureDimension::ThreeD,
+        }
+    }
+}
+
 impl From<webgpu::GpuTextureDimension> for wgpu_types::TextureDimension {
     fn from(value: webgpu::GpuTextureDimension) -> Self {
         match value {
@@ -244,6 +471,17 @@ impl From<webgpu::GpuTextureDimension> for wgpu_types::TextureDimension {
     }
 }
 
+impl From<webgpu::GpuPowerPreference> for wgpu_types::PowerPreference {
+    fn from(value: webgpu::GpuPowerPreference) -> Self {
+        match value {
+            webgpu::GpuPowerPreference::LowPower => wgpu_types::PowerPreference::LowPower,
+            webgpu::GpuPowerPreference::HighPerformance => {
+                wgpu_types::PowerPreference::HighPerformance
+            }
+        }
+    }
+}
+
 impl From<webgpu::GpuAddressMode> for wgpu_types::AddressMode {
     fn from(value: webgpu::GpuAddressMode) -> Self {
         match value {
@@ -330,6 +568,20 @@ impl From<webgpu::GpuTextureViewDimension> for wgpu_types::TextureViewDimension
     }
 }
 
+impl From<webgpu::GpuStorageTextureAccess> for wgpu_types::StorageTextureAccess {
+    fn from(value: webgpu::GpuStorageTextureAccess) -> Self {
+        match value {
+            webgpu::GpuStorageTextureAccess::WriteOnly => {
+                wgpu_types::StorageTextureAccess::WriteOnly
+            }
+            webgpu::GpuStorageTextureAccess::ReadOnly => wgpu_types::StorageTextureAccess::ReadOnly,
+            webgpu::GpuStorageTextureAccess::ReadWrite => {
+                wgpu_types::StorageTextureAccess::ReadWrite
+            }
+        }
+    }
+}
+
 impl From<webgpu::GpuBufferBindingType> for wgpu_types::BufferBindingType {
     fn from(value: webgpu::GpuBufferBindingType) -> Self {
         match value {
@@ -378,12 +630,12 @@ impl From<webgpu::GpuColor> for wgpu_types::Color {
     }
 }
 
-impl From<webgpu::GpuCullMode> for wgpu_types::Face {
+impl From<webgpu::GpuCullMode> for Option<wgpu_types::Face> {
     fn from(value: webgpu::GpuCullMode) -> Self {
         match value {
-            webgpu::GpuCullMode::None => todo!(),
-            webgpu::GpuCullMode::Front => wgpu_types::Face::Front,
-            webgpu::GpuCullMode::Back => wgpu_types::Face::Back,
+            webgpu::GpuCullMode::None => None,
+            webgpu::GpuCullMode::Front => Some(wgpu_types::Face::Front),
+            webgpu::GpuCullMode::Back => Some(wgpu_types::Face::Back),
         }
     }
 }
@@ -448,7 +700,7 @@ impl From<webgpu::GpuVertexFormat> for wgpu_types::VertexFormat {
             webgpu::GpuVertexFormat::Sint32x2 => wgpu_types::VertexFormat::Sint32x2,
             webgpu::GpuVertexFormat::Sint32x3 => wgpu_types::VertexFormat::Sint32x3,
             webgpu::GpuVertexFormat::Sint32x4 => wgpu_types::VertexFormat::Sint32x4,
-            webgpu::GpuVertexFormat::Unorm1010102 => todo!(),
+            webgpu::GpuVertexFormat::Unorm1010102 => wgpu_types::VertexFormat::Unorm10_10_10_2,
         }
     }
 }
@@ -469,6 +721,10 @@ impl From<webgpu::GpuBlendFactor> for wgpu_types::BlendFactor {
             webgpu::GpuBlendFactor::SrcAlphaSaturated => wgpu_types::BlendFactor::SrcAlphaSaturated,
             webgpu::GpuBlendFactor::Constant => wgpu_types::BlendFactor::Constant,
             webgpu::GpuBlendFactor::OneMinusConstant => wgpu_types::BlendFactor::OneMinusConstant,
+            webgpu::GpuBlendFactor::Src1 => wgpu_types::BlendFactor::Src1,
+            webgpu::GpuBlendFactor::OneMinusSrc1 => wgpu_types::BlendFactor::OneMinusSrc1,
+            webgpu::GpuBlendFactor::Src1Alpha => wgpu_types::BlendFactor::Src1Alpha,
+            webgpu::GpuBlendFactor::OneMinusSrc1Alpha => wgpu_types::BlendFactor::OneMinusSrc1Alpha,
         }
     }
 }
@@ -519,3 +775,203 @@ impl From<webgpu::GpuTextureAspect> for wgpu_types::TextureAspect {
         }
     }
 }
+
+impl From<webgpu::GpuFeatureName> for wgpu_types::Features {
+    fn from(value: webgpu::GpuFeatureName) -> Self {
+        match value {
+            webgpu::GpuFeatureName::DepthClipControl => wgpu_types::Features::DEPTH_CLIP_CONTROL,
+            webgpu::GpuFeatureName::Depth32floatStencil8 => {
+                wgpu_types::Features::DEPTH32FLOAT_STENCIL8
+            }
+            webgpu::GpuFeatureName::TextureCompressionBc => {
+                wgpu_types::Features::TEXTURE_COMPRESSION_BC
+            }
+            webgpu::GpuFeatureName::TextureCompressionEtc2 => {
+                wgpu_types::Features::TEXTURE_COMPRESSION_ETC2
+            }
+            webgpu::GpuFeatureName::TextureCompressionAstc => {
+                wgpu_types::Features::TEXTURE_COMPRESSION_ASTC
+            }
+            webgpu::GpuFeatureName::TimestampQuery => wgpu_types::Features::TIMESTAMP_QUERY,
+            webgpu::GpuFeatureName::IndirectFirstInstance => {
+                wgpu_types::Features::INDIRECT_FIRST_INSTANCE
+            }
+            webgpu::GpuFeatureName::ShaderF16 => wgpu_types::Features::SHADER_F16,
+            webgpu::GpuFeatureName::Rg11b10ufloatRenderable => {
+                wgpu_types::Features::RG11B10UFLOAT_RENDERABLE
+            }
+            webgpu::GpuFeatureName::Bgra8unormStorage => wgpu_types::Features::BGRA8UNORM_STORAGE,
+            webgpu::GpuFeatureName::Float32Filterable => wgpu_types::Features::FLOAT32_FILTERABLE,
+            webgpu::GpuFeatureName::DualSourceBlending => {
+                wgpu_types::Features::DUAL_SOURCE_BLENDING
+            }
+            webgpu::GpuFeatureName::PushConstants => wgpu_types::Features::PUSH_CONSTANTS,
+            webgpu::GpuFeatureName::PipelineStatisticsQuery => {
+                wgpu_types::Features::PIPELINE_STATISTICS_QUERY
+            }
+            webgpu::GpuFeatureName::Multiview => wgpu_types::Features::MULTIVIEW,
+            webgpu::GpuFeatureName::MultiDrawIndirectCount => {
+                wgpu_types::Features::MULTI_DRAW_INDIRECT_COUNT
+            }
+        }
+    }
+}
+
+impl From<webgpu::GpuQueryType> for wgpu_types::QueryType {
+    fn from(value: webgpu::GpuQueryType) -> Self {
+        match value {
+            webgpu::GpuQueryType::Occlusion => wgpu_types::QueryType::Occlusion,
+            webgpu::GpuQueryType::Timestamp => wgpu_types::QueryType::Timestamp,
+            // Without statistics to count, `create-query-set` rejects these before they get here.
+            webgpu::GpuQueryType::PipelineStatistics => wgpu_types::QueryType::PipelineStatistics(
+                wgpu_types::PipelineStatisticsTypes::empty(),
+            ),
+        }
+    }
+}
+
+impl From<wgpu_types::QueryType> for webgpu::GpuQueryType {
+    fn from(value: wgpu_types::QueryType) -> Self {
+        match value {
+            wgpu_types::QueryType::Occlusion => webgpu::GpuQueryType::Occlusion,
+            wgpu_types::QueryType::Timestamp => webgpu::GpuQueryType::Timestamp,
+            wgpu_types::QueryType::PipelineStatistics(_) => {
+                webgpu::GpuQueryType::PipelineStatistics
+            }
+        }
+    }
+}
+
+impl From<webgpu::GpuPipelineStatistic> for wgpu_types::PipelineStatisticsTypes {
+    fn from(value: webgpu::GpuPipelineStatistic) -> Self {
+        match value {
+            webgpu::GpuPipelineStatistic::VertexShaderInvocations => {
+                wgpu_types::PipelineStatisticsTypes::VERTEX_SHADER_INVOCATIONS
+            }
+            webgpu::GpuPipelineStatistic::ClipperInvocations => {
+                wgpu_types::PipelineStatisticsTypes::CLIPPER_INVOCATIONS
+            }
+            webgpu::GpuPipelineStatistic::ClipperPrimitivesOut => {
+                wgpu_types::PipelineStatisticsTypes::CLIPPER_PRIMITIVES_OUT
+            }
+            webgpu::GpuPipelineStatistic::FragmentShaderInvocations => {
+                wgpu_types::PipelineStatisticsTypes::FRAGMENT_SHADER_INVOCATIONS
+            }
+            webgpu::GpuPipelineStatistic::ComputeShaderInvocations => {
+                wgpu_types::PipelineStatisticsTypes::COMPUTE_SHADER_INVOCATIONS
+            }
+        }
+    }
+}
+
+impl From<wgpu_types::ShaderModel> for webgpu::GpuShaderModel {
+    fn from(value: wgpu_types::ShaderModel) -> Self {
+        match value {
+            wgpu_types::ShaderModel::Sm2 => webgpu::GpuShaderModel::Sm2,
+            wgpu_types::ShaderModel::Sm4 => webgpu::GpuShaderModel::Sm4,
+            wgpu_types::ShaderModel::Sm5 => webgpu::GpuShaderModel::Sm5,
+        }
+    }
+}
+
+const DOWNLEVEL_FLAGS: &[(wgpu_types::DownlevelFlags, webgpu::GpuDownlevelFlags)] = &[
+    (
+        wgpu_types::DownlevelFlags::COMPUTE_SHADERS,
+        webgpu::GpuDownlevelFlags::COMPUTE_SHADERS,
+    ),
+    (
+        wgpu_types::DownlevelFlags::FRAGMENT_WRITABLE_STORAGE,
+        webgpu::GpuDownlevelFlags::FRAGMENT_WRITABLE_STORAGE,
+    ),
+    (
+        wgpu_types::DownlevelFlags::INDIRECT_EXECUTION,
+        webgpu::GpuDownlevelFlags::INDIRECT_EXECUTION,
+    ),
+    (
+        wgpu_types::DownlevelFlags::BASE_VERTEX,
+        webgpu::GpuDownlevelFlags::BASE_VERTEX,
+    ),
+    (
+        wgpu_types::DownlevelFlags::READ_ONLY_DEPTH_STENCIL,
+        webgpu::GpuDownlevelFlags::READ_ONLY_DEPTH_STENCIL,
+    ),
+    (
+        wgpu_types::DownlevelFlags::NON_POWER_OF_TWO_MIPMAPPED_TEXTURES,
+        webgpu::GpuDownlevelFlags::NON_POWER_OF_TWO_MIPMAPPED_TEXTURES,
+    ),
+    (
+        wgpu_types::DownlevelFlags::CUBE_ARRAY_TEXTURES,
+        webgpu::GpuDownlevelFlags::CUBE_ARRAY_TEXTURES,
+    ),
+    (
+        wgpu_types::DownlevelFlags::COMPARISON_SAMPLERS,
+        webgpu::GpuDownlevelFlags::COMPARISON_SAMPLERS,
+    ),
+    (
+        wgpu_types::DownlevelFlags::INDEPENDENT_BLEND,
+        webgpu::GpuDownlevelFlags::INDEPENDENT_BLEND,
+    ),
+    (
+        wgpu_types::DownlevelFlags::VERTEX_STORAGE,
+        webgpu::GpuDownlevelFlags::VERTEX_STORAGE,
+    ),
+    (
+        wgpu_types::DownlevelFlags::ANISOTROPIC_FILTERING,
+        webgpu::GpuDownlevelFlags::ANISOTROPIC_FILTERING,
+    ),
+    (
+        wgpu_types::DownlevelFlags::FRAGMENT_STORAGE,
+        webgpu::GpuDownlevelFlags::FRAGMENT_STORAGE,
+    ),
+    (
+        wgpu_types::DownlevelFlags::MULTISAMPLED_SHADING,
+        webgpu::GpuDownlevelFlags::MULTISAMPLED_SHADING,
+    ),
+    (
+        wgpu_types::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES,
+        webgpu::GpuDownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES,
+    ),
+    (
+        wgpu_types::DownlevelFlags::WEBGPU_TEXTURE_FORMAT_SUPPORT,
+        webgpu::GpuDownlevelFlags::WEBGPU_TEXTURE_FORMAT_SUPPORT,
+    ),
+    (
+        wgpu_types::DownlevelFlags::UNRESTRICTED_INDEX_BUFFER,
+        webgpu::GpuDownlevelFlags::UNRESTRICTED_INDEX_BUFFER,
+    ),
+    (
+        wgpu_types::DownlevelFlags::DEPTH_BIAS_CLAMP,
+        webgpu::GpuDownlevelFlags::DEPTH_BIAS_CLAMP,
+    ),
+    (
+        wgpu_types::DownlevelFlags::VIEW_FORMATS,
+        webgpu::GpuDownlevelFlags::VIEW_FORMATS,
+    ),
+    (
+        wgpu_types::DownlevelFlags::UNRESTRICTED_EXTERNAL_TEXTURE_COPIES,
+        webgpu::GpuDownlevelFlags::UNRESTRICTED_EXTERNAL_TEXTURE_COPIES,
+    ),
+    (
+        wgpu_types::DownlevelFlags::SURFACE_VIEW_FORMATS,
+        webgpu::GpuDownlevelFlags::SURFACE_VIEW_FORMATS,
+    ),
+    (
+        wgpu_types::DownlevelFlags::NONBLOCKING_QUERY_RESOLVE,
+        webgpu::GpuDownlevelFlags::NONBLOCKING_QUERY_RESOLVE,
+    ),
+    (
+        wgpu_types::DownlevelFlags::VERTEX_AND_INSTANCE_INDEX_RESPECTS_RESPECTIVE_FIRST_VALUE_IN_INDIRECT_DRAW,
+        webgpu::GpuDownlevelFlags::VERTEX_AND_INSTANCE_INDEX_RESPECTS_RESPECTIVE_FIRST_VALUE_IN_INDIRECT_DRAW,
+    ),
+];
+
+impl From<wgpu_types::DownlevelFlags> for webgpu::GpuDownlevelFlags {
+    fn from(value: wgpu_types::DownlevelFlags) -> Self {
+        DOWNLEVEL_FLAGS
+            .iter()
+            .filter(|(flag, _)| value.contains(*flag))
+            .fold(webgpu::GpuDownlevelFlags::empty(), |acc, (_, flag)| {
+                acc | *flag
+            })
+    }
+}
@@ -0,0 +1,290 @@
+use std::error::Error;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use wgpu_core::pipeline::CreateRenderPipelineError;
+use wgpu_core::validation::StageError;
+
+use crate::wasi::webgpu::webgpu;
+
+/// The `gpu-error` resource. Every wgpu-core error reported to guests goes through [`GpuError::from_core`].
+#[derive(Clone, Debug)]
+pub struct GpuError {
+    kind: ErrorKind,
+    message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorKind {
+    Validation,
+    OutOfMemory,
+    Internal,
+    Lost,
+}
+
+impl GpuError {
+    /// Classifies `error` the same way wgpu does: by the `DeviceError` in its source chain, if any.
+    pub fn from_core(error: &(dyn Error + 'static)) -> Self {
+        let mut kind = ErrorKind::Validation;
+        let mut message = error.to_string();
+        let mut source = Some(error);
+        while let Some(error) = source {
+            if let Some(device_error) = error.downcast_ref::<wgpu_core::device::DeviceError>() {
+                kind = match device_error {
+                    wgpu_core::device::DeviceError::OutOfMemory => ErrorKind::OutOfMemory,
+                    wgpu_core::device::DeviceError::Lost => ErrorKind::Lost,
+                    wgpu_core::device::DeviceError::ResourceCreationFailed => ErrorKind::Internal,
+                    _ => ErrorKind::Validation,
+                };
+            }
+            source = error.source();
+            if let Some(source) = source {
+                // The top level message is often generic, the details are further down.
+                write!(message, ": {source}").unwrap();
+            }
+        }
+        Self { kind, message }
+    }
+
+    /// For checks done by this crate before calling into wgpu-core.
+    pub(crate) fn validation(message: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::Validation,
+            message: message.into(),
+        }
+    }
+
+    /// For allocations the host refused, like ones past a [`GpuQuota`](crate::GpuQuota).
+    pub(crate) fn out_of_memory(message: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::OutOfMemory,
+            message: message.into(),
+        }
+    }
+
+    /// For the `gpu-internal-error` constructor.
+    pub(crate) fn internal(message: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::Internal,
+            message: message.into(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub(crate) fn kind(&self) -> webgpu::GpuErrorKind {
+        let message = self.message.clone();
+        match self.kind {
+            ErrorKind::Validation => webgpu::GpuErrorKind::Validation(message),
+            ErrorKind::OutOfMemory => webgpu::GpuErrorKind::OutOfMemory(message),
+            ErrorKind::Internal => webgpu::GpuErrorKind::Internal(message),
+            ErrorKind::Lost => webgpu::GpuErrorKind::Lost(message),
+        }
+    }
+
+    /// Scopes without a filter capture every error.
+    fn captured_by(&self, filter: Option<webgpu::GpuErrorFilter>) -> bool {
+        let Some(filter) = filter else {
+            return true;
+        };
+        // Device loss isn't in the spec's error filters, and is closest to an internal error.
+        matches!(
+            (self.kind, filter),
+            (ErrorKind::Validation, webgpu::GpuErrorFilter::Validation)
+                | (ErrorKind::OutOfMemory, webgpu::GpuErrorFilter::OutOfMemory)
+                | (
+                    ErrorKind::Internal | ErrorKind::Lost,
+                    webgpu::GpuErrorFilter::Internal
+                )
+        )
+    }
+}
+
+/// The `gpu-uncaptured-error-event` resource. Guests can construct one, but the host never dispatches them, see
+/// [`ErrorScopes::report`].
+pub struct UncapturedErrorEvent {
+    pub(crate) error: GpuError,
+}
+
+/// The `gpu-pipeline-error` resource. Guests can construct one, but pipeline creation reports its errors to the error
+/// scopes, or as a `gpu-render-pipeline-error` from `try-create-render-pipeline`.
+pub struct PipelineError {
+    pub(crate) reason: webgpu::GpuPipelineErrorReason,
+}
+
+/// Picks out the errors guests can do something about, everything else is reported as `other`.
+pub(crate) fn render_pipeline_error(
+    error: &CreateRenderPipelineError,
+) -> webgpu::GpuRenderPipelineError {
+    use webgpu::GpuRenderPipelineError as E;
+    match error {
+        CreateRenderPipelineError::InvalidLayout => E::InvalidLayout,
+        CreateRenderPipelineError::Stage { stage, error } => {
+            let stage = stage.bits();
+            match error {
+                StageError::MissingEntryPoint(name) => {
+                    E::ShaderEntryPointNotFound(webgpu::GpuShaderEntryPointNotFound {
+                        stage,
+                        name: name.clone(),
+                    })
+                }
+                StageError::Input { location, .. } | StageError::InputNotConsumed { location } => {
+                    E::StageInputMismatch(webgpu::GpuStageInputMismatch {
+                        stage,
+                        location: *location,
+                        message: GpuError::from_core(error).message,
+                    })
+                }
+                _ => E::StageMismatch(webgpu::GpuStageMismatch {
+                    stage,
+                    message: GpuError::from_core(error).message,
+                }),
+            }
+        }
+        CreateRenderPipelineError::TooManyVertexBuffers { given, limit } => {
+            E::TooManyVertexBuffers(webgpu::GpuLimitExceeded {
+                given: *given,
+                limit: *limit,
+            })
+        }
+        CreateRenderPipelineError::TooManyVertexAttributes { given, limit } => {
+            E::TooManyVertexAttributes(webgpu::GpuLimitExceeded {
+                given: *given,
+                limit: *limit,
+            })
+        }
+        CreateRenderPipelineError::VertexStrideTooLarge {
+            index,
+            given,
+            limit,
+        } => E::VertexStrideTooLarge(webgpu::GpuVertexStrideTooLarge {
+            index: *index,
+            given: *given,
+            limit: *limit,
+        }),
+        CreateRenderPipelineError::UnalignedVertexStride { index, stride } => {
+            E::UnalignedVertexStride(webgpu::GpuUnalignedVertexStride {
+                index: *index,
+                stride: *stride,
+            })
+        }
+        CreateRenderPipelineError::InvalidVertexAttributeOffset { location, offset } => {
+            E::InvalidVertexAttributeOffset(webgpu::GpuInvalidVertexAttributeOffset {
+                location: *location,
+                offset: *offset,
+            })
+        }
+        CreateRenderPipelineError::ShaderLocationClash(location) => {
+            E::ShaderLocationClash(*location)
+        }
+        CreateRenderPipelineError::InvalidSampleCount(count) => E::InvalidSampleCount(*count),
+        CreateRenderPipelineError::ColorState(target, error) => {
+            E::ColorState(webgpu::GpuColorStateError {
+                target: (*target).into(),
+                message: GpuError::from_core(error).message,
+            })
+        }
+        CreateRenderPipelineError::DepthStencilState(error) => {
+            E::DepthStencilState(GpuError::from_core(error).message)
+        }
+        CreateRenderPipelineError::MissingFeatures(error) => {
+            E::MissingFeatures(GpuError::from_core(error).message)
+        }
+        _ => E::Other(GpuError::from_core(error).kind()),
+    }
+}
+
+/// A device's error scope stack.
+/// source: https://www.w3.org/TR/webgpu/#error-scopes
+#[derive(Clone, Default)]
+pub(crate) struct ErrorScopes(Arc<Mutex<Vec<ErrorScope>>>);
+
+struct ErrorScope {
+    filter: Option<webgpu::GpuErrorFilter>,
+    error: Option<GpuError>,
+}
+
+impl ErrorScopes {
+    pub fn push(&self, filter: webgpu::GpuErrorFilter) {
+        self.0.lock().unwrap().push(ErrorScope {
+            filter: Some(filter),
+            error: None,
+        });
+    }
+
+    /// Runs `f` in a scope capturing every error, and returns the first one instead of reporting it.
+    ///
+    /// For the non-standard calls that hand their errors back to the guest, like `generate-mipmaps`. Only errors reported
+    /// while `f` runs are caught, ones from callbacks running later still go to the guest's scopes.
+    pub fn catch<T>(&self, f: impl FnOnce() -> T) -> Result<T, GpuError> {
+        self.0.lock().unwrap().push(ErrorScope {
+            filter: None,
+            error: None,
+        });
+        let output = f();
+        // `f` can't reach the guest's push and pop, so the scope is still the innermost one.
+        let scope = self.0.lock().unwrap().pop().unwrap();
+        match scope.error {
+            Some(error) => Err(error),
+            None => Ok(output),
+        }
+    }
+
+    /// Popping an empty stack is an `OperationError` in the spec, so it's returned as a trap.
+    pub fn pop(&self) -> anyhow::Result<Option<GpuError>> {
+        let scope = self.0.lock().unwrap().pop();
+        match scope {
+            Some(scope) => Ok(scope.error),
+            None => anyhow::bail!("pop-error-scope called without a matching push-error-scope"),
+        }
+    }
+
+    /// Hands `error` to the innermost scope that captures it. Only the first error of a scope is kept.
+    ///
+    /// Uncaptured errors are logged, since there's no way to dispatch uncaptured error events to guests yet.
+    pub fn report(&self, error: GpuError) {
+        let mut scopes = self.0.lock().unwrap();
+        match scopes
+            .iter_mut()
+            .rev()
+            .find(|scope| error.captured_by(scope.filter))
+        {
+            Some(scope) => {
+                scope.error.get_or_insert(error);
+            }
+            None => log::error!("Uncaptured gpu error: {}", error.message()),
+        }
+    }
+
+    /// Like `core_result`, but reports the error instead of returning it.
+    /// wgpu-core hands out an invalid id on error, so there is always an id to return.
+    pub fn handle<I, E>(
+        &self,
+        (id, error): (wgpu_core::id::Id<I>, Option<E>),
+    ) -> wgpu_core::id::Id<I>
+    where
+        I: wgpu_core::id::Marker,
+        E: Error + 'static,
+    {
+        if let Some(error) = error {
+            self.report(GpuError::from_core(&error));
+        }
+        id
+    }
+
+    /// Like [`handle`](Self::handle), for wgpu-core calls that don't hand out an id.
+    pub fn check<T, E>(&self, result: Result<T, E>) -> Option<T>
+    where
+        E: Error + 'static,
+    {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.report(GpuError::from_core(&error));
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::sync::{Arc, Mutex};
+
+use futures::channel::oneshot;
+use wasmtime::component::Resource;
+use wasmtime_wasi::bindings::io::poll::Pollable;
+
+use crate::error::ErrorScopes;
+use crate::wasi::webgpu::{gpu_futures, webgpu};
+use crate::{Buffer, Device, Queue, RenderPipeline, WasiWebGpuImpl, WasiWebGpuView};
+
+/// Something the gpu finishes in the background, as a `wasi:io/poll` pollable.
+///
+/// The output is sent over by whatever finishes the work, usually a wgpu-core callback run by [`maintain`]. If the
+/// sender is dropped instead, the future is ready without an output.
+pub struct GpuFuture<T> {
+    receiver: oneshot::Receiver<T>,
+    output: Option<T>,
+    done: bool,
+}
+
+/// The `gpu-future` resource.
+pub type UnitFuture = GpuFuture<()>;
+/// The `device-lost-future` resource.
+pub type DeviceLostFuture = GpuFuture<gpu_futures::DeviceLost>;
+/// The `render-pipeline-future` resource.
+pub type RenderPipelineFuture = GpuFuture<Resource<RenderPipeline>>;
+/// The `compute-pipeline-future` resource.
+pub type ComputePipelineFuture = GpuFuture<Resource<wgpu_core::id::ComputePipelineId>>;
+
+impl<T> GpuFuture<T> {
+    pub(crate) fn new() -> (Self, oneshot::Sender<T>) {
+        let (sender, receiver) = oneshot::channel();
+        let future = Self {
+            receiver,
+            output: None,
+            done: false,
+        };
+        (future, sender)
+    }
+
+    /// A future that is ready right away.
+    pub(crate) fn ready(output: T) -> Self {
+        let (future, sender) = Self::new();
+        let _ = sender.send(output);
+        future
+    }
+
+    fn receive(&mut self) {
+        if self.done {
+            return;
+        }
+        match self.receiver.try_recv() {
+            Ok(Some(output)) => {
+                self.output = Some(output);
+                self.done = true;
+            }
+            Ok(None) => {}
+            Err(oneshot::Canceled) => self.done = true,
+        }
+    }
+
+    pub fn is_ready(&mut self) -> bool {
+        self.receive();
+        self.done
+    }
+
+    /// `None` until the future is ready, after the output was taken, or if there is none.
+    pub fn take(&mut self) -> Option<T> {
+        self.receive();
+        self.output.take()
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> wasmtime_wasi::Subscribe for GpuFuture<T> {
+    async fn ready(&mut self) {
+        if !self.done {
+            self.output = (&mut self.receiver).await.ok();
+            self.done = true;
+        }
+    }
+}
+
+/// Waits for the gpu on a thread of its own, which runs the callbacks that complete gpu futures.
+pub(crate) fn maintain(instance: Arc<wgpu_core::global::Global>, errors: ErrorScopes) {
+    std::thread::spawn(move || {
+        // TODO: only poll the device the future belongs to.
+        errors.check(instance.poll_all_devices(true));
+    });
+}
+
+/// Whether a device is lost, shared by every `device-lost-future` of the device.
+///
+/// wgpu-core only keeps one device lost callback per device, so it's set once, when the device is requested.
+#[derive(Clone, Default)]
+pub(crate) struct DeviceLostWatch(Arc<Mutex<DeviceLostState>>);
+
+#[derive(Default)]
+struct DeviceLostState {
+    lost: Option<gpu_futures::DeviceLost>,
+    waiting: Vec<oneshot::Sender<gpu_futures::DeviceLost>>,
+}
+
+impl DeviceLostWatch {
+    pub(crate) fn new(
+        instance: &wgpu_core::global::Global,
+        device: wgpu_core::id::DeviceId,
+    ) -> Self {
+        let watch = Self::default();
+        let state = Arc::clone(&watch.0);
+        instance.device_set_device_lost_closure::<crate::Backend>(
+            device,
+            wgpu_core::device::DeviceLostClosure::from_rust(Box::new(move |reason, message| {
+                let lost = gpu_futures::DeviceLost {
+                    reason: match reason {
+                        wgpu_types::DeviceLostReason::Destroyed
+                        | wgpu_types::DeviceLostReason::Dropped => {
+                            webgpu::GpuDeviceLostReason::Destroyed
+                        }
+                        _ => webgpu::GpuDeviceLostReason::Unknown,
+                    },
+                    message,
+                };
+                let mut state = state.lock().unwrap();
+                for sender in state.waiting.drain(..) {
+                    let _ = sender.send(lost.clone());
+                }
+                state.lost = Some(lost);
+            })),
+        );
+        watch
+    }
+
+    pub(crate) fn future(&self) -> DeviceLostFuture {
+        let mut state = self.0.lock().unwrap();
+        match &state.lost {
+            Some(lost) => GpuFuture::ready(lost.clone()),
+            None => {
+                let (future, sender) = GpuFuture::new();
+                state.waiting.push(sender);
+                future
+            }
+        }
+    }
+}
+
+impl<T: WasiWebGpuView> gpu_futures::Host for WasiWebGpuImpl<T> {
+    fn map_buffer(
+        &mut self,
+        buffer: Resource<Buffer>,
+        mode: webgpu::GpuMapModeFlags,
+        offset: Option<webgpu::GpuSize64>,
+        size: Option<webgpu::GpuSize64>,
+    ) -> Resource<UnitFuture> {
+        let buffer = self.0.table().get(&buffer).unwrap();
+        let (buffer, errors, map_state) = (
+            buffer.buffer,
+            buffer.errors.clone(),
+            Arc::clone(&buffer.map_state),
+        );
+        let instance = self.0.instance();
+        let (future, sender) = GpuFuture::new();
+        // If the mode is invalid, `sender` is dropped here, which makes the future ready right away.
+        if let Some(host) = crate::host_map(mode, &errors) {
+            let callback_errors = errors.clone();
+            let previous_state = crate::map_state_pending(&map_state);
+            let op = wgpu_core::resource::BufferMapOperation {
+                host,
+                callback: Some(wgpu_core::resource::BufferMapCallback::from_rust(Box::new(
+                    move |result| {
+                        crate::map_state_settled(&map_state, previous_state, result.is_ok());
+                        callback_errors.check(result);
+                        let _ = sender.send(());
+                    },
+                ))),
+            };
+            // Errors are also passed to the callback, so they're reported there.
+            let _ =
+                instance.buffer_map_async::<crate::Backend>(buffer, offset.unwrap_or(0), size, op);
+            maintain(instance, errors);
+        }
+        self.0.table().push(future).unwrap()
+    }
+
+    fn submitted_work_done(&mut self, queue: Resource<Queue>) -> Resource<UnitFuture> {
+        let queue = self.0.table().get(&queue).unwrap();
+        let (queue, errors) = (queue.queue, queue.errors.clone());
+        let instance = self.0.instance();
+        let (future, sender) = GpuFuture::new();
+        let closure =
+            wgpu_core::device::queue::SubmittedWorkDoneClosure::from_rust(Box::new(move || {
+                let _ = sender.send(());
+            }));
+        if errors
+            .check(instance.queue_on_submitted_work_done::<crate::Backend>(queue, closure))
+            .is_some()
+        {
+            maintain(instance, errors);
+        }
+        self.0.table().push(future).unwrap()
+    }
+
+    fn lost(&mut self, device: Resource<Device>) -> Resource<DeviceLostFuture> {
+        let future = self.0.table().get(&device).unwrap().lost.future();
+        self.0.table().push(future).unwrap()
+    }
+
+    fn create_render_pipeline(
+        &mut self,
+        device: Resource<Device>,
+        descriptor: webgpu::GpuRenderPipelineDescriptor,
+    ) -> Resource<RenderPipelineFuture> {
+        // wgpu-core creates pipelines right away, so there's nothing to wait for.
+        let pipeline = webgpu::HostGpuDevice::create_render_pipeline(self, device, descriptor);
+        self.0.table().push(GpuFuture::ready(pipeline)).unwrap()
+    }
+
+    fn create_compute_pipeline(
+        &mut self,
+        device: Resource<Device>,
+        descriptor: webgpu::GpuComputePipelineDescriptor,
+    ) -> Resource<ComputePipelineFuture> {
+        let pipeline = webgpu::HostGpuDevice::create_compute_pipeline(self, device, descriptor);
+        self.0.table().push(GpuFuture::ready(pipeline)).unwrap()
+    }
+}
+
+impl<T: WasiWebGpuView> gpu_futures::HostGpuFuture for WasiWebGpuImpl<T> {
+    fn subscribe(&mut self, future: Resource<UnitFuture>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.0.table(), future).unwrap()
+    }
+
+    fn is_ready(&mut self, future: Resource<UnitFuture>) -> bool {
+        self.0.table().get_mut(&future).unwrap().is_ready()
+    }
+
+    fn drop(&mut self, future: Resource<UnitFuture>) -> wasmtime::Result<()> {
+        self.0.table().delete(future)?;
+        Ok(())
+    }
+}
+
+impl<T: WasiWebGpuView> gpu_futures::HostDeviceLostFuture for WasiWebGpuImpl<T> {
+    fn subscribe(&mut self, future: Resource<DeviceLostFuture>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.0.table(), future).unwrap()
+    }
+
+    fn get(&mut self, future: Resource<DeviceLostFuture>) -> Option<gpu_futures::DeviceLost> {
+        self.0.table().get_mut(&future).unwrap().take()
+    }
+
+    fn drop(&mut self, future: Resource<DeviceLostFuture>) -> wasmtime::Result<()> {
+        self.0.table().delete(future)?;
+        Ok(())
+    }
+}
+
+impl<T: WasiWebGpuView> gpu_futures::HostRenderPipelineFuture for WasiWebGpuImpl<T> {
+    fn subscribe(&mut self, future: Resource<RenderPipelineFuture>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.0.table(), future).unwrap()
+    }
+
+    fn get(&mut self, future: Resource<RenderPipelineFuture>) -> Option<Resource<RenderPipeline>> {
+        self.0.table().get_mut(&future).unwrap().take()
+    }
+
+    fn drop(&mut self, future: Resource<RenderPipelineFuture>) -> wasmtime::Result<()> {
+        // A pipeline that was never taken is the future's to release.
+        if let Some(pipeline) = self.0.table().delete(future)?.take() {
+            webgpu::HostGpuRenderPipeline::drop(self, pipeline)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: WasiWebGpuView> gpu_futures::HostComputePipelineFuture for WasiWebGpuImpl<T> {
+    fn subscribe(&mut self, future: Resource<ComputePipelineFuture>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.0.table(), future).unwrap()
+    }
+
+    fn get(
+        &mut self,
+        future: Resource<ComputePipelineFuture>,
+    ) -> Option<Resource<wgpu_core::id::ComputePipelineId>> {
+        self.0.table().get_mut(&future).unwrap().take()
+    }
+
+    fn drop(&mut self, future: Resource<ComputePipelineFuture>) -> wasmtime::Result<()> {
+        if let Some(pipeline) = self.0.table().delete(future)?.take() {
+            webgpu::HostGpuComputePipeline::drop(self, pipeline)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmtime_wasi::bindings::clocks::monotonic_clock;
+    use wasmtime_wasi::bindings::sync::io::poll;
+    use wasmtime_wasi::WasiView;
+
+    use super::*;
+    use crate::testing::{borrow, TestGpu, TestHost};
+    use crate::webgpu::{HostGpuBuffer, HostGpuQueue};
+
+    // source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+    const MAP_MODE_READ: u32 = 0x0001;
+
+    #[test]
+    fn futures_and_timers_are_ready_in_the_order_they_finish() {
+        let mut host = TestHost::new(Arc::new(crate::WebGpuInstanceBuilder::new().build()));
+        let (future, sender) = UnitFuture::new();
+        let future = host.table().push(future).unwrap();
+        let future = wasmtime_wasi::subscribe(host.table(), future).unwrap();
+        let timer = monotonic_clock::Host::subscribe_duration(&mut host, 10_000_000).unwrap();
+        // Stands in for a wgpu-core callback, finishing well after the timer.
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            let _ = sender.send(());
+        });
+
+        let ready = poll::Host::poll(&mut host, vec![borrow(&future), borrow(&timer)]).unwrap();
+        assert_eq!(ready, [1]);
+        let ready = poll::Host::poll(&mut host, vec![borrow(&future)]).unwrap();
+        assert_eq!(ready, [0]);
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn gpu_futures_are_polled_together_with_timers() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let data = [1, 2, 3, 4];
+        let buffer = gpu.buffer(
+            data.len() as u64,
+            wgpu_types::BufferUsages::MAP_READ | wgpu_types::BufferUsages::COPY_DST,
+        );
+        gpu.host
+            .write_buffer(
+                borrow(&gpu.queue),
+                borrow(&buffer),
+                0,
+                None,
+                data.to_vec(),
+                None,
+            )
+            .unwrap();
+        let mapped = gpu_futures::Host::map_buffer(
+            &mut gpu.host,
+            borrow(&buffer),
+            MAP_MODE_READ,
+            None,
+            None,
+        );
+        let mapped_pollable = gpu_futures::HostGpuFuture::subscribe(&mut gpu.host, borrow(&mapped));
+        let timer = monotonic_clock::Host::subscribe_duration(&mut gpu.host.0, 10_000_000).unwrap();
+
+        let mut ready = [false; 2];
+        while ready != [true; 2] {
+            let pollables = vec![borrow(&mapped_pollable), borrow(&timer)];
+            for i in poll::Host::poll(&mut gpu.host.0, pollables).unwrap() {
+                ready[i as usize] = true;
+            }
+        }
+
+        assert!(gpu_futures::HostGpuFuture::is_ready(
+            &mut gpu.host,
+            borrow(&mapped)
+        ));
+        gpu.host
+            .get_mapped_range(borrow(&buffer), None, None)
+            .unwrap();
+        let mapped_data = gpu
+            .host
+            .0
+            .table()
+            .get(&buffer)
+            .unwrap()
+            .mapped
+            .as_ref()
+            .unwrap()
+            .slice()
+            .to_vec();
+        assert_eq!(mapped_data, data);
+        gpu.assert_no_errors();
+    }
+}
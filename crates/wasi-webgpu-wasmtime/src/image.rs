@@ -0,0 +1,65 @@
+use crate::wasi::webgpu::webgpu::GpuImageError;
+use wasi_graphics_context_wasmtime::Frame;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Decoded image, as tightly packed rgba8 rows.
+pub(crate) struct RgbaImage {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) data: Vec<u8>,
+}
+
+pub(crate) fn decode_rgba8(data: &[u8]) -> Result<RgbaImage, GpuImageError> {
+    if !data.starts_with(&PNG_SIGNATURE) {
+        return Err(GpuImageError::UnsupportedFormat);
+    }
+    let mut decoder = png::Decoder::new(data);
+    // Expands palettes and low bit depths, and strips 16 bit channels, so only 8 bit color types are left.
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(decode_error)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(decode_error)?;
+    buffer.truncate(info.buffer_size());
+
+    let data = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], u8::MAX])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, u8::MAX]).collect(),
+        png::ColorType::Indexed => {
+            return Err(GpuImageError::Decode(
+                "Palette wasn't expanded while decoding".into(),
+            ))
+        }
+    };
+    Ok(RgbaImage {
+        width: info.width,
+        height: info.height,
+        data,
+    })
+}
+
+fn decode_error(error: png::DecodingError) -> GpuImageError {
+    GpuImageError::Decode(error.to_string())
+}
+
+/// Encodes a read back frame, which is always tightly packed rgba8.
+pub(crate) fn encode_png(frame: &Frame) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, frame.width, frame.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(&frame.data)
+        .map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
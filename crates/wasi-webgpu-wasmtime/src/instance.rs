@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+/// Instance for [`WasiWebGpuView::instance`](crate::WasiWebGpuView::instance) to hand out clones of.
+///
+/// Other options are left at their defaults, use [`WebGpuInstanceBuilder`] to set them.
+pub fn default_instance(
+    backends: wgpu_types::Backends,
+    flags: wgpu_types::InstanceFlags,
+) -> Arc<wgpu_core::global::Global> {
+    Arc::new(
+        WebGpuInstanceBuilder::new()
+            .backends(backends)
+            .flags(flags)
+            .build(),
+    )
+}
+
+/// Builds the `wgpu_core::global::Global` that [`WasiWebGpuView::instance`](crate::WasiWebGpuView::instance) hands out.
+///
+/// Host functions always dispatch to the platform's native backend (Vulkan, Metal, DX12, or GL elsewhere),
+/// so restricting `backends` to something else leaves guests without adapters.
+pub struct WebGpuInstanceBuilder {
+    name: String,
+    descriptor: wgpu_types::InstanceDescriptor,
+}
+
+impl Default for WebGpuInstanceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebGpuInstanceBuilder {
+    pub fn new() -> Self {
+        Self {
+            name: "webgpu".into(),
+            descriptor: wgpu_types::InstanceDescriptor {
+                backends: wgpu_types::Backends::all(),
+                flags: wgpu_types::InstanceFlags::from_build_config(),
+                dx12_shader_compiler: wgpu_types::Dx12Compiler::Fxc,
+                gles_minor_version: wgpu_types::Gles3MinorVersion::default(),
+            },
+        }
+    }
+
+    /// Name used by wgpu for debugging.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn backends(mut self, backends: wgpu_types::Backends) -> Self {
+        self.descriptor.backends = backends;
+        self
+    }
+
+    pub fn flags(mut self, flags: wgpu_types::InstanceFlags) -> Self {
+        self.descriptor.flags = flags;
+        self
+    }
+
+    pub fn dx12_shader_compiler(mut self, compiler: wgpu_types::Dx12Compiler) -> Self {
+        self.descriptor.dx12_shader_compiler = compiler;
+        self
+    }
+
+    pub fn gles_minor_version(mut self, version: wgpu_types::Gles3MinorVersion) -> Self {
+        self.descriptor.gles_minor_version = version;
+        self
+    }
+
+    pub fn build(self) -> wgpu_core::global::Global {
+        wgpu_core::global::Global::new(&self.name, self.descriptor)
+    }
+}
+
+/// Pins the adapter handed out by `gpu.request-adapter`, regardless of the guest's options.
+///
+/// Useful for reproducible output, e.g. golden-image tests, where the result must not depend on which gpu the machine happens to have.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdapterSelector {
+    /// The software adapter, see `force_fallback_adapter` in [`wgpu_types::RequestAdapterOptions`].
+    Fallback,
+    /// The first adapter whose name contains this string.
+    Named(String),
+}
+
+impl AdapterSelector {
+    pub(crate) fn select(
+        &self,
+        instance: &wgpu_core::global::Global,
+    ) -> Option<wgpu_core::id::AdapterId> {
+        let backends =
+            wgpu_types::Backends::from(<crate::Backend as wgpu_core::hal_api::HalApi>::VARIANT);
+        match self {
+            AdapterSelector::Fallback => instance
+                .request_adapter(
+                    &wgpu_types::RequestAdapterOptions {
+                        force_fallback_adapter: true,
+                        ..Default::default()
+                    },
+                    wgpu_core::instance::AdapterInputs::Mask(backends, |_| None),
+                )
+                .ok(),
+            AdapterSelector::Named(name) => {
+                let mut selected = None;
+                for adapter in instance.enumerate_adapters(
+                    wgpu_core::instance::AdapterInputs::Mask(backends, |_| None),
+                ) {
+                    let matches = instance
+                        .adapter_get_info::<crate::Backend>(adapter)
+                        .is_ok_and(|info| info.name.contains(name.as_str()));
+                    if matches && selected.is_none() {
+                        selected = Some(adapter);
+                    } else {
+                        instance.adapter_drop::<crate::Backend>(adapter);
+                    }
+                }
+                selected
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use wasmtime::component::Resource;
+
+use crate::{WasiWebGpuImpl, WasiWebGpuView};
+
+/// Labels of the gpu objects of a store, by the type and rep of their resource.
+///
+/// wgpu-core keeps the label an object was created with, but can't change it, and makes one up for objects without
+/// one. So labels are kept here instead, for `label` to hand back exactly what the guest set.
+#[derive(Clone, Default)]
+pub struct Labels(Arc<Mutex<HashMap<(TypeId, u32), String>>>);
+
+impl Labels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: WasiWebGpuView> WasiWebGpuImpl<T> {
+    /// The label of `resource`, empty if it has none or the view keeps no labels.
+    pub(crate) fn label_of<R: 'static>(&self, resource: &Resource<R>) -> String {
+        let Some(labels) = self.0.labels() else {
+            return String::new();
+        };
+        let labels = labels.0.lock().unwrap();
+        labels
+            .get(&(TypeId::of::<R>(), resource.rep()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Sets the label of `resource`. An empty label is the same as none.
+    pub(crate) fn store_label<R: 'static>(&self, resource: &Resource<R>, label: Option<String>) {
+        let Some(labels) = self.0.labels() else {
+            return;
+        };
+        let mut labels = labels.0.lock().unwrap();
+        let key = (TypeId::of::<R>(), resource.rep());
+        match label.filter(|label| !label.is_empty()) {
+            Some(label) => labels.insert(key, label),
+            None => labels.remove(&key),
+        };
+    }
+
+    /// Gives `resource` the label it was created with, and hands it back.
+    pub(crate) fn labelled<R: 'static>(
+        &self,
+        resource: Resource<R>,
+        label: Option<String>,
+    ) -> Resource<R> {
+        self.store_label(&resource, label);
+        resource
+    }
+
+    /// Forgets the label of a dropped resource, so the next resource that gets its rep doesn't have it.
+    pub(crate) fn forget_label<R: 'static>(&self, resource: &Resource<R>) {
+        self.store_label(resource, None);
+    }
+}
@@ -4,20 +4,52 @@
 // - Remove all unwraps.
 // - Implement all the drop handlers.
 
+use anyhow::Context;
 use callback_future::CallbackFuture;
 use core::slice;
 use futures::executor::block_on;
 use std::borrow::Cow;
-use std::sync::Arc;
-use std::{future::Future, mem};
+use std::mem;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use wasmtime::component::Resource;
-use wasmtime_wasi::WasiView;
+use wasmtime_wasi::{Subscribe, WasiView};
 use wgpu_core::id::SurfaceId;
 
 use crate::wasi::webgpu::webgpu;
-use wasi_graphics_context_wasmtime::{DisplayApi, DrawApi, GraphicsContext, GraphicsContextBuffer};
-
+use wasi_frame_buffer_wasmtime::FBBuffer;
+use wasi_graphics_context_wasmtime::{
+    ConfigureContextDesc, ContextFormat, DisplayApi, DrawApi, Frame, GraphicsContext,
+    GraphicsContextBuffer, OffscreenTarget, PresentStatus, TimeoutError,
+};
+
+use self::error::ErrorScopes;
+pub use self::error::{GpuError, PipelineError, UncapturedErrorEvent};
+use self::gpu_future::DeviceLostWatch;
+pub use self::gpu_future::{
+    ComputePipelineFuture, DeviceLostFuture, GpuFuture, RenderPipelineFuture, UnitFuture,
+};
+pub use self::instance::{default_instance, AdapterSelector, WebGpuInstanceBuilder};
+pub use self::labels::Labels;
+pub use self::live_devices::LiveDevices;
+use self::mipmaps::MipmapCache;
+pub use self::observer::{CountingObserver, GpuUsage, ObservedResource, WasiWebGpuObserver};
+pub use self::query_results::QueryResults;
+pub use self::quota::{GpuQuota, GpuQuotaLimits, GpuQuotaUsage};
+use self::recording::Call;
+pub use self::recording::{replay, Recorder};
+use self::render_bundle::{invalid_render_bundle, BakedBundle, BundleCommand, StaticLayerCache};
+pub use self::render_bundle::{RenderBundleEncoder, StaticLayer};
+pub use self::shared_buffer::{SharedBuffer, SharedBuffers, MAX_SHARED_BUFFER_SIZE};
+pub use self::spawner::{
+    ChannelSpawner, DynMainThreadSpawner, InlineSpawner, MainThreadPump, MainThreadSpawner,
+    MainThreadTask, SpawnError,
+};
+use self::texture_atlas::AtlasTexture;
+pub use self::texture_atlas::TextureAtlas;
 use self::to_core_conversions::ToCore;
+pub use self::vertex_pulling::VertexPullBuffer;
 
 /// Re-export of `wgpu_core` and `wgpu_types` so that runtime implementors don't need to keep track of what version of wgpu this crate is using.
 pub mod reexports {
@@ -44,25 +76,54 @@ pub(crate) type Backend = wgpu_core::api::Metal;
 pub(crate) type Backend = wgpu_core::api::Gl;
 
 // needed for wasmtime::component::bindgen! as it only looks in the current crate.
-pub(crate) use wgpu_core;
-pub(crate) use wgpu_types;
 wasmtime::component::bindgen!({
     path: "../../wit/",
     world: "example",
     async: {
         only_imports: [
             "[method]gpu-buffer.map-async",
+            "[method]gpu-device.lost",
         ],
     },
+    // Calls the spec answers with an exception rather than a gpu error, they trap the calling component.
+    trappable_imports: [
+        "[method]gpu.request-adapter",
+        "[method]gpu-adapter.request-device",
+        "[method]gpu-device.pop-error-scope",
+        "[method]gpu-queue.write-buffer",
+        "[method]gpu-buffer.get-mapped-range",
+        "[method]remote-buffer.length",
+        "[method]remote-buffer.get",
+        "[method]remote-buffer.set",
+        "[method]gpu-render-pipeline.get-bind-group-layout",
+        "[method]gpu-compute-pipeline.get-bind-group-layout",
+        "[method]gpu-render-pass-encoder.set-push-constants",
+        // Nothing the host could do instead, the guest is better off finding out right away.
+        "[method]gpu-device.onuncapturederror",
+        "[method]gpu-device.import-external-texture",
+        "[method]gpu-queue.copy-external-image-to-texture",
+        // No canvas contexts or compilation messages are handed out, but the calls still need a body.
+        "[method]gpu-canvas-context.canvas",
+        "[method]gpu-canvas-context.configure",
+        "[method]gpu-canvas-context.unconfigure",
+        "[method]gpu-canvas-context.get-current-texture",
+        "[method]gpu-compilation-message.message",
+        "[method]gpu-compilation-message.type",
+        "[method]gpu-compilation-message.line-num",
+        "[method]gpu-compilation-message.line-pos",
+        "[method]gpu-compilation-message.offset",
+        "[method]gpu-compilation-message.length",
+    ],
     with: {
+        "wasi:io": wasmtime_wasi::bindings::io,
         "wasi:webgpu/webgpu/gpu-adapter": wgpu_core::id::AdapterId,
         "wasi:webgpu/webgpu/gpu-device": Device,
-        "wasi:webgpu/webgpu/gpu-queue": wgpu_core::id::QueueId,
-        "wasi:webgpu/webgpu/gpu-command-encoder": wgpu_core::id::CommandEncoderId,
-        "wasi:webgpu/webgpu/gpu-render-pass-encoder": wgpu_core::command::RenderPass,
-        "wasi:webgpu/webgpu/gpu-compute-pass-encoder": wgpu_core::command::ComputePass,
+        "wasi:webgpu/webgpu/gpu-queue": Queue,
+        "wasi:webgpu/webgpu/gpu-command-encoder": CommandEncoder,
+        "wasi:webgpu/webgpu/gpu-render-pass-encoder": RenderPassEncoder,
+        "wasi:webgpu/webgpu/gpu-compute-pass-encoder": ComputePassEncoder,
         "wasi:webgpu/webgpu/gpu-shader-module": wgpu_core::id::ShaderModuleId,
-        "wasi:webgpu/webgpu/gpu-render-pipeline": wgpu_core::id::RenderPipelineId,
+        "wasi:webgpu/webgpu/gpu-render-pipeline": RenderPipeline,
         "wasi:webgpu/webgpu/gpu-command-buffer": wgpu_core::id::CommandBufferId,
         // "wasi:webgpu/webgpu/gpu-buffer": wgpu_core::id::BufferId,
         "wasi:webgpu/webgpu/gpu-buffer": Buffer,
@@ -71,14 +132,36 @@ wasmtime::component::bindgen!({
         "wasi:webgpu/webgpu/gpu-bind-group-layout": wgpu_core::id::BindGroupLayoutId,
         "wasi:webgpu/webgpu/gpu-sampler": wgpu_core::id::SamplerId,
         "wasi:webgpu/webgpu/gpu-supported-features": wgpu_types::Features,
-        "wasi:webgpu/webgpu/gpu-texture": wgpu_core::id::TextureId,
+        "wasi:webgpu/webgpu/gpu-texture": Texture,
         "wasi:webgpu/webgpu/gpu-compute-pipeline": wgpu_core::id::ComputePipelineId,
         "wasi:webgpu/webgpu/gpu-bind-group": wgpu_core::id::BindGroupId,
-        "wasi:webgpu/webgpu/gpu-texture-view": wgpu_core::id::TextureViewId,
+        "wasi:webgpu/webgpu/gpu-texture-view": TextureView,
         "wasi:webgpu/webgpu/gpu-adapter-info": wgpu_types::AdapterInfo,
-        "wasi:webgpu/webgpu/gpu-query-set": wgpu_core::id::QuerySetId,
+        "wasi:webgpu/webgpu/gpu-query-set": QuerySet,
         "wasi:webgpu/webgpu/gpu-supported-limits": wgpu_types::Limits,
+        "wasi:webgpu/webgpu/gpu-render-bundle-encoder": RenderBundleEncoder,
+        "wasi:webgpu/webgpu/gpu-render-bundle": wgpu_core::id::RenderBundleId,
+        "wasi:webgpu/webgpu/gpu-static-layer": StaticLayer,
+        "wasi:webgpu/webgpu/gpu-error": GpuError,
+        "wasi:webgpu/webgpu/gpu-validation-error": GpuError,
+        "wasi:webgpu/webgpu/gpu-out-of-memory-error": GpuError,
+        "wasi:webgpu/webgpu/gpu-internal-error": GpuError,
+        "wasi:webgpu/webgpu/gpu-uncaptured-error-event": UncapturedErrorEvent,
+        "wasi:webgpu/webgpu/gpu-pipeline-error": PipelineError,
+        "wasi:webgpu/webgpu/gpu-device-lost-info": wasi::webgpu::gpu_futures::DeviceLost,
+        "wasi:webgpu/webgpu/gpu-compilation-info": CompilationInfo,
+        "wasi:webgpu/webgpu/wgsl-language-features": WgslLanguageFeatures,
+        "wasi:webgpu/texture-atlas/texture-atlas": TextureAtlas,
+        "wasi:webgpu/shared-buffer/shared-buffer": SharedBuffer,
+        "wasi:webgpu/vertex-pulling/vertex-pull-buffer": VertexPullBuffer,
+        "wasi:webgpu/query-set-resolve-to-buffer/query-results": QueryResults,
+        "wasi:webgpu/gpu-futures/gpu-future": UnitFuture,
+        "wasi:webgpu/gpu-futures/device-lost-future": DeviceLostFuture,
+        "wasi:webgpu/gpu-futures/render-pipeline-future": RenderPipelineFuture,
+        "wasi:webgpu/gpu-futures/compute-pipeline-future": ComputePipelineFuture,
         "wasi:webgpu/graphics-context": wasi_graphics_context_wasmtime,
+        "wasi:webgpu/frame-buffer/frame-buffer": wasi_frame_buffer_wasmtime::FBBuffer,
+        "wasi:webgpu/frame-buffer/surface": wasi_frame_buffer_wasmtime::FBSurfaceArc,
     },
 });
 
@@ -94,6 +177,12 @@ where
 {
     let closure = type_annotate::<T, _>(|t| WasiWebGpuImpl(t));
     wasi::webgpu::webgpu::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::texture_atlas::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::shared_buffer::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::query_set_resolve_to_buffer::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::mipmap_generation::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::vertex_pulling::add_to_linker_get_host(l, closure)?;
+    wasi::webgpu::gpu_futures::add_to_linker_get_host(l, closure)?;
     Ok(())
 }
 
@@ -101,8 +190,54 @@ pub trait WasiWebGpuView: WasiView {
     fn instance(&self) -> Arc<wgpu_core::global::Global>;
 
     /// Provide the ability to run closure on the UI thread.
-    /// On platforms that don't require UI to run on the UI thread, this can just execute in place.
+    /// On platforms that don't require UI to run on the UI thread, this can just execute in place with [`InlineSpawner`].
     fn ui_thread_spawner(&self) -> Box<impl MainThreadSpawner>;
+
+    /// Adapter to hand out from `gpu.request-adapter`, instead of letting wgpu pick one.
+    fn adapter_selector(&self) -> Option<AdapterSelector> {
+        None
+    }
+
+    /// Where devices handed to the guest are kept track of, for hosts that release them after the store is gone.
+    fn live_devices(&self) -> Option<LiveDevices> {
+        None
+    }
+
+    /// Registry `shared-buffer`s are looked up in by name. Stores handed the same registry can share buffers.
+    /// Without one, creating or opening a shared buffer fails with `unavailable`.
+    fn shared_buffers(&self) -> Option<SharedBuffers> {
+        None
+    }
+
+    /// Where the labels the guest gives its gpu objects are kept, one per store.
+    /// Without one, every object's label is empty.
+    fn labels(&self) -> Option<Labels> {
+        None
+    }
+
+    /// Directory to record a wgpu api trace of every device the guest requests into, for replaying with wgpu's player.
+    /// Each device gets a `device-<n>` subdirectory.
+    ///
+    /// Traces contain everything the guest hands to the gpu, shader source included.
+    /// Only recorded with the `trace` feature, otherwise a warning is logged.
+    fn trace_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Told about the gpu resources the guest creates and drops, its submissions and its presents.
+    fn observer(&self) -> Option<Arc<dyn WasiWebGpuObserver>> {
+        None
+    }
+
+    /// Limits on the buffers and textures the guest can allocate. Without one, only the device's own limits apply.
+    fn quota(&self) -> Option<GpuQuota> {
+        None
+    }
+
+    /// Where to record the guest's webgpu calls, for [`replay`] to make them again without the guest.
+    fn recorder(&self) -> Option<Recorder> {
+        None
+    }
 }
 
 pub struct WasiWebGpuImpl<T>(pub T);
@@ -125,6 +260,38 @@ impl<T: WasiWebGpuView> WasiWebGpuView for WasiWebGpuImpl<T> {
     fn ui_thread_spawner(&self) -> Box<impl MainThreadSpawner + 'static> {
         self.0.ui_thread_spawner()
     }
+
+    fn adapter_selector(&self) -> Option<AdapterSelector> {
+        self.0.adapter_selector()
+    }
+
+    fn live_devices(&self) -> Option<LiveDevices> {
+        self.0.live_devices()
+    }
+
+    fn shared_buffers(&self) -> Option<SharedBuffers> {
+        self.0.shared_buffers()
+    }
+
+    fn labels(&self) -> Option<Labels> {
+        self.0.labels()
+    }
+
+    fn trace_path(&self) -> Option<PathBuf> {
+        self.0.trace_path()
+    }
+
+    fn observer(&self) -> Option<Arc<dyn WasiWebGpuObserver>> {
+        self.0.observer()
+    }
+
+    fn quota(&self) -> Option<GpuQuota> {
+        self.0.quota()
+    }
+
+    fn recorder(&self) -> Option<Recorder> {
+        self.0.recorder()
+    }
 }
 
 impl<T: ?Sized + WasiWebGpuView> WasiWebGpuView for &mut T {
@@ -135,70 +302,241 @@ impl<T: ?Sized + WasiWebGpuView> WasiWebGpuView for &mut T {
     fn ui_thread_spawner(&self) -> Box<impl MainThreadSpawner + 'static> {
         T::ui_thread_spawner(self)
     }
-}
 
-pub trait MainThreadSpawner: Send + Sync + 'static {
-    fn spawn<F, T>(&self, f: F) -> impl Future<Output = T>
-    where
-        F: FnOnce() -> T + Send + Sync + 'static,
-        T: Send + Sync + 'static;
+    fn adapter_selector(&self) -> Option<AdapterSelector> {
+        T::adapter_selector(self)
+    }
+
+    fn live_devices(&self) -> Option<LiveDevices> {
+        T::live_devices(self)
+    }
+
+    fn shared_buffers(&self) -> Option<SharedBuffers> {
+        T::shared_buffers(self)
+    }
+
+    fn labels(&self) -> Option<Labels> {
+        T::labels(self)
+    }
+
+    fn trace_path(&self) -> Option<PathBuf> {
+        T::trace_path(self)
+    }
+
+    fn observer(&self) -> Option<Arc<dyn WasiWebGpuObserver>> {
+        T::observer(self)
+    }
+
+    fn quota(&self) -> Option<GpuQuota> {
+        T::quota(self)
+    }
+
+    fn recorder(&self) -> Option<Recorder> {
+        T::recorder(self)
+    }
 }
 
 pub struct WebGpuSurface<GI, CS, I>
 where
     I: AsRef<wgpu_core::global::Global>,
     GI: Fn() -> I,
-    CS: Fn(&(dyn DisplayApi + Send + Sync)) -> SurfaceId,
+    CS: Fn(&(dyn DisplayApi + Send + Sync)) -> Result<SurfaceId, SpawnError>,
 {
     get_instance: GI,
     create_surface: CS,
     device_id: wgpu_core::id::DeviceId,
+    queue_id: wgpu_core::id::QueueId,
     adapter_id: wgpu_core::id::AdapterId,
+    errors: ErrorScopes,
     surface_id: Option<wgpu_core::id::SurfaceId>,
+    config: Option<wgpu_types::SurfaceConfiguration<Vec<wgpu_types::TextureFormat>>>,
+    // Format the guest configured the context with, if it asked for one.
+    requested_format: Option<wgpu_types::TextureFormat>,
+    // Used instead of the surface when the display has no window.
+    offscreen: Option<(OffscreenTarget, Texture)>,
+    // Surface texture handed out by the last `get_current_buffer`, until it's presented.
+    current_texture: Option<Texture>,
+    status: PresentStatus,
+    observer: Option<Arc<dyn WasiWebGpuObserver>>,
 }
 
+// The window can outlive the guest, e.g. when it's reused for the next one, and some backends won't create a second surface for it.
+impl<GI, CS, I> Drop for WebGpuSurface<GI, CS, I>
+where
+    I: AsRef<wgpu_core::global::Global>,
+    GI: Fn() -> I,
+    CS: Fn(&(dyn DisplayApi + Send + Sync)) -> Result<SurfaceId, SpawnError>,
+{
+    fn drop(&mut self) {
+        if let Some(surface_id) = self.surface_id.take() {
+            (self.get_instance)().as_ref().surface_drop(surface_id);
+        }
+    }
+}
+
+/// How long creating a surface waits for the ui thread, see [`MainThreadSpawner::spawn_with_timeout`].
+const SURFACE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where the ui thread puts the surface it created, unless the wait for it was given up on.
+#[derive(Default)]
+struct SurfaceSlot {
+    surface: Option<SurfaceId>,
+    cancelled: bool,
+}
+
+/// Format guests render into on headless displays. Matches what most surfaces prefer.
+const OFFSCREEN_FORMAT: wgpu_types::TextureFormat = wgpu_types::TextureFormat::Bgra8UnormSrgb;
+
 impl<GI, CS, I> DrawApi for WebGpuSurface<GI, CS, I>
 where
     I: AsRef<wgpu_core::global::Global>,
     GI: Fn() -> I,
-    CS: Fn(&(dyn DisplayApi + Send + Sync)) -> SurfaceId,
+    CS: Fn(&(dyn DisplayApi + Send + Sync)) -> Result<SurfaceId, SpawnError>,
 {
     fn get_current_buffer(&mut self) -> wasmtime::Result<GraphicsContextBuffer> {
-        let texture: wgpu_core::id::TextureId = (self.get_instance)()
+        if let Some((target, texture)) = &self.offscreen {
+            if (target.width(), target.height()) != (texture.size.width, texture.size.height) {
+                self.status = PresentStatus::Outdated;
+                anyhow::bail!("Offscreen canvas was resized");
+            }
+            self.status = PresentStatus::Ok;
+            let buff: GraphicsContextBuffer = Box::new(texture.clone()).into();
+            return Ok(buff);
+        }
+        let Some(surface_id) = self.surface_id else {
+            anyhow::bail!("No surface or offscreen texture, status: {:?}", self.status);
+        };
+        let output = (self.get_instance)()
             .as_ref()
-            .surface_get_current_texture::<crate::Backend>(self.surface_id.unwrap(), None)
-            .unwrap()
-            .texture_id
-            .unwrap();
-        let buff = Box::new(texture);
-        let buff: GraphicsContextBuffer = buff.into();
+            .surface_get_current_texture::<crate::Backend>(surface_id, None)?;
+        self.status = present_status(output.status);
+        let Some(texture) = output.texture_id else {
+            anyhow::bail!("No surface texture available, status: {:?}", self.status);
+        };
+        let config = self.config.as_ref().unwrap();
+        let texture = Texture {
+            texture,
+            size: wgpu_types::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu_types::TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            device: self.device_id,
+            queue: self.queue_id,
+            errors: self.errors.clone(),
+            owned: false,
+            _atlas_texture: None,
+        };
+        self.current_texture = Some(texture.clone());
+        let buff: GraphicsContextBuffer = Box::new(texture).into();
         Ok(buff)
     }
 
     fn present(&mut self) -> wasmtime::Result<()> {
-        (self.get_instance)()
-            .as_ref()
-            .surface_present::<crate::Backend>(self.surface_id.unwrap())
-            .unwrap();
+        if let Some((target, texture)) = &self.offscreen {
+            let frame = self.read_back(texture)?;
+            target.present(frame);
+        } else if let Some(surface_id) = self.surface_id {
+            self.current_texture = None;
+            let status = (self.get_instance)()
+                .as_ref()
+                .surface_present::<crate::Backend>(surface_id)?;
+            self.status = present_status(status);
+        }
+        if let Some(observer) = &self.observer {
+            observer.presented();
+        }
         Ok(())
     }
 
-    fn display_api_ready(&mut self, display: &Box<dyn DisplayApi + Send + Sync>) {
-        let surface_id = (self.create_surface)(display.as_ref());
+    fn present_status(&self) -> PresentStatus {
+        self.status
+    }
+
+    fn capture_frame(&mut self) -> Option<Frame> {
+        let texture = match &self.offscreen {
+            Some((_, texture)) => texture.clone(),
+            None => self.current_texture.clone()?,
+        };
+        if !texture.usage.contains(wgpu_types::TextureUsages::COPY_SRC) {
+            return None;
+        }
+        self.read_back(&texture).ok()
+    }
+
+    fn configure(&mut self, desc: &ConfigureContextDesc) {
+        self.requested_format = desc.format.map(|format| match format {
+            ContextFormat::Rgba16float => wgpu_types::TextureFormat::Rgba16Float,
+        });
+    }
+
+    fn display_api_ready(&mut self, display: &(dyn DisplayApi + Send + Sync)) {
+        if let Some(target) = display.offscreen_target() {
+            // Displays turn offscreen when their window closes, which leaves the surface for it unusable.
+            if let Some(surface_id) = self.surface_id.take() {
+                self.current_texture = None;
+                (self.get_instance)().as_ref().surface_drop(surface_id);
+            }
+            self.configure_offscreen(target);
+            return;
+        }
+
+        let surface_id = match self.surface_id {
+            Some(surface_id) if self.status != PresentStatus::Lost => surface_id,
+            _ => {
+                if let Some(surface_id) = self.surface_id.take() {
+                    (self.get_instance)().as_ref().surface_drop(surface_id);
+                }
+                match (self.create_surface)(display) {
+                    Ok(surface_id) => surface_id,
+                    Err(error) => {
+                        // Tried again when the guest gets its next buffer.
+                        log::warn!("Failed to create a surface: {error}");
+                        self.status = PresentStatus::Timeout;
+                        return;
+                    }
+                }
+            }
+        };
 
         let swapchain_capabilities = (self.get_instance)()
             .as_ref()
             .surface_get_capabilities::<crate::Backend>(surface_id, self.adapter_id)
             .unwrap();
-        let swapchain_format = swapchain_capabilities.formats[0];
+        let (swapchain_format, alpha_mode) = if display.transparent() {
+            transparent_surface_format(&swapchain_capabilities)
+        } else {
+            (
+                swapchain_capabilities.formats[0],
+                swapchain_capabilities.alpha_modes[0],
+            )
+        };
+        // Backends pick the color space from the format, e.g. extended linear sRGB for rgba16float.
+        let swapchain_format = match self.requested_format {
+            Some(format) if swapchain_capabilities.formats.contains(&format) => format,
+            Some(format) => {
+                log::warn!(
+                    "The surface can't present {format:?}, falling back to {swapchain_format:?}"
+                );
+                swapchain_format
+            }
+            None => swapchain_format,
+        };
 
         let config = wgpu_types::SurfaceConfiguration {
-            usage: wgpu_types::TextureUsages::RENDER_ATTACHMENT,
+            // Copying out is only needed for `capture_frame`, so it's fine if it isn't supported.
+            usage: wgpu_types::TextureUsages::RENDER_ATTACHMENT
+                | (swapchain_capabilities.usages & wgpu_types::TextureUsages::COPY_SRC),
             format: swapchain_format,
             width: display.width(),
             height: display.height(),
             present_mode: wgpu_types::PresentMode::Fifo,
-            alpha_mode: swapchain_capabilities.alpha_modes[0],
+            alpha_mode,
             view_formats: vec![swapchain_format],
             // TODO: not sure what the correct value is
             desired_maximum_frame_latency: 2,
@@ -209,7 +547,675 @@ where
             .surface_configure::<crate::Backend>(surface_id, self.device_id, &config);
 
         self.surface_id = Some(surface_id);
+        self.config = Some(config);
+        self.status = PresentStatus::Ok;
+    }
+}
+
+/// Format and alpha mode that let the compositor blend a transparent window with what's behind it.
+///
+/// Falls back to the opaque defaults, with a warning, if the surface can't do that.
+fn transparent_surface_format(
+    capabilities: &wgpu_types::SurfaceCapabilities,
+) -> (wgpu_types::TextureFormat, wgpu_types::CompositeAlphaMode) {
+    use wgpu_types::{CompositeAlphaMode, TextureFormat};
+    let format = capabilities.formats.iter().copied().find(|format| {
+        matches!(
+            format,
+            TextureFormat::Rgba8Unorm
+                | TextureFormat::Rgba8UnormSrgb
+                | TextureFormat::Bgra8Unorm
+                | TextureFormat::Bgra8UnormSrgb
+                | TextureFormat::Rgba16Float
+        )
+    });
+    let alpha_mode = [
+        CompositeAlphaMode::PreMultiplied,
+        CompositeAlphaMode::PostMultiplied,
+        CompositeAlphaMode::Inherit,
+    ]
+    .into_iter()
+    .find(|mode| capabilities.alpha_modes.contains(mode));
+    match (format, alpha_mode) {
+        (Some(format), Some(alpha_mode)) => (format, alpha_mode),
+        _ => {
+            log::warn!("Transparent windows aren't supported on this surface, drawing it opaque");
+            (capabilities.formats[0], capabilities.alpha_modes[0])
+        }
+    }
+}
+
+impl<GI, CS, I> WebGpuSurface<GI, CS, I>
+where
+    I: AsRef<wgpu_core::global::Global>,
+    GI: Fn() -> I,
+    CS: Fn(&(dyn DisplayApi + Send + Sync)) -> Result<SurfaceId, SpawnError>,
+{
+    /// (Re)creates the texture guests render into for a display without a window.
+    fn configure_offscreen(&mut self, target: OffscreenTarget) {
+        if let Some(format) = self.requested_format {
+            // Frames are read back as 8 bit rgba or bgra.
+            log::warn!(
+                "Offscreen canvases can't use {format:?}, falling back to {OFFSCREEN_FORMAT:?}"
+            );
+        }
+        let instance = (self.get_instance)();
+        let instance = instance.as_ref();
+        if let Some((_, texture)) = self.offscreen.take() {
+            instance.texture_drop::<crate::Backend>(texture.texture, false);
+        }
+        if target.width() == 0 || target.height() == 0 {
+            // Textures can't be empty. Checked again on the next frame, in case the canvas was resized.
+            self.status = PresentStatus::Outdated;
+            return;
+        }
+
+        let descriptor = wgpu_core::resource::TextureDescriptor {
+            label: Some("offscreen canvas".into()),
+            size: wgpu_types::Extent3d {
+                width: target.width(),
+                height: target.height(),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu_types::TextureDimension::D2,
+            format: OFFSCREEN_FORMAT,
+            usage: wgpu_types::TextureUsages::RENDER_ATTACHMENT
+                | wgpu_types::TextureUsages::COPY_SRC,
+            view_formats: vec![],
+        };
+        let texture = match core_result(instance.device_create_texture::<crate::Backend>(
+            self.device_id,
+            &descriptor,
+            None,
+        )) {
+            Ok(texture) => texture,
+            Err(error) => {
+                // E.g. for a canvas larger than the device allows. Tried again on the next frame.
+                log::warn!("Failed to create the offscreen canvas texture: {error}");
+                self.status = PresentStatus::Outdated;
+                return;
+            }
+        };
+
+        self.offscreen = Some((
+            target,
+            Texture {
+                texture,
+                size: descriptor.size,
+                mip_level_count: descriptor.mip_level_count,
+                sample_count: descriptor.sample_count,
+                dimension: descriptor.dimension,
+                format: descriptor.format,
+                usage: descriptor.usage,
+                device: self.device_id,
+                queue: self.queue_id,
+                errors: self.errors.clone(),
+                owned: false,
+                _atlas_texture: None,
+            },
+        ));
+        self.status = PresentStatus::Ok;
+    }
+
+    fn read_back(&self, texture: &Texture) -> wasmtime::Result<Frame> {
+        read_back_texture((self.get_instance)().as_ref(), texture)
+    }
+}
+
+/// Whether a texture of this format can be read back, and if its channels need swapping to rgba.
+fn read_back_bgra(format: wgpu_types::TextureFormat) -> Option<bool> {
+    match format {
+        wgpu_types::TextureFormat::Bgra8Unorm | wgpu_types::TextureFormat::Bgra8UnormSrgb => {
+            Some(true)
+        }
+        wgpu_types::TextureFormat::Rgba8Unorm | wgpu_types::TextureFormat::Rgba8UnormSrgb => {
+            Some(false)
+        }
+        _ => None,
+    }
+}
+
+/// Copies the first mip level of an 8 bit rgba or bgra texture back to the host, blocking until the gpu is done with it.
+fn read_back_texture(
+    instance: &wgpu_core::global::Global,
+    texture: &Texture,
+) -> wasmtime::Result<Frame> {
+    let Some(bgra) = read_back_bgra(texture.format) else {
+        anyhow::bail!("Can't read back textures of format {:?}", texture.format);
+    };
+    let size = wgpu_types::Extent3d {
+        depth_or_array_layers: 1,
+        ..texture.size
+    };
+
+    let row_size = size.width * 4;
+    let padded_row_size = row_size.div_ceil(wgpu_types::COPY_BYTES_PER_ROW_ALIGNMENT)
+        * wgpu_types::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer_size = padded_row_size as u64 * size.height as u64;
+
+    let buffer = core_result(instance.device_create_buffer::<crate::Backend>(
+        texture.device,
+        &wgpu_types::BufferDescriptor {
+            label: Some("texture read back".into()),
+            size: buffer_size,
+            usage: wgpu_types::BufferUsages::MAP_READ | wgpu_types::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+        None,
+    ))?;
+    let encoder = core_result(instance.device_create_command_encoder::<crate::Backend>(
+        texture.device,
+        &Default::default(),
+        None,
+    ))?;
+    instance.command_encoder_copy_texture_to_buffer::<crate::Backend>(
+        encoder,
+        &wgpu_types::ImageCopyTexture {
+            texture: texture.texture,
+            mip_level: 0,
+            origin: wgpu_types::Origin3d::ZERO,
+            aspect: wgpu_types::TextureAspect::All,
+        },
+        &wgpu_types::ImageCopyBuffer {
+            buffer,
+            layout: wgpu_types::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_size),
+                rows_per_image: None,
+            },
+        },
+        &size,
+    )?;
+    let command_buffer = core_result(
+        instance.command_encoder_finish::<crate::Backend>(encoder, &Default::default()),
+    )?;
+    instance.queue_submit::<crate::Backend>(texture.queue, &[command_buffer])?;
+
+    instance.buffer_map_async::<crate::Backend>(
+        buffer,
+        0,
+        Some(buffer_size),
+        wgpu_core::resource::BufferMapOperation {
+            host: wgpu_core::device::HostMap::Read,
+            callback: None,
+        },
+    )?;
+    instance.device_poll::<crate::Backend>(texture.device, wgpu_types::Maintain::Wait)?;
+    let (ptr, len) =
+        instance.buffer_get_mapped_range::<crate::Backend>(buffer, 0, Some(buffer_size))?;
+    let mapped = BufferPtr {
+        ptr,
+        len,
+        written: false,
+    };
+
+    let mut data = Vec::with_capacity((row_size * size.height) as usize);
+    for row in mapped.slice().chunks(padded_row_size as usize) {
+        for pixel in row[..row_size as usize].chunks(4) {
+            if bgra {
+                data.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+            } else {
+                data.extend_from_slice(pixel);
+            }
+        }
     }
+
+    instance.buffer_unmap::<crate::Backend>(buffer)?;
+    instance.buffer_drop::<crate::Backend>(buffer, false);
+
+    Ok(Frame {
+        width: size.width,
+        height: size.height,
+        data,
+    })
+}
+
+// Can't implement From, both types are foreign.
+fn present_status(status: wgpu_types::SurfaceStatus) -> PresentStatus {
+    match status {
+        wgpu_types::SurfaceStatus::Good => PresentStatus::Ok,
+        wgpu_types::SurfaceStatus::Suboptimal => PresentStatus::Suboptimal,
+        wgpu_types::SurfaceStatus::Timeout => PresentStatus::Timeout,
+        wgpu_types::SurfaceStatus::Outdated => PresentStatus::Outdated,
+        wgpu_types::SurfaceStatus::Lost => PresentStatus::Lost,
+    }
+}
+
+/// An invalid buffer, for creations refused before they reach wgpu-core.
+/// Empty usage is rejected before anything is allocated, leaving an invalid id like any failed creation does.
+fn invalid_buffer(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+) -> wgpu_core::id::BufferId {
+    let (buffer, _) = instance.device_create_buffer::<crate::Backend>(
+        device,
+        &wgpu_types::BufferDescriptor {
+            label: None,
+            size: 0,
+            usage: wgpu_types::BufferUsages::empty(),
+            mapped_at_creation: false,
+        },
+        None,
+    );
+    buffer
+}
+
+/// Like [`invalid_buffer`], for textures.
+fn invalid_texture(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+) -> wgpu_core::id::TextureId {
+    let (texture, _) = instance.device_create_texture::<crate::Backend>(
+        device,
+        &wgpu_types::TextureDescriptor {
+            label: None,
+            size: wgpu_types::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu_types::TextureDimension::D2,
+            format: wgpu_types::TextureFormat::Rgba8Unorm,
+            usage: wgpu_types::TextureUsages::empty(),
+            view_formats: vec![],
+        },
+        None,
+    );
+    texture
+}
+
+/// Like [`invalid_buffer`], for bind group layouts. Two entries at the same binding are always invalid.
+fn invalid_bind_group_layout(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+) -> wgpu_core::id::BindGroupLayoutId {
+    let entry = wgpu_types::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu_types::ShaderStages::empty(),
+        ty: wgpu_types::BindingType::Sampler(wgpu_types::SamplerBindingType::Filtering),
+        count: None,
+    };
+    let (bind_group_layout, _) = instance.device_create_bind_group_layout::<crate::Backend>(
+        device,
+        &wgpu_core::binding_model::BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![entry, entry].into(),
+        },
+        None,
+    );
+    bind_group_layout
+}
+
+/// Like [`invalid_buffer`], for pipeline layouts. A layout of an invalid bind group layout is invalid.
+fn invalid_pipeline_layout(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+) -> wgpu_core::id::PipelineLayoutId {
+    let bind_group_layout = invalid_bind_group_layout(instance, device);
+    let (pipeline_layout, _) = instance.device_create_pipeline_layout::<crate::Backend>(
+        device,
+        &wgpu_core::binding_model::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: vec![bind_group_layout].into(),
+            push_constant_ranges: vec![].into(),
+        },
+        None,
+    );
+    instance.bind_group_layout_drop::<crate::Backend>(bind_group_layout);
+    pipeline_layout
+}
+
+/// Like [`invalid_buffer`], for query sets. Query sets can't be empty.
+fn invalid_query_set(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+) -> wgpu_core::id::QuerySetId {
+    let (query_set, _) = instance.device_create_query_set::<crate::Backend>(
+        device,
+        &wgpu_core::resource::QuerySetDescriptor {
+            label: None,
+            ty: wgpu_types::QueryType::Occlusion,
+            count: 0,
+        },
+        None,
+    );
+    query_set
+}
+
+/// Creates the directory the trace of a new device is recorded into, under the view's trace path.
+fn device_trace_dir(trace_path: PathBuf) -> Option<PathBuf> {
+    #[cfg(feature = "trace")]
+    {
+        static DEVICES: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+        let device = DEVICES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let dir = trace_path.join(format!("device-{device}"));
+        match std::fs::create_dir_all(&dir) {
+            Ok(()) => Some(dir),
+            Err(e) => {
+                log::warn!(
+                    "Not recording a wgpu trace, can't create {}: {e}",
+                    dir.display()
+                );
+                None
+            }
+        }
+    }
+    #[cfg(not(feature = "trace"))]
+    {
+        log::warn!(
+            "Not recording a wgpu trace to {}, wasi-webgpu-wasmtime was built without the `trace` feature",
+            trace_path.display()
+        );
+        None
+    }
+}
+
+/// Checks that `data` covers `size` texels of `format`, counted in whole blocks for compressed formats.
+fn validate_texture_data(
+    format: wgpu_types::TextureFormat,
+    aspect: wgpu_types::TextureAspect,
+    data_len: usize,
+    layout: &wgpu_types::ImageDataLayout,
+    size: &wgpu_types::Extent3d,
+) -> Result<(), GpuError> {
+    let (block_width, block_height) = format.block_dimensions();
+    let Some(block_size) = format.block_copy_size(Some(aspect)) else {
+        return Err(GpuError::validation(format!(
+            "Aspect {aspect:?} of {format:?} can't be written to"
+        )));
+    };
+    if !size.width.is_multiple_of(block_width) || !size.height.is_multiple_of(block_height) {
+        return Err(GpuError::validation(format!(
+            "Copy size {}x{} isn't a multiple of the {block_width}x{block_height} block size of {format:?}",
+            size.width, size.height,
+        )));
+    }
+
+    let blocks_per_row = size.width / block_width;
+    let rows = size.height / block_height;
+    let row_size = blocks_per_row as u64 * block_size as u64;
+    let bytes_per_row = layout.bytes_per_row.map_or(row_size, u64::from);
+    let rows_per_image = layout.rows_per_image.unwrap_or(rows);
+    if bytes_per_row < row_size {
+        return Err(GpuError::validation(format!(
+            "bytes-per-row {bytes_per_row} is smaller than one row of {blocks_per_row} blocks of {block_size} bytes"
+        )));
+    }
+    if rows_per_image < rows {
+        return Err(GpuError::validation(format!(
+            "rows-per-image {rows_per_image} is smaller than the copy height of {rows} block rows"
+        )));
+    }
+
+    let required = if size.width == 0 || size.height == 0 || size.depth_or_array_layers == 0 {
+        Some(0)
+    } else {
+        let image_size = bytes_per_row * rows_per_image as u64;
+        // Only the last image and row can be cut short, the ones before take up their whole stride.
+        image_size
+            .checked_mul(size.depth_or_array_layers as u64 - 1)
+            .and_then(|images| images.checked_add(bytes_per_row * (rows as u64 - 1)))
+            .and_then(|rows| rows.checked_add(row_size))
+    };
+    let end = required.and_then(|required| layout.offset.checked_add(required));
+    if end.is_none_or(|end| end > data_len as u64) {
+        return Err(GpuError::validation(format!(
+            "Texture data is {data_len} bytes, but the copy needs {} bytes starting at offset {}",
+            required.map_or("more than 2^64".to_string(), |required| required
+                .to_string()),
+            layout.offset
+        )));
+    }
+    Ok(())
+}
+
+/// Every entry needs exactly one binding type. External textures aren't supported by wgpu, so they're rejected too.
+fn validate_bind_group_layout(
+    descriptor: &webgpu::GpuBindGroupLayoutDescriptor,
+) -> Result<(), GpuError> {
+    for entry in &descriptor.entries {
+        if entry.external_texture.is_some() {
+            return Err(GpuError::validation(format!(
+                "Bind group layout entry {} is an external texture, which isn't supported",
+                entry.binding
+            )));
+        }
+        let binding_types = [
+            entry.buffer.is_some(),
+            entry.sampler.is_some(),
+            entry.texture.is_some(),
+            entry.storage_texture.is_some(),
+        ];
+        match binding_types.iter().filter(|is_some| **is_some).count() {
+            1 => {}
+            0 => {
+                return Err(GpuError::validation(format!(
+                    "Bind group layout entry {} has no binding type",
+                    entry.binding
+                )))
+            }
+            _ => {
+                return Err(GpuError::validation(format!(
+                    "Bind group layout entry {} has more than one binding type",
+                    entry.binding
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Viewports have to be within the attachments and their depth range within 0 to 1, like wgpu-core checks once the pass
+/// ends. Passes without attachments are reported by wgpu-core, so only the depth range is checked for those.
+fn validate_viewport(
+    attachment_size: Option<(u32, u32)>,
+    [x, y, width, height]: [f32; 4],
+    depth: std::ops::RangeInclusive<f32>,
+) -> Result<(), GpuError> {
+    if let Some((attachment_width, attachment_height)) = attachment_size {
+        if x < 0.0
+            || y < 0.0
+            || width <= 0.0
+            || height <= 0.0
+            || x + width > attachment_width as f32
+            || y + height > attachment_height as f32
+        {
+            return Err(GpuError::validation(format!(
+                "Viewport {x}, {y}, {width}x{height} isn't within the {attachment_width}x{attachment_height} attachments"
+            )));
+        }
+    }
+    let (min_depth, max_depth) = depth.into_inner();
+    if !(0.0..=1.0).contains(&min_depth)
+        || !(0.0..=1.0).contains(&max_depth)
+        || min_depth > max_depth
+    {
+        return Err(GpuError::validation(format!(
+            "Viewport depth range {min_depth}..{max_depth} isn't an increasing range within 0 to 1"
+        )));
+    }
+    Ok(())
+}
+
+/// Push constant ranges need the push-constants feature and have to fit in max-push-constant-size.
+fn validate_push_constant_ranges(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+    ranges: &[webgpu::GpuPushConstantRange],
+) -> Result<(), GpuError> {
+    if ranges.is_empty() {
+        return Ok(());
+    }
+    let features = instance.device_features::<crate::Backend>(device).unwrap();
+    if !features.contains(wgpu_types::Features::PUSH_CONSTANTS) {
+        return Err(GpuError::validation(
+            "Push constant ranges require the push-constants feature",
+        ));
+    }
+    let max_push_constant_size = instance
+        .device_limits::<crate::Backend>(device)
+        .unwrap()
+        .max_push_constant_size;
+    for range in ranges {
+        if range.start > range.end || range.end > max_push_constant_size {
+            return Err(GpuError::validation(format!(
+                "Push constant range {}..{} exceeds max-push-constant-size {}",
+                range.start, range.end, max_push_constant_size
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Extents given as a list need 1 to 3 coordinates, see
+/// <https://www.w3.org/TR/webgpu/#abstract-opdef-validate-gpuextent3d-shape>.
+fn validate_extent(extent: &webgpu::GpuExtent3D) -> Result<(), GpuError> {
+    match extent {
+        webgpu::GpuExtent3D::ListGpuIntegerCoordinate(coordinates)
+            if !(1..=3).contains(&coordinates.len()) =>
+        {
+            Err(GpuError::validation(format!(
+                "Extent has {} coordinates, it needs 1 to 3",
+                coordinates.len()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `aspect` is one of `format`'s. Copies to or from buffers take a single aspect, so the depth and stencil
+/// of combined formats are copied separately, while copies between textures take all of them.
+/// source: https://www.w3.org/TR/webgpu/#abstract-opdef-validating-gpuimagecopytexture
+fn validate_copy_aspect(
+    format: wgpu_types::TextureFormat,
+    aspect: wgpu_types::TextureAspect,
+    with_buffer: bool,
+) -> Result<(), GpuError> {
+    if format.aspect_specific_format(aspect).is_none() {
+        return Err(GpuError::validation(format!(
+            "{format:?} has no {aspect:?} aspect to copy"
+        )));
+    }
+    if !format.is_combined_depth_stencil_format() {
+        return Ok(());
+    }
+    match (aspect, with_buffer) {
+        (wgpu_types::TextureAspect::All, true) => Err(GpuError::validation(format!(
+            "Copies between buffers and {format:?} textures need a depth-only or stencil-only aspect"
+        ))),
+        (wgpu_types::TextureAspect::All, false) => Ok(()),
+        (_, true) => Ok(()),
+        (_, false) => Err(GpuError::validation(format!(
+            "Copies between {format:?} textures need the all aspect, not {aspect:?}"
+        ))),
+    }
+}
+
+/// Checks an index buffer binding, which wgpu-core passes on to the backend as is: the offset has to be a multiple of
+/// the index size, and the bound range has to fit in the buffer.
+/// source: https://www.w3.org/TR/webgpu/#dom-gpurendercommandsmixin-setindexbuffer
+fn validate_index_buffer(
+    buffer: &Buffer,
+    format: wgpu_types::IndexFormat,
+    offset: u64,
+    size: Option<core::num::NonZeroU64>,
+) -> Result<(), GpuError> {
+    let index_size = match format {
+        wgpu_types::IndexFormat::Uint16 => 2,
+        wgpu_types::IndexFormat::Uint32 => 4,
+    };
+    if !offset.is_multiple_of(index_size) {
+        return Err(GpuError::validation(format!(
+            "Index buffer offset {offset} is not a multiple of {index_size}, the size of {format:?} indices"
+        )));
+    }
+    let end = size.map_or(Some(buffer.size), |size| offset.checked_add(size.get()));
+    if offset > buffer.size || end.is_none_or(|end| end > buffer.size) {
+        return Err(GpuError::validation(format!(
+            "Index buffer range at offset {offset} with size {size:?} is out of bounds of a buffer of size {}",
+            buffer.size
+        )));
+    }
+    Ok(())
+}
+
+/// Read-only aspects are neither loaded nor stored, so a depth-stencil attachment can't give them ops.
+/// source: https://www.w3.org/TR/webgpu/#dictdef-gpurenderpassdepthstencilattachment
+fn validate_read_only_ops(
+    attachment: &webgpu::GpuRenderPassDepthStencilAttachment,
+) -> Result<(), GpuError> {
+    let aspects = [
+        (
+            "depth",
+            attachment.depth_read_only,
+            attachment.depth_load_op.is_some() || attachment.depth_store_op.is_some(),
+        ),
+        (
+            "stencil",
+            attachment.stencil_read_only,
+            attachment.stencil_load_op.is_some() || attachment.stencil_store_op.is_some(),
+        ),
+    ];
+    for (aspect, read_only, has_ops) in aspects {
+        if read_only == Some(true) && has_ops {
+            return Err(GpuError::validation(format!(
+                "The {aspect} aspect of the depth-stencil attachment is read-only, so it can't have a load or store op"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the device can render to every format bundles are recorded for, so a bundle can't be created that no
+/// render pass on this device could execute.
+fn check_render_bundle_formats(
+    instance: &wgpu_core::global::Global,
+    adapter: wgpu_core::id::AdapterId,
+    device: wgpu_core::id::DeviceId,
+    descriptor: &wgpu_core::command::RenderBundleEncoderDescriptor,
+) -> Result<(), webgpu::GpuRenderBundleFormatError> {
+    let features = instance.device_features::<crate::Backend>(device).unwrap();
+    let renderable = |format: wgpu_types::TextureFormat| {
+        missing_format_features(features, format).is_empty()
+            && instance
+                .adapter_get_texture_format_features::<crate::Backend>(adapter, format)
+                .is_ok_and(|f| {
+                    f.allowed_usages
+                        .contains(wgpu_types::TextureUsages::RENDER_ATTACHMENT)
+                })
+    };
+    for &format in descriptor.color_formats.iter().flatten() {
+        if format.is_depth_stencil_format() || !renderable(format) {
+            return Err(webgpu::GpuRenderBundleFormatError::UnsupportedColorFormat(
+                format.into(),
+            ));
+        }
+    }
+    if let Some(depth_stencil) = &descriptor.depth_stencil {
+        let format = depth_stencil.format;
+        if !format.is_depth_stencil_format() || !renderable(format) {
+            return Err(
+                webgpu::GpuRenderBundleFormatError::UnsupportedDepthStencilFormat(format.into()),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Strip pipelines restart strips at the largest index, so indexed draws need the pipeline to say which index format
+/// that is.
+/// source: https://www.w3.org/TR/webgpu/#dom-gpuprimitivestate-stripindexformat
+fn validate_indexed_draw(strip_without_index_format: bool) -> Result<(), GpuError> {
+    if strip_without_index_format {
+        return Err(GpuError::validation(
+            "Indexed draws with a strip topology need the pipeline to set a strip index format",
+        ));
+    }
+    Ok(())
 }
 
 // ToCore trait used for resources, records, and variants.
@@ -217,10 +1223,32 @@ where
 mod enum_conversions;
 mod to_core_conversions;
 
+mod error;
+mod gpu_future;
+mod image;
+mod instance;
+mod labels;
+mod live_devices;
+mod mipmaps;
+mod observer;
+mod query_results;
+mod quota;
+mod recording;
+mod recording_codec;
+mod render_bundle;
+mod shared_buffer;
+mod spawner;
+#[cfg(test)]
+mod testing;
+mod texture_atlas;
+mod vertex_pulling;
+
 pub struct BufferPtr {
     // See https://bytecodealliance.zulipchat.com/#narrow/stream/206238-general/topic/Should.20wasi.20resources.20be.20stored.20behind.20a.20mutex.3F
     pub(crate) ptr: *mut u8,
     pub(crate) len: u64,
+    /// Whether the guest wrote to the range, so recordings only keep ranges the guest filled in.
+    pub(crate) written: bool,
 }
 impl BufferPtr {
     pub fn slice(&self) -> &[u8] {
@@ -237,40 +1265,209 @@ pub struct Buffer {
     buffer: wgpu_core::id::BufferId,
     mapped: Option<BufferPtr>,
     size: u64,
+    usage: wgpu_types::BufferUsages,
+    /// Shared with the callbacks of `map-async`, which wgpu-core runs on whichever thread polls the device.
+    map_state: Arc<Mutex<webgpu::GpuBufferMapState>>,
+    errors: ErrorScopes,
+    /// Whether dropping the resource drops wgpu-core's buffer, which buffers lent out by a vertex pull buffer don't.
+    owned: bool,
 }
 
+/// wgpu-core can't be queried for a texture's properties, so they're kept next to the id.
+#[derive(Clone)]
+pub struct Texture {
+    pub(crate) texture: wgpu_core::id::TextureId,
+    pub(crate) size: wgpu_types::Extent3d,
+    pub(crate) mip_level_count: u32,
+    pub(crate) sample_count: u32,
+    pub(crate) dimension: wgpu_types::TextureDimension,
+    pub(crate) format: wgpu_types::TextureFormat,
+    pub(crate) usage: wgpu_types::TextureUsages,
+    /// The device and queue the texture was created from, for reading it back.
+    pub(crate) device: wgpu_core::id::DeviceId,
+    pub(crate) queue: wgpu_core::id::QueueId,
+    /// Errors of the texture's methods go to the device it was created from.
+    pub(crate) errors: ErrorScopes,
+    /// Whether dropping the resource drops wgpu-core's texture, which textures lent out by canvases and atlases don't.
+    pub(crate) owned: bool,
+    /// For atlas textures, shared by the atlas and every texture it lent out, the last of which drops wgpu-core's.
+    pub(crate) _atlas_texture: Option<Arc<AtlasTexture>>,
+}
+
+/// Like [`Texture`], the view's size is kept next to the id, for render passes to validate rects against.
+pub struct TextureView {
+    pub(crate) view: wgpu_core::id::TextureViewId,
+    /// Width and height of the view's base mip level, which is what a render pass drawing into it covers.
+    pub(crate) size: (u32, u32),
+}
+
+/// Like [`Texture`], the query set's type and count are kept next to the id.
+pub struct QuerySet {
+    pub(crate) query_set: wgpu_core::id::QuerySetId,
+    pub(crate) ty: wgpu_types::QueryType,
+    pub(crate) count: u32,
+    /// wgpu-core can't destroy a query set before it's dropped, so `destroy` only marks it, for later uses to fail.
+    pub(crate) destroyed: bool,
+    pub(crate) errors: ErrorScopes,
+}
+
+impl QuerySet {
+    /// The id to write or resolve queries with, `None` for a destroyed set after reporting it as a validation error.
+    pub(crate) fn usable(&self) -> Option<wgpu_core::id::QuerySetId> {
+        if self.destroyed {
+            self.errors
+                .report(GpuError::validation("Query set was destroyed"));
+            return None;
+        }
+        Some(self.query_set)
+    }
+}
+
+pub struct Queue {
+    pub(crate) queue: wgpu_core::id::QueueId,
+    pub(crate) errors: ErrorScopes,
+}
+
+#[derive(Clone)]
+pub struct CommandEncoder {
+    pub(crate) encoder: wgpu_core::id::CommandEncoderId,
+    pub(crate) errors: ErrorScopes,
+}
+
+/// Keeps what the pass needs to validate draws next to wgpu-core's pass, which doesn't expose its state.
+pub struct RenderPassEncoder {
+    pub(crate) pass: wgpu_core::command::RenderPass,
+    /// Whether the current pipeline has a strip topology but no strip index format.
+    pub(crate) strip_without_index_format: bool,
+    /// Width and height of the attachments, `None` for passes without any, which wgpu-core reports.
+    pub(crate) attachment_size: Option<(u32, u32)>,
+    /// Ended passes stay in the table until the guest drops them, since the guest still holds the handle.
+    pub(crate) ended: bool,
+    pub(crate) errors: ErrorScopes,
+}
+
+impl RenderPassEncoder {
+    /// `None` once the pass is ended, after reporting the call as a validation error.
+    fn open(&mut self) -> Option<&mut Self> {
+        if self.ended {
+            self.errors
+                .report(GpuError::validation("Render pass was already ended"));
+            return None;
+        }
+        Some(self)
+    }
+}
+
+/// wgpu-core's compute pass, with the errors of the encoder it was begun on.
+pub struct ComputePassEncoder {
+    pub(crate) pass: wgpu_core::command::ComputePass,
+    /// Like [`RenderPassEncoder::ended`].
+    pub(crate) ended: bool,
+    pub(crate) errors: ErrorScopes,
+}
+
+impl ComputePassEncoder {
+    /// `None` once the pass is ended, after reporting the call as a validation error.
+    fn open(&mut self) -> Option<&mut Self> {
+        if self.ended {
+            self.errors
+                .report(GpuError::validation("Compute pass was already ended"));
+            return None;
+        }
+        Some(self)
+    }
+}
+
+/// wgpu-core can't be queried for a pipeline's primitive state, so what indexed draws check is kept next to the id.
 #[derive(Clone, Copy)]
+pub struct RenderPipeline {
+    pub(crate) pipeline: wgpu_core::id::RenderPipelineId,
+    pub(crate) strip_without_index_format: bool,
+}
+
+impl RenderPipeline {
+    fn new(
+        pipeline: wgpu_core::id::RenderPipelineId,
+        primitive: &wgpu_types::PrimitiveState,
+    ) -> Self {
+        Self {
+            pipeline,
+            strip_without_index_format: primitive.topology.is_strip()
+                && primitive.strip_index_format.is_none(),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Device {
     pub device: wgpu_core::id::DeviceId,
     pub queue: wgpu_core::id::QueueId,
     // only needed when calling surface.get_capabilities in connect_graphics_context. If table would have a way to get parent from child, we could get it from device.
     pub adapter: wgpu_core::id::AdapterId,
+    pub(crate) static_layers: StaticLayerCache,
+    pub(crate) mipmaps: MipmapCache,
+    pub(crate) errors: ErrorScopes,
+    pub(crate) lost: DeviceLostWatch,
 }
 
+/// The `gpu-compilation-info` resource. wgpu-core reports shader errors to the error scopes instead, so there are no
+/// messages to hand out.
+pub struct CompilationInfo;
+
+/// The `wgsl-language-features` resource. wgpu-core's naga doesn't support any of the optional language extensions.
+pub struct WgslLanguageFeatures;
+
 impl<T: WasiWebGpuView> webgpu::Host for WasiWebGpuImpl<T> {
     fn get_gpu(&mut self) -> Resource<webgpu::Gpu> {
-        Resource::new_own(0)
+        let recorded = self.record_creation(Call::GetGpu, ());
+        recorded.created(Resource::new_own(0))
     }
 }
 
 impl<T: WasiWebGpuView> webgpu::HostRemoteBuffer for WasiWebGpuImpl<T> {
-    fn length(&mut self, buffer: Resource<webgpu::RemoteBuffer>) -> u32 {
-        let buffer = self.0.table().get(&buffer).unwrap();
-        let len = buffer.mapped.as_ref().unwrap().len;
-        len as u32
+    fn length(&mut self, buffer: Resource<webgpu::RemoteBuffer>) -> wasmtime::Result<u32> {
+        let buffer = self.0.table().get(&buffer)?;
+        let remote_buffer = buffer
+            .mapped
+            .as_ref()
+            .context("Buffer is no longer mapped")?;
+        Ok(remote_buffer.len as u32)
     }
 
-    fn get(&mut self, buffer: Resource<webgpu::RemoteBuffer>, i: u32) -> u8 {
-        let buffer = self.0.table().get(&buffer).unwrap();
-        let remote_buffer = buffer.mapped.as_ref().unwrap();
-        let val = remote_buffer.slice()[i as usize];
-        val
+    fn get(&mut self, buffer: Resource<webgpu::RemoteBuffer>, i: u32) -> wasmtime::Result<u8> {
+        let buffer = self.0.table().get(&buffer)?;
+        let remote_buffer = buffer
+            .mapped
+            .as_ref()
+            .context("Buffer is no longer mapped")?;
+        let val = remote_buffer.slice().get(i as usize).with_context(|| {
+            format!(
+                "Index {i} is out of range of the {} byte mapped range",
+                remote_buffer.len
+            )
+        })?;
+        Ok(*val)
     }
 
-    fn set(&mut self, buffer: Resource<webgpu::RemoteBuffer>, i: u32, val: u8) {
-        let buffer = self.0.table().get_mut(&buffer).unwrap();
-        let remote_buffer = buffer.mapped.as_mut().unwrap();
-        remote_buffer.slice_mut()[i as usize] = val;
+    fn set(
+        &mut self,
+        buffer: Resource<webgpu::RemoteBuffer>,
+        i: u32,
+        val: u8,
+    ) -> wasmtime::Result<()> {
+        let buffer = self.0.table().get_mut(&buffer)?;
+        let remote_buffer = buffer
+            .mapped
+            .as_mut()
+            .context("Buffer is no longer mapped")?;
+        remote_buffer.written = true;
+        let len = remote_buffer.len;
+        let slot = remote_buffer
+            .slice_mut()
+            .get_mut(i as usize)
+            .with_context(|| format!("Index {i} is out of range of the {len} byte mapped range"))?;
+        *slot = val;
+        Ok(())
     }
 
     fn drop(&mut self, _rep: Resource<webgpu::RemoteBuffer>) -> wasmtime::Result<()> {
@@ -278,18 +1475,94 @@ impl<T: WasiWebGpuView> webgpu::HostRemoteBuffer for WasiWebGpuImpl<T> {
     }
 }
 
+impl<T: WasiWebGpuView> WasiWebGpuImpl<T> {
+    /// Hands `resource` to the view's observer, if it has one.
+    pub(crate) fn observe_created(&self, resource: ObservedResource) {
+        if let Some(observer) = self.0.observer() {
+            observer.created(resource);
+        }
+    }
+
+    fn observe_dropped(&self, resource: ObservedResource) {
+        if let Some(observer) = self.0.observer() {
+            observer.dropped(resource);
+        }
+    }
+
+    /// Creates a texture within the view's quota, reporting errors to `errors`.
+    fn device_create_texture(
+        &self,
+        device: wgpu_core::id::DeviceId,
+        descriptor: &wgpu_core::resource::TextureDescriptor,
+        errors: &ErrorScopes,
+    ) -> wgpu_core::id::TextureId {
+        let quota = self.0.quota();
+        let size = quota::texture_size(descriptor);
+        if let Some(Err(error)) = quota.as_ref().map(|quota| quota.check_texture(size)) {
+            errors.report(error);
+            return invalid_texture(&self.0.instance(), device);
+        }
+        let (texture, error) = self
+            .0
+            .instance()
+            .device_create_texture::<crate::Backend>(device, descriptor, None);
+        match error {
+            Some(error) => errors.report(GpuError::from_core(&error)),
+            None => {
+                if let Some(quota) = quota {
+                    quota.charge_texture(texture, size);
+                }
+            }
+        }
+        texture
+    }
+
+    fn device_create_render_pipeline(
+        &mut self,
+        device: &Resource<Device>,
+        descriptor: webgpu::GpuRenderPipelineDescriptor,
+    ) -> (
+        RenderPipeline,
+        Option<wgpu_core::pipeline::CreateRenderPipelineError>,
+    ) {
+        let host_device = self.0.table().get(device).unwrap().device;
+        let descriptor = descriptor.to_core(self.0.table());
+        let implicit_pipeline_ids = match descriptor.layout {
+            Some(_) => None,
+            None => Some(wgpu_core::device::ImplicitPipelineIds {
+                root_id: None,
+                group_ids: &[None; wgpu_core::MAX_BIND_GROUPS],
+            }),
+        };
+        let (pipeline, error) = self
+            .0
+            .instance()
+            .device_create_render_pipeline::<crate::Backend>(
+                host_device,
+                &descriptor,
+                None,
+                implicit_pipeline_ids,
+            );
+        (RenderPipeline::new(pipeline, &descriptor.primitive), error)
+    }
+}
+
+#[async_trait::async_trait]
 impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
     fn connect_graphics_context(
         &mut self,
         device: Resource<Device>,
         context: Resource<GraphicsContext>,
-    ) {
+    ) -> Result<(), TimeoutError> {
         let device = self.0.table().get(&device).unwrap();
         let device_id = device.device;
+        let queue_id = device.queue;
         let adapter_id = device.adapter;
+        let errors = device.errors.clone();
 
         let instance = Arc::downgrade(&self.0.instance());
         let surface_creator = self.0.ui_thread_spawner();
+        let observer = self.0.observer();
 
         let context = self.0.table().get_mut(&context).unwrap();
 
@@ -322,46 +1595,91 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
 
                     let display: &'static (dyn DisplayApi + Send + Sync) =
                         unsafe { mem::transmute(display) };
-                    block_on(surface_creator.spawn(move || unsafe {
-                        instance
-                            .instance_create_surface(
-                                display.display_handle().unwrap().as_raw(),
-                                display.window_handle().unwrap().as_raw(),
-                                None,
-                            )
-                            .unwrap()
-                    }))
+                    // The closure can still run after timing out, so it only gets to `display` while it's borrowed.
+                    let slot = Arc::new(Mutex::new(SurfaceSlot::default()));
+                    let timed_out = block_on(surface_creator.spawn_with_timeout(
+                        {
+                            let slot = Arc::clone(&slot);
+                            move || {
+                                let mut slot = slot.lock().unwrap();
+                                if !slot.cancelled {
+                                    slot.surface = Some(unsafe {
+                                        instance
+                                            .instance_create_surface(
+                                                display.display_handle().unwrap().as_raw(),
+                                                display.window_handle().unwrap().as_raw(),
+                                                None,
+                                            )
+                                            .unwrap()
+                                    });
+                                }
+                            }
+                        },
+                        SURFACE_TIMEOUT,
+                    ))
+                    .err();
+                    // Waits for a closure that's running right now, which might still create the surface in time.
+                    let mut slot = slot.lock().unwrap();
+                    slot.cancelled = true;
+                    match slot.surface.take() {
+                        Some(surface_id) => Ok(surface_id),
+                        None => Err(timed_out.expect("surface creation ran without a surface")),
+                    }
                 }
             },
             device_id,
+            queue_id,
             adapter_id,
+            errors,
             surface_id: None,
+            config: None,
+            requested_format: None,
+            offscreen: None,
+            current_texture: None,
+            status: PresentStatus::Ok,
+            observer,
         };
 
         context.connect_draw_api(Box::new(surface));
+        if context.present_status() == PresentStatus::Timeout {
+            return Err(TimeoutError {
+                timeout_ms: SURFACE_TIMEOUT.as_millis() as u32,
+            });
+        }
+        Ok(())
     }
 
     fn create_command_encoder(
         &mut self,
         device: Resource<Device>,
         descriptor: Option<webgpu::GpuCommandEncoderDescriptor>,
-    ) -> Resource<wgpu_core::id::CommandEncoderId> {
+    ) -> Resource<CommandEncoder> {
+        let recorded = self.record_creation(Call::CreateCommandEncoder, (&device, &descriptor));
+        let label = descriptor.as_ref().and_then(|d| d.label.clone());
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
-        let command_encoder = core_result(
+        let command_encoder = errors.handle(
             self.0
                 .instance()
                 .device_create_command_encoder::<crate::Backend>(
                     device,
                     &descriptor
-                        .map(|d| d.to_core(&self.0.table()))
+                        .map(|d| d.to_core(self.0.table()))
                         .unwrap_or_default(),
                     None,
                 ),
-        )
-        .unwrap();
+        );
 
-        self.0.table().push(command_encoder).unwrap()
+        let command_encoder = self
+            .0
+            .table()
+            .push(CommandEncoder {
+                encoder: command_encoder,
+                errors,
+            })
+            .unwrap();
+        recorded.created(self.labelled(command_encoder, label))
     }
 
     fn create_shader_module(
@@ -369,59 +1687,152 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<Device>,
         descriptor: webgpu::GpuShaderModuleDescriptor,
     ) -> Resource<webgpu::GpuShaderModule> {
+        let recorded = self.record_creation(Call::CreateShaderModule, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
         let code =
             wgpu_core::pipeline::ShaderModuleSource::Wgsl(Cow::Owned(descriptor.code.to_owned()));
-        let shader = core_result(
+        let shader = errors.handle(
             self.0
                 .instance()
                 .device_create_shader_module::<crate::Backend>(
                     device,
-                    &descriptor.to_core(&self.0.table()),
+                    &descriptor.to_core(self.0.table()),
                     code,
                     None,
                 ),
-        )
-        .unwrap();
+        );
 
-        self.0.table().push(shader).unwrap()
+        let shader = self.0.table().push(shader).unwrap();
+        recorded.created(self.labelled(shader, label))
     }
 
     fn create_render_pipeline(
         &mut self,
         device: Resource<Device>,
         descriptor: webgpu::GpuRenderPipelineDescriptor,
-    ) -> Resource<wgpu_core::id::RenderPipelineId> {
-        let host_device = self.0.table().get(&device).unwrap().device;
+    ) -> Resource<RenderPipeline> {
+        let recorded = self.record_creation(Call::CreateRenderPipeline, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
+        let (render_pipeline, error) = self.device_create_render_pipeline(&device, descriptor);
+        if let Some(error) = error {
+            errors.report(GpuError::from_core(&error));
+        }
+        self.observe_created(ObservedResource::RenderPipeline);
+        let render_pipeline = self.0.table().push(render_pipeline).unwrap();
+        recorded.created(self.labelled(render_pipeline, label))
+    }
 
-        let descriptor = descriptor.to_core(&self.0.table());
+    fn try_create_render_pipeline(
+        &mut self,
+        device: Resource<Device>,
+        descriptor: webgpu::GpuRenderPipelineDescriptor,
+    ) -> Result<Resource<RenderPipeline>, webgpu::GpuRenderPipelineError> {
+        // Pipelines that fail aren't recorded, they don't leave anything behind to replay.
+        let recorded = self.record_creation(Call::TryCreateRenderPipeline, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        match self.device_create_render_pipeline(&device, descriptor) {
+            (render_pipeline, None) => {
+                self.observe_created(ObservedResource::RenderPipeline);
+                let render_pipeline = self.0.table().push(render_pipeline).unwrap();
+                Ok(recorded.created(self.labelled(render_pipeline, label)))
+            }
+            (render_pipeline, Some(error)) => {
+                self.0
+                    .instance()
+                    .render_pipeline_drop::<crate::Backend>(render_pipeline.pipeline);
+                Err(error::render_pipeline_error(&error))
+            }
+        }
+    }
 
-        let implicit_pipeline_ids = match descriptor.layout {
-            Some(_) => None,
-            None => Some(wgpu_core::device::ImplicitPipelineIds {
-                root_id: None,
-                group_ids: &[None; wgpu_core::MAX_BIND_GROUPS],
-            }),
+    fn create_texture_from_image(
+        &mut self,
+        device: Resource<Device>,
+        descriptor: webgpu::GpuImageTextureDescriptor,
+    ) -> Result<webgpu::GpuImageTexture, webgpu::GpuImageError> {
+        // Images that can't be decoded don't create a texture, so aren't recorded either.
+        let recorded = self.record_creation(Call::CreateTextureFromImage, (&device, &descriptor));
+        let image = image::decode_rgba8(&descriptor.data)?;
+        let device = self.0.table().get(&device).unwrap();
+        let (device, queue, errors) = (device.device, device.queue, device.errors.clone());
+
+        let size = wgpu_types::Extent3d {
+            width: image.width,
+            height: image.height,
+            depth_or_array_layers: 1,
         };
-        let render_pipeline = core_result(
-            self.0
-                .instance()
-                .device_create_render_pipeline::<crate::Backend>(
-                    host_device,
-                    &descriptor,
-                    None,
-                    implicit_pipeline_ids,
-                ),
-        )
-        .unwrap();
+        let texture_descriptor = wgpu_types::TextureDescriptor {
+            label: descriptor.label.as_deref().map(|l| l.into()),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu_types::TextureDimension::D2,
+            format: if descriptor.srgb {
+                wgpu_types::TextureFormat::Rgba8UnormSrgb
+            } else {
+                wgpu_types::TextureFormat::Rgba8Unorm
+            },
+            usage: wgpu_types::TextureUsages::from_bits_retain(descriptor.usage)
+                | wgpu_types::TextureUsages::COPY_DST,
+            view_formats: vec![],
+        };
+        // Images too large for the device end up as gpu errors, like any other texture would.
+        let texture = self.device_create_texture(device, &texture_descriptor, &errors);
+        errors.check(self.0.instance().queue_write_texture::<crate::Backend>(
+            queue,
+            &wgpu_types::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu_types::Origin3d::ZERO,
+                aspect: wgpu_types::TextureAspect::All,
+            },
+            &image.data,
+            &wgpu_types::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(image.width * 4),
+                rows_per_image: None,
+            },
+            &size,
+        ));
 
-        self.0.table().push_child(render_pipeline, &device).unwrap()
+        self.observe_created(ObservedResource::Texture);
+        let texture = self
+            .0
+            .table()
+            .push(Texture {
+                texture,
+                size,
+                mip_level_count: texture_descriptor.mip_level_count,
+                sample_count: texture_descriptor.sample_count,
+                dimension: texture_descriptor.dimension,
+                format: texture_descriptor.format,
+                usage: texture_descriptor.usage,
+                device,
+                queue,
+                errors,
+                owned: true,
+                _atlas_texture: None,
+            })
+            .unwrap();
+        Ok(webgpu::GpuImageTexture {
+            texture: recorded.created(self.labelled(texture, descriptor.label)),
+            width: image.width,
+            height: image.height,
+        })
     }
 
-    fn queue(&mut self, device: Resource<Device>) -> Resource<wgpu_core::id::QueueId> {
-        let queue = self.0.table().get(&device).unwrap().queue;
-        self.0.table().push(queue).unwrap()
+    fn queue(&mut self, device: Resource<Device>) -> Resource<Queue> {
+        let recorded = self.record_creation(Call::Queue, &device);
+        let device = self.0.table().get(&device).unwrap();
+        let queue = Queue {
+            queue: device.queue,
+            errors: device.errors.clone(),
+        };
+        recorded.created(self.0.table().push(queue).unwrap())
     }
 
     fn features(
@@ -450,8 +1861,15 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         self.0.table().push(limits).unwrap()
     }
 
-    fn destroy(&mut self, _device: Resource<webgpu::GpuDevice>) {
-        todo!()
+    fn destroy(&mut self, device: Resource<webgpu::GpuDevice>) {
+        self.record(Call::DestroyDevice, &device);
+        let device = self.0.table().get(&device).unwrap();
+        let (device, errors) = (device.device, device.errors.clone());
+        let instance = self.0.instance();
+        instance.device_destroy::<crate::Backend>(device);
+        // wgpu-core loses the device once it's polled, which resolves `lost` without waiting for the guest's next
+        // submission.
+        gpu_future::maintain(instance, errors);
     }
 
     fn create_buffer(
@@ -459,23 +1877,54 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<webgpu::GpuDevice>,
         descriptor: webgpu::GpuBufferDescriptor,
     ) -> Resource<webgpu::GpuBuffer> {
+        let recorded = self.record_creation(Call::CreateBuffer, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
         let size = descriptor.size;
-        let buffer = core_result(self.0.instance().device_create_buffer::<crate::Backend>(
-            device,
-            &descriptor.to_core(&self.0.table()),
-            None,
-        ))
-        .unwrap();
+        let usage = wgpu_types::BufferUsages::from_bits_retain(descriptor.usage);
+        let map_state = match descriptor.mapped_at_creation {
+            Some(true) => webgpu::GpuBufferMapState::Mapped,
+            _ => webgpu::GpuBufferMapState::Unmapped,
+        };
+        let quota = self.0.quota();
+        let buffer = match quota.as_ref().map(|quota| quota.check_buffer(size)) {
+            Some(Err(error)) => {
+                errors.report(error);
+                invalid_buffer(&self.0.instance(), device)
+            }
+            _ => {
+                let (buffer, error) = self.0.instance().device_create_buffer::<crate::Backend>(
+                    device,
+                    &descriptor.to_core(self.0.table()),
+                    None,
+                );
+                match error {
+                    Some(error) => errors.report(GpuError::from_core(&error)),
+                    None => {
+                        if let Some(quota) = quota {
+                            quota.charge_buffer(buffer, size);
+                        }
+                    }
+                }
+                buffer
+            }
+        };
 
         let buffer = Buffer {
             buffer,
             mapped: None,
             size,
+            usage,
+            map_state: Arc::new(Mutex::new(map_state)),
+            errors,
+            owned: true,
         };
 
-        self.0.table().push(buffer).unwrap()
+        self.observe_created(ObservedResource::Buffer { size });
+        let buffer = self.0.table().push(buffer).unwrap();
+        recorded.created(self.labelled(buffer, label))
     }
 
     fn create_texture(
@@ -483,15 +1932,56 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<webgpu::GpuDevice>,
         descriptor: webgpu::GpuTextureDescriptor,
     ) -> Resource<webgpu::GpuTexture> {
+        let recorded = self.record_creation(Call::CreateTexture, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
+        let queue = self.0.table().get(&device).unwrap().queue;
         let device = self.0.table().get(&device).unwrap().device;
-        let texture = core_result(self.0.instance().device_create_texture::<crate::Backend>(
-            device,
-            &descriptor.to_core(&self.0.table()),
-            None,
-        ))
-        .unwrap();
+        let size_error = validate_extent(&descriptor.size).err();
+        let descriptor = descriptor.to_core(self.0.table());
+
+        let features = self
+            .instance()
+            .device_features::<crate::Backend>(device)
+            .unwrap();
+        let missing_features = missing_format_features(features, descriptor.format);
+        let error = size_error.or_else(|| {
+            (!missing_features.is_empty()).then(|| {
+                GpuError::validation(format!(
+                    "Texture format {:?} requires features {missing_features:?}",
+                    descriptor.format
+                ))
+            })
+        });
+
+        let texture = match error {
+            Some(error) => {
+                errors.report(error);
+                invalid_texture(&self.instance(), device)
+            }
+            None => self.device_create_texture(device, &descriptor, &errors),
+        };
 
-        self.0.table().push(texture).unwrap()
+        self.observe_created(ObservedResource::Texture);
+        let texture = self
+            .0
+            .table()
+            .push(Texture {
+                texture,
+                size: descriptor.size,
+                mip_level_count: descriptor.mip_level_count,
+                sample_count: descriptor.sample_count,
+                dimension: descriptor.dimension,
+                format: descriptor.format,
+                usage: descriptor.usage,
+                device,
+                queue,
+                errors,
+                owned: true,
+                _atlas_texture: None,
+            })
+            .unwrap();
+        recorded.created(self.labelled(texture, label))
     }
 
     fn create_sampler(
@@ -499,26 +1989,43 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<webgpu::GpuDevice>,
         descriptor: Option<webgpu::GpuSamplerDescriptor>,
     ) -> Resource<webgpu::GpuSampler> {
+        let recorded = self.record_creation(Call::CreateSampler, (&device, &descriptor));
+        let label = descriptor.as_ref().and_then(|d| d.label.clone());
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
-        let descriptor = descriptor.unwrap();
-
-        let sampler = core_result(self.0.instance().device_create_sampler::<crate::Backend>(
+        // source: https://www.w3.org/TR/webgpu/#dom-gpudevice-createsampler
+        let descriptor = descriptor.unwrap_or(webgpu::GpuSamplerDescriptor {
+            label: None,
+            address_mode_u: None,
+            address_mode_v: None,
+            address_mode_w: None,
+            mag_filter: None,
+            min_filter: None,
+            mipmap_filter: None,
+            lod_min_clamp: None,
+            lod_max_clamp: None,
+            compare: None,
+            max_anisotropy: None,
+        });
+
+        let sampler = errors.handle(self.0.instance().device_create_sampler::<crate::Backend>(
             device,
-            &descriptor.to_core(&self.0.table()),
+            &descriptor.to_core(self.0.table()),
             None,
-        ))
-        .unwrap();
+        ));
 
-        self.0.table().push(sampler).unwrap()
+        let sampler = self.0.table().push(sampler).unwrap();
+        recorded.created(self.labelled(sampler, label))
     }
 
     fn import_external_texture(
         &mut self,
         _device: Resource<webgpu::GpuDevice>,
         _descriptor: webgpu::GpuExternalTextureDescriptor,
-    ) -> Resource<webgpu::GpuExternalTexture> {
-        todo!()
+    ) -> wasmtime::Result<Resource<webgpu::GpuExternalTexture>> {
+        // There are no video frames or image elements to import them from.
+        anyhow::bail!("External textures aren't supported")
     }
 
     fn create_bind_group_layout(
@@ -526,20 +2033,29 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<webgpu::GpuDevice>,
         descriptor: webgpu::GpuBindGroupLayoutDescriptor,
     ) -> Resource<webgpu::GpuBindGroupLayout> {
+        let recorded = self.record_creation(Call::CreateBindGroupLayout, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
-        let bind_group_layout = core_result(
-            self.0
-                .instance()
-                .device_create_bind_group_layout::<crate::Backend>(
-                    device,
-                    &descriptor.to_core(&self.0.table()),
-                    None,
-                ),
-        )
-        .unwrap();
+        let bind_group_layout = match validate_bind_group_layout(&descriptor) {
+            Ok(()) => errors.handle(
+                self.0
+                    .instance()
+                    .device_create_bind_group_layout::<crate::Backend>(
+                        device,
+                        &descriptor.to_core(self.0.table()),
+                        None,
+                    ),
+            ),
+            Err(error) => {
+                errors.report(error);
+                invalid_bind_group_layout(&self.instance(), device)
+            }
+        };
 
-        self.0.table().push(bind_group_layout).unwrap()
+        let bind_group_layout = self.0.table().push(bind_group_layout).unwrap();
+        recorded.created(self.labelled(bind_group_layout, label))
     }
 
     fn create_pipeline_layout(
@@ -547,20 +2063,34 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<webgpu::GpuDevice>,
         descriptor: webgpu::GpuPipelineLayoutDescriptor,
     ) -> Resource<webgpu::GpuPipelineLayout> {
+        let recorded = self.record_creation(Call::CreatePipelineLayout, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
-        let pipeline_layout = core_result(
-            self.0
-                .instance()
-                .device_create_pipeline_layout::<crate::Backend>(
-                    device,
-                    &descriptor.to_core(&self.0.table()),
-                    None,
-                ),
-        )
-        .unwrap();
+        let ranges = descriptor
+            .push_constant_ranges
+            .as_deref()
+            .unwrap_or_default();
+        let pipeline_layout = match validate_push_constant_ranges(&self.instance(), device, ranges)
+        {
+            Ok(()) => errors.handle(
+                self.0
+                    .instance()
+                    .device_create_pipeline_layout::<crate::Backend>(
+                        device,
+                        &descriptor.to_core(self.0.table()),
+                        None,
+                    ),
+            ),
+            Err(error) => {
+                errors.report(error);
+                invalid_pipeline_layout(&self.instance(), device)
+            }
+        };
 
-        self.0.table().push(pipeline_layout).unwrap()
+        let pipeline_layout = self.0.table().push(pipeline_layout).unwrap();
+        recorded.created(self.labelled(pipeline_layout, label))
     }
 
     fn create_bind_group(
@@ -568,20 +2098,23 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<webgpu::GpuDevice>,
         descriptor: webgpu::GpuBindGroupDescriptor,
     ) -> Resource<webgpu::GpuBindGroup> {
+        let recorded = self.record_creation(Call::CreateBindGroup, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
-        let bind_group = core_result(
+        let bind_group = errors.handle(
             self.0
                 .instance()
                 .device_create_bind_group::<crate::Backend>(
                     device,
-                    &descriptor.to_core(&self.0.table()),
+                    &descriptor.to_core(self.0.table()),
                     None,
                 ),
-        )
-        .unwrap();
+        );
 
-        self.0.table().push(bind_group).unwrap()
+        let bind_group = self.0.table().push(bind_group).unwrap();
+        recorded.created(self.labelled(bind_group, label))
     }
 
     fn create_compute_pipeline(
@@ -589,102 +2122,225 @@ impl<T: WasiWebGpuView> webgpu::HostGpuDevice for WasiWebGpuImpl<T> {
         device: Resource<webgpu::GpuDevice>,
         descriptor: webgpu::GpuComputePipelineDescriptor,
     ) -> Resource<webgpu::GpuComputePipeline> {
+        let recorded = self.record_creation(Call::CreateComputePipeline, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
         let device = self.0.table().get(&device).unwrap().device;
 
-        let implicit_pipeline_ids = match &descriptor.layout {
-            webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuPipelineLayout(_) => None,
-            webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(mode) => match mode {
-                webgpu::GpuAutoLayoutMode::Auto => Some(wgpu_core::device::ImplicitPipelineIds {
-                    root_id: None,
-                    group_ids: &[None; wgpu_core::MAX_BIND_GROUPS],
-                }),
-            },
+        let descriptor = descriptor.to_core(self.0.table());
+
+        let implicit_pipeline_ids = match descriptor.layout {
+            Some(_) => None,
+            None => Some(wgpu_core::device::ImplicitPipelineIds {
+                root_id: None,
+                group_ids: &[None; wgpu_core::MAX_BIND_GROUPS],
+            }),
         };
 
-        let compute_pipeline = core_result(
+        let compute_pipeline = errors.handle(
             self.0
                 .instance()
                 .device_create_compute_pipeline::<crate::Backend>(
                     device,
-                    &descriptor.to_core(&self.0.table()),
+                    &descriptor,
                     None,
                     implicit_pipeline_ids,
                 ),
-        )
-        .unwrap();
-        self.0.table().push(compute_pipeline).unwrap()
-    }
-
-    // fn create_compute_pipeline_async(
-    //     &mut self,
-    //     self_: Resource<webgpu::GpuDevice>,
-    //     descriptor: webgpu::GpuComputePipelineDescriptor,
-    // ) -> Resource<webgpu::GpuComputePipeline> {
-    //     todo!()
-    // }
-
-    // fn create_render_pipeline_async(
-    //     &mut self,
-    //     self_: Resource<webgpu::GpuDevice>,
-    //     descriptor: webgpu::GpuRenderPipelineDescriptor,
-    // ) -> Resource<wgpu_core::id::RenderPipelineId> {
-    //     todo!()
-    // }
+        );
+        self.observe_created(ObservedResource::ComputePipeline);
+        let compute_pipeline = self.0.table().push(compute_pipeline).unwrap();
+        recorded.created(self.labelled(compute_pipeline, label))
+    }
 
     fn create_render_bundle_encoder(
         &mut self,
-        _device: Resource<webgpu::GpuDevice>,
-        _descriptor: webgpu::GpuRenderBundleEncoderDescriptor,
-    ) -> Resource<webgpu::GpuRenderBundleEncoder> {
-        todo!()
+        device: Resource<webgpu::GpuDevice>,
+        descriptor: webgpu::GpuRenderBundleEncoderDescriptor,
+    ) -> Result<Resource<webgpu::GpuRenderBundleEncoder>, webgpu::GpuRenderBundleFormatError> {
+        self.record_unrecorded("create-render-bundle-encoder");
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
+        let adapter = self.0.table().get(&device).unwrap().adapter;
+        let device = self.0.table().get(&device).unwrap().device;
+        let descriptor = descriptor.to_core(self.0.table());
+        check_render_bundle_formats(&self.instance(), adapter, device, &descriptor)?;
+        let encoder = RenderBundleEncoder::new(&descriptor, device, errors);
+        let encoder = self.0.table().push(encoder).unwrap();
+        Ok(self.labelled(encoder, label))
+    }
+
+    fn bake_static_layer(
+        &mut self,
+        device: Resource<webgpu::GpuDevice>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        descriptor: Option<webgpu::GpuRenderBundleDescriptor>,
+    ) -> Resource<webgpu::GpuStaticLayer> {
+        self.record_unrecorded("bake-static-layer");
+        let static_layers = self.0.table().get(&device).unwrap().static_layers.clone();
+        let instance = self.0.instance();
+        let encoder = self.0.table().get_mut(&encoder).unwrap();
+        let Some(finished) = encoder.take() else {
+            let bundle = invalid_render_bundle(&instance, encoder.encoder.parent());
+            let bundle = Arc::new(BakedBundle::new(instance, bundle));
+            return self.0.table().push(StaticLayer { bundle }).unwrap();
+        };
+        let bundle = match static_layers.get(&encoder.key) {
+            Some(bundle) => bundle,
+            None => {
+                let (bundle, error) = instance.render_bundle_encoder_finish::<crate::Backend>(
+                    finished,
+                    &wgpu_types::RenderBundleDescriptor {
+                        label: descriptor.and_then(|d| d.label).map(|l| l.into()),
+                    },
+                    None,
+                );
+                let bundle = Arc::new(BakedBundle::new(instance, bundle));
+                // Invalid bundles aren't cached, so the next bake reports the error again.
+                match error {
+                    Some(error) => encoder.errors.report(GpuError::from_core(&error)),
+                    None => static_layers.insert(encoder.key.clone(), Arc::clone(&bundle)),
+                }
+                bundle
+            }
+        };
+        self.0.table().push(StaticLayer { bundle }).unwrap()
     }
 
     fn create_query_set(
         &mut self,
-        _device: Resource<webgpu::GpuDevice>,
-        _descriptor: webgpu::GpuQuerySetDescriptor,
+        device: Resource<webgpu::GpuDevice>,
+        descriptor: webgpu::GpuQuerySetDescriptor,
     ) -> Resource<webgpu::GpuQuerySet> {
-        todo!()
+        let recorded = self.record_creation(Call::CreateQuerySet, (&device, &descriptor));
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
+        let device = self.0.table().get(&device).unwrap().device;
+
+        let descriptor = descriptor.to_core(self.0.table());
+        let query_set = if let wgpu_types::QueryType::PipelineStatistics(_) = descriptor.ty {
+            errors.report(GpuError::validation(
+                "Pipeline statistics query sets are created with create-pipeline-statistics-query-set",
+            ));
+            invalid_query_set(&self.0.instance(), device)
+        } else {
+            errors.handle(self.0.instance().device_create_query_set::<crate::Backend>(
+                device,
+                &descriptor,
+                None,
+            ))
+        };
+
+        let query_set = QuerySet {
+            query_set,
+            ty: descriptor.ty,
+            count: descriptor.count,
+            destroyed: false,
+            errors,
+        };
+        let query_set = self.0.table().push(query_set).unwrap();
+        recorded.created(self.labelled(query_set, label))
     }
 
-    fn label(&mut self, _device: Resource<webgpu::GpuDevice>) -> String {
-        todo!()
+    fn create_pipeline_statistics_query_set(
+        &mut self,
+        device: Resource<webgpu::GpuDevice>,
+        descriptor: webgpu::GpuPipelineStatisticsQuerySetDescriptor,
+    ) -> Resource<webgpu::GpuQuerySet> {
+        let recorded = self.record_creation(
+            Call::CreatePipelineStatisticsQuerySet,
+            (&device, &descriptor),
+        );
+        let label = descriptor.label.clone();
+        let errors = self.0.table().get(&device).unwrap().errors.clone();
+        let device = self.0.table().get(&device).unwrap().device;
+
+        let features = self
+            .instance()
+            .device_features::<crate::Backend>(device)
+            .unwrap();
+        let descriptor = descriptor.to_core(self.0.table());
+        let query_set = if features.contains(wgpu_types::Features::PIPELINE_STATISTICS_QUERY) {
+            errors.handle(self.0.instance().device_create_query_set::<crate::Backend>(
+                device,
+                &descriptor,
+                None,
+            ))
+        } else {
+            errors.report(GpuError::validation(
+                "Pipeline statistics query sets require the pipeline-statistics-query feature",
+            ));
+            invalid_query_set(&self.0.instance(), device)
+        };
+
+        let query_set = QuerySet {
+            query_set,
+            ty: descriptor.ty,
+            count: descriptor.count,
+            destroyed: false,
+            errors,
+        };
+        let query_set = self.0.table().push(query_set).unwrap();
+        recorded.created(self.labelled(query_set, label))
     }
 
-    fn set_label(&mut self, _device: Resource<webgpu::GpuDevice>, _label: String) -> () {
-        todo!()
+    fn label(&mut self, device: Resource<webgpu::GpuDevice>) -> String {
+        self.label_of(&device)
     }
 
-    fn lost(
+    fn set_label(&mut self, device: Resource<webgpu::GpuDevice>, label: String) {
+        self.store_label(&device, Some(label));
+    }
+
+    /// Waits until the device is lost, which for a device that isn't destroyed can be never. Guests that can't block
+    /// on that want `gpu-futures.lost`.
+    async fn lost(
         &mut self,
-        _device: Resource<webgpu::GpuDevice>,
+        device: Resource<webgpu::GpuDevice>,
     ) -> Resource<webgpu::GpuDeviceLostInfo> {
-        todo!()
+        let mut future = self.0.table().get(&device).unwrap().lost.future();
+        future.ready().await;
+        // The watch keeps its senders until the device is lost, so there's always an output.
+        let lost = future.take().unwrap();
+        self.0.table().push(lost).unwrap()
     }
 
     fn push_error_scope(
         &mut self,
-        _device: Resource<webgpu::GpuDevice>,
-        _filter: webgpu::GpuErrorFilter,
-    ) -> () {
-        todo!()
+        device: Resource<webgpu::GpuDevice>,
+        filter: webgpu::GpuErrorFilter,
+    ) {
+        let device = self.0.table().get(&device).unwrap();
+        device.errors.push(filter);
     }
 
     fn pop_error_scope(
         &mut self,
-        _device: Resource<webgpu::GpuDevice>,
-    ) -> Resource<webgpu::GpuError> {
-        todo!()
+        device: Resource<webgpu::GpuDevice>,
+    ) -> wasmtime::Result<Option<Resource<webgpu::GpuError>>> {
+        let device = self.0.table().get(&device)?;
+        let Some(error) = device.errors.pop()? else {
+            return Ok(None);
+        };
+        Ok(Some(self.0.table().push(error)?))
     }
 
     fn onuncapturederror(
         &mut self,
         _device: Resource<webgpu::GpuDevice>,
-    ) -> Resource<webgpu::EventHandler> {
-        todo!()
-    }
-
-    fn drop(&mut self, _rep: Resource<webgpu::GpuDevice>) -> wasmtime::Result<()> {
+    ) -> wasmtime::Result<Resource<webgpu::EventHandler>> {
+        anyhow::bail!("Uncaptured error events aren't supported, use error scopes instead")
+    }
+
+    fn drop(&mut self, device: Resource<webgpu::GpuDevice>) -> wasmtime::Result<()> {
+        self.forget_label(&device);
+        let device = self.0.table().delete(device)?;
+        device.static_layers.clear();
+        let (device, queue) = (device.device, device.queue);
+        // Releases the device right away instead of when the instance goes, which may be shared with other guests.
+        if let Some(live_devices) = self.0.live_devices() {
+            live_devices.remove(device);
+        }
+        live_devices::release_device(&self.0.instance(), device, queue);
         Ok(())
     }
 }
@@ -693,158 +2349,262 @@ impl<T: WasiWebGpuView> webgpu::HostGpuTexture for WasiWebGpuImpl<T> {
     fn from_graphics_buffer(
         &mut self,
         buffer: Resource<GraphicsContextBuffer>,
-    ) -> Resource<wgpu_core::id::TextureId> {
+    ) -> Resource<Texture> {
         let host_buffer = self.0.table().delete(buffer).unwrap();
-        let host_buffer: wgpu_core::id::TextureId = host_buffer.inner_type();
-        self.0.table().push(host_buffer).unwrap()
+        let host_buffer: Texture = host_buffer.inner_type();
+        let recorded = self.record_creation(Call::FromGraphicsBuffer, &host_buffer);
+        self.observe_created(ObservedResource::Texture);
+        recorded.created(self.0.table().push(host_buffer).unwrap())
     }
 
     fn create_view(
         &mut self,
-        texture: Resource<wgpu_core::id::TextureId>,
+        texture: Resource<Texture>,
         descriptor: Option<webgpu::GpuTextureViewDescriptor>,
-    ) -> Resource<wgpu_core::id::TextureViewId> {
-        let texture_id = *self.0.table().get(&texture).unwrap();
-        let texture_view = core_result(
+    ) -> Resource<TextureView> {
+        let recorded = self.record_creation(Call::CreateView, (&texture, &descriptor));
+        let label = descriptor.as_ref().and_then(|d| d.label.clone());
+        let texture = self.0.table().get(&texture).unwrap();
+        let (texture_id, errors) = (texture.texture, texture.errors.clone());
+        let base_mip_level = descriptor
+            .as_ref()
+            .and_then(|d| d.base_mip_level)
+            .unwrap_or(0);
+        // Levels past the last are reported by wgpu-core, the size doesn't matter for those.
+        let size = (
+            texture
+                .size
+                .width
+                .checked_shr(base_mip_level)
+                .unwrap_or(0)
+                .max(1),
+            texture
+                .size
+                .height
+                .checked_shr(base_mip_level)
+                .unwrap_or(0)
+                .max(1),
+        );
+        let view = errors.handle(
             self.0.instance().texture_create_view::<crate::Backend>(
                 texture_id,
                 &descriptor
-                    .map(|d| d.to_core(&self.0.table()))
+                    .map(|d| d.to_core(self.0.table()))
                     .unwrap_or_default(),
                 None,
             ),
-        )
-        .unwrap();
-        self.0.table().push(texture_view).unwrap()
+        );
+        let texture_view = self.0.table().push(TextureView { view, size }).unwrap();
+        recorded.created(self.labelled(texture_view, label))
     }
 
-    fn drop(&mut self, _rep: Resource<wgpu_core::id::TextureId>) -> wasmtime::Result<()> {
-        // TODO:
+    fn drop(&mut self, texture: Resource<Texture>) -> wasmtime::Result<()> {
+        self.forget_label(&texture);
+        let texture = self.0.table().delete(texture)?;
+        if let Some(quota) = self.0.quota() {
+            quota.release_texture(texture.texture);
+        }
+        if texture.owned {
+            self.0
+                .instance()
+                .texture_drop::<crate::Backend>(texture.texture, false);
+        }
+        self.observe_dropped(ObservedResource::Texture);
         Ok(())
     }
 
-    fn destroy(&mut self, _self_: Resource<webgpu::GpuTexture>) {
-        todo!()
+    fn destroy(&mut self, texture: Resource<webgpu::GpuTexture>) {
+        self.record(Call::DestroyTexture, &texture);
+        let texture = self.0.table().get(&texture).unwrap();
+        if !texture.owned {
+            texture.errors.report(GpuError::validation(
+                "Texture is lent out by a canvas or atlas and can't be destroyed",
+            ));
+            return;
+        }
+        let (texture, errors) = (texture.texture, texture.errors.clone());
+        errors.check(self.0.instance().texture_destroy::<crate::Backend>(texture));
+        // Like `gpu-buffer.destroy`, the memory is freed now and the id lives on until the texture is dropped.
+        if let Some(quota) = self.0.quota() {
+            quota.release_texture(texture);
+        }
     }
 
-    fn width(&mut self, _self_: Resource<webgpu::GpuTexture>) -> webgpu::GpuIntegerCoordinateOut {
-        todo!()
+    fn width(&mut self, texture: Resource<webgpu::GpuTexture>) -> webgpu::GpuIntegerCoordinateOut {
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.size.width
     }
 
-    fn height(&mut self, _self_: Resource<webgpu::GpuTexture>) -> webgpu::GpuIntegerCoordinateOut {
-        todo!()
+    fn height(&mut self, texture: Resource<webgpu::GpuTexture>) -> webgpu::GpuIntegerCoordinateOut {
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.size.height
     }
 
     fn depth_or_array_layers(
         &mut self,
-        _self_: Resource<webgpu::GpuTexture>,
+        texture: Resource<webgpu::GpuTexture>,
     ) -> webgpu::GpuIntegerCoordinateOut {
-        todo!()
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.size.depth_or_array_layers
     }
 
     fn mip_level_count(
         &mut self,
-        _self_: Resource<webgpu::GpuTexture>,
+        texture: Resource<webgpu::GpuTexture>,
     ) -> webgpu::GpuIntegerCoordinateOut {
-        todo!()
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.mip_level_count
+    }
+
+    fn sample_count(&mut self, texture: Resource<webgpu::GpuTexture>) -> webgpu::GpuSize32Out {
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.sample_count
     }
 
-    fn sample_count(&mut self, _self_: Resource<webgpu::GpuTexture>) -> webgpu::GpuSize32Out {
-        todo!()
+    fn dimension(&mut self, texture: Resource<webgpu::GpuTexture>) -> webgpu::GpuTextureDimension {
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.dimension.into()
     }
 
-    fn dimension(&mut self, _self_: Resource<webgpu::GpuTexture>) -> webgpu::GpuTextureDimension {
-        todo!()
+    fn format(&mut self, texture: Resource<webgpu::GpuTexture>) -> webgpu::GpuTextureFormat {
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.format.into()
     }
 
-    fn format(&mut self, _self_: Resource<webgpu::GpuTexture>) -> webgpu::GpuTextureFormat {
-        todo!()
+    fn usage(&mut self, texture: Resource<webgpu::GpuTexture>) -> webgpu::GpuFlagsConstant {
+        let texture = self.0.table().get(&texture).unwrap();
+        texture.usage.bits()
     }
 
-    fn usage(&mut self, _self_: Resource<webgpu::GpuTexture>) -> webgpu::GpuFlagsConstant {
-        todo!()
+    fn label(&mut self, texture: Resource<webgpu::GpuTexture>) -> String {
+        self.label_of(&texture)
     }
 
-    fn label(&mut self, _self_: Resource<webgpu::GpuTexture>) -> String {
-        todo!()
+    fn set_label(&mut self, texture: Resource<webgpu::GpuTexture>, label: String) {
+        self.store_label(&texture, Some(label));
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuTexture>, _label: String) {
-        todo!()
+    fn to_png_bytes(
+        &mut self,
+        texture: Resource<webgpu::GpuTexture>,
+    ) -> Result<Vec<u8>, webgpu::GpuTextureEncodeError> {
+        let texture = self.0.table().get(&texture).unwrap().clone();
+        if !texture.usage.contains(wgpu_types::TextureUsages::COPY_SRC) {
+            return Err(webgpu::GpuTextureEncodeError::MissingCopySrcUsage);
+        }
+        if read_back_bgra(texture.format).is_none() || texture.sample_count > 1 {
+            return Err(webgpu::GpuTextureEncodeError::UnsupportedFormat);
+        }
+        let frame = read_back_texture(&self.0.instance(), &texture).map_err(|e| {
+            webgpu::GpuTextureEncodeError::ReadBack(GpuError::from_core(e.as_ref()).kind())
+        })?;
+        image::encode_png(&frame).map_err(webgpu::GpuTextureEncodeError::Encode)
     }
 }
 
 impl<T: WasiWebGpuView> webgpu::HostGpuTextureView for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<wgpu_core::id::TextureViewId>) -> wasmtime::Result<()> {
+    fn drop(&mut self, view: Resource<TextureView>) -> wasmtime::Result<()> {
+        self.forget_label(&view);
+        let view = self.0.table().delete(view)?;
+        // Only fails for views that are already gone.
+        let _ = self
+            .0
+            .instance()
+            .texture_view_drop::<crate::Backend>(view.view, false);
         Ok(())
     }
 
-    fn label(&mut self, _self_: Resource<wgpu_core::id::TextureViewId>) -> String {
-        todo!()
+    fn label(&mut self, view: Resource<TextureView>) -> String {
+        self.label_of(&view)
     }
 
-    fn set_label(&mut self, _self_: Resource<wgpu_core::id::TextureViewId>, _label: String) {
-        todo!()
+    fn set_label(&mut self, view: Resource<TextureView>, label: String) {
+        self.store_label(&view, Some(label));
     }
 }
 
 impl<T: WasiWebGpuView> webgpu::HostGpuCommandBuffer for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::GpuCommandBuffer>) -> wasmtime::Result<()> {
+    fn drop(&mut self, command_buffer: Resource<webgpu::GpuCommandBuffer>) -> wasmtime::Result<()> {
+        self.forget_label(&command_buffer);
         // self.web_gpu_host.command_buffers.remove(&rep.rep());
         Ok(())
     }
 
-    fn label(&mut self, _self_: Resource<wgpu_core::id::CommandBufferId>) -> String {
-        todo!()
+    fn label(&mut self, command_buffer: Resource<wgpu_core::id::CommandBufferId>) -> String {
+        self.label_of(&command_buffer)
     }
 
-    fn set_label(&mut self, _self_: Resource<wgpu_core::id::CommandBufferId>, _label: String) {
-        todo!()
+    fn set_label(
+        &mut self,
+        command_buffer: Resource<wgpu_core::id::CommandBufferId>,
+        label: String,
+    ) {
+        self.store_label(&command_buffer, Some(label));
     }
 }
 
 impl<T: WasiWebGpuView> webgpu::HostGpuShaderModule for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::GpuShaderModule>) -> wasmtime::Result<()> {
-        // self.web_gpu_host.shaders.remove(&rep.rep());
+    fn drop(&mut self, shader: Resource<webgpu::GpuShaderModule>) -> wasmtime::Result<()> {
+        self.forget_label(&shader);
+        let shader = self.0.table().delete(shader)?;
+        self.0
+            .instance()
+            .shader_module_drop::<crate::Backend>(shader);
         Ok(())
     }
 
     fn get_compilation_info(
         &mut self,
-        _self_: Resource<wgpu_core::id::ShaderModuleId>,
+        _shader: Resource<wgpu_core::id::ShaderModuleId>,
     ) -> Resource<webgpu::GpuCompilationInfo> {
-        todo!()
+        self.0.table().push(CompilationInfo).unwrap()
     }
 
-    fn label(&mut self, _self_: Resource<wgpu_core::id::ShaderModuleId>) -> String {
-        todo!()
+    fn label(&mut self, shader: Resource<wgpu_core::id::ShaderModuleId>) -> String {
+        self.label_of(&shader)
     }
 
-    fn set_label(&mut self, _self_: Resource<wgpu_core::id::ShaderModuleId>, _label: String) {
-        todo!()
+    fn set_label(&mut self, shader: Resource<wgpu_core::id::ShaderModuleId>, label: String) {
+        self.store_label(&shader, Some(label));
     }
 }
 
 impl<T: WasiWebGpuView> webgpu::HostGpuRenderPipeline for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::GpuRenderPipeline>) -> wasmtime::Result<()> {
-        // TODO:
+    fn drop(&mut self, pipeline: Resource<webgpu::GpuRenderPipeline>) -> wasmtime::Result<()> {
+        self.forget_label(&pipeline);
+        let pipeline = self.0.table().delete(pipeline)?;
+        self.0
+            .instance()
+            .render_pipeline_drop::<crate::Backend>(pipeline.pipeline);
+        self.observe_dropped(ObservedResource::RenderPipeline);
         Ok(())
     }
 
-    fn label(&mut self, _self_: Resource<wgpu_core::id::RenderPipelineId>) -> String {
-        todo!()
+    fn label(&mut self, pipeline: Resource<RenderPipeline>) -> String {
+        self.label_of(&pipeline)
     }
 
-    fn set_label(&mut self, _self_: Resource<wgpu_core::id::RenderPipelineId>, _label: String) {
-        todo!()
+    fn set_label(&mut self, pipeline: Resource<RenderPipeline>, label: String) {
+        self.store_label(&pipeline, Some(label));
     }
 
     fn get_bind_group_layout(
         &mut self,
-        _self_: Resource<wgpu_core::id::RenderPipelineId>,
-        _index: u32,
-    ) -> Resource<webgpu::GpuBindGroupLayout> {
-        todo!()
+        render_pipeline: Resource<RenderPipeline>,
+        index: u32,
+    ) -> wasmtime::Result<Resource<webgpu::GpuBindGroupLayout>> {
+        let recorded = self.record_creation(
+            Call::RenderPipelineGetBindGroupLayout,
+            (&render_pipeline, index),
+        );
+        let pipeline_id = self.0.table().get(&render_pipeline)?.pipeline;
+        // Out of range indices are reported by wgpu-core as an invalid group index, a `RangeError` in the spec.
+        let bind_group_layout = core_result(
+            self.0
+                .instance()
+                .render_pipeline_get_bind_group_layout::<crate::Backend>(pipeline_id, index, None),
+        )?;
+        Ok(recorded.created(self.0.table().push(bind_group_layout)?))
     }
 }
 
@@ -853,33 +2613,52 @@ impl<T: WasiWebGpuView> webgpu::HostGpuAdapter for WasiWebGpuImpl<T> {
         &mut self,
         adapter: Resource<wgpu_core::id::AdapterId>,
         descriptor: Option<webgpu::GpuDeviceDescriptor>,
-    ) -> Resource<webgpu::GpuDevice> {
-        let adapter_id = *self.0.table().get(&adapter).unwrap();
+    ) -> wasmtime::Result<Resource<webgpu::GpuDevice>> {
+        let recorded = self.record_creation(Call::RequestDevice, (&adapter, &descriptor));
+        let label = descriptor.as_ref().and_then(|d| d.label.clone());
+        let adapter_id = *self.0.table().get(&adapter)?;
+
+        let mut descriptor = descriptor
+            .map(|d| d.to_core(self.0.table()))
+            .unwrap_or_default();
+        if descriptor
+            .required_features
+            .contains(wgpu_types::Features::PUSH_CONSTANTS)
+        {
+            // Default limits don't allow any push constants.
+            descriptor.required_limits.max_push_constant_size = self
+                .instance()
+                .adapter_limits::<crate::Backend>(adapter_id)
+                .unwrap()
+                .max_push_constant_size;
+        }
 
-        let (device_id, queue_id) = core_results_2(
-            self.0.instance().adapter_request_device::<crate::Backend>(
+        let trace_dir = self.0.trace_path().and_then(device_trace_dir);
+        let (device_id, queue_id) =
+            core_results_2(self.0.instance().adapter_request_device::<crate::Backend>(
                 adapter_id,
-                &descriptor
-                    .map(|d| d.to_core(&self.0.table()))
-                    .unwrap_or_default(),
-                None,
+                &descriptor,
+                trace_dir.as_deref(),
                 None,
                 None,
-            ),
-        )
-        .unwrap();
+            ))
+            .context("Failed to request a device")?;
+        if let Some(live_devices) = self.0.live_devices() {
+            live_devices.insert(device_id, queue_id);
+        }
 
-        let device = self
-            .0
-            .table()
-            .push(Device {
-                device: device_id,
-                queue: queue_id,
-                adapter: adapter_id,
-            })
-            .unwrap();
+        let lost = DeviceLostWatch::new(&self.0.instance(), device_id);
+        let device = self.0.table().push(Device {
+            device: device_id,
+            queue: queue_id,
+            adapter: adapter_id,
+            static_layers: Default::default(),
+            mipmaps: Default::default(),
+            errors: Default::default(),
+            lost,
+        })?;
 
-        device
+        Ok(recorded.created(self.labelled(device, label)))
     }
 
     fn drop(&mut self, _adapter: Resource<webgpu::GpuAdapter>) -> wasmtime::Result<()> {
@@ -911,11 +2690,15 @@ impl<T: WasiWebGpuView> webgpu::HostGpuAdapter for WasiWebGpuImpl<T> {
         self.0.table().push(limits).unwrap()
     }
 
-    fn is_fallback_adapter(
-        &mut self,
-        _self_: wasmtime::component::Resource<wgpu_core::id::AdapterId>,
-    ) -> bool {
-        todo!()
+    fn is_fallback_adapter(&mut self, adapter: Resource<wgpu_core::id::AdapterId>) -> bool {
+        let adapter = *self.0.table().get(&adapter).unwrap();
+        let info = self
+            .0
+            .instance()
+            .adapter_get_info::<crate::Backend>(adapter)
+            .unwrap();
+        // wgpu only falls back to software rasterizers, like lavapipe or WARP.
+        info.device_type == wgpu_types::DeviceType::Cpu
     }
 
     fn request_adapter_info(
@@ -931,320 +2714,742 @@ impl<T: WasiWebGpuView> webgpu::HostGpuAdapter for WasiWebGpuImpl<T> {
         let info = self.0.table().push(info).unwrap();
         info
     }
+
+    fn get_downlevel_capabilities(
+        &mut self,
+        adapter: Resource<wgpu_core::id::AdapterId>,
+    ) -> webgpu::GpuDownlevelCapabilities {
+        let adapter_id = *self.0.table().get(&adapter).unwrap();
+        let capabilities = self
+            .0
+            .instance()
+            .adapter_downlevel_capabilities::<crate::Backend>(adapter_id)
+            .unwrap();
+        webgpu::GpuDownlevelCapabilities {
+            shader_model: capabilities.shader_model.into(),
+            flags: capabilities.flags.into(),
+        }
+    }
+
+    fn get_supported_texture_compression_formats(
+        &mut self,
+        adapter: Resource<wgpu_core::id::AdapterId>,
+    ) -> Vec<webgpu::GpuTextureFormat> {
+        let adapter_id = *self.0.table().get(&adapter).unwrap();
+        let features = self
+            .0
+            .instance()
+            .adapter_features::<crate::Backend>(adapter_id)
+            .unwrap();
+        compressed_texture_formats()
+            .into_iter()
+            .filter(|&format| missing_format_features(features, format).is_empty())
+            .map(Into::into)
+            .collect()
+    }
+}
+
+/// Features `format` needs that aren't in `features`, e.g. `texture-compression-bc` for the BC formats.
+fn missing_format_features(
+    features: wgpu_types::Features,
+    format: wgpu_types::TextureFormat,
+) -> wgpu_types::Features {
+    format.required_features() - features
+}
+
+/// All BC, ETC2/EAC and ASTC formats that have a `gpu-texture-format`.
+fn compressed_texture_formats() -> Vec<wgpu_types::TextureFormat> {
+    use wgpu_types::{AstcBlock, AstcChannel, TextureFormat};
+    let mut formats = vec![
+        TextureFormat::Bc1RgbaUnorm,
+        TextureFormat::Bc1RgbaUnormSrgb,
+        TextureFormat::Bc2RgbaUnorm,
+        TextureFormat::Bc2RgbaUnormSrgb,
+        TextureFormat::Bc3RgbaUnorm,
+        TextureFormat::Bc3RgbaUnormSrgb,
+        TextureFormat::Bc4RUnorm,
+        TextureFormat::Bc4RSnorm,
+        TextureFormat::Bc5RgUnorm,
+        TextureFormat::Bc5RgSnorm,
+        TextureFormat::Bc6hRgbUfloat,
+        TextureFormat::Bc6hRgbFloat,
+        TextureFormat::Bc7RgbaUnorm,
+        TextureFormat::Bc7RgbaUnormSrgb,
+        TextureFormat::Etc2Rgb8Unorm,
+        TextureFormat::Etc2Rgb8UnormSrgb,
+        TextureFormat::Etc2Rgb8A1Unorm,
+        TextureFormat::Etc2Rgb8A1UnormSrgb,
+        TextureFormat::Etc2Rgba8Unorm,
+        TextureFormat::Etc2Rgba8UnormSrgb,
+        TextureFormat::EacR11Unorm,
+        TextureFormat::EacR11Snorm,
+        TextureFormat::EacRg11Unorm,
+        TextureFormat::EacRg11Snorm,
+    ];
+    const ASTC_BLOCKS: [AstcBlock; 14] = [
+        AstcBlock::B4x4,
+        AstcBlock::B5x4,
+        AstcBlock::B5x5,
+        AstcBlock::B6x5,
+        AstcBlock::B6x6,
+        AstcBlock::B8x5,
+        AstcBlock::B8x6,
+        AstcBlock::B8x8,
+        AstcBlock::B10x5,
+        AstcBlock::B10x6,
+        AstcBlock::B10x8,
+        AstcBlock::B10x10,
+        AstcBlock::B12x10,
+        AstcBlock::B12x12,
+    ];
+    // HDR ASTC has no `gpu-texture-format`.
+    for block in ASTC_BLOCKS {
+        for channel in [AstcChannel::Unorm, AstcChannel::UnormSrgb] {
+            formats.push(TextureFormat::Astc { block, channel });
+        }
+    }
+    formats
 }
 
 impl<T: WasiWebGpuView> webgpu::HostGpuQueue for WasiWebGpuImpl<T> {
-    fn submit(
-        &mut self,
-        queue: Resource<wgpu_core::id::QueueId>,
-        val: Vec<Resource<webgpu::GpuCommandBuffer>>,
-    ) {
+    fn submit(&mut self, queue: Resource<Queue>, val: Vec<Resource<webgpu::GpuCommandBuffer>>) {
+        self.record(Call::Submit, (&queue, &val));
+        // Submitting a command buffer twice is reported by wgpu-core, so they stay in the table until dropped.
         let command_buffers = val
             .into_iter()
-            .map(|buffer| self.0.table().delete(buffer).unwrap())
+            .map(|buffer| *self.0.table().get(&buffer).unwrap())
             .collect::<Vec<_>>();
 
-        let queue = *self.0.table().get(&queue).unwrap();
-        self.0
-            .instance()
-            .queue_submit::<crate::Backend>(queue, &command_buffers)
-            .unwrap();
+        let queue = self.0.table().get(&queue).unwrap();
+        let (queue, errors) = (queue.queue, queue.errors.clone());
+        errors.check(
+            self.0
+                .instance()
+                .queue_submit::<crate::Backend>(queue, &command_buffers),
+        );
+        if let Some(observer) = self.0.observer() {
+            observer.submitted(command_buffers.len());
+        }
     }
 
-    fn drop(&mut self, _rep: Resource<wgpu_core::id::QueueId>) -> wasmtime::Result<()> {
-        // todo!()
+    fn drop(&mut self, queue: Resource<Queue>) -> wasmtime::Result<()> {
+        self.forget_label(&queue);
+        self.0.table().delete(queue)?;
         Ok(())
     }
 
-    fn on_submitted_work_done(&mut self, _self_: Resource<wgpu_core::id::QueueId>) {
-        todo!()
+    fn on_submitted_work_done(&mut self, queue: Resource<Queue>) {
+        let errors = self.0.table().get(&queue).unwrap().errors.clone();
+        // Waiting on every device is more than needed, but the spec only promises the work is done when this returns.
+        // TODO: only poll the queue's device.
+        errors.check(self.0.instance().poll_all_devices(true));
     }
 
     fn write_buffer(
         &mut self,
-        queue: Resource<wgpu_core::id::QueueId>,
+        queue: Resource<Queue>,
         buffer: Resource<webgpu::GpuBuffer>,
         buffer_offset: webgpu::GpuSize64,
         data_offset: Option<webgpu::GpuSize64>,
         data: Vec<u8>,
         size: Option<webgpu::GpuSize64>,
-    ) {
-        let queue = *self.0.table().get(&queue).unwrap();
-        let buffer = self.0.table().get(&buffer).unwrap().buffer;
+    ) -> wasmtime::Result<()> {
+        self.record(
+            Call::WriteBuffer,
+            (&queue, &buffer, buffer_offset, data_offset, &data, size),
+        );
+        let queue = self.0.table().get(&queue)?;
+        let (queue, errors) = (queue.queue, queue.errors.clone());
+        let buffer = self.0.table().get(&buffer)?.buffer;
+        // source: https://www.w3.org/TR/webgpu/#dom-gpuqueue-writebuffer
+        // Data ranges outside of `data` are an `OperationError`.
+        let len = data.len();
         let mut data = &data[..];
         if let Some(data_offset) = data_offset {
-            let data_offset = data_offset as usize;
-            data = &data[data_offset..];
+            data = data.get(data_offset as usize..).with_context(|| {
+                format!("data-offset {data_offset} is out of range of the {len} bytes of data")
+            })?;
         }
         if let Some(size) = size {
-            let size = size as usize;
-            data = &data[..size];
+            data = data.get(..size as usize).with_context(|| {
+                format!("size {size} is out of range of the {len} bytes of data")
+            })?;
         }
-        self.0
-            .instance()
-            .queue_write_buffer::<crate::Backend>(queue, buffer, buffer_offset, &data)
-            .unwrap();
+        errors.check(self.0.instance().queue_write_buffer::<crate::Backend>(
+            queue,
+            buffer,
+            buffer_offset,
+            data,
+        ));
+        Ok(())
     }
 
     fn write_texture(
         &mut self,
-        queue: Resource<wgpu_core::id::QueueId>,
+        queue: Resource<Queue>,
         destination: webgpu::GpuImageCopyTexture,
         data: Vec<u8>,
         data_layout: webgpu::GpuImageDataLayout,
         size: webgpu::GpuExtent3D,
     ) {
-        let queue = *self.0.table().get(&queue).unwrap();
-        self.0
-            .instance()
-            .queue_write_texture::<crate::Backend>(
-                queue,
-                &destination.to_core(&self.0.table()),
-                &data,
-                &data_layout.to_core(&self.0.table()),
-                &size.to_core(&self.0.table()),
-            )
-            .unwrap();
+        self.record(
+            Call::WriteTexture,
+            (&queue, &destination, &data, &data_layout, &size),
+        );
+        let queue = self.0.table().get(&queue).unwrap();
+        let (queue, errors) = (queue.queue, queue.errors.clone());
+        if let Err(error) = validate_extent(&size) {
+            errors.report(error);
+            return;
+        }
+        let format = self.0.table().get(&destination.texture).unwrap().format;
+        let destination = destination.to_core(self.0.table());
+        let data_layout = data_layout.to_core(self.0.table());
+        let size = size.to_core(self.0.table());
+        if let Err(error) = validate_copy_aspect(format, destination.aspect, true).and_then(|()| {
+            validate_texture_data(format, destination.aspect, data.len(), &data_layout, &size)
+        }) {
+            errors.report(error);
+            return;
+        }
+        errors.check(self.0.instance().queue_write_texture::<crate::Backend>(
+            queue,
+            &destination,
+            &data,
+            &data_layout,
+            &size,
+        ));
+    }
+
+    fn copy_frame_buffer_to_texture(
+        &mut self,
+        queue: Resource<Queue>,
+        source: Resource<FBBuffer>,
+        destination: webgpu::GpuImageCopyTexture,
+    ) {
+        self.record_unrecorded("copy-frame-buffer-to-texture");
+        let queue = self.0.table().get(&queue).unwrap();
+        let (queue, errors) = (queue.queue, queue.errors.clone());
+        let texture = self.0.table().get(&destination.texture).unwrap();
+        let (format, dimension, size) = (texture.format, texture.dimension, texture.size);
+        let destination = destination.to_core(self.0.table());
+        let width = (size.width >> destination.mip_level).max(1);
+        let height = (size.height >> destination.mip_level).max(1);
+        if dimension != wgpu_types::TextureDimension::D2 {
+            errors.report(GpuError::validation(format!(
+                "Frame buffers can only be copied to 2d textures, not {dimension:?}"
+            )));
+            return;
+        }
+        let source = self.0.table().get(&source).unwrap();
+        // softbuffer leaves the top byte of each pixel 0, so the pixels are always rewritten with an opaque alpha.
+        let data = source.with_pixels(|pixels| {
+            if pixels.len() as u64 != width as u64 * height as u64 {
+                return Err(GpuError::validation(format!(
+                    "Frame buffer has {} pixels, mip level {} of the texture has {width}x{height}",
+                    pixels.len(),
+                    destination.mip_level,
+                )));
+            }
+            let data: Vec<u8> = match format {
+                wgpu_types::TextureFormat::Bgra8Unorm
+                | wgpu_types::TextureFormat::Bgra8UnormSrgb => pixels
+                    .iter()
+                    .flat_map(|pixel| (pixel | 0xff00_0000).to_le_bytes())
+                    .collect(),
+                wgpu_types::TextureFormat::Rgba8Unorm
+                | wgpu_types::TextureFormat::Rgba8UnormSrgb => pixels
+                    .iter()
+                    .flat_map(|pixel| {
+                        let [_, r, g, b] = pixel.to_be_bytes();
+                        [r, g, b, 255]
+                    })
+                    .collect(),
+                format => {
+                    return Err(GpuError::validation(format!(
+                        "Frame buffers can't be copied to {format:?} textures"
+                    )))
+                }
+            };
+            Ok(data)
+        });
+        let data = match data {
+            Some(Ok(data)) => data,
+            Some(Err(error)) => {
+                errors.report(error);
+                return;
+            }
+            None => {
+                errors.report(GpuError::validation("Frame buffer was already dropped"));
+                return;
+            }
+        };
+        errors.check(self.0.instance().queue_write_texture::<crate::Backend>(
+            queue,
+            &destination,
+            &data,
+            &wgpu_types::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            &wgpu_types::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        ));
     }
 
     fn copy_external_image_to_texture(
         &mut self,
-        _self_: Resource<wgpu_core::id::QueueId>,
+        _queue: Resource<Queue>,
         _source: webgpu::GpuImageCopyExternalImage,
         _destination: webgpu::GpuImageCopyTextureTagged,
         _copy_size: webgpu::GpuExtent3D,
-    ) {
-        todo!()
+    ) -> wasmtime::Result<()> {
+        anyhow::bail!("External images aren't supported, use create-texture-from-image instead")
     }
 
-    fn label(&mut self, _self_: Resource<wgpu_core::id::QueueId>) -> String {
-        todo!()
+    fn label(&mut self, queue: Resource<Queue>) -> String {
+        self.label_of(&queue)
     }
 
-    fn set_label(&mut self, _self_: Resource<wgpu_core::id::QueueId>, _label: String) {
-        todo!()
+    fn set_label(&mut self, queue: Resource<Queue>, label: String) {
+        self.store_label(&queue, Some(label));
     }
 }
 
 impl<T: WasiWebGpuView> webgpu::HostGpuCommandEncoder for WasiWebGpuImpl<T> {
     fn begin_render_pass(
         &mut self,
-        command_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        command_encoder: Resource<CommandEncoder>,
         descriptor: webgpu::GpuRenderPassDescriptor,
     ) -> Resource<webgpu::GpuRenderPassEncoder> {
+        let recorded = self.record_creation(Call::BeginRenderPass, (&command_encoder, &descriptor));
+        if let Some(Err(error)) = descriptor
+            .depth_stencil_attachment
+            .as_ref()
+            .map(validate_read_only_ops)
+        {
+            // wgpu-core ignores the ops of read-only aspects, so the pass can still be recorded.
+            let errors = &self.0.table().get(&command_encoder).unwrap().errors;
+            errors.report(error);
+        }
+        // Attachments of different sizes are reported by wgpu-core, so the first one stands for all of them.
+        let attachment_size = descriptor
+            .color_attachments
+            .iter()
+            .map(|attachment| &attachment.view)
+            .chain(
+                descriptor
+                    .depth_stencil_attachment
+                    .as_ref()
+                    .map(|d| &d.view),
+            )
+            .next()
+            .map(|view| self.0.table().get(view).unwrap().size);
         // can't use to_core because depth_stencil_attachment is Option<&x>.
         let depth_stencil_attachment = descriptor
             .depth_stencil_attachment
-            .map(|d| d.to_core(&self.0.table()));
+            .map(|d| d.to_core(self.0.table()));
+        // Destroyed query sets are reported and left out, so the pass fails where it uses them.
+        let occlusion_query_set = descriptor
+            .occlusion_query_set
+            .and_then(|query_set| self.0.table().get(&query_set).unwrap().usable());
+        let timestamp_writes = descriptor.timestamp_writes.and_then(|timestamp_writes| {
+            self.0
+                .table()
+                .get(&timestamp_writes.query_set)
+                .unwrap()
+                .usable()?;
+            Some(timestamp_writes.to_core(self.0.table()))
+        });
         let descriptor = wgpu_core::command::RenderPassDescriptor {
-            label: descriptor.label.map(|l| l.into()),
+            label: descriptor.label.as_deref().map(|l| l.into()),
             color_attachments: descriptor
                 .color_attachments
                 .into_iter()
-                .map(|c| Some(c.to_core(&self.0.table())))
+                .map(|c| Some(c.to_core(self.0.table())))
                 .collect::<Vec<_>>()
                 .into(),
             depth_stencil_attachment: depth_stencil_attachment.as_ref(),
-            // timestamp_writes: self.timestamp_writes,
-            // occlusion_query_set: self.occlusion_query_set,
+            timestamp_writes: timestamp_writes.as_ref(),
+            occlusion_query_set,
             // TODO: self.max_draw_count not used
-            // TODO: remove default
-            ..Default::default()
         };
-        let render_pass = wgpu_core::command::RenderPass::new(
-            command_encoder.to_core(&self.0.table()),
-            &descriptor,
-        );
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&command_encoder).unwrap().clone();
+        let render_pass = RenderPassEncoder {
+            pass: wgpu_core::command::RenderPass::new(encoder, &descriptor),
+            strip_without_index_format: false,
+            attachment_size,
+            ended: false,
+            errors,
+        };
 
-        self.0.table().push(render_pass).unwrap()
+        let render_pass = self.0.table().push(render_pass).unwrap();
+        recorded.created(self.labelled(render_pass, descriptor.label.map(Cow::into_owned)))
     }
 
     fn finish(
         &mut self,
-        command_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        command_encoder: Resource<CommandEncoder>,
         descriptor: Option<webgpu::GpuCommandBufferDescriptor>,
     ) -> Resource<webgpu::GpuCommandBuffer> {
-        let command_encoder = self.0.table().delete(command_encoder).unwrap();
-        let command_buffer = core_result(
+        let recorded = self.record_creation(Call::Finish, (&command_encoder, &descriptor));
+        let label = descriptor.as_ref().and_then(|d| d.label.clone());
+        // Finishing twice is reported by wgpu-core, so the encoder is only removed from the table in `drop`.
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&command_encoder).unwrap().clone();
+        let command_buffer = errors.handle(
             self.0.instance().command_encoder_finish::<crate::Backend>(
-                command_encoder,
+                encoder,
                 &descriptor
-                    .map(|d| d.to_core(&self.0.table()))
+                    .map(|d| d.to_core(self.0.table()))
                     .unwrap_or_default(),
             ),
-        )
-        .unwrap();
-        self.0.table().push(command_buffer).unwrap()
+        );
+        let command_buffer = self.0.table().push(command_buffer).unwrap();
+        recorded.created(self.labelled(command_buffer, label))
     }
 
-    fn drop(&mut self, _rep: Resource<wgpu_core::id::CommandEncoderId>) -> wasmtime::Result<()> {
+    fn drop(&mut self, command_encoder: Resource<CommandEncoder>) -> wasmtime::Result<()> {
+        self.forget_label(&command_encoder);
+        self.0.table().delete(command_encoder)?;
         Ok(())
     }
 
     fn begin_compute_pass(
         &mut self,
-        command_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        command_encoder: Resource<CommandEncoder>,
         descriptor: Option<webgpu::GpuComputePassDescriptor>,
     ) -> Resource<webgpu::GpuComputePassEncoder> {
-        let command_encoder = self.0.table().get(&command_encoder).unwrap();
-        let compute_pass = wgpu_core::command::ComputePass::new(
-            *command_encoder,
-            &wgpu_core::command::ComputePassDescriptor {
-                label: Default::default(),
-                timestamp_writes: descriptor
-                    .map(|d| d.timestamp_writes.map(|tw| tw.to_core(&self.0.table())))
-                    .flatten()
-                    .as_ref(),
-            },
-        );
-        self.0.table().push(compute_pass).unwrap()
+        let recorded =
+            self.record_creation(Call::BeginComputePass, (&command_encoder, &descriptor));
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&command_encoder).unwrap().clone();
+        let (label, timestamp_writes) = match descriptor {
+            Some(d) => (d.label, d.timestamp_writes),
+            None => (None, None),
+        };
+        let timestamp_writes = timestamp_writes.filter(|timestamp_writes| {
+            self.0
+                .table()
+                .get(&timestamp_writes.query_set)
+                .unwrap()
+                .usable()
+                .is_some()
+        });
+        let compute_pass = ComputePassEncoder {
+            pass: wgpu_core::command::ComputePass::new(
+                encoder,
+                &wgpu_core::command::ComputePassDescriptor {
+                    label: label.as_deref().map(|l| l.into()),
+                    timestamp_writes: timestamp_writes
+                        .map(|tw| tw.to_core(self.0.table()))
+                        .as_ref(),
+                },
+            ),
+            ended: false,
+            errors,
+        };
+        let compute_pass = self.0.table().push(compute_pass).unwrap();
+        recorded.created(self.labelled(compute_pass, label))
     }
 
     fn copy_buffer_to_buffer(
         &mut self,
-        command_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        command_encoder: Resource<CommandEncoder>,
         source: Resource<webgpu::GpuBuffer>,
         source_offset: webgpu::GpuSize64,
         destination: Resource<webgpu::GpuBuffer>,
         destination_offset: webgpu::GpuSize64,
         size: webgpu::GpuSize64,
     ) {
-        let command_encoder = *self.0.table().get(&command_encoder).unwrap();
-        let source = self.0.table().get(&source).unwrap().buffer;
-        let destination = self.0.table().get(&destination).unwrap().buffer;
-        self.0
-            .instance()
-            .command_encoder_copy_buffer_to_buffer::<crate::Backend>(
-                command_encoder,
-                source,
+        self.record(
+            Call::CopyBufferToBuffer,
+            (
+                &command_encoder,
+                &source,
                 source_offset,
-                destination,
+                &destination,
                 destination_offset,
                 size,
-            )
-            .unwrap();
+            ),
+        );
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&command_encoder).unwrap().clone();
+        let source = self.0.table().get(&source).unwrap().buffer;
+        let destination = self.0.table().get(&destination).unwrap().buffer;
+        errors.check(
+            self.0
+                .instance()
+                .command_encoder_copy_buffer_to_buffer::<crate::Backend>(
+                    encoder,
+                    source,
+                    source_offset,
+                    destination,
+                    destination_offset,
+                    size,
+                ),
+        );
     }
 
     fn copy_buffer_to_texture(
         &mut self,
-        _self_: Resource<wgpu_core::id::CommandEncoderId>,
-        _source: webgpu::GpuImageCopyBuffer,
-        _destination: webgpu::GpuImageCopyTexture,
-        _copy_size: webgpu::GpuExtent3D,
+        command_encoder: Resource<CommandEncoder>,
+        source: webgpu::GpuImageCopyBuffer,
+        destination: webgpu::GpuImageCopyTexture,
+        copy_size: webgpu::GpuExtent3D,
     ) {
-        todo!()
+        self.record(
+            Call::CopyBufferToTexture,
+            (&command_encoder, &source, &destination, &copy_size),
+        );
+        let CommandEncoder { encoder, errors } =
+            self.table().get(&command_encoder).unwrap().clone();
+        let format = self.table().get(&destination.texture).unwrap().format;
+        let destination = destination.to_core(self.table());
+        if let Err(error) = validate_extent(&copy_size)
+            .and_then(|()| validate_copy_aspect(format, destination.aspect, true))
+        {
+            errors.report(error);
+            return;
+        }
+        errors.check(
+            self.instance()
+                .command_encoder_copy_buffer_to_texture::<crate::Backend>(
+                    encoder,
+                    &source.to_core(self.table()),
+                    &destination,
+                    &copy_size.to_core(self.table()),
+                ),
+        );
     }
 
     fn copy_texture_to_buffer(
         &mut self,
-        command_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        command_encoder: Resource<CommandEncoder>,
         source: webgpu::GpuImageCopyTexture,
         destination: webgpu::GpuImageCopyBuffer,
         copy_size: webgpu::GpuExtent3D,
     ) {
-        let command_encoder = *self.table().get(&command_encoder).unwrap();
-        self.instance()
-            .command_encoder_copy_texture_to_buffer::<crate::Backend>(
-                command_encoder,
-                &source.to_core(&self.table()),
-                &destination.to_core(&self.table()),
-                &copy_size.to_core(&self.table()),
-            )
-            .unwrap();
+        self.record(
+            Call::CopyTextureToBuffer,
+            (&command_encoder, &source, &destination, &copy_size),
+        );
+        let CommandEncoder { encoder, errors } =
+            self.table().get(&command_encoder).unwrap().clone();
+        let format = self.table().get(&source.texture).unwrap().format;
+        let source = source.to_core(self.table());
+        if let Err(error) = validate_extent(&copy_size)
+            .and_then(|()| validate_copy_aspect(format, source.aspect, true))
+        {
+            errors.report(error);
+            return;
+        }
+        errors.check(
+            self.instance()
+                .command_encoder_copy_texture_to_buffer::<crate::Backend>(
+                    encoder,
+                    &source,
+                    &destination.to_core(self.table()),
+                    &copy_size.to_core(self.table()),
+                ),
+        );
     }
 
     fn copy_texture_to_texture(
         &mut self,
-        _self_: Resource<wgpu_core::id::CommandEncoderId>,
-        _source: webgpu::GpuImageCopyTexture,
-        _destination: webgpu::GpuImageCopyTexture,
-        _copy_size: webgpu::GpuExtent3D,
+        command_encoder: Resource<CommandEncoder>,
+        source: webgpu::GpuImageCopyTexture,
+        destination: webgpu::GpuImageCopyTexture,
+        copy_size: webgpu::GpuExtent3D,
     ) {
-        todo!()
+        self.record(
+            Call::CopyTextureToTexture,
+            (&command_encoder, &source, &destination, &copy_size),
+        );
+        let CommandEncoder { encoder, errors } =
+            self.table().get(&command_encoder).unwrap().clone();
+        let source_format = self.table().get(&source.texture).unwrap().format;
+        let destination_format = self.table().get(&destination.texture).unwrap().format;
+        let source = source.to_core(self.table());
+        let destination = destination.to_core(self.table());
+        if let Err(error) = validate_extent(&copy_size)
+            .and_then(|()| validate_copy_aspect(source_format, source.aspect, false))
+            .and_then(|()| validate_copy_aspect(destination_format, destination.aspect, false))
+        {
+            errors.report(error);
+            return;
+        }
+        errors.check(
+            self.instance()
+                .command_encoder_copy_texture_to_texture::<crate::Backend>(
+                    encoder,
+                    &source,
+                    &destination,
+                    &copy_size.to_core(self.table()),
+                ),
+        );
     }
 
     fn clear_buffer(
         &mut self,
-        _self_: Resource<wgpu_core::id::CommandEncoderId>,
-        _buffer: Resource<webgpu::GpuBuffer>,
-        _offset: Option<webgpu::GpuSize64>,
-        _size: Option<webgpu::GpuSize64>,
+        command_encoder: Resource<CommandEncoder>,
+        buffer: Resource<webgpu::GpuBuffer>,
+        offset: Option<webgpu::GpuSize64>,
+        size: Option<webgpu::GpuSize64>,
     ) {
-        todo!()
+        self.record(Call::ClearBuffer, (&command_encoder, &buffer, offset, size));
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&command_encoder).unwrap().clone();
+        let buffer = self.0.table().get(&buffer).unwrap().buffer;
+        // Alignment of `offset` and `size` is validated by wgpu-core.
+        // `None` size clears to the end of the buffer.
+        errors.check(
+            self.0
+                .instance()
+                .command_encoder_clear_buffer::<crate::Backend>(
+                    encoder,
+                    buffer,
+                    offset.unwrap_or(0),
+                    size,
+                ),
+        );
     }
 
     fn resolve_query_set(
         &mut self,
-        _self_: Resource<wgpu_core::id::CommandEncoderId>,
-        _query_set: Resource<webgpu::GpuQuerySet>,
-        _first_query: webgpu::GpuSize32,
-        _query_count: webgpu::GpuSize32,
-        _destination: Resource<webgpu::GpuBuffer>,
-        _destination_offset: webgpu::GpuSize64,
+        command_encoder: Resource<CommandEncoder>,
+        query_set: Resource<webgpu::GpuQuerySet>,
+        first_query: webgpu::GpuSize32,
+        query_count: webgpu::GpuSize32,
+        destination: Resource<webgpu::GpuBuffer>,
+        destination_offset: webgpu::GpuSize64,
     ) {
-        todo!()
+        self.record(
+            Call::ResolveQuerySet,
+            (
+                &command_encoder,
+                &query_set,
+                first_query,
+                query_count,
+                &destination,
+                destination_offset,
+            ),
+        );
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&command_encoder).unwrap().clone();
+        let Some(query_set) = self.0.table().get(&query_set).unwrap().usable() else {
+            return;
+        };
+        let destination = self.0.table().get(&destination).unwrap().buffer;
+        errors.check(
+            self.0
+                .instance()
+                .command_encoder_resolve_query_set::<crate::Backend>(
+                    encoder,
+                    query_set,
+                    first_query,
+                    query_count,
+                    destination,
+                    destination_offset,
+                ),
+        );
     }
 
-    fn label(&mut self, command_encoder: Resource<wgpu_core::id::CommandEncoderId>) -> String {
-        let _command_encoder = self.0.table().get(&command_encoder).unwrap();
-        // TODO: return real label
-        String::new()
+    fn label(&mut self, command_encoder: Resource<CommandEncoder>) -> String {
+        self.label_of(&command_encoder)
     }
 
-    fn set_label(&mut self, _self_: Resource<wgpu_core::id::CommandEncoderId>, _label: String) {
-        todo!()
+    fn set_label(&mut self, command_encoder: Resource<CommandEncoder>, label: String) {
+        self.store_label(&command_encoder, Some(label));
     }
 
-    fn push_debug_group(
-        &mut self,
-        command_encoder: Resource<wgpu_core::id::CommandEncoderId>,
-        group_label: String,
-    ) {
-        let command_encoder = *self.table().get(&command_encoder).unwrap();
-        self.instance()
-            .command_encoder_push_debug_group::<crate::Backend>(command_encoder, &group_label)
-            .unwrap();
+    fn push_debug_group(&mut self, command_encoder: Resource<CommandEncoder>, group_label: String) {
+        self.record(
+            Call::CommandEncoderPushDebugGroup,
+            (&command_encoder, &group_label),
+        );
+        let CommandEncoder { encoder, errors } =
+            self.table().get(&command_encoder).unwrap().clone();
+        errors.check(
+            self.instance()
+                .command_encoder_push_debug_group::<crate::Backend>(encoder, &group_label),
+        );
     }
 
-    fn pop_debug_group(&mut self, command_encoder: Resource<wgpu_core::id::CommandEncoderId>) {
-        let command_encoder = *self.table().get(&command_encoder).unwrap();
-        self.instance()
-            .command_encoder_pop_debug_group::<crate::Backend>(command_encoder)
-            .unwrap();
+    fn pop_debug_group(&mut self, command_encoder: Resource<CommandEncoder>) {
+        self.record(Call::CommandEncoderPopDebugGroup, &command_encoder);
+        let CommandEncoder { encoder, errors } =
+            self.table().get(&command_encoder).unwrap().clone();
+        errors.check(
+            self.instance()
+                .command_encoder_pop_debug_group::<crate::Backend>(encoder),
+        );
     }
 
     fn insert_debug_marker(
         &mut self,
-        command_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        command_encoder: Resource<CommandEncoder>,
         marker_label: String,
     ) {
-        let command_encoder = *self.table().get(&command_encoder).unwrap();
-        self.instance()
-            .command_encoder_insert_debug_marker::<crate::Backend>(command_encoder, &marker_label)
-            .unwrap();
+        self.record(
+            Call::CommandEncoderInsertDebugMarker,
+            (&command_encoder, &marker_label),
+        );
+        let CommandEncoder { encoder, errors } =
+            self.table().get(&command_encoder).unwrap().clone();
+        errors.check(
+            self.instance()
+                .command_encoder_insert_debug_marker::<crate::Backend>(encoder, &marker_label),
+        );
     }
 }
 
 impl<T: WasiWebGpuView> webgpu::HostGpuRenderPassEncoder for WasiWebGpuImpl<T> {
     fn set_pipeline(
         &mut self,
-        render_pass: Resource<wgpu_core::command::RenderPass>,
+        render_pass: Resource<RenderPassEncoder>,
         pipeline: Resource<webgpu::GpuRenderPipeline>,
     ) {
-        let pipeline = pipeline.to_core(&self.0.table());
-        let render_pass = self.0.table().get_mut(&render_pass).unwrap();
-        wgpu_core::command::render_commands::wgpu_render_pass_set_pipeline(render_pass, pipeline);
+        self.record(Call::RenderPassSetPipeline, (&render_pass, &pipeline));
+        let pipeline = pipeline.to_core(self.0.table());
+        let Some(render_pass) = self.0.table().get_mut(&render_pass).unwrap().open() else {
+            return;
+        };
+        render_pass.strip_without_index_format = pipeline.strip_without_index_format;
+        wgpu_core::command::render_commands::wgpu_render_pass_set_pipeline(
+            &mut render_pass.pass,
+            pipeline.pipeline,
+        );
     }
 
     fn draw(
         &mut self,
-        rpass: Resource<wgpu_core::command::RenderPass>,
+        rpass: Resource<RenderPassEncoder>,
         vertex_count: webgpu::GpuSize32,
         instance_count: webgpu::GpuSize32,
         first_vertex: webgpu::GpuSize32,
         first_instance: webgpu::GpuSize32,
     ) {
-        let rpass = self.0.table().get_mut(&rpass).unwrap();
+        self.record(
+            Call::RenderPassDraw,
+            (
+                &rpass,
+                vertex_count,
+                instance_count,
+                first_vertex,
+                first_instance,
+            ),
+        );
+        let Some(RenderPassEncoder { pass: rpass, .. }) =
+            self.0.table().get_mut(&rpass).unwrap().open()
+        else {
+            return;
+        };
 
         wgpu_core::command::render_commands::wgpu_render_pass_draw(
             rpass,
@@ -1257,123 +3462,358 @@ impl<T: WasiWebGpuView> webgpu::HostGpuRenderPassEncoder for WasiWebGpuImpl<T> {
 
     fn end(
         &mut self,
-        rpass: Resource<wgpu_core::command::RenderPass>,
-        non_standard_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        rpass: Resource<RenderPassEncoder>,
+        non_standard_encoder: Resource<CommandEncoder>,
     ) {
-        let encoder = *self.0.table().get(&non_standard_encoder).unwrap();
-        let rpass = self.0.table().delete(rpass).unwrap();
-        self.0
-            .instance()
-            .command_encoder_run_render_pass::<crate::Backend>(encoder, &rpass)
-            .unwrap();
+        self.record(Call::RenderPassEnd, (&rpass, &non_standard_encoder));
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&non_standard_encoder).unwrap().clone();
+        let instance = self.0.instance();
+        let Some(rpass) = self.0.table().get_mut(&rpass).unwrap().open() else {
+            return;
+        };
+        rpass.ended = true;
+        errors.check(
+            instance.command_encoder_run_render_pass::<crate::Backend>(encoder, &rpass.pass),
+        );
     }
 
-    fn drop(&mut self, cwr: Resource<wgpu_core::command::RenderPass>) -> wasmtime::Result<()> {
-        self.0.table().delete(cwr).unwrap();
+    fn drop(&mut self, cwr: Resource<RenderPassEncoder>) -> wasmtime::Result<()> {
+        self.forget_label(&cwr);
+        self.0.table().delete(cwr)?;
         Ok(())
     }
 
     fn set_viewport(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _x: f32,
-        _y: f32,
-        _width: f32,
-        _height: f32,
-        _min_depth: f32,
-        _max_depth: f32,
+        render_pass: Resource<RenderPassEncoder>,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        min_depth: f32,
+        max_depth: f32,
     ) {
-        todo!()
+        self.record(
+            Call::RenderPassSetViewport,
+            (&render_pass, (x, y, width, height), (min_depth, max_depth)),
+        );
+        let Some(RenderPassEncoder {
+            pass: render_pass,
+            attachment_size,
+            errors,
+            ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        // wgpu-core only validates the rect and depth range once the pass ends, without saying which call was wrong.
+        // source: https://www.w3.org/TR/webgpu/#dom-gpurenderpassencoder-setviewport
+        if let Err(error) = validate_viewport(
+            *attachment_size,
+            [x, y, width, height],
+            min_depth..=max_depth,
+        ) {
+            errors.report(error);
+            return;
+        }
+        wgpu_core::command::render_commands::wgpu_render_pass_set_viewport(
+            render_pass,
+            x,
+            y,
+            width,
+            height,
+            min_depth,
+            max_depth,
+        );
     }
 
     fn set_scissor_rect(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _x: webgpu::GpuIntegerCoordinate,
-        _y: webgpu::GpuIntegerCoordinate,
-        _width: webgpu::GpuIntegerCoordinate,
-        _height: webgpu::GpuIntegerCoordinate,
+        render_pass: Resource<RenderPassEncoder>,
+        x: webgpu::GpuIntegerCoordinate,
+        y: webgpu::GpuIntegerCoordinate,
+        width: webgpu::GpuIntegerCoordinate,
+        height: webgpu::GpuIntegerCoordinate,
+    ) {
+        self.record(
+            Call::RenderPassSetScissorRect,
+            (&render_pass, x, y, width, height),
+        );
+        let Some(RenderPassEncoder {
+            pass: render_pass,
+            attachment_size,
+            errors,
+            ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        // source: https://www.w3.org/TR/webgpu/#dom-gpurenderpassencoder-setscissorrect
+        if let Some((attachment_width, attachment_height)) = *attachment_size {
+            let fits = |start: u32, length: u32, size: u32| {
+                start.checked_add(length).is_some_and(|end| end <= size)
+            };
+            if !fits(x, width, attachment_width) || !fits(y, height, attachment_height) {
+                errors.report(GpuError::validation(format!(
+                    "Scissor rect {x}, {y}, {width}x{height} isn't within the {attachment_width}x{attachment_height} attachments"
+                )));
+                return;
+            }
+        }
+        wgpu_core::command::render_commands::wgpu_render_pass_set_scissor_rect(
+            render_pass,
+            x,
+            y,
+            width,
+            height,
+        );
+    }
+
+    fn set_blend_constant(
+        &mut self,
+        render_pass: Resource<RenderPassEncoder>,
+        color: webgpu::GpuColor,
+    ) {
+        self.record(Call::RenderPassSetBlendConstant, (&render_pass, &color));
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_set_blend_constant(
+            render_pass,
+            &color.into(),
+        );
+    }
+
+    fn set_stencil_reference(
+        &mut self,
+        render_pass: Resource<RenderPassEncoder>,
+        reference: webgpu::GpuStencilValue,
     ) {
-        todo!()
+        self.record(
+            Call::RenderPassSetStencilReference,
+            (&render_pass, reference),
+        );
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_set_stencil_reference(
+            render_pass,
+            reference,
+        );
+    }
+
+    fn set_push_constants(
+        &mut self,
+        render_pass: Resource<RenderPassEncoder>,
+        stages: webgpu::GpuShaderStageFlags,
+        offset: u32,
+        data: Vec<u8>,
+    ) -> wasmtime::Result<()> {
+        // wgpu-core panics on unaligned push constants instead of reporting an error.
+        let alignment = wgpu_types::PUSH_CONSTANT_ALIGNMENT;
+        anyhow::ensure!(
+            offset.is_multiple_of(alignment) && (data.len() as u32).is_multiple_of(alignment),
+            "Push constant offset {offset} and size {} must be multiples of {alignment}",
+            data.len()
+        );
+        self.record(
+            Call::RenderPassSetPushConstants,
+            (&render_pass, stages, offset, &data),
+        );
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass)?.open()
+        else {
+            return Ok(());
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_set_push_constants(
+            render_pass,
+            wgpu_types::ShaderStages::from_bits_truncate(stages),
+            offset,
+            &data,
+        );
+        Ok(())
     }
 
-    fn set_blend_constant(
+    fn begin_pipeline_statistics_query(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _color: webgpu::GpuColor,
+        render_pass: Resource<RenderPassEncoder>,
+        query_set: Resource<webgpu::GpuQuerySet>,
+        query_index: webgpu::GpuSize32,
     ) {
-        todo!()
+        self.record(
+            Call::RenderPassBeginPipelineStatisticsQuery,
+            (&render_pass, &query_set, query_index),
+        );
+        let Some(query_set) = self.0.table().get(&query_set).unwrap().usable() else {
+            return;
+        };
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_begin_pipeline_statistics_query(
+            render_pass,
+            query_set,
+            query_index,
+        );
     }
 
-    fn set_stencil_reference(
-        &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _reference: webgpu::GpuStencilValue,
-    ) {
-        todo!()
+    fn end_pipeline_statistics_query(&mut self, render_pass: Resource<RenderPassEncoder>) {
+        self.record(Call::RenderPassEndPipelineStatisticsQuery, &render_pass);
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_end_pipeline_statistics_query(
+            render_pass,
+        );
     }
 
     fn begin_occlusion_query(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _query_index: webgpu::GpuSize32,
+        render_pass: Resource<RenderPassEncoder>,
+        query_index: webgpu::GpuSize32,
     ) {
-        todo!()
+        self.record(
+            Call::RenderPassBeginOcclusionQuery,
+            (&render_pass, query_index),
+        );
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        // Counts into the pass's occlusion query set, wgpu-core reports passes without one.
+        wgpu_core::command::render_commands::wgpu_render_pass_begin_occlusion_query(
+            render_pass,
+            query_index,
+        );
     }
 
-    fn end_occlusion_query(&mut self, _self_: Resource<wgpu_core::command::RenderPass>) {
-        todo!()
+    fn end_occlusion_query(&mut self, render_pass: Resource<RenderPassEncoder>) {
+        self.record(Call::RenderPassEndOcclusionQuery, &render_pass);
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_end_occlusion_query(render_pass);
     }
 
     fn execute_bundles(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _bundles: Vec<Resource<webgpu::GpuRenderBundle>>,
+        render_pass: Resource<RenderPassEncoder>,
+        bundles: Vec<Resource<webgpu::GpuRenderBundle>>,
     ) {
-        todo!()
+        self.record_unrecorded("execute-bundles");
+        let bundles = bundles
+            .into_iter()
+            .map(|bundle| *self.0.table().get(&bundle).unwrap())
+            .collect::<Vec<_>>();
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_execute_bundles(
+            render_pass,
+            &bundles,
+        );
     }
 
-    fn label(&mut self, _self_: Resource<wgpu_core::command::RenderPass>) -> String {
-        todo!()
+    fn label(&mut self, render_pass: Resource<RenderPassEncoder>) -> String {
+        self.label_of(&render_pass)
     }
 
-    fn set_label(&mut self, _self_: Resource<wgpu_core::command::RenderPass>, _label: String) {
-        todo!()
+    fn set_label(&mut self, render_pass: Resource<RenderPassEncoder>, label: String) {
+        self.store_label(&render_pass, Some(label));
     }
 
-    fn push_debug_group(
-        &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _group_label: String,
-    ) {
-        todo!()
+    fn push_debug_group(&mut self, render_pass: Resource<RenderPassEncoder>, group_label: String) {
+        self.record(Call::RenderPassPushDebugGroup, (&render_pass, &group_label));
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_push_debug_group(
+            render_pass,
+            &group_label,
+            0,
+        );
     }
 
-    fn pop_debug_group(&mut self, _self_: Resource<wgpu_core::command::RenderPass>) {
-        todo!()
+    fn pop_debug_group(&mut self, render_pass: Resource<RenderPassEncoder>) {
+        self.record(Call::RenderPassPopDebugGroup, &render_pass);
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_pop_debug_group(render_pass);
     }
 
     fn insert_debug_marker(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _marker_label: String,
+        render_pass: Resource<RenderPassEncoder>,
+        marker_label: String,
     ) {
-        todo!()
+        self.record(
+            Call::RenderPassInsertDebugMarker,
+            (&render_pass, &marker_label),
+        );
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_insert_debug_marker(
+            render_pass,
+            &marker_label,
+            0,
+        );
     }
 
     fn set_bind_group(
         &mut self,
-        render_pass: Resource<wgpu_core::command::RenderPass>,
+        render_pass: Resource<RenderPassEncoder>,
         index: webgpu::GpuIndex32,
         bind_group: Resource<webgpu::GpuBindGroup>,
         dynamic_offsets: Option<Vec<webgpu::GpuBufferDynamicOffset>>,
     ) {
+        self.record(
+            Call::RenderPassSetBindGroup,
+            (&render_pass, index, &bind_group, &dynamic_offsets),
+        );
         let bind_group = *self.0.table().get(&bind_group).unwrap();
-        let mut render_pass = self.0.table().get_mut(&render_pass).unwrap();
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
 
-        let dynamic_offsets = dynamic_offsets.unwrap();
+        let dynamic_offsets = dynamic_offsets.unwrap_or_default();
         wgpu_core::command::render_commands::wgpu_render_pass_set_bind_group(
-            &mut render_pass,
+            render_pass,
             index,
             bind_group,
             &dynamic_offsets,
@@ -1382,52 +3822,91 @@ impl<T: WasiWebGpuView> webgpu::HostGpuRenderPassEncoder for WasiWebGpuImpl<T> {
 
     fn set_index_buffer(
         &mut self,
-        render_pass: Resource<wgpu_core::command::RenderPass>,
+        render_pass: Resource<RenderPassEncoder>,
         buffer: Resource<webgpu::GpuBuffer>,
         index_format: webgpu::GpuIndexFormat,
         offset: webgpu::GpuSize64,
         size: webgpu::GpuSize64,
     ) {
-        let buffer_id = self.table().get(&buffer).unwrap().buffer;
-        let render_pass = self.table().get_mut(&render_pass).unwrap();
-        render_pass.set_index_buffer(
-            buffer_id,
-            index_format.into(),
-            offset,
-            core::num::NonZeroU64::new(size),
+        self.record(
+            Call::RenderPassSetIndexBuffer,
+            (&render_pass, &buffer, index_format, offset, size),
         );
+        let buffer = self.table().get(&buffer).unwrap();
+        let buffer_id = buffer.buffer;
+        let index_format = index_format.into();
+        let size = core::num::NonZeroU64::new(size);
+        // Draws past the end of the bound range are caught by wgpu-core when the pass is run.
+        if let Err(error) = validate_index_buffer(buffer, index_format, offset, size) {
+            buffer.errors.report(error);
+            return;
+        }
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        render_pass.set_index_buffer(buffer_id, index_format, offset, size);
     }
 
     fn set_vertex_buffer(
         &mut self,
-        render_pass: Resource<wgpu_core::command::RenderPass>,
+        render_pass: Resource<RenderPassEncoder>,
         slot: webgpu::GpuIndex32,
         buffer: Resource<webgpu::GpuBuffer>,
         offset: webgpu::GpuSize64,
         size: webgpu::GpuSize64,
     ) {
+        self.record(
+            Call::RenderPassSetVertexBuffer,
+            (&render_pass, slot, &buffer, offset, size),
+        );
         let buffer_id = self.0.table().get(&buffer).unwrap().buffer;
-        let mut render_pass = self.0.table().get_mut(&render_pass).unwrap();
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
 
         wgpu_core::command::render_commands::wgpu_render_pass_set_vertex_buffer(
-            &mut render_pass,
+            render_pass,
             slot,
             buffer_id,
             offset,
-            Some(size.try_into().unwrap()),
+            core::num::NonZeroU64::new(size),
         );
     }
 
     fn draw_indexed(
         &mut self,
-        render_pass: Resource<wgpu_core::command::RenderPass>,
+        render_pass: Resource<RenderPassEncoder>,
         index_count: webgpu::GpuSize32,
         instance_count: webgpu::GpuSize32,
         first_index: webgpu::GpuSize32,
         base_vertex: webgpu::GpuSignedOffset32,
         first_instance: webgpu::GpuSize32,
     ) {
-        let render_pass = self.table().get_mut(&render_pass).unwrap();
+        self.record(
+            Call::RenderPassDrawIndexed,
+            (
+                &render_pass,
+                index_count,
+                instance_count,
+                first_index,
+                base_vertex,
+                first_instance,
+            ),
+        );
+        let Some(render_pass) = self.0.table().get_mut(&render_pass).unwrap().open() else {
+            return;
+        };
+        if let Err(error) = validate_indexed_draw(render_pass.strip_without_index_format) {
+            render_pass.errors.report(error);
+            return;
+        }
+        let render_pass = &mut render_pass.pass;
 
         wgpu_core::command::render_commands::wgpu_render_pass_draw_indexed(
             render_pass,
@@ -1441,20 +3920,131 @@ impl<T: WasiWebGpuView> webgpu::HostGpuRenderPassEncoder for WasiWebGpuImpl<T> {
 
     fn draw_indirect(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _indirect_buffer: Resource<webgpu::GpuBuffer>,
-        _indirect_offset: webgpu::GpuSize64,
+        render_pass: Resource<RenderPassEncoder>,
+        indirect_buffer: Resource<webgpu::GpuBuffer>,
+        indirect_offset: webgpu::GpuSize64,
     ) {
-        todo!()
+        self.record(
+            Call::RenderPassDrawIndirect,
+            (&render_pass, &indirect_buffer, indirect_offset),
+        );
+        let buffer = self.0.table().get(&indirect_buffer).unwrap().buffer;
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::render_commands::wgpu_render_pass_draw_indirect(
+            render_pass,
+            buffer,
+            indirect_offset,
+        );
     }
 
     fn draw_indexed_indirect(
         &mut self,
-        _self_: Resource<wgpu_core::command::RenderPass>,
-        _indirect_buffer: Resource<webgpu::GpuBuffer>,
-        _indirect_offset: webgpu::GpuSize64,
+        render_pass: Resource<RenderPassEncoder>,
+        indirect_buffer: Resource<webgpu::GpuBuffer>,
+        indirect_offset: webgpu::GpuSize64,
+    ) {
+        self.record(
+            Call::RenderPassDrawIndexedIndirect,
+            (&render_pass, &indirect_buffer, indirect_offset),
+        );
+        let buffer = self.0.table().get(&indirect_buffer).unwrap().buffer;
+        let Some(render_pass) = self.0.table().get_mut(&render_pass).unwrap().open() else {
+            return;
+        };
+        if let Err(error) = validate_indexed_draw(render_pass.strip_without_index_format) {
+            render_pass.errors.report(error);
+            return;
+        }
+        let render_pass = &mut render_pass.pass;
+        wgpu_core::command::render_commands::wgpu_render_pass_draw_indexed_indirect(
+            render_pass,
+            buffer,
+            indirect_offset,
+        );
+    }
+
+    fn draw_indirect_count(
+        &mut self,
+        render_pass: Resource<RenderPassEncoder>,
+        indirect_buffer: Resource<webgpu::GpuBuffer>,
+        indirect_offset: webgpu::GpuSize64,
+        count_buffer: Resource<webgpu::GpuBuffer>,
+        count_offset: webgpu::GpuSize64,
+        max_count: webgpu::GpuSize32,
+    ) {
+        self.record(
+            Call::RenderPassDrawIndirectCount,
+            (
+                &render_pass,
+                &indirect_buffer,
+                indirect_offset,
+                &count_buffer,
+                count_offset,
+                max_count,
+            ),
+        );
+        let buffer = self.0.table().get(&indirect_buffer).unwrap().buffer;
+        let count_buffer = self.0.table().get(&count_buffer).unwrap().buffer;
+        let Some(RenderPassEncoder {
+            pass: render_pass, ..
+        }) = self.0.table().get_mut(&render_pass).unwrap().open()
+        else {
+            return;
+        };
+        // The multi-draw-indirect-count feature is checked by wgpu-core when the pass is run.
+        wgpu_core::command::render_commands::wgpu_render_pass_multi_draw_indirect_count(
+            render_pass,
+            buffer,
+            indirect_offset,
+            count_buffer,
+            count_offset,
+            max_count,
+        );
+    }
+
+    fn draw_indexed_indirect_count(
+        &mut self,
+        render_pass: Resource<RenderPassEncoder>,
+        indirect_buffer: Resource<webgpu::GpuBuffer>,
+        indirect_offset: webgpu::GpuSize64,
+        count_buffer: Resource<webgpu::GpuBuffer>,
+        count_offset: webgpu::GpuSize64,
+        max_count: webgpu::GpuSize32,
     ) {
-        todo!()
+        self.record(
+            Call::RenderPassDrawIndexedIndirectCount,
+            (
+                &render_pass,
+                &indirect_buffer,
+                indirect_offset,
+                &count_buffer,
+                count_offset,
+                max_count,
+            ),
+        );
+        let buffer = self.0.table().get(&indirect_buffer).unwrap().buffer;
+        let count_buffer = self.0.table().get(&count_buffer).unwrap().buffer;
+        let Some(render_pass) = self.0.table().get_mut(&render_pass).unwrap().open() else {
+            return;
+        };
+        if let Err(error) = validate_indexed_draw(render_pass.strip_without_index_format) {
+            render_pass.errors.report(error);
+            return;
+        }
+        let render_pass = &mut render_pass.pass;
+        wgpu_core::command::render_commands::wgpu_render_pass_multi_draw_indexed_indirect_count(
+            render_pass,
+            buffer,
+            indirect_offset,
+            count_buffer,
+            count_offset,
+            max_count,
+        );
     }
 }
 
@@ -1462,138 +4052,217 @@ impl<T: WasiWebGpuView> webgpu::HostGpuUncapturedErrorEvent for WasiWebGpuImpl<T
     fn new(
         &mut self,
         _type_: String,
-        _gpu_uncaptured_error_event_init_dict: webgpu::GpuUncapturedErrorEventInit,
+        init: webgpu::GpuUncapturedErrorEventInit,
     ) -> Resource<webgpu::GpuUncapturedErrorEvent> {
-        todo!()
+        let error = self.0.table().delete(init.error).unwrap();
+        self.0.table().push(UncapturedErrorEvent { error }).unwrap()
     }
 
     fn error(
         &mut self,
-        _self_: Resource<webgpu::GpuUncapturedErrorEvent>,
+        event: Resource<webgpu::GpuUncapturedErrorEvent>,
     ) -> Resource<webgpu::GpuError> {
-        todo!()
+        let error = self.0.table().get(&event).unwrap().error.clone();
+        self.0.table().push(error).unwrap()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuUncapturedErrorEvent>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, event: Resource<webgpu::GpuUncapturedErrorEvent>) -> wasmtime::Result<()> {
+        self.0.table().delete(event)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuInternalError for WasiWebGpuImpl<T> {
-    fn new(&mut self, _message: String) -> Resource<webgpu::GpuInternalError> {
-        todo!()
+    fn new(&mut self, message: String) -> Resource<webgpu::GpuInternalError> {
+        self.0.table().push(GpuError::internal(message)).unwrap()
     }
 
-    fn message(&mut self, _self_: Resource<webgpu::GpuInternalError>) -> String {
-        todo!()
+    fn message(&mut self, error: Resource<webgpu::GpuInternalError>) -> String {
+        let error = self.0.table().get(&error).unwrap();
+        error.message().to_string()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuInternalError>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, error: Resource<webgpu::GpuInternalError>) -> wasmtime::Result<()> {
+        self.0.table().delete(error)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuOutOfMemoryError for WasiWebGpuImpl<T> {
-    fn new(&mut self, _message: String) -> Resource<webgpu::GpuOutOfMemoryError> {
-        todo!()
+    fn new(&mut self, message: String) -> Resource<webgpu::GpuOutOfMemoryError> {
+        self.0
+            .table()
+            .push(GpuError::out_of_memory(message))
+            .unwrap()
     }
 
-    fn message(&mut self, _self_: Resource<webgpu::GpuOutOfMemoryError>) -> String {
-        todo!()
+    fn message(&mut self, error: Resource<webgpu::GpuOutOfMemoryError>) -> String {
+        let error = self.0.table().get(&error).unwrap();
+        error.message().to_string()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuOutOfMemoryError>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, error: Resource<webgpu::GpuOutOfMemoryError>) -> wasmtime::Result<()> {
+        self.0.table().delete(error)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuValidationError for WasiWebGpuImpl<T> {
-    fn new(&mut self, _message: String) -> Resource<webgpu::GpuValidationError> {
-        todo!()
+    fn new(&mut self, message: String) -> Resource<webgpu::GpuValidationError> {
+        self.0.table().push(GpuError::validation(message)).unwrap()
     }
 
-    fn message(&mut self, _self_: Resource<webgpu::GpuValidationError>) -> String {
-        todo!()
+    fn message(&mut self, error: Resource<webgpu::GpuValidationError>) -> String {
+        let error = self.0.table().get(&error).unwrap();
+        error.message().to_string()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuValidationError>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, error: Resource<webgpu::GpuValidationError>) -> wasmtime::Result<()> {
+        self.0.table().delete(error)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuError for WasiWebGpuImpl<T> {
-    fn message(&mut self, _self_: Resource<webgpu::GpuError>) -> String {
-        todo!()
+    fn message(&mut self, error: Resource<webgpu::GpuError>) -> String {
+        let error = self.0.table().get(&error).unwrap();
+        error.message().to_string()
+    }
+
+    fn kind(&mut self, error: Resource<webgpu::GpuError>) -> webgpu::GpuErrorKind {
+        let error = self.0.table().get(&error).unwrap();
+        error.kind()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuError>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, error: Resource<webgpu::GpuError>) -> wasmtime::Result<()> {
+        self.0.table().delete(error)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuDeviceLostInfo for WasiWebGpuImpl<T> {
-    fn reason(
-        &mut self,
-        _self_: Resource<webgpu::GpuDeviceLostInfo>,
-    ) -> webgpu::GpuDeviceLostReason {
-        todo!()
+    fn reason(&mut self, info: Resource<webgpu::GpuDeviceLostInfo>) -> webgpu::GpuDeviceLostReason {
+        self.0.table().get(&info).unwrap().reason
     }
 
-    fn message(&mut self, _self_: Resource<webgpu::GpuDeviceLostInfo>) -> String {
-        todo!()
+    fn message(&mut self, info: Resource<webgpu::GpuDeviceLostInfo>) -> String {
+        self.0.table().get(&info).unwrap().message.clone()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuDeviceLostInfo>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, info: Resource<webgpu::GpuDeviceLostInfo>) -> wasmtime::Result<()> {
+        self.0.table().delete(info)?;
+        Ok(())
     }
 }
+// Canvas contexts are `graphics-context`s here, so none are ever handed out. The resource has no values, so matching
+// on one that's in the table covers every case.
 impl<T: WasiWebGpuView> webgpu::HostGpuCanvasContext for WasiWebGpuImpl<T> {
     fn canvas(
         &mut self,
-        _self_: Resource<webgpu::GpuCanvasContext>,
-    ) -> webgpu::HtmlCanvasElementOrOffscreenCanvas {
-        todo!()
+        context: Resource<webgpu::GpuCanvasContext>,
+    ) -> wasmtime::Result<webgpu::HtmlCanvasElementOrOffscreenCanvas> {
+        match *self.0.table().get(&context)? {}
     }
 
     fn configure(
         &mut self,
-        _self_: Resource<webgpu::GpuCanvasContext>,
+        context: Resource<webgpu::GpuCanvasContext>,
         _configuration: webgpu::GpuCanvasConfiguration,
-    ) {
-        todo!()
+    ) -> wasmtime::Result<()> {
+        match *self.0.table().get(&context)? {}
     }
 
-    fn unconfigure(&mut self, _self_: Resource<webgpu::GpuCanvasContext>) {
-        todo!()
+    fn unconfigure(&mut self, context: Resource<webgpu::GpuCanvasContext>) -> wasmtime::Result<()> {
+        match *self.0.table().get(&context)? {}
     }
 
     fn get_current_texture(
         &mut self,
-        _self_: Resource<webgpu::GpuCanvasContext>,
-    ) -> Resource<webgpu::GpuTexture> {
-        todo!()
+        context: Resource<webgpu::GpuCanvasContext>,
+    ) -> wasmtime::Result<Resource<webgpu::GpuTexture>> {
+        match *self.0.table().get(&context)? {}
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuCanvasContext>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, context: Resource<webgpu::GpuCanvasContext>) -> wasmtime::Result<()> {
+        match self.0.table().delete(context)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuRenderBundle for WasiWebGpuImpl<T> {
-    fn label(&mut self, _self_: Resource<webgpu::GpuRenderBundle>) -> String {
-        todo!()
+    fn label(&mut self, bundle: Resource<webgpu::GpuRenderBundle>) -> String {
+        self.label_of(&bundle)
+    }
+
+    fn set_label(&mut self, bundle: Resource<webgpu::GpuRenderBundle>, label: String) {
+        self.store_label(&bundle, Some(label));
+    }
+
+    fn drop(&mut self, bundle: Resource<webgpu::GpuRenderBundle>) -> wasmtime::Result<()> {
+        self.forget_label(&bundle);
+        self.0.table().delete(bundle)?;
+        Ok(())
     }
+}
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuRenderBundle>, _label: String) {
-        todo!()
+impl<T: WasiWebGpuView> webgpu::HostGpuStaticLayer for WasiWebGpuImpl<T> {
+    fn bundle(
+        &mut self,
+        static_layer: Resource<webgpu::GpuStaticLayer>,
+    ) -> Resource<webgpu::GpuRenderBundle> {
+        let bundle = self.0.table().get(&static_layer).unwrap().bundle.bundle;
+        self.0.table().push(bundle).unwrap()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuRenderBundle>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, static_layer: Resource<webgpu::GpuStaticLayer>) -> wasmtime::Result<()> {
+        // The bundle stays cached on the device, unless it was evicted.
+        self.0.table().delete(static_layer)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuComputePassEncoder for WasiWebGpuImpl<T> {
+    fn begin_pipeline_statistics_query(
+        &mut self,
+        encoder: Resource<webgpu::GpuComputePassEncoder>,
+        query_set: Resource<webgpu::GpuQuerySet>,
+        query_index: webgpu::GpuSize32,
+    ) {
+        self.record(
+            Call::ComputePassBeginPipelineStatisticsQuery,
+            (&encoder, &query_set, query_index),
+        );
+        let Some(query_set) = self.0.table().get(&query_set).unwrap().usable() else {
+            return;
+        };
+        let Some(ComputePassEncoder { pass: encoder, .. }) =
+            self.0.table().get_mut(&encoder).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::compute_commands::wgpu_compute_pass_begin_pipeline_statistics_query(
+            encoder,
+            query_set,
+            query_index,
+        );
+    }
+
+    fn end_pipeline_statistics_query(&mut self, encoder: Resource<webgpu::GpuComputePassEncoder>) {
+        self.record(Call::ComputePassEndPipelineStatisticsQuery, &encoder);
+        let Some(ComputePassEncoder { pass: encoder, .. }) =
+            self.0.table().get_mut(&encoder).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::compute_commands::wgpu_compute_pass_end_pipeline_statistics_query(
+            encoder,
+        );
+    }
+
     fn set_pipeline(
         &mut self,
         encoder: Resource<webgpu::GpuComputePassEncoder>,
         pipeline: Resource<webgpu::GpuComputePipeline>,
     ) {
+        self.record(Call::ComputePassSetPipeline, (&encoder, &pipeline));
         let pipeline = *self.0.table().get(&pipeline).unwrap();
-        let encoder = self.0.table().get_mut(&encoder).unwrap();
+        let Some(ComputePassEncoder { pass: encoder, .. }) =
+            self.0.table().get_mut(&encoder).unwrap().open()
+        else {
+            return;
+        };
         wgpu_core::command::compute_commands::wgpu_compute_pass_set_pipeline(encoder, pipeline);
     }
 
@@ -1604,55 +4273,104 @@ impl<T: WasiWebGpuView> webgpu::HostGpuComputePassEncoder for WasiWebGpuImpl<T>
         workgroup_count_y: Option<webgpu::GpuSize32>,
         workgroup_count_z: Option<webgpu::GpuSize32>,
     ) {
-        let encoder = self.0.table().get_mut(&encoder).unwrap();
+        self.record(
+            Call::ComputePassDispatchWorkgroups,
+            (
+                &encoder,
+                workgroup_count_x,
+                workgroup_count_y,
+                workgroup_count_z,
+            ),
+        );
+        let Some(ComputePassEncoder { pass: encoder, .. }) =
+            self.0.table().get_mut(&encoder).unwrap().open()
+        else {
+            return;
+        };
         wgpu_core::command::compute_commands::wgpu_compute_pass_dispatch_workgroups(
             encoder,
             workgroup_count_x,
-            workgroup_count_y.unwrap(),
-            workgroup_count_z.unwrap(),
+            // source: https://www.w3.org/TR/webgpu/#dom-gpucomputepassencoder-dispatchworkgroups
+            workgroup_count_y.unwrap_or(1),
+            workgroup_count_z.unwrap_or(1),
         );
     }
 
     fn dispatch_workgroups_indirect(
         &mut self,
-        _self_: Resource<webgpu::GpuComputePassEncoder>,
-        _indirect_buffer: Resource<webgpu::GpuBuffer>,
-        _indirect_offset: webgpu::GpuSize64,
+        encoder: Resource<webgpu::GpuComputePassEncoder>,
+        indirect_buffer: Resource<webgpu::GpuBuffer>,
+        indirect_offset: webgpu::GpuSize64,
     ) {
-        todo!()
+        self.record(
+            Call::ComputePassDispatchWorkgroupsIndirect,
+            (&encoder, &indirect_buffer, indirect_offset),
+        );
+        let buffer = self.0.table().get(&indirect_buffer).unwrap().buffer;
+        let Some(ComputePassEncoder { pass: encoder, .. }) =
+            self.0.table().get_mut(&encoder).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::compute_commands::wgpu_compute_pass_dispatch_workgroups_indirect(
+            encoder,
+            buffer,
+            indirect_offset,
+        );
     }
 
     fn end(
         &mut self,
-        cpass: Resource<wgpu_core::command::ComputePass>,
-        non_standard_encoder: Resource<wgpu_core::id::CommandEncoderId>,
+        cpass: Resource<ComputePassEncoder>,
+        non_standard_encoder: Resource<CommandEncoder>,
     ) {
-        let encoder = *self.0.table().get(&non_standard_encoder).unwrap();
-        let cpass = self.0.table().delete(cpass).unwrap();
-        self.0
-            .instance()
-            .command_encoder_run_compute_pass::<crate::Backend>(encoder, &cpass)
-            .unwrap();
+        self.record(Call::ComputePassEnd, (&cpass, &non_standard_encoder));
+        let CommandEncoder { encoder, errors } =
+            self.0.table().get(&non_standard_encoder).unwrap().clone();
+        let instance = self.0.instance();
+        let Some(cpass) = self.0.table().get_mut(&cpass).unwrap().open() else {
+            return;
+        };
+        cpass.ended = true;
+        errors.check(
+            instance.command_encoder_run_compute_pass::<crate::Backend>(encoder, &cpass.pass),
+        );
     }
 
-    fn label(&mut self, _self_: Resource<webgpu::GpuComputePassEncoder>) -> String {
-        todo!()
+    fn label(&mut self, cpass: Resource<webgpu::GpuComputePassEncoder>) -> String {
+        self.label_of(&cpass)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuComputePassEncoder>, _label: String) {
-        todo!()
+    fn set_label(&mut self, cpass: Resource<webgpu::GpuComputePassEncoder>, label: String) {
+        self.store_label(&cpass, Some(label));
     }
 
     fn push_debug_group(
         &mut self,
-        _self_: Resource<webgpu::GpuComputePassEncoder>,
-        _group_label: String,
+        cpass: Resource<webgpu::GpuComputePassEncoder>,
+        group_label: String,
     ) {
-        todo!()
+        self.record(Call::ComputePassPushDebugGroup, (&cpass, &group_label));
+        let Some(ComputePassEncoder { pass: cpass, .. }) =
+            self.0.table().get_mut(&cpass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::compute_commands::wgpu_compute_pass_push_debug_group(
+            cpass,
+            &group_label,
+            0,
+        );
     }
 
-    fn pop_debug_group(&mut self, _self_: Resource<webgpu::GpuComputePassEncoder>) {
-        todo!()
+    fn pop_debug_group(&mut self, cpass: Resource<webgpu::GpuComputePassEncoder>) {
+        self.record(Call::ComputePassPopDebugGroup, &cpass);
+        let Some(ComputePassEncoder { pass: cpass, .. }) =
+            self.0.table().get_mut(&cpass).unwrap().open()
+        else {
+            return;
+        };
+        wgpu_core::command::compute_commands::wgpu_compute_pass_pop_debug_group(cpass);
     }
 
     fn insert_debug_marker(
@@ -1660,7 +4378,12 @@ impl<T: WasiWebGpuView> webgpu::HostGpuComputePassEncoder for WasiWebGpuImpl<T>
         cpass: Resource<webgpu::GpuComputePassEncoder>,
         label: String,
     ) {
-        let cpass = self.0.table().get_mut(&cpass).unwrap();
+        self.record(Call::ComputePassInsertDebugMarker, (&cpass, &label));
+        let Some(ComputePassEncoder { pass: cpass, .. }) =
+            self.0.table().get_mut(&cpass).unwrap().open()
+        else {
+            return;
+        };
         wgpu_core::command::compute_commands::wgpu_compute_pass_insert_debug_marker(
             cpass, &label, 0,
         );
@@ -1673,9 +4396,17 @@ impl<T: WasiWebGpuView> webgpu::HostGpuComputePassEncoder for WasiWebGpuImpl<T>
         bind_group: Resource<webgpu::GpuBindGroup>,
         dynamic_offsets: Option<Vec<webgpu::GpuBufferDynamicOffset>>,
     ) {
+        self.record(
+            Call::ComputePassSetBindGroup,
+            (&encoder, index, &bind_group, &dynamic_offsets),
+        );
         let bind_group = *self.0.table().get(&bind_group).unwrap();
-        let encoder = self.0.table().get_mut(&encoder).unwrap();
-        let dynamic_offsets = dynamic_offsets.unwrap();
+        let Some(ComputePassEncoder { pass: encoder, .. }) =
+            self.0.table().get_mut(&encoder).unwrap().open()
+        else {
+            return;
+        };
+        let dynamic_offsets = dynamic_offsets.unwrap_or_default();
         wgpu_core::command::compute_commands::wgpu_compute_pass_set_bind_group(
             encoder,
             index,
@@ -1684,306 +4415,523 @@ impl<T: WasiWebGpuView> webgpu::HostGpuComputePassEncoder for WasiWebGpuImpl<T>
         )
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuComputePassEncoder>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, cpass: Resource<webgpu::GpuComputePassEncoder>) -> wasmtime::Result<()> {
+        self.forget_label(&cpass);
+        self.0.table().delete(cpass)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuPipelineError for WasiWebGpuImpl<T> {
     fn new(
         &mut self,
         _message: Option<String>,
-        _options: webgpu::GpuPipelineErrorInit,
+        options: webgpu::GpuPipelineErrorInit,
     ) -> Resource<webgpu::GpuPipelineError> {
-        todo!()
+        let error = PipelineError {
+            reason: options.reason,
+        };
+        self.0.table().push(error).unwrap()
     }
 
     fn reason(
         &mut self,
-        _self_: Resource<webgpu::GpuPipelineError>,
+        error: Resource<webgpu::GpuPipelineError>,
     ) -> webgpu::GpuPipelineErrorReason {
-        todo!()
+        self.0.table().get(&error).unwrap().reason
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuPipelineError>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, error: Resource<webgpu::GpuPipelineError>) -> wasmtime::Result<()> {
+        self.0.table().delete(error)?;
+        Ok(())
     }
 }
+// Like canvas contexts, no compilation messages are ever handed out, see `CompilationInfo`.
 impl<T: WasiWebGpuView> webgpu::HostGpuCompilationMessage for WasiWebGpuImpl<T> {
-    fn message(&mut self, _self_: Resource<webgpu::GpuCompilationMessage>) -> String {
-        todo!()
+    fn message(
+        &mut self,
+        message: Resource<webgpu::GpuCompilationMessage>,
+    ) -> wasmtime::Result<String> {
+        match *self.0.table().get(&message)? {}
     }
 
     fn type_(
         &mut self,
-        _self_: Resource<webgpu::GpuCompilationMessage>,
-    ) -> webgpu::GpuCompilationMessageType {
-        todo!()
+        message: Resource<webgpu::GpuCompilationMessage>,
+    ) -> wasmtime::Result<webgpu::GpuCompilationMessageType> {
+        match *self.0.table().get(&message)? {}
     }
 
-    fn line_num(&mut self, _self_: Resource<webgpu::GpuCompilationMessage>) -> u64 {
-        todo!()
+    fn line_num(
+        &mut self,
+        message: Resource<webgpu::GpuCompilationMessage>,
+    ) -> wasmtime::Result<u64> {
+        match *self.0.table().get(&message)? {}
     }
 
-    fn line_pos(&mut self, _self_: Resource<webgpu::GpuCompilationMessage>) -> u64 {
-        todo!()
+    fn line_pos(
+        &mut self,
+        message: Resource<webgpu::GpuCompilationMessage>,
+    ) -> wasmtime::Result<u64> {
+        match *self.0.table().get(&message)? {}
     }
 
-    fn offset(&mut self, _self_: Resource<webgpu::GpuCompilationMessage>) -> u64 {
-        todo!()
+    fn offset(
+        &mut self,
+        message: Resource<webgpu::GpuCompilationMessage>,
+    ) -> wasmtime::Result<u64> {
+        match *self.0.table().get(&message)? {}
     }
 
-    fn length(&mut self, _self_: Resource<webgpu::GpuCompilationMessage>) -> u64 {
-        todo!()
+    fn length(
+        &mut self,
+        message: Resource<webgpu::GpuCompilationMessage>,
+    ) -> wasmtime::Result<u64> {
+        match *self.0.table().get(&message)? {}
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuCompilationMessage>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, message: Resource<webgpu::GpuCompilationMessage>) -> wasmtime::Result<()> {
+        match self.0.table().delete(message)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuCompilationInfo for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::GpuCompilationInfo>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, info: Resource<webgpu::GpuCompilationInfo>) -> wasmtime::Result<()> {
+        self.0.table().delete(info)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuQuerySet for WasiWebGpuImpl<T> {
-    fn destroy(&mut self, _self_: Resource<webgpu::GpuQuerySet>) {
-        todo!()
+    fn destroy(&mut self, query_set: Resource<webgpu::GpuQuerySet>) {
+        self.record(Call::DestroyQuerySet, &query_set);
+        self.0.table().get_mut(&query_set).unwrap().destroyed = true;
     }
 
-    fn type_(&mut self, _self_: Resource<webgpu::GpuQuerySet>) -> webgpu::GpuQueryType {
-        todo!()
+    fn type_(&mut self, query_set: Resource<webgpu::GpuQuerySet>) -> webgpu::GpuQueryType {
+        let query_set = self.0.table().get(&query_set).unwrap();
+        query_set.ty.into()
     }
 
-    fn count(&mut self, _self_: Resource<webgpu::GpuQuerySet>) -> webgpu::GpuSize32Out {
-        todo!()
+    fn count(&mut self, query_set: Resource<webgpu::GpuQuerySet>) -> webgpu::GpuSize32Out {
+        let query_set = self.0.table().get(&query_set).unwrap();
+        query_set.count
     }
 
-    fn label(&mut self, _self_: Resource<webgpu::GpuQuerySet>) -> String {
-        todo!()
+    fn label(&mut self, query_set: Resource<webgpu::GpuQuerySet>) -> String {
+        self.label_of(&query_set)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuQuerySet>, _label: String) {
-        todo!()
+    fn set_label(&mut self, query_set: Resource<webgpu::GpuQuerySet>, label: String) {
+        self.store_label(&query_set, Some(label));
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuQuerySet>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, query_set: Resource<webgpu::GpuQuerySet>) -> wasmtime::Result<()> {
+        self.forget_label(&query_set);
+        let query_set = self.0.table().delete(query_set)?;
+        self.0
+            .instance()
+            .query_set_drop::<crate::Backend>(query_set.query_set);
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuRenderBundleEncoder for WasiWebGpuImpl<T> {
     fn finish(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _descriptor: Option<webgpu::GpuRenderBundleDescriptor>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        descriptor: Option<webgpu::GpuRenderBundleDescriptor>,
     ) -> Resource<webgpu::GpuRenderBundle> {
-        todo!()
+        let label = descriptor.and_then(|d| d.label);
+        let instance = self.0.instance();
+        let encoder = self.0.table().get_mut(&encoder).unwrap();
+        let bundle = match encoder.take() {
+            Some(finished) => {
+                encoder
+                    .errors
+                    .handle(instance.render_bundle_encoder_finish::<crate::Backend>(
+                        finished,
+                        &wgpu_types::RenderBundleDescriptor {
+                            label: label.as_deref().map(|l| l.into()),
+                        },
+                        None,
+                    ))
+            }
+            None => invalid_render_bundle(&instance, encoder.encoder.parent()),
+        };
+        let bundle = self.0.table().push(bundle).unwrap();
+        self.labelled(bundle, label)
     }
 
-    fn label(&mut self, _self_: Resource<webgpu::GpuRenderBundleEncoder>) -> String {
-        todo!()
+    fn label(&mut self, encoder: Resource<webgpu::GpuRenderBundleEncoder>) -> String {
+        self.label_of(&encoder)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuRenderBundleEncoder>, _label: String) {
-        todo!()
+    fn set_label(&mut self, encoder: Resource<webgpu::GpuRenderBundleEncoder>, label: String) {
+        self.store_label(&encoder, Some(label));
     }
 
+    // wgpu-core ignores debug groups and markers in render bundles, only calls after `finish` are reported.
     fn push_debug_group(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
         _group_label: String,
     ) {
-        todo!()
+        self.0.table().get_mut(&encoder).unwrap().open();
     }
 
-    fn pop_debug_group(&mut self, _self_: Resource<webgpu::GpuRenderBundleEncoder>) {
-        todo!()
+    fn pop_debug_group(&mut self, encoder: Resource<webgpu::GpuRenderBundleEncoder>) {
+        self.0.table().get_mut(&encoder).unwrap().open();
     }
 
     fn insert_debug_marker(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
         _marker_label: String,
     ) {
-        todo!()
+        self.0.table().get_mut(&encoder).unwrap().open();
     }
 
     fn set_bind_group(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _index: webgpu::GpuIndex32,
-        _bind_group: Resource<webgpu::GpuBindGroup>,
-        _dynamic_offsets: Option<Vec<webgpu::GpuBufferDynamicOffset>>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        index: webgpu::GpuIndex32,
+        bind_group: Resource<webgpu::GpuBindGroup>,
+        dynamic_offsets: Option<Vec<webgpu::GpuBufferDynamicOffset>>,
     ) {
-        todo!()
+        let bind_group = *self.0.table().get(&bind_group).unwrap();
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        let dynamic_offsets = dynamic_offsets.unwrap_or_default();
+        unsafe {
+            wgpu_core::command::bundle_ffi::wgpu_render_bundle_set_bind_group(
+                &mut encoder.encoder,
+                index,
+                bind_group,
+                dynamic_offsets.as_ptr(),
+                dynamic_offsets.len(),
+            )
+        };
+        encoder.key.commands.push(BundleCommand::SetBindGroup {
+            index,
+            bind_group,
+            dynamic_offsets,
+        });
     }
 
     fn set_pipeline(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _pipeline: Resource<wgpu_core::id::RenderPipelineId>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        pipeline: Resource<RenderPipeline>,
     ) {
-        todo!()
+        let pipeline = pipeline.to_core(self.0.table());
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        encoder.strip_without_index_format = pipeline.strip_without_index_format;
+        wgpu_core::command::bundle_ffi::wgpu_render_bundle_set_pipeline(
+            &mut encoder.encoder,
+            pipeline.pipeline,
+        );
+        encoder
+            .key
+            .commands
+            .push(BundleCommand::SetPipeline(pipeline.pipeline));
     }
 
     fn set_index_buffer(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _buffer: Resource<webgpu::GpuBuffer>,
-        _index_format: webgpu::GpuIndexFormat,
-        _offset: Option<webgpu::GpuSize64>,
-        _size: Option<webgpu::GpuSize64>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        buffer: Resource<webgpu::GpuBuffer>,
+        index_format: webgpu::GpuIndexFormat,
+        offset: Option<webgpu::GpuSize64>,
+        size: Option<webgpu::GpuSize64>,
     ) {
-        todo!()
+        let index_format = index_format.into();
+        let offset = offset.unwrap_or(0);
+        let buffer = self.0.table().get(&buffer).unwrap();
+        let valid = validate_index_buffer(
+            buffer,
+            index_format,
+            offset,
+            size.and_then(core::num::NonZeroU64::new),
+        );
+        let buffer = buffer.buffer;
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        if let Err(error) = valid {
+            encoder.errors.report(error);
+            return;
+        }
+        wgpu_core::command::bundle_ffi::wgpu_render_bundle_set_index_buffer(
+            &mut encoder.encoder,
+            buffer,
+            index_format,
+            offset,
+            size.and_then(core::num::NonZeroU64::new),
+        );
+        encoder.key.commands.push(BundleCommand::SetIndexBuffer {
+            buffer,
+            index_format,
+            offset,
+            size,
+        });
     }
 
     fn set_vertex_buffer(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _slot: webgpu::GpuIndex32,
-        _buffer: Resource<webgpu::GpuBuffer>,
-        _offset: Option<webgpu::GpuSize64>,
-        _size: Option<webgpu::GpuSize64>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        slot: webgpu::GpuIndex32,
+        buffer: Resource<webgpu::GpuBuffer>,
+        offset: Option<webgpu::GpuSize64>,
+        size: Option<webgpu::GpuSize64>,
     ) {
-        todo!()
+        let buffer = self.0.table().get(&buffer).unwrap().buffer;
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        let offset = offset.unwrap_or(0);
+        wgpu_core::command::bundle_ffi::wgpu_render_bundle_set_vertex_buffer(
+            &mut encoder.encoder,
+            slot,
+            buffer,
+            offset,
+            size.and_then(core::num::NonZeroU64::new),
+        );
+        encoder.key.commands.push(BundleCommand::SetVertexBuffer {
+            slot,
+            buffer,
+            offset,
+            size,
+        });
     }
 
     fn draw(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _vertex_count: webgpu::GpuSize32,
-        _instance_count: Option<webgpu::GpuSize32>,
-        _first_vertex: Option<webgpu::GpuSize32>,
-        _first_instance: Option<webgpu::GpuSize32>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        vertex_count: webgpu::GpuSize32,
+        instance_count: Option<webgpu::GpuSize32>,
+        first_vertex: Option<webgpu::GpuSize32>,
+        first_instance: Option<webgpu::GpuSize32>,
     ) {
-        todo!()
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        // source: https://www.w3.org/TR/webgpu/#dom-gpurendercommandsmixin-draw
+        let instance_count = instance_count.unwrap_or(1);
+        let first_vertex = first_vertex.unwrap_or(0);
+        let first_instance = first_instance.unwrap_or(0);
+        wgpu_core::command::bundle_ffi::wgpu_render_bundle_draw(
+            &mut encoder.encoder,
+            vertex_count,
+            instance_count,
+            first_vertex,
+            first_instance,
+        );
+        encoder.key.commands.push(BundleCommand::Draw {
+            vertex_count,
+            instance_count,
+            first_vertex,
+            first_instance,
+        });
     }
 
     fn draw_indexed(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _index_count: webgpu::GpuSize32,
-        _instance_count: Option<webgpu::GpuSize32>,
-        _first_index: Option<webgpu::GpuSize32>,
-        _base_vertex: Option<webgpu::GpuSignedOffset32>,
-        _first_instance: Option<webgpu::GpuSize32>,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        index_count: webgpu::GpuSize32,
+        instance_count: Option<webgpu::GpuSize32>,
+        first_index: Option<webgpu::GpuSize32>,
+        base_vertex: Option<webgpu::GpuSignedOffset32>,
+        first_instance: Option<webgpu::GpuSize32>,
     ) {
-        todo!()
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        if let Err(error) = validate_indexed_draw(encoder.strip_without_index_format) {
+            encoder.errors.report(error);
+            return;
+        }
+        // source: https://www.w3.org/TR/webgpu/#dom-gpurendercommandsmixin-drawindexed
+        let instance_count = instance_count.unwrap_or(1);
+        let first_index = first_index.unwrap_or(0);
+        let base_vertex = base_vertex.unwrap_or(0);
+        let first_instance = first_instance.unwrap_or(0);
+        wgpu_core::command::bundle_ffi::wgpu_render_bundle_draw_indexed(
+            &mut encoder.encoder,
+            index_count,
+            instance_count,
+            first_index,
+            base_vertex,
+            first_instance,
+        );
+        encoder.key.commands.push(BundleCommand::DrawIndexed {
+            index_count,
+            instance_count,
+            first_index,
+            base_vertex,
+            first_instance,
+        });
     }
 
     fn draw_indirect(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _indirect_buffer: Resource<webgpu::GpuBuffer>,
-        _indirect_offset: webgpu::GpuSize64,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        indirect_buffer: Resource<webgpu::GpuBuffer>,
+        indirect_offset: webgpu::GpuSize64,
     ) {
-        todo!()
+        let buffer = self.0.table().get(&indirect_buffer).unwrap().buffer;
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        wgpu_core::command::bundle_ffi::wgpu_render_bundle_draw_indirect(
+            &mut encoder.encoder,
+            buffer,
+            indirect_offset,
+        );
+        encoder.key.commands.push(BundleCommand::DrawIndirect {
+            buffer,
+            offset: indirect_offset,
+        });
     }
 
     fn draw_indexed_indirect(
         &mut self,
-        _self_: Resource<webgpu::GpuRenderBundleEncoder>,
-        _indirect_buffer: Resource<webgpu::GpuBuffer>,
-        _indirect_offset: webgpu::GpuSize64,
+        encoder: Resource<webgpu::GpuRenderBundleEncoder>,
+        indirect_buffer: Resource<webgpu::GpuBuffer>,
+        indirect_offset: webgpu::GpuSize64,
     ) {
-        todo!()
-    }
-
-    fn drop(&mut self, _rep: Resource<webgpu::GpuRenderBundleEncoder>) -> wasmtime::Result<()> {
-        todo!()
+        let buffer = self.0.table().get(&indirect_buffer).unwrap().buffer;
+        let Some(encoder) = self.0.table().get_mut(&encoder).unwrap().open() else {
+            return;
+        };
+        if let Err(error) = validate_indexed_draw(encoder.strip_without_index_format) {
+            encoder.errors.report(error);
+            return;
+        }
+        wgpu_core::command::bundle_ffi::wgpu_render_bundle_draw_indexed_indirect(
+            &mut encoder.encoder,
+            buffer,
+            indirect_offset,
+        );
+        encoder
+            .key
+            .commands
+            .push(BundleCommand::DrawIndexedIndirect {
+                buffer,
+                offset: indirect_offset,
+            });
+    }
+
+    fn drop(&mut self, encoder: Resource<webgpu::GpuRenderBundleEncoder>) -> wasmtime::Result<()> {
+        self.forget_label(&encoder);
+        self.0.table().delete(encoder)?;
+        Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuComputePipeline for WasiWebGpuImpl<T> {
-    fn label(&mut self, _self_: Resource<webgpu::GpuComputePipeline>) -> String {
-        todo!()
+    fn label(&mut self, pipeline: Resource<webgpu::GpuComputePipeline>) -> String {
+        self.label_of(&pipeline)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuComputePipeline>, _label: String) {
-        todo!()
+    fn set_label(&mut self, pipeline: Resource<webgpu::GpuComputePipeline>, label: String) {
+        self.store_label(&pipeline, Some(label));
     }
 
     fn get_bind_group_layout(
         &mut self,
         compute_pipeline: Resource<webgpu::GpuComputePipeline>,
         index: u32,
-    ) -> Resource<webgpu::GpuBindGroupLayout> {
-        let pipeline_id = *self.0.table().get(&compute_pipeline).unwrap();
+    ) -> wasmtime::Result<Resource<webgpu::GpuBindGroupLayout>> {
+        let recorded = self.record_creation(
+            Call::ComputePipelineGetBindGroupLayout,
+            (&compute_pipeline, index),
+        );
+        let pipeline_id = *self.0.table().get(&compute_pipeline)?;
         let bind_group_layout = core_result(
             self.0
                 .instance()
                 .compute_pipeline_get_bind_group_layout::<crate::Backend>(pipeline_id, index, None),
-        )
-        .unwrap();
-        self.0.table().push(bind_group_layout).unwrap()
+        )?;
+        Ok(recorded.created(self.0.table().push(bind_group_layout)?))
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuComputePipeline>) -> wasmtime::Result<()> {
-        // TODO:
+    fn drop(&mut self, pipeline: Resource<webgpu::GpuComputePipeline>) -> wasmtime::Result<()> {
+        self.forget_label(&pipeline);
+        let pipeline = self.0.table().delete(pipeline)?;
+        self.0
+            .instance()
+            .compute_pipeline_drop::<crate::Backend>(pipeline);
+        self.observe_dropped(ObservedResource::ComputePipeline);
         Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuBindGroup for WasiWebGpuImpl<T> {
-    fn label(&mut self, _self_: Resource<webgpu::GpuBindGroup>) -> String {
-        todo!()
+    fn label(&mut self, bind_group: Resource<webgpu::GpuBindGroup>) -> String {
+        self.label_of(&bind_group)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuBindGroup>, _label: String) {
-        todo!()
+    fn set_label(&mut self, bind_group: Resource<webgpu::GpuBindGroup>, label: String) {
+        self.store_label(&bind_group, Some(label));
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuBindGroup>) -> wasmtime::Result<()> {
+    fn drop(&mut self, bind_group: Resource<webgpu::GpuBindGroup>) -> wasmtime::Result<()> {
+        self.forget_label(&bind_group);
         Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuPipelineLayout for WasiWebGpuImpl<T> {
-    fn label(&mut self, _self_: Resource<webgpu::GpuPipelineLayout>) -> String {
-        todo!()
+    fn label(&mut self, layout: Resource<webgpu::GpuPipelineLayout>) -> String {
+        self.label_of(&layout)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuPipelineLayout>, _label: String) {
-        todo!()
+    fn set_label(&mut self, layout: Resource<webgpu::GpuPipelineLayout>, label: String) {
+        self.store_label(&layout, Some(label));
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuPipelineLayout>) -> wasmtime::Result<()> {
+    fn drop(&mut self, layout: Resource<webgpu::GpuPipelineLayout>) -> wasmtime::Result<()> {
+        self.forget_label(&layout);
         Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuBindGroupLayout for WasiWebGpuImpl<T> {
-    fn label(&mut self, _self_: Resource<webgpu::GpuBindGroupLayout>) -> String {
-        todo!()
+    fn label(&mut self, layout: Resource<webgpu::GpuBindGroupLayout>) -> String {
+        self.label_of(&layout)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuBindGroupLayout>, _label: String) {
-        todo!()
+    fn set_label(&mut self, layout: Resource<webgpu::GpuBindGroupLayout>, label: String) {
+        self.store_label(&layout, Some(label));
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuBindGroupLayout>) -> wasmtime::Result<()> {
+    fn drop(&mut self, layout: Resource<webgpu::GpuBindGroupLayout>) -> wasmtime::Result<()> {
+        self.forget_label(&layout);
         // TODO:
         Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuExternalTexture for WasiWebGpuImpl<T> {
-    fn label(&mut self, _self_: Resource<webgpu::GpuExternalTexture>) -> String {
-        todo!()
+    fn label(&mut self, texture: Resource<webgpu::GpuExternalTexture>) -> String {
+        self.label_of(&texture)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuExternalTexture>, _label: String) {
-        todo!()
+    fn set_label(&mut self, texture: Resource<webgpu::GpuExternalTexture>, label: String) {
+        self.store_label(&texture, Some(label));
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuExternalTexture>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, texture: Resource<webgpu::GpuExternalTexture>) -> wasmtime::Result<()> {
+        // `import-external-texture` traps, so there are none to drop.
+        match self.0.table().delete(texture)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpuSampler for WasiWebGpuImpl<T> {
-    fn label(&mut self, _self_: Resource<webgpu::GpuSampler>) -> String {
-        todo!()
+    fn label(&mut self, sampler: Resource<webgpu::GpuSampler>) -> String {
+        self.label_of(&sampler)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuSampler>, _label: String) {
-        todo!()
+    fn set_label(&mut self, sampler: Resource<webgpu::GpuSampler>, label: String) {
+        self.store_label(&sampler, Some(label));
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuSampler>) -> wasmtime::Result<()> {
+    fn drop(&mut self, sampler: Resource<webgpu::GpuSampler>) -> wasmtime::Result<()> {
+        self.forget_label(&sampler);
         // TODO:
         Ok(())
     }
@@ -1996,12 +4944,15 @@ impl<T: WasiWebGpuView> webgpu::HostGpuBuffer for WasiWebGpuImpl<T> {
         buffer.size
     }
 
-    fn usage(&mut self, _self_: Resource<webgpu::GpuBuffer>) -> webgpu::GpuFlagsConstant {
-        todo!()
+    fn usage(&mut self, buffer: Resource<webgpu::GpuBuffer>) -> webgpu::GpuFlagsConstant {
+        let buffer = self.table().get(&buffer).unwrap();
+        buffer.usage.bits()
     }
 
-    fn map_state(&mut self, _self_: Resource<webgpu::GpuBuffer>) -> webgpu::GpuBufferMapState {
-        todo!()
+    fn map_state(&mut self, buffer: Resource<webgpu::GpuBuffer>) -> webgpu::GpuBufferMapState {
+        let buffer = self.table().get(&buffer).unwrap();
+        let map_state = *buffer.map_state.lock().unwrap();
+        map_state
     }
 
     async fn map_async(
@@ -2011,18 +4962,23 @@ impl<T: WasiWebGpuView> webgpu::HostGpuBuffer for WasiWebGpuImpl<T> {
         offset: Option<webgpu::GpuSize64>,
         size: Option<webgpu::GpuSize64>,
     ) {
-        let buffer = self.0.table().get(&buffer).unwrap().buffer;
+        self.record(Call::MapAsync, (&buffer, mode, offset, size));
+        let buffer = self.0.table().get(&buffer).unwrap();
+        let (buffer, errors, map_state) = (
+            buffer.buffer,
+            buffer.errors.clone(),
+            Arc::clone(&buffer.map_state),
+        );
         let instance = self.0.instance();
-        CallbackFuture::new(Box::new(
+        let Some(host) = host_map(mode, &errors) else {
+            return;
+        };
+        let previous_state = map_state_pending(&map_state);
+        let poll_errors = errors.clone();
+        let result = CallbackFuture::new(Box::new(
             move |resolve: Box<
                 dyn FnOnce(Box<Result<(), wgpu_core::resource::BufferAccessError>>) + Send,
             >| {
-                // source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
-                let host = match mode {
-                    0 => wgpu_core::device::HostMap::Read,
-                    1 => wgpu_core::device::HostMap::Write,
-                    _ => panic!(),
-                };
                 let op = wgpu_core::resource::BufferMapOperation {
                     host,
                     callback: Some(wgpu_core::resource::BufferMapCallback::from_rust(Box::new(
@@ -2032,16 +4988,20 @@ impl<T: WasiWebGpuView> webgpu::HostGpuBuffer for WasiWebGpuImpl<T> {
                     ))),
                 };
 
-                let offset = offset.unwrap();
-                instance
-                    .buffer_map_async::<crate::Backend>(buffer, offset, size, op)
-                    .unwrap();
+                // Errors are also passed to the callback, so they're reported below.
+                let _ = instance.buffer_map_async::<crate::Backend>(
+                    buffer,
+                    offset.unwrap_or(0),
+                    size,
+                    op,
+                );
                 // TODO: only poll this device.
-                instance.poll_all_devices(true).unwrap();
+                poll_errors.check(instance.poll_all_devices(true));
             },
         ))
-        .await
-        .unwrap();
+        .await;
+        map_state_settled(&map_state, previous_state, result.is_ok());
+        errors.check(*result);
     }
 
     fn get_mapped_range(
@@ -2049,61 +5009,129 @@ impl<T: WasiWebGpuView> webgpu::HostGpuBuffer for WasiWebGpuImpl<T> {
         buffer: Resource<webgpu::GpuBuffer>,
         offset: Option<webgpu::GpuSize64>,
         size: Option<webgpu::GpuSize64>,
-    ) -> Resource<webgpu::GpuBuffer> {
+    ) -> wasmtime::Result<Resource<webgpu::GpuBuffer>> {
+        let recorded = self.record_creation(Call::GetMappedRange, (&buffer, offset, size));
         let buffer_rep = buffer.rep();
-        let buffer_id = self.0.table().get(&buffer).unwrap().buffer;
+        let buffer_id = self.0.table().get(&buffer)?.buffer;
+        // Failing to get the range is an `OperationError` in the spec.
         let (ptr, len) = self
             .0
             .instance()
             .buffer_get_mapped_range::<crate::Backend>(buffer_id, offset.unwrap_or(0), size)
-            .unwrap();
-        let remote_buffer = BufferPtr { ptr, len };
-        let buffer = self.0.table().get_mut(&buffer).unwrap();
+            .context("Failed to get the mapped range of the buffer")?;
+        let remote_buffer = BufferPtr {
+            ptr,
+            len,
+            written: false,
+        };
+        let buffer = self.0.table().get_mut(&buffer)?;
         buffer.mapped = Some(remote_buffer);
-        Resource::new_own(buffer_rep)
+        Ok(recorded.created(Resource::new_own(buffer_rep)))
     }
 
     fn unmap(&mut self, buffer: Resource<webgpu::GpuBuffer>) {
-        let buffer = self.0.table().get_mut(&buffer).unwrap();
-        buffer.mapped.take().unwrap();
-        let buffer_id = buffer.buffer;
-        self.0
-            .instance()
-            .buffer_unmap::<crate::Backend>(buffer_id)
-            .unwrap();
+        let recorder = self.0.recorder();
+        let host_buffer = self.0.table().get_mut(&buffer).unwrap();
+        // Only what the guest wrote needs recording, the rest of the range comes from the gpu again on replay.
+        let written = host_buffer
+            .mapped
+            .as_ref()
+            .filter(|mapped| mapped.written)
+            .map(BufferPtr::slice);
+        recording::record(recorder, Call::Unmap, (&buffer, written));
+        host_buffer.mapped = None;
+        *host_buffer.map_state.lock().unwrap() = webgpu::GpuBufferMapState::Unmapped;
+        let (buffer_id, errors) = (host_buffer.buffer, host_buffer.errors.clone());
+        match self.0.instance().buffer_unmap::<crate::Backend>(buffer_id) {
+            // source: https://www.w3.org/TR/webgpu/#dom-gpubuffer-unmap
+            // Unmapping a buffer that isn't mapped does nothing.
+            Ok(()) | Err(wgpu_core::resource::BufferAccessError::NotMapped) => {}
+            Err(error) => errors.report(GpuError::from_core(&error)),
+        }
     }
 
-    fn destroy(&mut self, _self_: Resource<webgpu::GpuBuffer>) {
-        todo!()
+    fn destroy(&mut self, buffer: Resource<webgpu::GpuBuffer>) {
+        self.record(Call::DestroyBuffer, &buffer);
+        let host_buffer = self.0.table().get_mut(&buffer).unwrap();
+        if !host_buffer.owned {
+            host_buffer.errors.report(GpuError::validation(
+                "Buffer is lent out by a vertex pull buffer and can't be destroyed",
+            ));
+            return;
+        }
+        host_buffer.mapped = None;
+        *host_buffer.map_state.lock().unwrap() = webgpu::GpuBufferMapState::Unmapped;
+        let (buffer_id, errors) = (host_buffer.buffer, host_buffer.errors.clone());
+        errors.check(
+            self.0
+                .instance()
+                .buffer_destroy::<crate::Backend>(buffer_id),
+        );
+        // The memory is freed now, only the id lives on until the buffer is dropped.
+        if let Some(quota) = self.0.quota() {
+            quota.release_buffer(buffer_id);
+        }
     }
 
-    fn label(&mut self, _self_: Resource<webgpu::GpuBuffer>) -> String {
-        todo!()
+    fn label(&mut self, buffer: Resource<webgpu::GpuBuffer>) -> String {
+        self.label_of(&buffer)
     }
 
-    fn set_label(&mut self, _self_: Resource<webgpu::GpuBuffer>, _label: String) {
-        todo!()
+    fn set_label(&mut self, buffer: Resource<webgpu::GpuBuffer>, label: String) {
+        self.store_label(&buffer, Some(label));
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuBuffer>) -> wasmtime::Result<()> {
+    fn drop(&mut self, buffer: Resource<webgpu::GpuBuffer>) -> wasmtime::Result<()> {
+        self.forget_label(&buffer);
+        let buffer = self.0.table().delete(buffer)?;
+        if let Some(quota) = self.0.quota() {
+            quota.release_buffer(buffer.buffer);
+        }
+        if buffer.owned {
+            self.0
+                .instance()
+                .buffer_drop::<crate::Backend>(buffer.buffer, false);
+        }
+        self.observe_dropped(ObservedResource::Buffer { size: buffer.size });
         Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostGpu for WasiWebGpuImpl<T> {
     fn request_adapter(
         &mut self,
-        _self_: Resource<webgpu::Gpu>,
-        _options: Option<webgpu::GpuRequestAdapterOptions>,
-    ) -> Resource<wgpu_core::id::AdapterId> {
-        let adapter = self
-            .0
-            .instance()
-            .request_adapter(
-                &Default::default(),
-                wgpu_core::instance::AdapterInputs::Mask(wgpu_types::Backends::all(), |_| None),
-            )
-            .unwrap();
-        self.0.table().push(adapter).unwrap()
+        gpu: Resource<webgpu::Gpu>,
+        options: Option<webgpu::GpuRequestAdapterOptions>,
+    ) -> wasmtime::Result<Option<Resource<wgpu_core::id::AdapterId>>> {
+        let recorded = self.record_creation(Call::RequestAdapter, (&gpu, &options));
+        let instance = self.0.instance();
+        let options = options.map_or_else(Default::default, |options| {
+            wgpu_types::RequestAdapterOptions {
+                power_preference: options.power_preference.map(Into::into).unwrap_or_default(),
+                force_fallback_adapter: options.force_fallback_adapter.unwrap_or(false),
+                // Adapters aren't tied to a surface, so guests without a canvas get one just the same.
+                compatible_surface: None,
+            }
+        });
+        // Like the spec, guests get none without an adapter, e.g. to fall back to `frame-buffer`.
+        let adapter = match self.0.adapter_selector() {
+            Some(selector) => {
+                let adapter = selector.select(&instance);
+                if adapter.is_none() {
+                    log::warn!("No adapter matches {selector:?}");
+                }
+                adapter
+            }
+            None => instance
+                .request_adapter(
+                    &options,
+                    wgpu_core::instance::AdapterInputs::Mask(wgpu_types::Backends::all(), |_| None),
+                )
+                .ok(),
+        };
+        let Some(adapter) = adapter else {
+            return Ok(None);
+        };
+        Ok(Some(recorded.created(self.0.table().push(adapter)?)))
     }
 
     fn get_preferred_canvas_format(
@@ -2119,75 +5147,130 @@ impl<T: WasiWebGpuView> webgpu::HostGpu for WasiWebGpuImpl<T> {
 
     fn wgsl_language_features(
         &mut self,
-        _self_: Resource<webgpu::Gpu>,
+        _gpu: Resource<webgpu::Gpu>,
     ) -> Resource<webgpu::WgslLanguageFeatures> {
-        todo!()
+        self.0.table().push(WgslLanguageFeatures).unwrap()
     }
 
     fn drop(&mut self, _rep: Resource<webgpu::Gpu>) -> wasmtime::Result<()> {
         Ok(())
     }
 }
+// wgpu only knows the pci ids of the adapter, not the names the spec has in mind, so they're handed out as hex, the way
+// browsers that don't want to fingerprint do with unknown vendors.
 impl<T: WasiWebGpuView> webgpu::HostGpuAdapterInfo for WasiWebGpuImpl<T> {
-    fn vendor(&mut self, _self_: Resource<webgpu::GpuAdapterInfo>) -> String {
-        todo!()
+    fn vendor(&mut self, info: Resource<webgpu::GpuAdapterInfo>) -> String {
+        let info = self.0.table().get(&info).unwrap();
+        match info.vendor {
+            0 => String::new(),
+            vendor => format!("{vendor:#06x}"),
+        }
     }
 
-    fn architecture(&mut self, _self_: Resource<webgpu::GpuAdapterInfo>) -> String {
-        todo!()
+    fn architecture(&mut self, _info: Resource<webgpu::GpuAdapterInfo>) -> String {
+        // Not reported by wgpu.
+        String::new()
     }
 
-    fn device(&mut self, _self_: Resource<webgpu::GpuAdapterInfo>) -> String {
-        todo!()
+    fn device(&mut self, info: Resource<webgpu::GpuAdapterInfo>) -> String {
+        let info = self.0.table().get(&info).unwrap();
+        match info.device {
+            0 => String::new(),
+            device => format!("{device:#06x}"),
+        }
     }
 
-    fn description(&mut self, _self_: Resource<webgpu::GpuAdapterInfo>) -> String {
-        todo!()
+    fn description(&mut self, info: Resource<webgpu::GpuAdapterInfo>) -> String {
+        let info = self.0.table().get(&info).unwrap();
+        info.name.clone()
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::GpuAdapterInfo>) -> wasmtime::Result<()> {
-        // TODO:
+    fn drop(&mut self, info: Resource<webgpu::GpuAdapterInfo>) -> wasmtime::Result<()> {
+        self.0.table().delete(info)?;
         Ok(())
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostWgslLanguageFeatures for WasiWebGpuImpl<T> {
-    fn has(&mut self, _self_: Resource<webgpu::WgslLanguageFeatures>, _key: String) -> bool {
-        todo!()
+    fn has(&mut self, _features: Resource<webgpu::WgslLanguageFeatures>, _key: String) -> bool {
+        false
     }
 
-    fn drop(&mut self, _rep: Resource<webgpu::WgslLanguageFeatures>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, features: Resource<webgpu::WgslLanguageFeatures>) -> wasmtime::Result<()> {
+        self.0.table().delete(features)?;
+        Ok(())
     }
 }
+/// WebGPU feature names paired with the wgpu feature they map to.
+const FEATURE_NAMES: &[(&str, wgpu_types::Features)] = &[
+    (
+        "depth-clip-control",
+        wgpu_types::Features::DEPTH_CLIP_CONTROL,
+    ),
+    ("timestamp-query", wgpu_types::Features::TIMESTAMP_QUERY),
+    (
+        "indirect-first-instance",
+        wgpu_types::Features::INDIRECT_FIRST_INSTANCE,
+    ),
+    ("shader-f16", wgpu_types::Features::SHADER_F16),
+    (
+        "depth32float-stencil8",
+        wgpu_types::Features::DEPTH32FLOAT_STENCIL8,
+    ),
+    (
+        "texture-compression-bc",
+        wgpu_types::Features::TEXTURE_COMPRESSION_BC,
+    ),
+    (
+        "texture-compression-etc2",
+        wgpu_types::Features::TEXTURE_COMPRESSION_ETC2,
+    ),
+    (
+        "texture-compression-astc",
+        wgpu_types::Features::TEXTURE_COMPRESSION_ASTC,
+    ),
+    (
+        "rg11b10ufloat-renderable",
+        wgpu_types::Features::RG11B10UFLOAT_RENDERABLE,
+    ),
+    (
+        "bgra8unorm-storage",
+        wgpu_types::Features::BGRA8UNORM_STORAGE,
+    ),
+    (
+        "float32-filterable",
+        wgpu_types::Features::FLOAT32_FILTERABLE,
+    ),
+    (
+        "dual-source-blending",
+        wgpu_types::Features::DUAL_SOURCE_BLENDING,
+    ),
+    ("push-constants", wgpu_types::Features::PUSH_CONSTANTS),
+    (
+        "pipeline-statistics-query",
+        wgpu_types::Features::PIPELINE_STATISTICS_QUERY,
+    ),
+    ("multiview", wgpu_types::Features::MULTIVIEW),
+    (
+        "multi-draw-indirect-count",
+        wgpu_types::Features::MULTI_DRAW_INDIRECT_COUNT,
+    ),
+];
+
 impl<T: WasiWebGpuView> webgpu::HostGpuSupportedFeatures for WasiWebGpuImpl<T> {
     fn has(&mut self, features: Resource<webgpu::GpuSupportedFeatures>, query: String) -> bool {
         let features = self.0.table().get(&features).unwrap();
-        match query.as_str() {
-            "depth-clip-control" => features.contains(wgpu_types::Features::DEPTH_CLIP_CONTROL),
-            "timestamp-query" => features.contains(wgpu_types::Features::TIMESTAMP_QUERY),
-            "indirect-first-instance" => {
-                features.contains(wgpu_types::Features::INDIRECT_FIRST_INSTANCE)
-            }
-            "shader-f16" => features.contains(wgpu_types::Features::SHADER_F16),
-            "depth32float-stencil8" => {
-                features.contains(wgpu_types::Features::DEPTH32FLOAT_STENCIL8)
-            }
-            "texture-compression-bc" => {
-                features.contains(wgpu_types::Features::TEXTURE_COMPRESSION_BC)
-            }
-            "texture-compression-etc2" => {
-                features.contains(wgpu_types::Features::TEXTURE_COMPRESSION_ETC2)
-            }
-            "texture-compression-astc" => {
-                features.contains(wgpu_types::Features::TEXTURE_COMPRESSION_ASTC)
-            }
-            "rg11b10ufloat-renderable" => {
-                features.contains(wgpu_types::Features::RG11B10UFLOAT_RENDERABLE)
-            }
-            "bgra8unorm-storage" => features.contains(wgpu_types::Features::BGRA8UNORM_STORAGE),
-            "float32-filterable" => features.contains(wgpu_types::Features::FLOAT32_FILTERABLE),
-            _ => todo!(),
-        }
+        FEATURE_NAMES
+            .iter()
+            .any(|(name, feature)| *name == query && features.contains(*feature))
+    }
+
+    fn values(&mut self, features: Resource<webgpu::GpuSupportedFeatures>) -> Vec<String> {
+        let features = self.0.table().get(&features).unwrap();
+        FEATURE_NAMES
+            .iter()
+            .filter(|(_, feature)| features.contains(*feature))
+            .map(|(name, _)| name.to_string())
+            .collect()
     }
 
     fn drop(&mut self, _rep: Resource<webgpu::GpuSupportedFeatures>) -> wasmtime::Result<()> {
@@ -2222,9 +5305,11 @@ impl<T: WasiWebGpuView> webgpu::HostGpuSupportedLimits for WasiWebGpuImpl<T> {
 
     fn max_bind_groups_plus_vertex_buffers(
         &mut self,
-        _limits: Resource<webgpu::GpuSupportedLimits>,
+        limits: Resource<webgpu::GpuSupportedLimits>,
     ) -> u32 {
-        todo!()
+        // wgpu has no combined limit, so report the sum of the two it does have.
+        let limits = self.0.table().get(&limits).unwrap();
+        limits.max_bind_groups + limits.max_vertex_buffers
     }
 
     fn max_bindings_per_bind_group(&mut self, limits: Resource<webgpu::GpuSupportedLimits>) -> u32 {
@@ -2353,20 +5438,24 @@ impl<T: WasiWebGpuView> webgpu::HostGpuSupportedLimits for WasiWebGpuImpl<T> {
 
     fn max_inter_stage_shader_variables(
         &mut self,
-        _limits: Resource<webgpu::GpuSupportedLimits>,
+        limits: Resource<webgpu::GpuSupportedLimits>,
     ) -> u32 {
-        todo!()
+        // wgpu only tracks components; each variable is up to four of them.
+        let limits = self.0.table().get(&limits).unwrap();
+        limits.max_inter_stage_shader_components / 4
     }
 
-    fn max_color_attachments(&mut self, _limits: Resource<webgpu::GpuSupportedLimits>) -> u32 {
-        todo!()
+    fn max_color_attachments(&mut self, limits: Resource<webgpu::GpuSupportedLimits>) -> u32 {
+        let limits = self.0.table().get(&limits).unwrap();
+        limits.max_color_attachments
     }
 
     fn max_color_attachment_bytes_per_sample(
         &mut self,
-        _limits: Resource<webgpu::GpuSupportedLimits>,
+        limits: Resource<webgpu::GpuSupportedLimits>,
     ) -> u32 {
-        todo!()
+        let limits = self.0.table().get(&limits).unwrap();
+        limits.max_color_attachment_bytes_per_sample
     }
 
     fn max_compute_workgroup_storage_size(
@@ -2417,69 +5506,123 @@ impl<T: WasiWebGpuView> webgpu::HostGpuSupportedLimits for WasiWebGpuImpl<T> {
         limits.max_compute_workgroups_per_dimension
     }
 
+    fn max_push_constant_size(&mut self, limits: Resource<webgpu::GpuSupportedLimits>) -> u32 {
+        let limits = self.0.table().get(&limits).unwrap();
+        limits.max_push_constant_size
+    }
+
     fn drop(&mut self, _rep: Resource<webgpu::GpuSupportedLimits>) -> wasmtime::Result<()> {
         // TODO:
         Ok(())
     }
 }
+// The web's types, which are in the wit for the sake of the spec's signatures. None are ever handed out.
 impl<T: WasiWebGpuView> webgpu::HostAllowSharedBufferSource for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::AllowSharedBufferSource>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::AllowSharedBufferSource>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostPredefinedColorSpace for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::PredefinedColorSpace>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::PredefinedColorSpace>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostEventHandler for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::EventHandler>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::EventHandler>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostOffscreenCanvas for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::OffscreenCanvas>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::OffscreenCanvas>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostHtmlCanvasElement for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::HtmlCanvasElement>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::HtmlCanvasElement>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostVideoFrame for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::VideoFrame>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::VideoFrame>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostHtmlVideoElement for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::HtmlVideoElement>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::HtmlVideoElement>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostHtmlImageElement for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::HtmlImageElement>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::HtmlImageElement>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostImageData for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::ImageData>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::ImageData>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostImageBitmap for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::ImageBitmap>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::ImageBitmap>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostArrayBuffer for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::ArrayBuffer>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::ArrayBuffer>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
     }
 }
 impl<T: WasiWebGpuView> webgpu::HostUint32Array for WasiWebGpuImpl<T> {
-    fn drop(&mut self, _rep: Resource<webgpu::Uint32Array>) -> wasmtime::Result<()> {
-        todo!()
+    fn drop(&mut self, value: Resource<webgpu::Uint32Array>) -> wasmtime::Result<()> {
+        match self.0.table().delete(value)? {}
+    }
+}
+
+/// Marks a buffer that's about to be mapped as pending, and returns the state to go back to if mapping fails.
+///
+/// Buffers that are already mapped or pending stay as they are, wgpu-core fails the map and reports why.
+pub(crate) fn map_state_pending(
+    map_state: &Mutex<webgpu::GpuBufferMapState>,
+) -> webgpu::GpuBufferMapState {
+    let mut map_state = map_state.lock().unwrap();
+    let previous = *map_state;
+    if previous == webgpu::GpuBufferMapState::Unmapped {
+        *map_state = webgpu::GpuBufferMapState::Pending;
+    }
+    previous
+}
+
+/// Settles a map begun with [`map_state_pending`], unless the buffer was unmapped or destroyed in the meantime.
+pub(crate) fn map_state_settled(
+    map_state: &Mutex<webgpu::GpuBufferMapState>,
+    previous: webgpu::GpuBufferMapState,
+    mapped: bool,
+) {
+    let mut map_state = map_state.lock().unwrap();
+    if *map_state == webgpu::GpuBufferMapState::Pending {
+        *map_state = if mapped {
+            webgpu::GpuBufferMapState::Mapped
+        } else {
+            previous
+        };
+    }
+}
+
+/// Reports invalid modes to `errors`.
+fn host_map(
+    mode: webgpu::GpuMapModeFlags,
+    errors: &ErrorScopes,
+) -> Option<wgpu_core::device::HostMap> {
+    // source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+    const MAP_MODE_READ: u32 = 0x0001;
+    const MAP_MODE_WRITE: u32 = 0x0002;
+    match mode {
+        MAP_MODE_READ => Some(wgpu_core::device::HostMap::Read),
+        MAP_MODE_WRITE => Some(wgpu_core::device::HostMap::Write),
+        _ => {
+            errors.report(GpuError::validation(format!("Invalid map mode {mode}")));
+            None
+        }
     }
 }
 
@@ -2508,3 +5651,1192 @@ where
         None => Ok((a, b)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{borrow, extent, TestGpu};
+    use crate::webgpu::{
+        HostGpuBuffer, HostGpuCommandEncoder, HostGpuComputePassEncoder, HostGpuComputePipeline,
+        HostGpuDevice, HostGpuDeviceLostInfo, HostGpuError, HostGpuOutOfMemoryError,
+        HostGpuPipelineLayout, HostGpuQuerySet, HostGpuQueue, HostGpuRenderBundleEncoder,
+        HostGpuRenderPassEncoder, HostGpuRenderPipeline, HostGpuShaderModule, HostGpuStaticLayer,
+        HostGpuTexture, HostGpuTextureView, HostGpuUncapturedErrorEvent, HostGpuValidationError,
+    };
+
+    /// Samples `texture` over a whole 4x4 render target, `@group(0) @binding(0)` being a sampler and `@binding(1)`
+    /// the texture.
+    const SAMPLE_SHADER: &str = r#"
+@group(0) @binding(0) var block_sampler: sampler;
+@group(0) @binding(1) var block_texture: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let corner = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return textureSample(block_texture, block_sampler, position.xy / 4.0);
+}
+"#;
+
+    /// Fills whatever `vs_main` covers red, `vs_main` covering a whole target with 3 vertices.
+    const RED_SHADER: &str = r#"
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let corner = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_quarter() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.25, 0.0, 0.0, 1.0);
+}
+"#;
+
+    /// Uploads `block` as a 4x4 texture of a compressed `format`, and samples it into rgba8 texels.
+    fn sample_block(
+        feature: webgpu::GpuFeatureName,
+        format: webgpu::GpuTextureFormat,
+        block: &[u8],
+    ) -> Option<Vec<u8>> {
+        let mut gpu = TestGpu::new(&[feature])?;
+        let texture = gpu.texture(
+            format,
+            (4, 4),
+            wgpu_types::TextureUsages::TEXTURE_BINDING | wgpu_types::TextureUsages::COPY_DST,
+        );
+        gpu.host.write_texture(
+            borrow(&gpu.queue),
+            webgpu::GpuImageCopyTexture {
+                texture: borrow(&texture),
+                mip_level: None,
+                origin: None,
+                aspect: None,
+            },
+            block.to_vec(),
+            webgpu::GpuImageDataLayout {
+                offset: None,
+                bytes_per_row: Some(block.len() as u32),
+                rows_per_image: None,
+            },
+            extent(4, 4),
+        );
+
+        let target = gpu.texture(
+            webgpu::GpuTextureFormat::Rgba8unorm,
+            (4, 4),
+            wgpu_types::TextureUsages::RENDER_ATTACHMENT | wgpu_types::TextureUsages::COPY_SRC,
+        );
+        let shader = gpu.shader(SAMPLE_SHADER);
+        let pipeline = gpu.render_pipeline(
+            &shader,
+            "fs_main",
+            webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Rgba8unorm,
+                blend: None,
+                write_mask: None,
+            },
+            None,
+        );
+        let layout =
+            HostGpuRenderPipeline::get_bind_group_layout(&mut gpu.host, borrow(&pipeline), 0)
+                .unwrap();
+        let sampler = gpu.host.create_sampler(borrow(&gpu.device), None);
+        let view = gpu.view(&texture);
+        let bind_group = gpu.host.create_bind_group(
+            borrow(&gpu.device),
+            webgpu::GpuBindGroupDescriptor {
+                layout: borrow(&layout),
+                entries: vec![
+                    webgpu::GpuBindGroupEntry {
+                        binding: 0,
+                        resource: webgpu::GpuBindingResource::GpuSampler(borrow(&sampler)),
+                    },
+                    webgpu::GpuBindGroupEntry {
+                        binding: 1,
+                        resource: webgpu::GpuBindingResource::GpuTextureView(borrow(&view)),
+                    },
+                ],
+                label: None,
+            },
+        );
+
+        let encoder = gpu.encoder();
+        let target_view = gpu.view(&target);
+        let pass = gpu.render_pass(&encoder, &target_view, Some([0.0; 4]), None);
+        HostGpuRenderPassEncoder::set_pipeline(&mut gpu.host, borrow(&pass), borrow(&pipeline));
+        HostGpuRenderPassEncoder::set_bind_group(
+            &mut gpu.host,
+            borrow(&pass),
+            0,
+            borrow(&bind_group),
+            None,
+        );
+        HostGpuRenderPassEncoder::draw(&mut gpu.host, borrow(&pass), 3, 1, 0, 0);
+        HostGpuRenderPassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+        gpu.submit(encoder);
+        let texels = gpu.read_texture(&target, (4, 4));
+        gpu.assert_no_errors();
+        Some(texels)
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn bc1_blocks_are_uploaded_and_sampled() {
+        // Endpoints red and black as rgb565, every texel picking the first one.
+        let block = [0x00, 0xf8, 0x00, 0x00, 0, 0, 0, 0];
+        let Some(texels) = sample_block(
+            webgpu::GpuFeatureName::TextureCompressionBc,
+            webgpu::GpuTextureFormat::Bc1RgbaUnorm,
+            &block,
+        ) else {
+            return;
+        };
+        for texel in texels.chunks(4) {
+            assert_eq!(texel, [255, 0, 0, 255]);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn bc7_blocks_are_uploaded_and_sampled() {
+        // Mode 6, with both endpoints (127, 0, 0, 127) and both p-bits set, which makes every texel (255, 1, 1, 255).
+        let mut bits = 1u128 << 6;
+        for (i, endpoint) in [127, 127, 0, 0, 0, 0, 127, 127].into_iter().enumerate() {
+            bits |= endpoint << (7 + 7 * i);
+        }
+        bits |= 0b11 << 63;
+        let Some(texels) = sample_block(
+            webgpu::GpuFeatureName::TextureCompressionBc,
+            webgpu::GpuTextureFormat::Bc7RgbaUnorm,
+            &bits.to_le_bytes(),
+        ) else {
+            return;
+        };
+        for texel in texels.chunks(4) {
+            assert_eq!(texel, [255, 1, 1, 255]);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn atlas_textures_leave_the_registry_with_the_last_handle() {
+        use crate::wasi::webgpu::texture_atlas::{Host as _, HostTextureAtlas};
+
+        let instance = Arc::new(WebGpuInstanceBuilder::new().build());
+        let kept = || {
+            let report = instance.generate_report();
+            let hub = report.hub_report(<crate::Backend as wgpu_core::hal_api::HalApi>::VARIANT);
+            hub.textures.num_kept_from_user
+        };
+        let mut gpu = TestGpu::with_instance(Arc::clone(&instance), &[]).unwrap();
+        let atlas = gpu.host.create_texture_atlas(
+            borrow(&gpu.device),
+            512,
+            webgpu::GpuTextureFormat::Rgba8unorm,
+        );
+        let lent = gpu.host.get_atlas_texture(borrow(&atlas));
+        // Too big for the first texture, so the atlas grows onto a second one.
+        gpu.host
+            .pack_image(borrow(&atlas), vec![0; 300 * 300 * 4], 300, 300)
+            .unwrap();
+        gpu.assert_no_errors();
+        assert_eq!(kept(), 2);
+        HostGpuTexture::drop(&mut gpu.host, lent).unwrap();
+        assert_eq!(kept(), 1);
+        HostTextureAtlas::drop(&mut gpu.host, atlas).unwrap();
+        assert_eq!(kept(), 0);
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn dropped_resources_leave_the_registries() {
+        let instance = Arc::new(WebGpuInstanceBuilder::new().build());
+        let kept = || {
+            let report = instance.generate_report();
+            let hub = report.hub_report(<crate::Backend as wgpu_core::hal_api::HalApi>::VARIANT);
+            [
+                &hub.buffers,
+                &hub.textures,
+                &hub.texture_views,
+                &hub.shader_modules,
+                &hub.render_pipelines,
+            ]
+            .map(|registry| registry.num_kept_from_user)
+        };
+        // A guest instantiated over and over, each time creating and dropping a few resources.
+        for _ in 0..100 {
+            let mut gpu = TestGpu::with_instance(Arc::clone(&instance), &[]).unwrap();
+            let buffer = gpu.buffer(256, wgpu_types::BufferUsages::COPY_DST);
+            let texture = gpu.texture(
+                webgpu::GpuTextureFormat::Rgba8unorm,
+                (4, 4),
+                wgpu_types::TextureUsages::RENDER_ATTACHMENT,
+            );
+            let view = gpu.view(&texture);
+            let shader = gpu.shader(SAMPLE_SHADER);
+            let pipeline = gpu.render_pipeline(
+                &shader,
+                "fs_main",
+                webgpu::GpuColorTargetState {
+                    format: webgpu::GpuTextureFormat::Rgba8unorm,
+                    blend: None,
+                    write_mask: None,
+                },
+                None,
+            );
+            gpu.assert_no_errors();
+
+            HostGpuBuffer::drop(&mut gpu.host, buffer).unwrap();
+            HostGpuTextureView::drop(&mut gpu.host, view).unwrap();
+            HostGpuTexture::drop(&mut gpu.host, texture).unwrap();
+            HostGpuRenderPipeline::drop(&mut gpu.host, pipeline).unwrap();
+            HostGpuShaderModule::drop(&mut gpu.host, shader).unwrap();
+            HostGpuDevice::drop(&mut gpu.host, gpu.device).unwrap();
+        }
+        assert_eq!(kept(), [0; 5]);
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn static_layers_evict_the_least_recently_used() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let shader = gpu.shader(RED_SHADER);
+        let pipeline = gpu.render_pipeline(
+            &shader,
+            "fs_main",
+            webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Rgba8unorm,
+                blend: None,
+                write_mask: None,
+            },
+            None,
+        );
+        // Static layers that only differ in their vertex count, by the id of their bundle.
+        let mut bake = |vertex_count| {
+            let encoder = gpu
+                .host
+                .create_render_bundle_encoder(
+                    borrow(&gpu.device),
+                    webgpu::GpuRenderBundleEncoderDescriptor {
+                        depth_read_only: None,
+                        stencil_read_only: None,
+                        color_formats: vec![webgpu::GpuTextureFormat::Rgba8unorm],
+                        depth_stencil_format: None,
+                        sample_count: None,
+                        label: None,
+                        multiview: None,
+                    },
+                )
+                .unwrap();
+            HostGpuRenderBundleEncoder::set_pipeline(
+                &mut gpu.host,
+                borrow(&encoder),
+                borrow(&pipeline),
+            );
+            HostGpuRenderBundleEncoder::draw(
+                &mut gpu.host,
+                borrow(&encoder),
+                vertex_count,
+                None,
+                None,
+                None,
+            );
+            let layer = gpu
+                .host
+                .bake_static_layer(borrow(&gpu.device), encoder, None);
+            let bundle = gpu.host.0.table().get(&layer).unwrap().bundle.bundle;
+            HostGpuStaticLayer::drop(&mut gpu.host, layer).unwrap();
+            bundle
+        };
+
+        let first = bake(3);
+        let second = bake(6);
+        assert_eq!(bake(3), first);
+        for i in 2..render_bundle::MAX_STATIC_LAYERS {
+            bake(9 + 3 * i as u32);
+        }
+        // The cache is full. `first` was reused after `second` was baked, so `second` goes.
+        assert_eq!(bake(3), first);
+        bake(3000);
+        assert_eq!(bake(3), first);
+        assert_ne!(bake(6), second);
+        gpu.assert_no_errors();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn stencil_masks_limit_drawing_to_the_marked_area() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let target = gpu.texture(
+            webgpu::GpuTextureFormat::Rgba8unorm,
+            (4, 4),
+            wgpu_types::TextureUsages::RENDER_ATTACHMENT | wgpu_types::TextureUsages::COPY_SRC,
+        );
+        let target_view = gpu.view(&target);
+        let stencil = gpu.texture(
+            webgpu::GpuTextureFormat::Stencil8,
+            (4, 4),
+            wgpu_types::TextureUsages::RENDER_ATTACHMENT,
+        );
+        let stencil_view = gpu.view(&stencil);
+        let depth_stencil = |compare, pass_op| {
+            let face = webgpu::GpuStencilFaceState {
+                compare: Some(compare),
+                fail_op: None,
+                depth_fail_op: None,
+                pass_op: Some(pass_op),
+            };
+            Some(webgpu::GpuDepthStencilState {
+                format: webgpu::GpuTextureFormat::Stencil8,
+                depth_write_enabled: None,
+                depth_compare: None,
+                stencil_front: Some(face),
+                stencil_back: Some(face),
+                stencil_read_mask: None,
+                stencil_write_mask: None,
+                depth_bias: None,
+                depth_bias_slope_scale: None,
+                depth_bias_clamp: None,
+            })
+        };
+        let color_target = |write_mask| webgpu::GpuColorTargetState {
+            format: webgpu::GpuTextureFormat::Rgba8unorm,
+            blend: None,
+            write_mask: Some(write_mask),
+        };
+        let shader = gpu.shader(RED_SHADER);
+        // Only goes to the stencil buffer.
+        let mask = gpu.render_pipeline(
+            &shader,
+            "fs_main",
+            color_target(0),
+            depth_stencil(
+                webgpu::GpuCompareFunction::Always,
+                webgpu::GpuStencilOperation::Replace,
+            ),
+        );
+        let masked = gpu.render_pipeline(
+            &shader,
+            "fs_main",
+            color_target(wgpu_types::ColorWrites::ALL.bits()),
+            depth_stencil(
+                webgpu::GpuCompareFunction::Equal,
+                webgpu::GpuStencilOperation::Keep,
+            ),
+        );
+
+        let encoder = gpu.encoder();
+        for (pipeline, stencil_load_op) in [
+            (&mask, webgpu::GpuLoadOp::Clear),
+            (&masked, webgpu::GpuLoadOp::Load),
+        ] {
+            let clear = match stencil_load_op {
+                webgpu::GpuLoadOp::Clear => Some([0.0, 0.0, 0.0, 1.0]),
+                webgpu::GpuLoadOp::Load => None,
+            };
+            let pass = gpu.render_pass(
+                &encoder,
+                &target_view,
+                clear,
+                Some(webgpu::GpuRenderPassDepthStencilAttachment {
+                    view: borrow(&stencil_view),
+                    depth_clear_value: None,
+                    depth_load_op: None,
+                    depth_store_op: None,
+                    depth_read_only: None,
+                    stencil_clear_value: Some(0),
+                    stencil_load_op: Some(stencil_load_op),
+                    stencil_store_op: Some(webgpu::GpuStoreOp::Store),
+                    stencil_read_only: None,
+                }),
+            );
+            HostGpuRenderPassEncoder::set_pipeline(&mut gpu.host, borrow(&pass), borrow(pipeline));
+            gpu.host.set_stencil_reference(borrow(&pass), 1);
+            if stencil_load_op == webgpu::GpuLoadOp::Clear {
+                // The mask covers the left half of the target.
+                gpu.host.set_scissor_rect(borrow(&pass), 0, 0, 2, 4);
+            }
+            HostGpuRenderPassEncoder::draw(&mut gpu.host, borrow(&pass), 3, 1, 0, 0);
+            HostGpuRenderPassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+        }
+        gpu.submit(encoder);
+        let texels = gpu.read_texture(&target, (4, 4));
+        gpu.assert_no_errors();
+
+        for (i, texel) in texels.chunks(4).enumerate() {
+            let expected = if i % 4 < 2 {
+                [255, 0, 0, 255]
+            } else {
+                [0, 0, 0, 255]
+            };
+            assert_eq!(texel, expected, "texel {i}");
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn viewports_and_scissor_rects_outside_the_attachments_are_errors() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        // The pass draws into the 4x4 second level of an 8x8 texture.
+        let target = gpu.host.create_texture(
+            borrow(&gpu.device),
+            webgpu::GpuTextureDescriptor {
+                size: extent(8, 8),
+                mip_level_count: Some(2),
+                sample_count: None,
+                dimension: webgpu::GpuTextureDimension::TwoD,
+                format: webgpu::GpuTextureFormat::Rgba8unorm,
+                usage: wgpu_types::TextureUsages::RENDER_ATTACHMENT.bits(),
+                view_formats: None,
+                label: None,
+            },
+        );
+        let view = gpu.host.create_view(
+            borrow(&target),
+            Some(webgpu::GpuTextureViewDescriptor {
+                format: None,
+                dimension: None,
+                aspect: None,
+                base_mip_level: Some(1),
+                mip_level_count: Some(1),
+                base_array_layer: None,
+                array_layer_count: None,
+                label: None,
+            }),
+        );
+        let encoder = gpu.encoder();
+        let pass = gpu.render_pass(&encoder, &view, Some([0.0, 0.0, 0.0, 1.0]), None);
+
+        gpu.host
+            .set_viewport(borrow(&pass), 2.0, 0.0, 4.0, 4.0, 0.0, 1.0);
+        let error = gpu.pop_error().expect("viewport past the right edge");
+        assert_eq!(
+            error.message(),
+            "Viewport 2, 0, 4x4 isn't within the 4x4 attachments"
+        );
+        gpu.host
+            .set_viewport(borrow(&pass), 0.0, 0.0, 4.0, 4.0, 0.5, 0.25);
+        assert!(gpu.pop_error().is_some(), "decreasing depth range");
+        gpu.host.set_scissor_rect(borrow(&pass), 0, 2, 4, 4);
+        let error = gpu.pop_error().expect("scissor rect past the bottom edge");
+        assert_eq!(
+            error.message(),
+            "Scissor rect 0, 2, 4x4 isn't within the 4x4 attachments"
+        );
+
+        // Rects covering the whole level are fine, and the refused ones never reached wgpu-core.
+        gpu.host
+            .set_viewport(borrow(&pass), 0.0, 0.0, 4.0, 4.0, 0.0, 1.0);
+        gpu.host.set_scissor_rect(borrow(&pass), 0, 0, 4, 4);
+        HostGpuRenderPassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+        gpu.submit(encoder);
+        gpu.assert_no_errors();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn loaded_attachments_keep_what_earlier_passes_drew() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let target = gpu.texture(
+            webgpu::GpuTextureFormat::Rgba8unorm,
+            (4, 4),
+            wgpu_types::TextureUsages::RENDER_ATTACHMENT | wgpu_types::TextureUsages::COPY_SRC,
+        );
+        let view = gpu.view(&target);
+        let add = webgpu::GpuBlendComponent {
+            operation: Some(webgpu::GpuBlendOperation::Add),
+            src_factor: Some(webgpu::GpuBlendFactor::One),
+            dst_factor: Some(webgpu::GpuBlendFactor::One),
+        };
+        let shader = gpu.shader(RED_SHADER);
+        let pipeline = gpu.render_pipeline(
+            &shader,
+            "fs_quarter",
+            webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Rgba8unorm,
+                blend: Some(webgpu::GpuBlendState {
+                    color: add,
+                    alpha: add,
+                }),
+                write_mask: None,
+            },
+            None,
+        );
+
+        let encoder = gpu.encoder();
+        let pass = gpu.render_pass(&encoder, &view, Some([0.25, 0.0, 0.0, 0.0]), None);
+        HostGpuRenderPassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+        // No clear value, so this one loads the first pass's quarter red and adds another.
+        let pass = gpu.render_pass(&encoder, &view, None, None);
+        HostGpuRenderPassEncoder::set_pipeline(&mut gpu.host, borrow(&pass), borrow(&pipeline));
+        HostGpuRenderPassEncoder::draw(&mut gpu.host, borrow(&pass), 3, 1, 0, 0);
+        HostGpuRenderPassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+        gpu.submit(encoder);
+        let texels = gpu.read_texture(&target, (4, 4));
+        gpu.assert_no_errors();
+
+        for (i, texel) in texels.chunks(4).enumerate() {
+            assert!(texel[0].abs_diff(128) <= 1, "texel {i}: {texel:?}");
+            assert_eq!(&texel[1..], [0, 0, 255], "texel {i}");
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn overridden_constants_change_what_is_drawn() {
+        const SHADER: &str = r#"
+override red: f32 = 0.0;
+@id(7) override green: f32 = 0.0;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let corner = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(red, green, 0.0, 1.0);
+}
+"#;
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let shader = gpu.shader(SHADER);
+        // Constants are set by name, or by id for the ones that have one.
+        for (constants, expected) in [
+            (vec![("red".to_string(), 1.0)], [255, 0, 0, 255]),
+            (vec![("7".to_string(), 1.0)], [0, 255, 0, 255]),
+        ] {
+            let pipeline = gpu.host.create_render_pipeline(
+                borrow(&gpu.device),
+                webgpu::GpuRenderPipelineDescriptor {
+                    vertex: webgpu::GpuVertexState {
+                        buffers: None,
+                        module: borrow(&shader),
+                        entry_point: "vs_main".to_string(),
+                        constants: None,
+                    },
+                    primitive: None,
+                    depth_stencil: None,
+                    multisample: None,
+                    fragment: Some(webgpu::GpuFragmentState {
+                        targets: vec![Some(webgpu::GpuColorTargetState {
+                            format: webgpu::GpuTextureFormat::Rgba8unorm,
+                            blend: None,
+                            write_mask: None,
+                        })],
+                        module: borrow(&shader),
+                        entry_point: "fs_main".to_string(),
+                        constants: Some(constants),
+                    }),
+                    layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+                        webgpu::GpuAutoLayoutMode::Auto,
+                    ),
+                    label: None,
+                    multiview: None,
+                },
+            );
+            let target = gpu.texture(
+                webgpu::GpuTextureFormat::Rgba8unorm,
+                (4, 4),
+                wgpu_types::TextureUsages::RENDER_ATTACHMENT | wgpu_types::TextureUsages::COPY_SRC,
+            );
+            let view = gpu.view(&target);
+            let encoder = gpu.encoder();
+            let pass = gpu.render_pass(&encoder, &view, Some([0.0; 4]), None);
+            HostGpuRenderPassEncoder::set_pipeline(&mut gpu.host, borrow(&pass), borrow(&pipeline));
+            HostGpuRenderPassEncoder::draw(&mut gpu.host, borrow(&pass), 3, 1, 0, 0);
+            HostGpuRenderPassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+            gpu.submit(encoder);
+            let texels = gpu.read_texture(&target, (4, 4));
+            gpu.assert_no_errors();
+
+            for (i, texel) in texels.chunks(4).enumerate() {
+                assert_eq!(texel, expected, "texel {i}");
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn calls_after_end_and_finish_are_validation_errors() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let target = gpu.texture(
+            webgpu::GpuTextureFormat::Rgba8unorm,
+            (4, 4),
+            wgpu_types::TextureUsages::RENDER_ATTACHMENT,
+        );
+        let view = gpu.view(&target);
+        let encoder = gpu.encoder();
+
+        let pass = gpu.render_pass(&encoder, &view, Some([0.0, 0.0, 0.0, 1.0]), None);
+        HostGpuRenderPassEncoder::end(&mut gpu.host, borrow(&pass), borrow(&encoder));
+        gpu.assert_no_errors();
+        HostGpuRenderPassEncoder::draw(&mut gpu.host, borrow(&pass), 3, 1, 0, 0);
+        let error = gpu.pop_error().expect("draw after end");
+        assert_eq!(error.message(), "Render pass was already ended");
+        HostGpuRenderPassEncoder::end(&mut gpu.host, borrow(&pass), borrow(&encoder));
+        assert!(gpu.pop_error().is_some(), "second end");
+        HostGpuRenderPassEncoder::drop(&mut gpu.host, pass).unwrap();
+
+        let pass = gpu.host.begin_compute_pass(borrow(&encoder), None);
+        HostGpuComputePassEncoder::end(&mut gpu.host, borrow(&pass), borrow(&encoder));
+        gpu.assert_no_errors();
+        gpu.host.dispatch_workgroups(borrow(&pass), 1, None, None);
+        let error = gpu.pop_error().expect("dispatch after end");
+        assert_eq!(error.message(), "Compute pass was already ended");
+        HostGpuComputePassEncoder::drop(&mut gpu.host, pass).unwrap();
+
+        let bundle_encoder = gpu
+            .host
+            .create_render_bundle_encoder(
+                borrow(&gpu.device),
+                webgpu::GpuRenderBundleEncoderDescriptor {
+                    depth_read_only: None,
+                    stencil_read_only: None,
+                    color_formats: vec![webgpu::GpuTextureFormat::Rgba8unorm],
+                    depth_stencil_format: None,
+                    sample_count: None,
+                    label: None,
+                    multiview: None,
+                },
+            )
+            .unwrap();
+        HostGpuRenderBundleEncoder::finish(&mut gpu.host, borrow(&bundle_encoder), None);
+        gpu.assert_no_errors();
+        HostGpuRenderBundleEncoder::draw(
+            &mut gpu.host,
+            borrow(&bundle_encoder),
+            3,
+            None,
+            None,
+            None,
+        );
+        let error = gpu.pop_error().expect("draw after finish");
+        assert_eq!(
+            error.message(),
+            "Render bundle encoder was already finished"
+        );
+        HostGpuRenderBundleEncoder::finish(&mut gpu.host, borrow(&bundle_encoder), None);
+        assert!(gpu.pop_error().is_some(), "second finish");
+        HostGpuRenderBundleEncoder::drop(&mut gpu.host, bundle_encoder).unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn labels_round_trip_and_go_with_the_resource() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let buffer = gpu.host.create_buffer(
+            borrow(&gpu.device),
+            webgpu::GpuBufferDescriptor {
+                label: Some("vertices".into()),
+                size: 4,
+                usage: wgpu_types::BufferUsages::VERTEX.bits(),
+                mapped_at_creation: None,
+            },
+        );
+        assert_eq!(
+            HostGpuBuffer::label(&mut gpu.host, borrow(&buffer)),
+            "vertices"
+        );
+        HostGpuBuffer::set_label(&mut gpu.host, borrow(&buffer), "indices".into());
+        assert_eq!(
+            HostGpuBuffer::label(&mut gpu.host, borrow(&buffer)),
+            "indices"
+        );
+        HostGpuBuffer::drop(&mut gpu.host, buffer).unwrap();
+
+        // Likely gets the rep of the dropped buffer.
+        let buffer = gpu.buffer(4, wgpu_types::BufferUsages::VERTEX);
+        assert_eq!(HostGpuBuffer::label(&mut gpu.host, borrow(&buffer)), "");
+        let encoder = gpu.encoder();
+        assert_eq!(
+            HostGpuCommandEncoder::label(&mut gpu.host, borrow(&encoder)),
+            ""
+        );
+        gpu.assert_no_errors();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn buffer_map_state_follows_mapping_and_destroy() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let usage = wgpu_types::BufferUsages::MAP_READ | wgpu_types::BufferUsages::COPY_DST;
+        let buffer = gpu.buffer(4, usage);
+        assert_eq!(
+            HostGpuBuffer::usage(&mut gpu.host, borrow(&buffer)),
+            usage.bits()
+        );
+        let map_state =
+            |gpu: &mut TestGpu| HostGpuBuffer::map_state(&mut gpu.host, borrow(&buffer));
+        assert_eq!(map_state(&mut gpu), webgpu::GpuBufferMapState::Unmapped);
+
+        // source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+        block_on(HostGpuBuffer::map_async(
+            &mut gpu.host,
+            borrow(&buffer),
+            1,
+            None,
+            None,
+        ));
+        assert_eq!(map_state(&mut gpu), webgpu::GpuBufferMapState::Mapped);
+        HostGpuBuffer::unmap(&mut gpu.host, borrow(&buffer));
+        assert_eq!(map_state(&mut gpu), webgpu::GpuBufferMapState::Unmapped);
+
+        block_on(HostGpuBuffer::map_async(
+            &mut gpu.host,
+            borrow(&buffer),
+            1,
+            None,
+            None,
+        ));
+        HostGpuBuffer::destroy(&mut gpu.host, borrow(&buffer));
+        assert_eq!(map_state(&mut gpu), webgpu::GpuBufferMapState::Unmapped);
+        gpu.assert_no_errors();
+
+        // Destroyed buffers can't be used anymore, but are still there to call methods on.
+        block_on(HostGpuBuffer::map_async(
+            &mut gpu.host,
+            borrow(&buffer),
+            1,
+            None,
+            None,
+        ));
+        assert!(gpu.pop_error().is_some(), "map after destroy");
+        assert_eq!(map_state(&mut gpu), webgpu::GpuBufferMapState::Unmapped);
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn destroying_the_device_loses_it() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        HostGpuDevice::destroy(&mut gpu.host, borrow(&gpu.device));
+        let info = block_on(HostGpuDevice::lost(&mut gpu.host, borrow(&gpu.device)));
+        assert_eq!(
+            HostGpuDeviceLostInfo::reason(&mut gpu.host, borrow(&info)),
+            webgpu::GpuDeviceLostReason::Destroyed
+        );
+        HostGpuDeviceLostInfo::drop(&mut gpu.host, info).unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn query_sets_know_their_type_and_count_and_fail_once_destroyed() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let query_set = gpu.host.create_query_set(
+            borrow(&gpu.device),
+            webgpu::GpuQuerySetDescriptor {
+                type_: webgpu::GpuQueryType::Occlusion,
+                count: 4,
+                label: None,
+            },
+        );
+        assert_eq!(
+            HostGpuQuerySet::type_(&mut gpu.host, borrow(&query_set)),
+            webgpu::GpuQueryType::Occlusion
+        );
+        assert_eq!(HostGpuQuerySet::count(&mut gpu.host, borrow(&query_set)), 4);
+        gpu.assert_no_errors();
+
+        let destination = gpu.buffer(
+            32,
+            wgpu_types::BufferUsages::QUERY_RESOLVE | wgpu_types::BufferUsages::COPY_SRC,
+        );
+        HostGpuQuerySet::destroy(&mut gpu.host, borrow(&query_set));
+        let encoder = gpu.encoder();
+        gpu.host.resolve_query_set(
+            borrow(&encoder),
+            borrow(&query_set),
+            0,
+            4,
+            borrow(&destination),
+            0,
+        );
+        let error = gpu.pop_error().expect("resolve after destroy");
+        assert_eq!(error.message(), "Query set was destroyed");
+        HostGpuQuerySet::drop(&mut gpu.host, query_set).unwrap();
+
+        let query_set = gpu.host.create_query_set(
+            borrow(&gpu.device),
+            webgpu::GpuQuerySetDescriptor {
+                type_: webgpu::GpuQueryType::PipelineStatistics,
+                count: 1,
+                label: None,
+            },
+        );
+        assert!(
+            gpu.pop_error().is_some(),
+            "pipeline statistics without statistics"
+        );
+        HostGpuQuerySet::drop(&mut gpu.host, query_set).unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn occlusion_queries_are_read_back() {
+        use crate::wasi::webgpu::query_set_resolve_to_buffer::{Host as _, HostQueryResults};
+
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let query_set = gpu.host.create_query_set(
+            borrow(&gpu.device),
+            webgpu::GpuQuerySetDescriptor {
+                type_: webgpu::GpuQueryType::Occlusion,
+                count: 2,
+                label: None,
+            },
+        );
+        let texture = gpu.texture(
+            webgpu::GpuTextureFormat::Rgba8unorm,
+            (4, 4),
+            wgpu_types::TextureUsages::RENDER_ATTACHMENT,
+        );
+        let view = gpu.view(&texture);
+        let shader = gpu.shader(RED_SHADER);
+        let pipeline = gpu.render_pipeline(
+            &shader,
+            "fs_main",
+            webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Rgba8unorm,
+                blend: None,
+                write_mask: None,
+            },
+            None,
+        );
+        let encoder = gpu.encoder();
+        let render_pass = gpu.host.begin_render_pass(
+            borrow(&encoder),
+            webgpu::GpuRenderPassDescriptor {
+                color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                    view: borrow(&view),
+                    depth_slice: None,
+                    resolve_target: None,
+                    clear_value: None,
+                    load_op: webgpu::GpuLoadOp::Clear,
+                    store_op: webgpu::GpuStoreOp::Store,
+                }],
+                depth_stencil_attachment: None,
+                occlusion_query_set: Some(borrow(&query_set)),
+                timestamp_writes: None,
+                max_draw_count: None,
+                label: None,
+            },
+        );
+        HostGpuRenderPassEncoder::set_pipeline(
+            &mut gpu.host,
+            borrow(&render_pass),
+            borrow(&pipeline),
+        );
+        // Every texel of the target passes the first query, nothing is drawn in the second.
+        HostGpuRenderPassEncoder::begin_occlusion_query(&mut gpu.host, borrow(&render_pass), 0);
+        HostGpuRenderPassEncoder::draw(&mut gpu.host, borrow(&render_pass), 3, 1, 0, 0);
+        HostGpuRenderPassEncoder::end_occlusion_query(&mut gpu.host, borrow(&render_pass));
+        HostGpuRenderPassEncoder::begin_occlusion_query(&mut gpu.host, borrow(&render_pass), 1);
+        HostGpuRenderPassEncoder::end_occlusion_query(&mut gpu.host, borrow(&render_pass));
+        HostGpuRenderPassEncoder::end(&mut gpu.host, render_pass, borrow(&encoder));
+        gpu.submit(encoder);
+
+        let results =
+            gpu.host
+                .resolve_and_read_async(borrow(&gpu.device), borrow(&query_set), 0, 2);
+        block_on(wasmtime_wasi::Subscribe::ready(
+            gpu.host.0.table().get_mut(&results).unwrap(),
+        ));
+        let values = HostQueryResults::get(&mut gpu.host, borrow(&results)).unwrap();
+        gpu.assert_no_errors();
+        assert_eq!(values.len(), 2);
+        assert!(values[0] > 0, "{values:?}");
+        assert_eq!(values[1], 0);
+        HostQueryResults::drop(&mut gpu.host, results).unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn pipeline_statistics_without_the_feature_is_one_error() {
+        // Devices only have the features they were requested with.
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let query_set = gpu.host.create_pipeline_statistics_query_set(
+            borrow(&gpu.device),
+            webgpu::GpuPipelineStatisticsQuerySetDescriptor {
+                statistics: vec![webgpu::GpuPipelineStatistic::VertexShaderInvocations],
+                count: 1,
+                label: None,
+            },
+        );
+        let error = gpu.pop_error().expect("missing feature");
+        assert_eq!(
+            error.message(),
+            "Pipeline statistics query sets require the pipeline-statistics-query feature"
+        );
+        // The set is invalid, using it is an error but doesn't panic.
+        assert_eq!(
+            HostGpuQuerySet::type_(&mut gpu.host, borrow(&query_set)),
+            webgpu::GpuQueryType::PipelineStatistics
+        );
+        gpu.assert_no_errors();
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn push_constants_without_the_feature_is_one_error() {
+        let mut gpu = TestGpu::new(&[]).unwrap();
+        let pipeline_layout = gpu.host.create_pipeline_layout(
+            borrow(&gpu.device),
+            webgpu::GpuPipelineLayoutDescriptor {
+                bind_group_layouts: vec![],
+                push_constant_ranges: Some(vec![webgpu::GpuPushConstantRange {
+                    stages: 1,
+                    start: 0,
+                    end: 4,
+                }]),
+                label: None,
+            },
+        );
+        let error = gpu.pop_error().expect("missing feature");
+        assert_eq!(
+            error.message(),
+            "Push constant ranges require the push-constants feature"
+        );
+        // wgpu-core never saw the ranges, so it doesn't report them again.
+        gpu.assert_no_errors();
+        HostGpuPipelineLayout::drop(&mut gpu.host, pipeline_layout).unwrap();
+    }
+
+    #[test]
+    fn guests_can_construct_errors() {
+        let mut host = WasiWebGpuImpl(crate::testing::TestHost::new(Arc::new(
+            crate::WebGpuInstanceBuilder::new().build(),
+        )));
+        let error = HostGpuOutOfMemoryError::new(&mut host, "too big".into());
+        assert_eq!(
+            HostGpuOutOfMemoryError::message(&mut host, borrow(&error)),
+            "too big"
+        );
+        // The spec's error classes are all `gpu-error`s.
+        let kind = HostGpuError::kind(&mut host, borrow(&error));
+        assert!(matches!(kind, webgpu::GpuErrorKind::OutOfMemory(message) if message == "too big"));
+        HostGpuOutOfMemoryError::drop(&mut host, error).unwrap();
+
+        let error = HostGpuValidationError::new(&mut host, "bad".into());
+        let event = HostGpuUncapturedErrorEvent::new(
+            &mut host,
+            "uncapturederror".into(),
+            webgpu::GpuUncapturedErrorEventInit { error },
+        );
+        let error = HostGpuUncapturedErrorEvent::error(&mut host, borrow(&event));
+        assert_eq!(HostGpuError::message(&mut host, borrow(&error)), "bad");
+        HostGpuError::drop(&mut host, error).unwrap();
+        HostGpuUncapturedErrorEvent::drop(&mut host, event).unwrap();
+    }
+
+    /// `cull` writes a 6 vertex draw for every visible instance, and counts them. Instance `i` fills column `i` of a 4
+    /// texel wide target red.
+    const CULL_SHADER: &str = r#"
+struct DrawArgs {
+    vertex_count: u32,
+    instance_count: u32,
+    first_vertex: u32,
+    first_instance: u32,
+}
+
+@group(0) @binding(0) var<storage, read> visible: array<u32, 4>;
+@group(0) @binding(1) var<storage, read_write> draws: array<DrawArgs, 4>;
+@group(0) @binding(2) var<storage, read_write> draw_count: atomic<u32>;
+
+@compute @workgroup_size(4)
+fn cull(@builtin(global_invocation_id) id: vec3<u32>) {
+    if visible[id.x] != 0u {
+        // Instances are told apart by their first vertex, which doesn't need the indirect-first-instance feature.
+        draws[atomicAdd(&draw_count, 1u)] = DrawArgs(6u, 1u, id.x * 6u, 0u);
+    }
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let corner = corners[index % 6u];
+    return vec4<f32>((f32(index / 6u) + corner.x) * 0.5 - 1.0, corner.y * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn indirect_count_draws_what_a_compute_pass_left_visible() {
+        let Some(mut gpu) = TestGpu::new(&[webgpu::GpuFeatureName::MultiDrawIndirectCount]) else {
+            return;
+        };
+        let visible = gpu.buffer(
+            16,
+            wgpu_types::BufferUsages::STORAGE | wgpu_types::BufferUsages::COPY_DST,
+        );
+        let data = [1u32, 0, 1, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        gpu.host
+            .write_buffer(borrow(&gpu.queue), borrow(&visible), 0, None, data, None)
+            .unwrap();
+        let indirect = wgpu_types::BufferUsages::STORAGE | wgpu_types::BufferUsages::INDIRECT;
+        let draws = gpu.buffer(4 * 16, indirect);
+        let draw_count = gpu.buffer(4, indirect);
+
+        let shader = gpu.shader(CULL_SHADER);
+        let cull = gpu.host.create_compute_pipeline(
+            borrow(&gpu.device),
+            webgpu::GpuComputePipelineDescriptor {
+                compute: webgpu::GpuProgrammableStage {
+                    module: borrow(&shader),
+                    entry_point: Some("cull".to_string()),
+                    constants: None,
+                },
+                layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+                    webgpu::GpuAutoLayoutMode::Auto,
+                ),
+                label: None,
+            },
+        );
+        let layout =
+            HostGpuComputePipeline::get_bind_group_layout(&mut gpu.host, borrow(&cull), 0).unwrap();
+        let entry = |binding, buffer: &Resource<webgpu::GpuBuffer>| webgpu::GpuBindGroupEntry {
+            binding,
+            resource: webgpu::GpuBindingResource::GpuBufferBinding(webgpu::GpuBufferBinding {
+                buffer: borrow(buffer),
+                offset: None,
+                size: None,
+            }),
+        };
+        let bind_group = gpu.host.create_bind_group(
+            borrow(&gpu.device),
+            webgpu::GpuBindGroupDescriptor {
+                layout: borrow(&layout),
+                entries: vec![entry(0, &visible), entry(1, &draws), entry(2, &draw_count)],
+                label: None,
+            },
+        );
+        let pipeline = gpu.render_pipeline(
+            &shader,
+            "fs_main",
+            webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Rgba8unorm,
+                blend: None,
+                write_mask: None,
+            },
+            None,
+        );
+        let target = gpu.texture(
+            webgpu::GpuTextureFormat::Rgba8unorm,
+            (4, 4),
+            wgpu_types::TextureUsages::RENDER_ATTACHMENT | wgpu_types::TextureUsages::COPY_SRC,
+        );
+        let target_view = gpu.view(&target);
+
+        let encoder = gpu.encoder();
+        let pass = gpu.host.begin_compute_pass(borrow(&encoder), None);
+        HostGpuComputePassEncoder::set_pipeline(&mut gpu.host, borrow(&pass), borrow(&cull));
+        HostGpuComputePassEncoder::set_bind_group(
+            &mut gpu.host,
+            borrow(&pass),
+            0,
+            borrow(&bind_group),
+            None,
+        );
+        gpu.host.dispatch_workgroups(borrow(&pass), 1, None, None);
+        HostGpuComputePassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+        let pass = gpu.render_pass(&encoder, &target_view, Some([0.0, 0.0, 0.0, 1.0]), None);
+        HostGpuRenderPassEncoder::set_pipeline(&mut gpu.host, borrow(&pass), borrow(&pipeline));
+        gpu.host
+            .draw_indirect_count(borrow(&pass), borrow(&draws), 0, borrow(&draw_count), 0, 4);
+        HostGpuRenderPassEncoder::end(&mut gpu.host, pass, borrow(&encoder));
+        gpu.submit(encoder);
+        let texels = gpu.read_texture(&target, (4, 4));
+        gpu.assert_no_errors();
+
+        for (i, texel) in texels.chunks(4).enumerate() {
+            let expected = if i % 2 == 0 {
+                [255, 0, 0, 255]
+            } else {
+                [0, 0, 0, 255]
+            };
+            assert_eq!(texel, expected, "texel {i}");
+        }
+    }
+
+    #[test]
+    fn texture_data_is_counted_in_blocks() {
+        let bc1 = wgpu_types::TextureFormat::Bc1RgbaUnorm;
+        let layout = |offset| wgpu_types::ImageDataLayout {
+            offset,
+            bytes_per_row: None,
+            rows_per_image: None,
+        };
+        let size = |width, height| wgpu_types::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let all = wgpu_types::TextureAspect::All;
+        assert!(validate_texture_data(bc1, all, 8, &layout(0), &size(4, 4)).is_ok());
+        assert!(validate_texture_data(bc1, all, 7, &layout(0), &size(4, 4)).is_err());
+        assert!(validate_texture_data(bc1, all, 8, &layout(1), &size(4, 4)).is_err());
+        assert!(validate_texture_data(bc1, all, 8, &layout(0), &size(2, 4)).is_err());
+        assert!(validate_texture_data(bc1, all, 8, &layout(u64::MAX), &size(4, 4)).is_err());
+    }
+
+    #[test]
+    fn extent_lists_need_1_to_3_coordinates() {
+        let list = |coordinates: &[u32]| {
+            webgpu::GpuExtent3D::ListGpuIntegerCoordinate(coordinates.to_vec())
+        };
+        assert!(validate_extent(&list(&[])).is_err());
+        assert!(validate_extent(&list(&[4])).is_ok());
+        assert!(validate_extent(&list(&[4, 4, 4])).is_ok());
+        assert!(validate_extent(&list(&[4, 4, 4, 4])).is_err());
+    }
+
+    #[test]
+    fn bind_group_layout_entries_need_one_binding_type() {
+        let entry = |buffer, sampler| webgpu::GpuBindGroupLayoutEntry {
+            binding: 0,
+            visibility: 0,
+            buffer,
+            sampler,
+            texture: None,
+            storage_texture: None,
+            external_texture: None,
+        };
+        let layout = |entries| webgpu::GpuBindGroupLayoutDescriptor {
+            entries,
+            label: None,
+        };
+        let buffer = || {
+            Some(webgpu::GpuBufferBindingLayout {
+                type_: None,
+                has_dynamic_offset: None,
+                min_binding_size: None,
+            })
+        };
+        let sampler = || Some(webgpu::GpuSamplerBindingLayout { type_: None });
+        assert!(validate_bind_group_layout(&layout(vec![entry(buffer(), None)])).is_ok());
+        assert!(validate_bind_group_layout(&layout(vec![entry(None, None)])).is_err());
+        assert!(validate_bind_group_layout(&layout(vec![entry(buffer(), sampler())])).is_err());
+    }
+}
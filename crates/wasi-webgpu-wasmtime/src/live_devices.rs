@@ -0,0 +1,53 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Devices the guest requested and hasn't dropped yet.
+///
+/// Dropping a store doesn't run the guest's resource drop handlers, so devices a guest still holds when it's done
+/// stay alive in the instance. Hosts that keep the instance around, e.g. to restart guests, call [`release_all`](Self::release_all)
+/// once the store is gone.
+#[derive(Clone, Default)]
+pub struct LiveDevices(Arc<Mutex<HashMap<wgpu_core::id::DeviceId, wgpu_core::id::QueueId>>>);
+
+impl LiveDevices {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn insert(&self, device: wgpu_core::id::DeviceId, queue: wgpu_core::id::QueueId) {
+        self.0.lock().unwrap().insert(device, queue);
+    }
+
+    pub(crate) fn remove(&self, device: wgpu_core::id::DeviceId) {
+        self.0.lock().unwrap().remove(&device);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Waits for the work in flight on each device, then releases it and its queue.
+    pub fn release_all(&self, instance: &wgpu_core::global::Global) {
+        let devices = std::mem::take(&mut *self.0.lock().unwrap());
+        for (device, queue) in devices {
+            release_device(instance, device, queue);
+        }
+    }
+}
+
+pub(crate) fn release_device(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+    queue: wgpu_core::id::QueueId,
+) {
+    // wgpu-core expects no work in flight when a device is dropped.
+    let _ = instance.device_poll::<crate::Backend>(device, wgpu_types::Maintain::Wait);
+    instance.queue_drop::<crate::Backend>(queue);
+    instance.device_drop::<crate::Backend>(device);
+}
@@ -0,0 +1,384 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use wasmtime::component::Resource;
+
+use crate::wasi::webgpu::mipmap_generation;
+use crate::{Device, Texture, WasiWebGpuImpl, WasiWebGpuView};
+
+/// Draws one triangle covering the target, sampling the level above.
+const SHADER: &str = r#"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(source, source_sampler, in.uv);
+}
+"#;
+
+const LABEL: &str = "mipmap generation";
+
+/// What a device needs to generate mipmaps, created the first time the guest generates any.
+///
+/// Command encoders can't be reused once finished, so only the pipelines and what they're made from are kept.
+/// There's a pipeline per format mipmaps were generated for, which keeps the cache small.
+pub(crate) type MipmapCache = Arc<Mutex<Option<MipmapBlitter>>>;
+
+pub(crate) struct MipmapBlitter {
+    shader: wgpu_core::id::ShaderModuleId,
+    sampler: wgpu_core::id::SamplerId,
+    bind_group_layout: wgpu_core::id::BindGroupLayoutId,
+    pipeline_layout: wgpu_core::id::PipelineLayoutId,
+    pipelines: HashMap<wgpu_types::TextureFormat, wgpu_core::id::RenderPipelineId>,
+}
+
+impl MipmapBlitter {
+    fn new(instance: &wgpu_core::global::Global, device: &Device) -> Self {
+        let errors = &device.errors;
+        let shader = errors.handle(instance.device_create_shader_module::<crate::Backend>(
+            device.device,
+            &wgpu_core::pipeline::ShaderModuleDescriptor {
+                label: Some(LABEL.into()),
+                shader_bound_checks: Default::default(),
+            },
+            wgpu_core::pipeline::ShaderModuleSource::Wgsl(Cow::Borrowed(SHADER)),
+            None,
+        ));
+        let sampler = errors.handle(instance.device_create_sampler::<crate::Backend>(
+            device.device,
+            &wgpu_core::resource::SamplerDescriptor {
+                label: Some(LABEL.into()),
+                address_modes: [wgpu_types::AddressMode::ClampToEdge; 3],
+                mag_filter: wgpu_types::FilterMode::Linear,
+                min_filter: wgpu_types::FilterMode::Linear,
+                mipmap_filter: wgpu_types::FilterMode::Nearest,
+                lod_min_clamp: 0.0,
+                lod_max_clamp: 32.0,
+                compare: None,
+                anisotropy_clamp: 1,
+                border_color: None,
+            },
+            None,
+        ));
+        let bind_group_layout =
+            errors.handle(instance.device_create_bind_group_layout::<crate::Backend>(
+                device.device,
+                &wgpu_core::binding_model::BindGroupLayoutDescriptor {
+                    label: Some(LABEL.into()),
+                    entries: Cow::Borrowed(&[
+                        wgpu_types::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu_types::ShaderStages::FRAGMENT,
+                            ty: wgpu_types::BindingType::Texture {
+                                sample_type: wgpu_types::TextureSampleType::Float {
+                                    filterable: true,
+                                },
+                                view_dimension: wgpu_types::TextureViewDimension::D2,
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                        wgpu_types::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu_types::ShaderStages::FRAGMENT,
+                            ty: wgpu_types::BindingType::Sampler(
+                                wgpu_types::SamplerBindingType::Filtering,
+                            ),
+                            count: None,
+                        },
+                    ]),
+                },
+                None,
+            ));
+        let pipeline_layout =
+            errors.handle(instance.device_create_pipeline_layout::<crate::Backend>(
+                device.device,
+                &wgpu_core::binding_model::PipelineLayoutDescriptor {
+                    label: Some(LABEL.into()),
+                    bind_group_layouts: Cow::Owned(vec![bind_group_layout]),
+                    push_constant_ranges: Cow::Borrowed(&[]),
+                },
+                None,
+            ));
+        Self {
+            shader,
+            sampler,
+            bind_group_layout,
+            pipeline_layout,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    fn pipeline(
+        &mut self,
+        instance: &wgpu_core::global::Global,
+        device: &Device,
+        format: wgpu_types::TextureFormat,
+    ) -> wgpu_core::id::RenderPipelineId {
+        let (shader, pipeline_layout) = (self.shader, self.pipeline_layout);
+        *self.pipelines.entry(format).or_insert_with(|| {
+            let stage =
+                |entry_point: &'static str| wgpu_core::pipeline::ProgrammableStageDescriptor {
+                    module: shader,
+                    entry_point: Some(entry_point.into()),
+                    constants: Default::default(),
+                    zero_initialize_workgroup_memory: true,
+                };
+            let descriptor = wgpu_core::pipeline::RenderPipelineDescriptor {
+                label: Some(LABEL.into()),
+                layout: Some(pipeline_layout),
+                vertex: wgpu_core::pipeline::VertexState {
+                    stage: stage("vs_main"),
+                    buffers: Cow::Borrowed(&[]),
+                },
+                primitive: Default::default(),
+                depth_stencil: None,
+                multisample: Default::default(),
+                fragment: Some(wgpu_core::pipeline::FragmentState {
+                    stage: stage("fs_main"),
+                    targets: Cow::Owned(vec![Some(wgpu_types::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu_types::ColorWrites::ALL,
+                    })]),
+                }),
+                multiview: None,
+            };
+            device
+                .errors
+                .handle(instance.device_create_render_pipeline::<crate::Backend>(
+                    device.device,
+                    &descriptor,
+                    None,
+                    None,
+                ))
+        })
+    }
+
+    /// Records the levels of every layer of `texture`, and submits them.
+    fn generate(
+        &mut self,
+        instance: &wgpu_core::global::Global,
+        device: &Device,
+        texture: &Texture,
+    ) {
+        let errors = &device.errors;
+        let pipeline = self.pipeline(instance, device, texture.format);
+        // The texture doesn't need `texture-binding` usage, so levels are sampled from a copy.
+        let source = errors.handle(instance.device_create_texture::<crate::Backend>(
+            device.device,
+            &wgpu_core::resource::TextureDescriptor {
+                label: Some(LABEL.into()),
+                size: wgpu_types::Extent3d {
+                    depth_or_array_layers: 1,
+                    ..texture.size
+                },
+                mip_level_count: texture.mip_level_count - 1,
+                sample_count: 1,
+                dimension: wgpu_types::TextureDimension::D2,
+                format: texture.format,
+                usage: wgpu_types::TextureUsages::TEXTURE_BINDING
+                    | wgpu_types::TextureUsages::COPY_DST,
+                view_formats: vec![],
+            },
+            None,
+        ));
+        let encoder = errors.handle(instance.device_create_command_encoder::<crate::Backend>(
+            device.device,
+            &Default::default(),
+            None,
+        ));
+
+        let mut views = vec![];
+        let mut bind_groups = vec![];
+        for layer in 0..texture.size.depth_or_array_layers {
+            for level in 1..texture.mip_level_count {
+                let size = texture.size.mip_level_size(level - 1, texture.dimension);
+                errors.check(
+                    instance.command_encoder_copy_texture_to_texture::<crate::Backend>(
+                        encoder,
+                        &wgpu_types::ImageCopyTexture {
+                            texture: texture.texture,
+                            mip_level: level - 1,
+                            origin: wgpu_types::Origin3d {
+                                x: 0,
+                                y: 0,
+                                z: layer,
+                            },
+                            aspect: wgpu_types::TextureAspect::All,
+                        },
+                        &wgpu_types::ImageCopyTexture {
+                            texture: source,
+                            mip_level: level - 1,
+                            origin: wgpu_types::Origin3d::ZERO,
+                            aspect: wgpu_types::TextureAspect::All,
+                        },
+                        &wgpu_types::Extent3d {
+                            depth_or_array_layers: 1,
+                            ..size
+                        },
+                    ),
+                );
+
+                let view = |texture, level, layer| {
+                    errors.handle(instance.texture_create_view::<crate::Backend>(
+                        texture,
+                        &wgpu_core::resource::TextureViewDescriptor {
+                            label: Some(LABEL.into()),
+                            format: None,
+                            dimension: Some(wgpu_types::TextureViewDimension::D2),
+                            range: wgpu_types::ImageSubresourceRange {
+                                aspect: wgpu_types::TextureAspect::All,
+                                base_mip_level: level,
+                                mip_level_count: Some(1),
+                                base_array_layer: layer,
+                                array_layer_count: Some(1),
+                            },
+                        },
+                        None,
+                    ))
+                };
+                let source_view = view(source, level - 1, 0);
+                let target_view = view(texture.texture, level, layer);
+                let bind_group =
+                    errors.handle(instance.device_create_bind_group::<crate::Backend>(
+                        device.device,
+                        &wgpu_core::binding_model::BindGroupDescriptor {
+                            label: Some(LABEL.into()),
+                            layout: self.bind_group_layout,
+                            entries: Cow::Owned(vec![
+                                wgpu_core::binding_model::BindGroupEntry {
+                                    binding: 0,
+                                    resource:
+                                        wgpu_core::binding_model::BindingResource::TextureView(
+                                            source_view,
+                                        ),
+                                },
+                                wgpu_core::binding_model::BindGroupEntry {
+                                    binding: 1,
+                                    resource: wgpu_core::binding_model::BindingResource::Sampler(
+                                        self.sampler,
+                                    ),
+                                },
+                            ]),
+                        },
+                        None,
+                    ));
+
+                let mut pass = wgpu_core::command::RenderPass::new(
+                    encoder,
+                    &wgpu_core::command::RenderPassDescriptor {
+                        label: Some(LABEL.into()),
+                        color_attachments: Cow::Owned(vec![Some(
+                            wgpu_core::command::RenderPassColorAttachment {
+                                view: target_view,
+                                resolve_target: None,
+                                channel: wgpu_core::command::PassChannel {
+                                    load_op: wgpu_core::command::LoadOp::Clear,
+                                    store_op: wgpu_core::command::StoreOp::Store,
+                                    clear_value: wgpu_types::Color::TRANSPARENT,
+                                    read_only: false,
+                                },
+                            },
+                        )]),
+                        ..Default::default()
+                    },
+                );
+                wgpu_core::command::render_commands::wgpu_render_pass_set_pipeline(
+                    &mut pass, pipeline,
+                );
+                wgpu_core::command::render_commands::wgpu_render_pass_set_bind_group(
+                    &mut pass,
+                    0,
+                    bind_group,
+                    &[],
+                );
+                wgpu_core::command::render_commands::wgpu_render_pass_draw(&mut pass, 3, 1, 0, 0);
+                errors.check(
+                    instance.command_encoder_run_render_pass::<crate::Backend>(encoder, &pass),
+                );
+
+                views.extend([source_view, target_view]);
+                bind_groups.push(bind_group);
+            }
+        }
+
+        let command_buffer = errors.handle(
+            instance.command_encoder_finish::<crate::Backend>(encoder, &Default::default()),
+        );
+        errors.check(instance.queue_submit::<crate::Backend>(device.queue, &[command_buffer]));
+        // Only drops our handles, the submission keeps them alive until the gpu is done with them.
+        for bind_group in bind_groups {
+            instance.bind_group_drop::<crate::Backend>(bind_group);
+        }
+        for view in views {
+            let _ = instance.texture_view_drop::<crate::Backend>(view, false);
+        }
+        instance.texture_drop::<crate::Backend>(source, false);
+    }
+}
+
+impl<T: WasiWebGpuView> mipmap_generation::Host for WasiWebGpuImpl<T> {
+    fn generate_mipmaps(
+        &mut self,
+        device: Resource<Device>,
+        texture: Resource<Texture>,
+    ) -> Result<(), mipmap_generation::MipmapError> {
+        let device = self.0.table().get(&device).unwrap().clone();
+        let texture = self.0.table().get(&texture).unwrap().clone();
+        let instance = self.0.instance();
+
+        let usage = wgpu_types::TextureUsages::COPY_SRC
+            | wgpu_types::TextureUsages::COPY_DST
+            | wgpu_types::TextureUsages::RENDER_ATTACHMENT;
+        if !texture.usage.contains(usage) {
+            return Err(mipmap_generation::MipmapError::MissingUsage);
+        }
+        if texture.dimension != wgpu_types::TextureDimension::D2 || texture.sample_count != 1 {
+            return Err(mipmap_generation::MipmapError::UnsupportedTexture);
+        }
+        let features = instance
+            .device_features::<crate::Backend>(device.device)
+            .unwrap();
+        let format_features = texture.format.guaranteed_format_features(features);
+        if texture.format.is_depth_stencil_format()
+            || !format_features
+                .allowed_usages
+                .contains(wgpu_types::TextureUsages::RENDER_ATTACHMENT)
+            || !format_features
+                .flags
+                .contains(wgpu_types::TextureFormatFeatureFlags::FILTERABLE)
+        {
+            return Err(mipmap_generation::MipmapError::UnsupportedFormat);
+        }
+        if texture.mip_level_count < 2 {
+            return Ok(());
+        }
+
+        let mut blitter = device.mipmaps.lock().unwrap();
+        device
+            .errors
+            .catch(|| {
+                blitter
+                    .get_or_insert_with(|| MipmapBlitter::new(&instance, &device))
+                    .generate(&instance, &device, &texture)
+            })
+            .map_err(|error| mipmap_generation::MipmapError::Gpu(error.kind()))
+    }
+}
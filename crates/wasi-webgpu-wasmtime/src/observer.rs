@@ -0,0 +1,103 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Gpu resources a [`WasiWebGpuObserver`] is told about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObservedResource {
+    /// `size` is what the guest asked for, not what the driver allocated.
+    Buffer {
+        size: u64,
+    },
+    /// Every texture handle counts, including ones for the canvas and texture atlases.
+    Texture,
+    RenderPipeline,
+    ComputePipeline,
+}
+
+/// Lets embedders follow what a guest does with the gpu, e.g. to show its usage in their own ui.
+///
+/// Calls happen on whichever thread runs the guest, so implementations should be quick.
+pub trait WasiWebGpuObserver: Send + Sync {
+    /// The guest created `resource`. Called even when creating it failed validation, since the guest still holds a handle.
+    fn created(&self, _resource: ObservedResource) {}
+    /// The guest dropped its handle to `resource`.
+    fn dropped(&self, _resource: ObservedResource) {}
+    /// The guest submitted `command_buffers` command buffers to a queue.
+    fn submitted(&self, _command_buffers: usize) {}
+    /// A frame rendered with webgpu was presented.
+    fn presented(&self) {}
+}
+
+/// Counts, at one point in time, of what a [`CountingObserver`] saw.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GpuUsage {
+    pub live_buffers: u64,
+    pub live_textures: u64,
+    pub live_pipelines: u64,
+    /// Total size of every buffer requested so far, including dropped ones.
+    pub buffer_bytes_requested: u64,
+    pub submissions: u64,
+    pub frames_presented: u64,
+}
+
+/// Observer that keeps count, for embedders that only want the numbers.
+///
+/// Rates like submissions per second come from comparing two [`snapshot`](Self::snapshot)s.
+#[derive(Debug, Default)]
+pub struct CountingObserver {
+    live_buffers: AtomicU64,
+    live_textures: AtomicU64,
+    live_pipelines: AtomicU64,
+    buffer_bytes_requested: AtomicU64,
+    submissions: AtomicU64,
+    frames_presented: AtomicU64,
+}
+
+impl CountingObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Each count is read on its own, so a snapshot taken while the guest runs can be off by the calls in between.
+    pub fn snapshot(&self) -> GpuUsage {
+        GpuUsage {
+            live_buffers: self.live_buffers.load(Ordering::Relaxed),
+            live_textures: self.live_textures.load(Ordering::Relaxed),
+            live_pipelines: self.live_pipelines.load(Ordering::Relaxed),
+            buffer_bytes_requested: self.buffer_bytes_requested.load(Ordering::Relaxed),
+            submissions: self.submissions.load(Ordering::Relaxed),
+            frames_presented: self.frames_presented.load(Ordering::Relaxed),
+        }
+    }
+
+    fn live(&self, resource: ObservedResource) -> &AtomicU64 {
+        match resource {
+            ObservedResource::Buffer { .. } => &self.live_buffers,
+            ObservedResource::Texture => &self.live_textures,
+            ObservedResource::RenderPipeline | ObservedResource::ComputePipeline => {
+                &self.live_pipelines
+            }
+        }
+    }
+}
+
+impl WasiWebGpuObserver for CountingObserver {
+    fn created(&self, resource: ObservedResource) {
+        self.live(resource).fetch_add(1, Ordering::Relaxed);
+        if let ObservedResource::Buffer { size } = resource {
+            self.buffer_bytes_requested
+                .fetch_add(size, Ordering::Relaxed);
+        }
+    }
+
+    fn dropped(&self, resource: ObservedResource) {
+        self.live(resource).fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn submitted(&self, _command_buffers: usize) {
+        self.submissions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn presented(&self) {
+        self.frames_presented.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,163 @@
+use wasmtime::component::Resource;
+use wasmtime_wasi::bindings::io::poll::Pollable;
+
+use crate::wasi::webgpu::query_set_resolve_to_buffer;
+use crate::{core_result, BufferPtr, Device, GpuFuture, QuerySet, WasiWebGpuImpl, WasiWebGpuView};
+
+/// Size of each value of a resolved query.
+/// source: https://www.w3.org/TR/webgpu/#dom-gpucommandencoder-resolvequeryset
+const VALUE_SIZE: u64 = 8;
+
+/// Size of a resolved query. Pipeline statistics queries resolve to a value per statistic, the others to one value.
+fn query_size(ty: wgpu_types::QueryType) -> u64 {
+    match ty {
+        wgpu_types::QueryType::PipelineStatistics(statistics) => {
+            statistics.bits().count_ones() as u64 * VALUE_SIZE
+        }
+        wgpu_types::QueryType::Occlusion | wgpu_types::QueryType::Timestamp => VALUE_SIZE,
+    }
+}
+
+/// The `query-results` resource. The results are sent over once the thread waiting on the gpu has read them back.
+pub type QueryResults = GpuFuture<Vec<u64>>;
+
+impl<T: WasiWebGpuView> query_set_resolve_to_buffer::Host for WasiWebGpuImpl<T> {
+    fn resolve_and_read_async(
+        &mut self,
+        device: Resource<Device>,
+        query_set: Resource<QuerySet>,
+        first_query: u32,
+        query_count: u32,
+    ) -> Resource<QueryResults> {
+        let device = self.0.table().get(&device).unwrap();
+        let (device, queue, errors) = (device.device, device.queue, device.errors.clone());
+        let query_set = self.0.table().get(&query_set).unwrap();
+        let size = query_count as u64 * query_size(query_set.ty);
+        let query_set = query_set.usable();
+        let instance = self.0.instance();
+
+        let (results, sender) = GpuFuture::new();
+        let staging = query_set.and_then(|query_set| {
+            let resolve = errors.check(core_result(
+                instance.device_create_buffer::<crate::Backend>(
+                    device,
+                    &wgpu_types::BufferDescriptor {
+                        label: Some("query resolve".into()),
+                        size,
+                        usage: wgpu_types::BufferUsages::QUERY_RESOLVE
+                            | wgpu_types::BufferUsages::COPY_SRC,
+                        mapped_at_creation: false,
+                    },
+                    None,
+                ),
+            ))?;
+            let staging = errors.check(core_result(
+                instance.device_create_buffer::<crate::Backend>(
+                    device,
+                    &wgpu_types::BufferDescriptor {
+                        label: Some("query read back".into()),
+                        size,
+                        usage: wgpu_types::BufferUsages::MAP_READ
+                            | wgpu_types::BufferUsages::COPY_DST,
+                        mapped_at_creation: false,
+                    },
+                    None,
+                ),
+            ));
+            let encoder = errors.handle(instance.device_create_command_encoder::<crate::Backend>(
+                device,
+                &Default::default(),
+                None,
+            ));
+            let submitted = staging.and_then(|staging| {
+                errors.check(
+                    instance.command_encoder_resolve_query_set::<crate::Backend>(
+                        encoder,
+                        query_set,
+                        first_query,
+                        query_count,
+                        resolve,
+                        0,
+                    ),
+                )?;
+                errors.check(
+                    instance.command_encoder_copy_buffer_to_buffer::<crate::Backend>(
+                        encoder, resolve, 0, staging, 0, size,
+                    ),
+                )?;
+                let command_buffer = errors.check(core_result(
+                    instance.command_encoder_finish::<crate::Backend>(encoder, &Default::default()),
+                ))?;
+                errors.check(instance.queue_submit::<crate::Backend>(queue, &[command_buffer]))
+            });
+            // Only drops our handles, the submission keeps what it uses alive until the gpu is done with it. Whatever
+            // failed along the way, nothing is left behind.
+            instance.command_encoder_drop::<crate::Backend>(encoder);
+            instance.buffer_drop::<crate::Backend>(resolve, false);
+            if submitted.is_none() {
+                if let Some(staging) = staging {
+                    instance.buffer_drop::<crate::Backend>(staging, false);
+                }
+                return None;
+            }
+            staging
+        });
+
+        // Waiting for the gpu happens on its own thread, so the guest can keep going in the meantime.
+        // If resolving failed, `sender` is dropped here, which makes the results ready with nothing in them.
+        if let Some(staging) = staging {
+            std::thread::spawn(move || {
+                let results = (|| {
+                    errors.check(instance.buffer_map_async::<crate::Backend>(
+                        staging,
+                        0,
+                        Some(size),
+                        wgpu_core::resource::BufferMapOperation {
+                            host: wgpu_core::device::HostMap::Read,
+                            callback: None,
+                        },
+                    ))?;
+                    errors.check(
+                        instance.device_poll::<crate::Backend>(device, wgpu_types::Maintain::Wait),
+                    )?;
+                    let (ptr, len) = errors.check(
+                        instance.buffer_get_mapped_range::<crate::Backend>(staging, 0, Some(size)),
+                    )?;
+                    let mapped = BufferPtr {
+                        ptr,
+                        len,
+                        written: false,
+                    };
+                    let results = mapped
+                        .slice()
+                        .chunks_exact(VALUE_SIZE as usize)
+                        .map(|query| u64::from_le_bytes(query.try_into().unwrap()))
+                        .collect::<Vec<_>>();
+                    errors.check(instance.buffer_unmap::<crate::Backend>(staging))?;
+                    Some(results)
+                })();
+                instance.buffer_drop::<crate::Backend>(staging, false);
+                if let Some(results) = results {
+                    let _ = sender.send(results);
+                }
+            });
+        }
+
+        self.0.table().push(results).unwrap()
+    }
+}
+
+impl<T: WasiWebGpuView> query_set_resolve_to_buffer::HostQueryResults for WasiWebGpuImpl<T> {
+    fn subscribe(&mut self, results: Resource<QueryResults>) -> Resource<Pollable> {
+        wasmtime_wasi::subscribe(self.0.table(), results).unwrap()
+    }
+
+    fn get(&mut self, results: Resource<QueryResults>) -> Option<Vec<u64>> {
+        self.0.table().get_mut(&results).unwrap().take()
+    }
+
+    fn drop(&mut self, results: Resource<QueryResults>) -> wasmtime::Result<()> {
+        self.0.table().delete(results)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::GpuError;
+
+/// Caps on what a guest can allocate on the gpu. `None` leaves that part unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GpuQuotaLimits {
+    /// Total size of the guest's live buffers.
+    pub max_buffer_bytes: Option<u64>,
+    /// Total estimated size of the guest's live textures, counting every mip level and sample.
+    pub max_texture_bytes: Option<u64>,
+    /// Live buffers and textures together.
+    pub max_resources: Option<u64>,
+}
+
+/// What a [`GpuQuota`] has handed out so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GpuQuotaUsage {
+    pub buffer_bytes: u64,
+    pub texture_bytes: u64,
+    pub resources: u64,
+}
+
+/// Keeps a guest's gpu allocations within [`GpuQuotaLimits`].
+///
+/// Creating a buffer or texture past the limits fails with an out-of-memory error, reported to the device's error scopes
+/// like any other, and hands the guest an invalid resource. Dropping a resource gives its share back.
+///
+/// Give each store its own quota, handles clone the same counts. Limits can be changed while the guest runs, which only
+/// affects later allocations.
+#[derive(Clone, Default)]
+pub struct GpuQuota(Arc<Mutex<QuotaState>>);
+
+#[derive(Default)]
+struct QuotaState {
+    limits: GpuQuotaLimits,
+    usage: GpuQuotaUsage,
+    buffers: HashMap<wgpu_core::id::BufferId, u64>,
+    textures: HashMap<wgpu_core::id::TextureId, u64>,
+}
+
+impl QuotaState {
+    fn check(&self, buffer_bytes: u64, texture_bytes: u64) -> Result<(), GpuError> {
+        let limits = &self.limits;
+        let usage = &self.usage;
+        let over = |used: u64, requested: u64, max: Option<u64>| {
+            max.is_some_and(|max| used.saturating_add(requested) > max)
+        };
+        if over(usage.resources, 1, limits.max_resources) {
+            return Err(GpuError::out_of_memory(format!(
+                "Gpu resource quota of {} live buffers and textures reached",
+                limits.max_resources.unwrap()
+            )));
+        }
+        if over(usage.buffer_bytes, buffer_bytes, limits.max_buffer_bytes) {
+            return Err(GpuError::out_of_memory(format!(
+                "Buffer of {buffer_bytes} bytes exceeds the quota of {} buffer bytes, {} are in use",
+                limits.max_buffer_bytes.unwrap(),
+                usage.buffer_bytes
+            )));
+        }
+        if over(usage.texture_bytes, texture_bytes, limits.max_texture_bytes) {
+            return Err(GpuError::out_of_memory(format!(
+                "Texture of {texture_bytes} bytes exceeds the quota of {} texture bytes, {} are in use",
+                limits.max_texture_bytes.unwrap(),
+                usage.texture_bytes
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl GpuQuota {
+    pub fn new(limits: GpuQuotaLimits) -> Self {
+        let quota = Self::default();
+        quota.set_limits(limits);
+        quota
+    }
+
+    pub fn limits(&self) -> GpuQuotaLimits {
+        self.0.lock().unwrap().limits
+    }
+
+    /// Lowering a limit below the current usage doesn't free anything, it only fails further allocations.
+    pub fn set_limits(&self, limits: GpuQuotaLimits) {
+        self.0.lock().unwrap().limits = limits;
+    }
+
+    pub fn usage(&self) -> GpuQuotaUsage {
+        self.0.lock().unwrap().usage
+    }
+
+    pub(crate) fn check_buffer(&self, size: u64) -> Result<(), GpuError> {
+        self.0.lock().unwrap().check(size, 0)
+    }
+
+    pub(crate) fn check_texture(&self, size: u64) -> Result<(), GpuError> {
+        self.0.lock().unwrap().check(0, size)
+    }
+
+    pub(crate) fn charge_buffer(&self, buffer: wgpu_core::id::BufferId, size: u64) {
+        let mut state = self.0.lock().unwrap();
+        state.usage.buffer_bytes += size;
+        state.usage.resources += 1;
+        state.buffers.insert(buffer, size);
+    }
+
+    pub(crate) fn charge_texture(&self, texture: wgpu_core::id::TextureId, size: u64) {
+        let mut state = self.0.lock().unwrap();
+        state.usage.texture_bytes += size;
+        state.usage.resources += 1;
+        state.textures.insert(texture, size);
+    }
+
+    /// Like [`Self::charge_buffer`], for host memory with no buffer id, like shared buffers.
+    pub(crate) fn charge_buffer_bytes(&self, size: u64) {
+        let mut state = self.0.lock().unwrap();
+        state.usage.buffer_bytes += size;
+        state.usage.resources += 1;
+    }
+
+    /// Gives back what [`Self::charge_buffer_bytes`] took.
+    pub(crate) fn release_buffer_bytes(&self, size: u64) {
+        let mut state = self.0.lock().unwrap();
+        state.usage.buffer_bytes -= size;
+        state.usage.resources -= 1;
+    }
+
+    /// Does nothing for buffers that weren't charged, e.g. ones that failed to create.
+    pub(crate) fn release_buffer(&self, buffer: wgpu_core::id::BufferId) {
+        let mut state = self.0.lock().unwrap();
+        if let Some(size) = state.buffers.remove(&buffer) {
+            state.usage.buffer_bytes -= size;
+            state.usage.resources -= 1;
+        }
+    }
+
+    /// Does nothing for textures that weren't charged, like canvas textures.
+    pub(crate) fn release_texture(&self, texture: wgpu_core::id::TextureId) {
+        let mut state = self.0.lock().unwrap();
+        if let Some(size) = state.textures.remove(&texture) {
+            state.usage.texture_bytes -= size;
+            state.usage.resources -= 1;
+        }
+    }
+}
+
+/// Estimated memory used by a texture, summed over its mip levels.
+pub(crate) fn texture_size<L, V>(descriptor: &wgpu_types::TextureDescriptor<L, V>) -> u64 {
+    let format = descriptor.format;
+    // Combined depth stencil formats have no single block size, 8 bytes covers the largest of them.
+    let block_size = format.block_copy_size(None).unwrap_or(8) as u64;
+    let (block_width, block_height) = format.block_dimensions();
+    (0..descriptor.mip_level_count)
+        .map(|level| {
+            let size = descriptor.mip_level_size(level).unwrap_or(descriptor.size);
+            let blocks_wide = size.width.div_ceil(block_width) as u64;
+            let blocks_high = size.height.div_ceil(block_height) as u64;
+            let layers = size.depth_or_array_layers as u64;
+            blocks_wide * blocks_high * layers * block_size
+        })
+        .sum::<u64>()
+        * descriptor.sample_count as u64
+}
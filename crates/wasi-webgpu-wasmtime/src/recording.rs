@@ -0,0 +1,761 @@
+//! Recording the webgpu calls of a guest, to replay them later without the guest, e.g. to reproduce a bug report.
+//!
+//! A recording is a header followed by one frame per call: the call's tag, the length of the rest of the frame, its
+//! arguments, and the rep of the resource it created, if any. See `recording_codec` for how values are encoded.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use wasmtime::component::Resource;
+
+use crate::recording_codec::{split_frame, Decoder, Encode, Encoder};
+use crate::wasi::webgpu::webgpu;
+use crate::{Device, Texture, WasiWebGpuImpl, WasiWebGpuView};
+
+const MAGIC: &[u8] = b"wasi-webgpu-recording-1\n";
+
+macro_rules! calls {
+    ($($(#[$attr:meta])* $call:ident),* $(,)?) => {
+        /// The calls that are recorded. Nothing else the guest does is, so replaying a call on a resource that came
+        /// from any other call fails.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub(crate) enum Call {
+            $($(#[$attr])* $call),*
+        }
+
+        impl Call {
+            fn from_tag(tag: u8) -> anyhow::Result<Self> {
+                const CALLS: &[Call] = &[$(Call::$call),*];
+                CALLS
+                    .get(tag as usize)
+                    .copied()
+                    .with_context(|| format!("Unknown call {tag} in the recording"))
+            }
+        }
+    };
+}
+
+calls! {
+    GetGpu,
+    RequestAdapter,
+    RequestDevice,
+    Queue,
+    CreateBuffer,
+    CreateTexture,
+    CreateSampler,
+    CreateShaderModule,
+    CreateBindGroupLayout,
+    CreatePipelineLayout,
+    CreateBindGroup,
+    CreateRenderPipeline,
+    CreateComputePipeline,
+    CreateCommandEncoder,
+    CreateView,
+    /// Recorded as the descriptor of the canvas texture, which replays create a texture of their own from.
+    FromGraphicsBuffer,
+    RenderPipelineGetBindGroupLayout,
+    ComputePipelineGetBindGroupLayout,
+    BeginRenderPass,
+    BeginComputePass,
+    Finish,
+    CopyBufferToBuffer,
+    CopyTextureToBuffer,
+    RenderPassSetPipeline,
+    RenderPassSetBindGroup,
+    RenderPassSetVertexBuffer,
+    RenderPassSetIndexBuffer,
+    RenderPassSetViewport,
+    RenderPassSetScissorRect,
+    RenderPassDraw,
+    RenderPassDrawIndexed,
+    RenderPassEnd,
+    ComputePassSetPipeline,
+    ComputePassSetBindGroup,
+    ComputePassDispatchWorkgroups,
+    ComputePassEnd,
+    Submit,
+    WriteBuffer,
+    WriteTexture,
+    MapAsync,
+    GetMappedRange,
+    /// Recorded with what the guest wrote into the mapped range, which replays write back before unmapping.
+    Unmap,
+    // Added after the first recordings were made, tags are the position in this list.
+    CopyBufferToTexture,
+    CopyTextureToTexture,
+    CreateQuerySet,
+    ClearBuffer,
+    ResolveQuerySet,
+    RenderPassSetBlendConstant,
+    RenderPassSetStencilReference,
+    RenderPassSetPushConstants,
+    RenderPassDrawIndirect,
+    RenderPassDrawIndexedIndirect,
+    RenderPassDrawIndirectCount,
+    RenderPassDrawIndexedIndirectCount,
+    ComputePassDispatchWorkgroupsIndirect,
+    /// A call that isn't recorded, with its name. Replays stop there, rather than go on without it and render
+    /// something the guest didn't.
+    Unrecorded,
+    DestroyDevice,
+    DestroyBuffer,
+    DestroyTexture,
+    CreatePipelineStatisticsQuerySet,
+    DestroyQuerySet,
+    RenderPassBeginOcclusionQuery,
+    RenderPassEndOcclusionQuery,
+    CommandEncoderPushDebugGroup,
+    CommandEncoderPopDebugGroup,
+    CommandEncoderInsertDebugMarker,
+    RenderPassPushDebugGroup,
+    RenderPassPopDebugGroup,
+    RenderPassInsertDebugMarker,
+    ComputePassPushDebugGroup,
+    ComputePassPopDebugGroup,
+    ComputePassInsertDebugMarker,
+    RenderPassBeginPipelineStatisticsQuery,
+    RenderPassEndPipelineStatisticsQuery,
+    ComputePassBeginPipelineStatisticsQuery,
+    ComputePassEndPipelineStatisticsQuery,
+    TryCreateRenderPipeline,
+    CreateTextureFromImage,
+}
+
+/// Records a guest's webgpu calls into a file, for [`replay`] to make them again without the guest.
+///
+/// Hand it to the guest through [`WasiWebGpuView::recorder`]. Clones write to the same file, so give each store a
+/// recorder of its own.
+#[derive(Clone)]
+pub struct Recorder(Arc<Mutex<Recording>>);
+
+struct Recording {
+    file: BufWriter<File>,
+    /// Set by the first unrecorded call, which replays stop at.
+    unrecorded: bool,
+}
+
+impl Recorder {
+    /// Starts a recording at `path`, replacing any file that's there.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        Ok(Self(Arc::new(Mutex::new(Recording {
+            file,
+            unrecorded: false,
+        }))))
+    }
+
+    fn write(&self, call: Call, frame: &[u8]) {
+        let mut header = Encoder(vec![call as u8]);
+        header.varint(frame.len() as u64);
+        let mut recording = self.0.lock().unwrap();
+        let file = &mut recording.file;
+        let mut result = file
+            .write_all(&header.0)
+            .and_then(|()| file.write_all(frame));
+        // Submissions are rare enough to flush on, and keep the recording useful if the host doesn't exit cleanly.
+        if call == Call::Submit {
+            result = result.and_then(|()| file.flush());
+        }
+        if let Err(e) = result {
+            log::error!("Failed to record a webgpu call: {e}");
+        }
+    }
+}
+
+/// A call that creates a resource, written to the recording once the resource is there.
+pub(crate) struct RecordedCall(Option<(Recorder, Call, Encoder)>);
+
+impl RecordedCall {
+    pub(crate) fn created<T: 'static>(self, resource: Resource<T>) -> Resource<T> {
+        if let Some((recorder, call, mut frame)) = self.0 {
+            resource.encode(&mut frame);
+            recorder.write(call, &frame.0);
+        }
+        resource
+    }
+}
+
+impl<T: WasiWebGpuView> WasiWebGpuImpl<T> {
+    /// Records a call that doesn't create a resource, if the view has a recorder.
+    pub(crate) fn record(&self, call: Call, args: impl Encode) {
+        record(self.0.recorder(), call, args);
+    }
+
+    /// Marks a call that isn't recorded, so replays fail at it instead of leaving it out. `name` is the call's name in
+    /// the wit.
+    ///
+    /// Logs an error the first time, since nothing the guest does after it can be replayed.
+    pub(crate) fn record_unrecorded(&self, name: &str) {
+        let Some(recorder) = self.0.recorder() else {
+            return;
+        };
+        let first = !std::mem::replace(&mut recorder.0.lock().unwrap().unrecorded, true);
+        if first {
+            log::error!(
+                "The guest called {name}, which isn't recorded, replays of this recording stop there"
+            );
+        }
+        record(Some(recorder), Call::Unrecorded, name);
+    }
+
+    /// Records a call that creates a resource, once [`RecordedCall::created`] is called with it.
+    /// `args` are encoded right away, since most calls consume them.
+    pub(crate) fn record_creation(&self, call: Call, args: impl Encode) -> RecordedCall {
+        RecordedCall(self.0.recorder().map(|recorder| {
+            let mut frame = Encoder(Vec::new());
+            args.encode(&mut frame);
+            (recorder, call, frame)
+        }))
+    }
+}
+
+/// [`WasiWebGpuImpl::record`], for calls that need the table borrowed while encoding their arguments.
+pub(crate) fn record(recorder: Option<Recorder>, call: Call, args: impl Encode) {
+    if let Some(recorder) = recorder {
+        let mut frame = Encoder(Vec::new());
+        args.encode(&mut frame);
+        recorder.write(call, &frame.0);
+    }
+}
+
+/// Canvas textures aren't there without the canvas, so they're recorded as a texture that looks the same.
+impl Encode for Texture {
+    fn encode(&self, e: &mut Encoder) {
+        webgpu::GpuTextureDescriptor {
+            size: webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+                width: self.size.width,
+                height: Some(self.size.height),
+                depth_or_array_layers: Some(self.size.depth_or_array_layers),
+            }),
+            mip_level_count: Some(self.mip_level_count),
+            sample_count: Some(self.sample_count),
+            dimension: self.dimension.into(),
+            format: self.format.into(),
+            usage: self.usage.bits(),
+            view_formats: None,
+            label: None,
+        }
+        .encode(e);
+    }
+}
+
+/// Makes the calls in the recording at `path` on `view`, as if the guest that was recorded made them. Returns how
+/// many calls were replayed.
+///
+/// Resources are created in the same order as when recording, and calls on them go to the recreated ones. Canvas
+/// textures are replaced with plain textures created from the last requested device, and nothing is presented.
+/// Resources the guest dropped are kept until the view's table is dropped.
+pub async fn replay<T: WasiWebGpuView>(view: T, path: impl AsRef<Path>) -> anyhow::Result<u64> {
+    let path = path.as_ref();
+    let recording = std::fs::read(path)
+        .with_context(|| format!("Failed to read recording {}", path.display()))?;
+    let mut frames = recording
+        .strip_prefix(MAGIC)
+        .with_context(|| format!("{} isn't a webgpu recording", path.display()))?;
+
+    let mut host = WasiWebGpuImpl(view);
+    let mut resources = HashMap::new();
+    let mut device = None;
+    let mut calls = 0;
+    while let Some((&tag, rest)) = frames.split_first() {
+        let call = Call::from_tag(tag)?;
+        let (frame, rest) = split_frame(rest)?;
+        frames = rest;
+        let mut d = Decoder::new(frame, &mut resources);
+        replay_call(&mut host, call, &mut d, &mut device)
+            .await
+            .with_context(|| format!("Failed to replay call {calls}, {call:?}"))?;
+        calls += 1;
+    }
+    Ok(calls)
+}
+
+async fn replay_call<T: WasiWebGpuView>(
+    host: &mut WasiWebGpuImpl<T>,
+    call: Call,
+    d: &mut Decoder<'_>,
+    device: &mut Option<u32>,
+) -> anyhow::Result<()> {
+    use webgpu::{
+        Host, HostGpu, HostGpuAdapter, HostGpuBuffer, HostGpuCommandEncoder,
+        HostGpuComputePassEncoder, HostGpuComputePipeline, HostGpuDevice, HostGpuQuerySet,
+        HostGpuQueue, HostGpuRenderPassEncoder, HostGpuRenderPipeline, HostGpuTexture,
+    };
+
+    match call {
+        Call::GetGpu => {
+            let gpu = Host::get_gpu(host);
+            d.created(&gpu)?;
+        }
+        Call::RequestAdapter => {
+            let (gpu, options) = d.decode()?;
+            let adapter = HostGpu::request_adapter(host, gpu, options)?
+                .context("No adapter to replay the recording with")?;
+            d.created(&adapter)?;
+        }
+        Call::RequestDevice => {
+            let (adapter, descriptor) = d.decode()?;
+            let created = HostGpuAdapter::request_device(host, adapter, descriptor)?;
+            *device = Some(created.rep());
+            d.created(&created)?;
+        }
+        Call::Queue => {
+            let device = d.decode()?;
+            let queue = HostGpuDevice::queue(host, device);
+            d.created(&queue)?;
+        }
+        Call::CreateBuffer => {
+            let (device, descriptor) = d.decode()?;
+            let buffer = HostGpuDevice::create_buffer(host, device, descriptor);
+            d.created(&buffer)?;
+        }
+        Call::CreateTexture => {
+            let (device, descriptor) = d.decode()?;
+            let texture = HostGpuDevice::create_texture(host, device, descriptor);
+            d.created(&texture)?;
+        }
+        Call::CreateSampler => {
+            let (device, descriptor) = d.decode()?;
+            let sampler = HostGpuDevice::create_sampler(host, device, descriptor);
+            d.created(&sampler)?;
+        }
+        Call::CreateShaderModule => {
+            let (device, descriptor) = d.decode()?;
+            let shader = HostGpuDevice::create_shader_module(host, device, descriptor);
+            d.created(&shader)?;
+        }
+        Call::CreateBindGroupLayout => {
+            let (device, descriptor) = d.decode()?;
+            let layout = HostGpuDevice::create_bind_group_layout(host, device, descriptor);
+            d.created(&layout)?;
+        }
+        Call::CreatePipelineLayout => {
+            let (device, descriptor) = d.decode()?;
+            let layout = HostGpuDevice::create_pipeline_layout(host, device, descriptor);
+            d.created(&layout)?;
+        }
+        Call::CreateBindGroup => {
+            let (device, descriptor) = d.decode()?;
+            let bind_group = HostGpuDevice::create_bind_group(host, device, descriptor);
+            d.created(&bind_group)?;
+        }
+        Call::CreateRenderPipeline => {
+            let (device, descriptor) = d.decode()?;
+            let pipeline = HostGpuDevice::create_render_pipeline(host, device, descriptor);
+            d.created(&pipeline)?;
+        }
+        Call::CreateComputePipeline => {
+            let (device, descriptor) = d.decode()?;
+            let pipeline = HostGpuDevice::create_compute_pipeline(host, device, descriptor);
+            d.created(&pipeline)?;
+        }
+        Call::CreateQuerySet => {
+            let (device, descriptor) = d.decode()?;
+            let query_set = HostGpuDevice::create_query_set(host, device, descriptor);
+            d.created(&query_set)?;
+        }
+        Call::CreateCommandEncoder => {
+            let (device, descriptor) = d.decode()?;
+            let encoder = HostGpuDevice::create_command_encoder(host, device, descriptor);
+            d.created(&encoder)?;
+        }
+        Call::CreateView => {
+            let (texture, descriptor) = d.decode()?;
+            let view = HostGpuTexture::create_view(host, texture, descriptor);
+            d.created(&view)?;
+        }
+        Call::FromGraphicsBuffer => {
+            let descriptor = d.decode()?;
+            let device: Resource<Device> = Resource::new_borrow(
+                (*device).context("Recording uses a canvas texture before requesting a device")?,
+            );
+            let texture = HostGpuDevice::create_texture(host, device, descriptor);
+            d.created(&texture)?;
+        }
+        Call::RenderPipelineGetBindGroupLayout => {
+            let (pipeline, index) = d.decode()?;
+            let layout = HostGpuRenderPipeline::get_bind_group_layout(host, pipeline, index)?;
+            d.created(&layout)?;
+        }
+        Call::ComputePipelineGetBindGroupLayout => {
+            let (pipeline, index) = d.decode()?;
+            let layout = HostGpuComputePipeline::get_bind_group_layout(host, pipeline, index)?;
+            d.created(&layout)?;
+        }
+        Call::BeginRenderPass => {
+            let (encoder, descriptor) = d.decode()?;
+            let pass = HostGpuCommandEncoder::begin_render_pass(host, encoder, descriptor);
+            d.created(&pass)?;
+        }
+        Call::BeginComputePass => {
+            let (encoder, descriptor) = d.decode()?;
+            let pass = HostGpuCommandEncoder::begin_compute_pass(host, encoder, descriptor);
+            d.created(&pass)?;
+        }
+        Call::Finish => {
+            let (encoder, descriptor) = d.decode()?;
+            let command_buffer = HostGpuCommandEncoder::finish(host, encoder, descriptor);
+            d.created(&command_buffer)?;
+        }
+        Call::CopyBufferToBuffer => {
+            let (encoder, source, source_offset, destination, destination_offset, size) =
+                d.decode()?;
+            HostGpuCommandEncoder::copy_buffer_to_buffer(
+                host,
+                encoder,
+                source,
+                source_offset,
+                destination,
+                destination_offset,
+                size,
+            );
+        }
+        Call::CopyTextureToBuffer => {
+            let (encoder, source, destination, copy_size) = d.decode()?;
+            HostGpuCommandEncoder::copy_texture_to_buffer(
+                host,
+                encoder,
+                source,
+                destination,
+                copy_size,
+            );
+        }
+        Call::CopyBufferToTexture => {
+            let (encoder, source, destination, copy_size) = d.decode()?;
+            HostGpuCommandEncoder::copy_buffer_to_texture(
+                host,
+                encoder,
+                source,
+                destination,
+                copy_size,
+            );
+        }
+        Call::CopyTextureToTexture => {
+            let (encoder, source, destination, copy_size) = d.decode()?;
+            HostGpuCommandEncoder::copy_texture_to_texture(
+                host,
+                encoder,
+                source,
+                destination,
+                copy_size,
+            );
+        }
+        Call::ClearBuffer => {
+            let (encoder, buffer, offset, size) = d.decode()?;
+            HostGpuCommandEncoder::clear_buffer(host, encoder, buffer, offset, size);
+        }
+        Call::ResolveQuerySet => {
+            let (encoder, query_set, first_query, query_count, destination, destination_offset) =
+                d.decode()?;
+            HostGpuCommandEncoder::resolve_query_set(
+                host,
+                encoder,
+                query_set,
+                first_query,
+                query_count,
+                destination,
+                destination_offset,
+            );
+        }
+        Call::RenderPassSetPipeline => {
+            let (pass, pipeline) = d.decode()?;
+            HostGpuRenderPassEncoder::set_pipeline(host, pass, pipeline);
+        }
+        Call::RenderPassSetBindGroup => {
+            let (pass, index, bind_group, dynamic_offsets) = d.decode()?;
+            HostGpuRenderPassEncoder::set_bind_group(
+                host,
+                pass,
+                index,
+                bind_group,
+                dynamic_offsets,
+            );
+        }
+        Call::RenderPassSetVertexBuffer => {
+            let (pass, slot, buffer, offset, size) = d.decode()?;
+            HostGpuRenderPassEncoder::set_vertex_buffer(host, pass, slot, buffer, offset, size);
+        }
+        Call::RenderPassSetIndexBuffer => {
+            let (pass, buffer, index_format, offset, size) = d.decode()?;
+            HostGpuRenderPassEncoder::set_index_buffer(
+                host,
+                pass,
+                buffer,
+                index_format,
+                offset,
+                size,
+            );
+        }
+        Call::RenderPassSetViewport => {
+            let (pass, (x, y, width, height), (min_depth, max_depth)) = d.decode()?;
+            HostGpuRenderPassEncoder::set_viewport(
+                host, pass, x, y, width, height, min_depth, max_depth,
+            );
+        }
+        Call::RenderPassSetScissorRect => {
+            let (pass, x, y, width, height) = d.decode()?;
+            HostGpuRenderPassEncoder::set_scissor_rect(host, pass, x, y, width, height);
+        }
+        Call::RenderPassDraw => {
+            let (pass, vertex_count, instance_count, first_vertex, first_instance) = d.decode()?;
+            HostGpuRenderPassEncoder::draw(
+                host,
+                pass,
+                vertex_count,
+                instance_count,
+                first_vertex,
+                first_instance,
+            );
+        }
+        Call::RenderPassDrawIndexed => {
+            let (pass, index_count, instance_count, first_index, base_vertex, first_instance) =
+                d.decode()?;
+            HostGpuRenderPassEncoder::draw_indexed(
+                host,
+                pass,
+                index_count,
+                instance_count,
+                first_index,
+                base_vertex,
+                first_instance,
+            );
+        }
+        Call::RenderPassSetBlendConstant => {
+            let (pass, color) = d.decode()?;
+            HostGpuRenderPassEncoder::set_blend_constant(host, pass, color);
+        }
+        Call::RenderPassSetStencilReference => {
+            let (pass, reference) = d.decode()?;
+            HostGpuRenderPassEncoder::set_stencil_reference(host, pass, reference);
+        }
+        Call::RenderPassSetPushConstants => {
+            let (pass, stages, offset, data) = d.decode()?;
+            HostGpuRenderPassEncoder::set_push_constants(host, pass, stages, offset, data)?;
+        }
+        Call::RenderPassDrawIndirect => {
+            let (pass, buffer, offset) = d.decode()?;
+            HostGpuRenderPassEncoder::draw_indirect(host, pass, buffer, offset);
+        }
+        Call::RenderPassDrawIndexedIndirect => {
+            let (pass, buffer, offset) = d.decode()?;
+            HostGpuRenderPassEncoder::draw_indexed_indirect(host, pass, buffer, offset);
+        }
+        Call::RenderPassDrawIndirectCount => {
+            let (pass, buffer, offset, count_buffer, count_offset, max_count) = d.decode()?;
+            HostGpuRenderPassEncoder::draw_indirect_count(
+                host,
+                pass,
+                buffer,
+                offset,
+                count_buffer,
+                count_offset,
+                max_count,
+            );
+        }
+        Call::RenderPassDrawIndexedIndirectCount => {
+            let (pass, buffer, offset, count_buffer, count_offset, max_count) = d.decode()?;
+            HostGpuRenderPassEncoder::draw_indexed_indirect_count(
+                host,
+                pass,
+                buffer,
+                offset,
+                count_buffer,
+                count_offset,
+                max_count,
+            );
+        }
+        Call::RenderPassEnd => {
+            let (pass, encoder) = d.decode()?;
+            HostGpuRenderPassEncoder::end(host, pass, encoder);
+        }
+        Call::ComputePassSetPipeline => {
+            let (pass, pipeline) = d.decode()?;
+            HostGpuComputePassEncoder::set_pipeline(host, pass, pipeline);
+        }
+        Call::ComputePassSetBindGroup => {
+            let (pass, index, bind_group, dynamic_offsets) = d.decode()?;
+            HostGpuComputePassEncoder::set_bind_group(
+                host,
+                pass,
+                index,
+                bind_group,
+                dynamic_offsets,
+            );
+        }
+        Call::ComputePassDispatchWorkgroups => {
+            let (pass, x, y, z) = d.decode()?;
+            HostGpuComputePassEncoder::dispatch_workgroups(host, pass, x, y, z);
+        }
+        Call::ComputePassDispatchWorkgroupsIndirect => {
+            let (pass, buffer, offset) = d.decode()?;
+            HostGpuComputePassEncoder::dispatch_workgroups_indirect(host, pass, buffer, offset);
+        }
+        Call::ComputePassEnd => {
+            let (pass, encoder) = d.decode()?;
+            HostGpuComputePassEncoder::end(host, pass, encoder);
+        }
+        Call::Submit => {
+            let (queue, command_buffers) = d.decode()?;
+            HostGpuQueue::submit(host, queue, command_buffers);
+        }
+        Call::WriteBuffer => {
+            let (queue, buffer, buffer_offset, data_offset, data, size) = d.decode()?;
+            HostGpuQueue::write_buffer(
+                host,
+                queue,
+                buffer,
+                buffer_offset,
+                data_offset,
+                data,
+                size,
+            )?;
+        }
+        Call::WriteTexture => {
+            let (queue, destination, data, data_layout, size) = d.decode()?;
+            HostGpuQueue::write_texture(host, queue, destination, data, data_layout, size);
+        }
+        Call::MapAsync => {
+            let (buffer, mode, offset, size) = d.decode()?;
+            HostGpuBuffer::map_async(host, buffer, mode, offset, size).await;
+        }
+        Call::GetMappedRange => {
+            let (buffer, offset, size) = d.decode()?;
+            let mapped = HostGpuBuffer::get_mapped_range(host, buffer, offset, size)?;
+            d.created(&mapped)?;
+        }
+        Call::Unmap => {
+            let (buffer, written): (Resource<webgpu::GpuBuffer>, Option<Vec<u8>>) = d.decode()?;
+            if let Some(written) = written {
+                let mapped = host
+                    .0
+                    .table()
+                    .get_mut(&buffer)?
+                    .mapped
+                    .as_mut()
+                    .context("Buffer isn't mapped")?;
+                mapped
+                    .slice_mut()
+                    .get_mut(..written.len())
+                    .context("Mapped range is smaller than when recording")?
+                    .copy_from_slice(&written);
+            }
+            HostGpuBuffer::unmap(host, buffer);
+        }
+        Call::DestroyDevice => {
+            let device = d.decode()?;
+            HostGpuDevice::destroy(host, device);
+        }
+        Call::DestroyBuffer => {
+            let buffer = d.decode()?;
+            HostGpuBuffer::destroy(host, buffer);
+        }
+        Call::DestroyTexture => {
+            let texture = d.decode()?;
+            HostGpuTexture::destroy(host, texture);
+        }
+        Call::CreatePipelineStatisticsQuerySet => {
+            let (device, descriptor) = d.decode()?;
+            let query_set =
+                HostGpuDevice::create_pipeline_statistics_query_set(host, device, descriptor);
+            d.created(&query_set)?;
+        }
+        Call::DestroyQuerySet => {
+            let query_set = d.decode()?;
+            HostGpuQuerySet::destroy(host, query_set);
+        }
+        Call::RenderPassBeginOcclusionQuery => {
+            let (pass, query_index) = d.decode()?;
+            HostGpuRenderPassEncoder::begin_occlusion_query(host, pass, query_index);
+        }
+        Call::RenderPassEndOcclusionQuery => {
+            let pass = d.decode()?;
+            HostGpuRenderPassEncoder::end_occlusion_query(host, pass);
+        }
+        Call::CommandEncoderPushDebugGroup => {
+            let (encoder, group_label) = d.decode()?;
+            HostGpuCommandEncoder::push_debug_group(host, encoder, group_label);
+        }
+        Call::CommandEncoderPopDebugGroup => {
+            let encoder = d.decode()?;
+            HostGpuCommandEncoder::pop_debug_group(host, encoder);
+        }
+        Call::CommandEncoderInsertDebugMarker => {
+            let (encoder, marker_label) = d.decode()?;
+            HostGpuCommandEncoder::insert_debug_marker(host, encoder, marker_label);
+        }
+        Call::RenderPassPushDebugGroup => {
+            let (pass, group_label) = d.decode()?;
+            HostGpuRenderPassEncoder::push_debug_group(host, pass, group_label);
+        }
+        Call::RenderPassPopDebugGroup => {
+            let pass = d.decode()?;
+            HostGpuRenderPassEncoder::pop_debug_group(host, pass);
+        }
+        Call::RenderPassInsertDebugMarker => {
+            let (pass, marker_label) = d.decode()?;
+            HostGpuRenderPassEncoder::insert_debug_marker(host, pass, marker_label);
+        }
+        Call::ComputePassPushDebugGroup => {
+            let (pass, group_label) = d.decode()?;
+            HostGpuComputePassEncoder::push_debug_group(host, pass, group_label);
+        }
+        Call::ComputePassPopDebugGroup => {
+            let pass = d.decode()?;
+            HostGpuComputePassEncoder::pop_debug_group(host, pass);
+        }
+        Call::ComputePassInsertDebugMarker => {
+            let (pass, marker_label) = d.decode()?;
+            HostGpuComputePassEncoder::insert_debug_marker(host, pass, marker_label);
+        }
+        Call::RenderPassBeginPipelineStatisticsQuery => {
+            let (pass, query_set, query_index) = d.decode()?;
+            HostGpuRenderPassEncoder::begin_pipeline_statistics_query(
+                host,
+                pass,
+                query_set,
+                query_index,
+            );
+        }
+        Call::RenderPassEndPipelineStatisticsQuery => {
+            let pass = d.decode()?;
+            HostGpuRenderPassEncoder::end_pipeline_statistics_query(host, pass);
+        }
+        Call::ComputePassBeginPipelineStatisticsQuery => {
+            let (pass, query_set, query_index) = d.decode()?;
+            HostGpuComputePassEncoder::begin_pipeline_statistics_query(
+                host,
+                pass,
+                query_set,
+                query_index,
+            );
+        }
+        Call::ComputePassEndPipelineStatisticsQuery => {
+            let pass = d.decode()?;
+            HostGpuComputePassEncoder::end_pipeline_statistics_query(host, pass);
+        }
+        Call::TryCreateRenderPipeline => {
+            let (device, descriptor) = d.decode()?;
+            let pipeline = HostGpuDevice::try_create_render_pipeline(host, device, descriptor)
+                .map_err(|e| anyhow::anyhow!("{e:?}"))
+                .context("The render pipeline was created when recording")?;
+            d.created(&pipeline)?;
+        }
+        Call::CreateTextureFromImage => {
+            let (device, descriptor) = d.decode()?;
+            let image = HostGpuDevice::create_texture_from_image(host, device, descriptor)
+                .map_err(|e| anyhow::anyhow!("{e:?}"))
+                .context("The image was decoded when recording")?;
+            d.created(&image.texture)?;
+        }
+        Call::Unrecorded => {
+            let name: String = d.decode()?;
+            anyhow::bail!("The guest called {name}, which isn't recorded");
+        }
+    }
+    Ok(())
+}
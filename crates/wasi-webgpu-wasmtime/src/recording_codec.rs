@@ -0,0 +1,525 @@
+//! The encoding of call arguments in recordings.
+//!
+//! Integers are LEB128, zigzagged when signed, so the small values most arguments are take a byte or two. Resources are
+//! their table rep, which replays map to the rep of the resource they recreated.
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use wasmtime::component::Resource;
+
+use crate::wasi::webgpu::webgpu;
+
+pub(crate) struct Encoder(pub(crate) Vec<u8>);
+
+pub(crate) struct Decoder<'a> {
+    bytes: &'a [u8],
+    /// Reps in the recording to reps in the replaying table.
+    resources: &'a mut HashMap<u32, u32>,
+}
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(bytes: &'a [u8], resources: &'a mut HashMap<u32, u32>) -> Self {
+        Self { bytes, resources }
+    }
+
+    pub(crate) fn decode<T: Decode>(&mut self) -> anyhow::Result<T> {
+        T::decode(self)
+    }
+
+    /// Reads the rep `resource` had when it was recorded, so later calls on it go to `resource`.
+    pub(crate) fn created<T: 'static>(&mut self, resource: &Resource<T>) -> anyhow::Result<()> {
+        // Recorded like any other resource, see `Encode for Resource`.
+        let recorded = (self.varint()? >> 1) as u32;
+        self.resources.insert(recorded, resource.rep());
+        Ok(())
+    }
+
+    fn bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        take(&mut self.bytes, len)
+    }
+
+    fn byte(&mut self) -> anyhow::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn varint(&mut self) -> anyhow::Result<u64> {
+        varint(&mut self.bytes)
+    }
+}
+
+/// Splits the frame of a call, whose length comes first, off the start of `bytes`. Returns the frame and what's after it.
+pub(crate) fn split_frame(mut bytes: &[u8]) -> anyhow::Result<(&[u8], &[u8])> {
+    let len = varint(&mut bytes)?;
+    let frame = take(&mut bytes, len as usize)?;
+    Ok((frame, bytes))
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> anyhow::Result<&'a [u8]> {
+    anyhow::ensure!(bytes.len() >= len, "Recording ends in the middle of a call");
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+fn varint(bytes: &mut &[u8]) -> anyhow::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = take(bytes, 1)?[0];
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    anyhow::bail!("Integer in the recording is too long")
+}
+
+impl Encoder {
+    pub(crate) fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+}
+
+pub(crate) trait Encode {
+    fn encode(&self, e: &mut Encoder);
+}
+
+pub(crate) trait Decode: Sized {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self>;
+}
+
+impl Encode for () {
+    fn encode(&self, _e: &mut Encoder) {}
+}
+
+impl Decode for () {
+    fn decode(_d: &mut Decoder) -> anyhow::Result<Self> {
+        Ok(())
+    }
+}
+
+impl Encode for u8 {
+    fn encode(&self, e: &mut Encoder) {
+        e.0.push(*self);
+    }
+}
+
+impl Decode for u8 {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+        d.byte()
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, e: &mut Encoder) {
+        e.0.push(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+        Ok(d.byte()? != 0)
+    }
+}
+
+macro_rules! unsigned {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, e: &mut Encoder) {
+                e.varint(u64::from(*self));
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+                let value = d.varint()?;
+                <$ty>::try_from(value).with_context(|| format!("{value} is out of range in the recording"))
+            }
+        }
+    )*};
+}
+
+unsigned!(u16, u32, u64);
+
+impl Encode for i32 {
+    fn encode(&self, e: &mut Encoder) {
+        e.varint(u64::from(((self << 1) ^ (self >> 31)) as u32));
+    }
+}
+
+impl Decode for i32 {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+        let value = u32::decode(d)?;
+        Ok((value >> 1) as i32 ^ -((value & 1) as i32))
+    }
+}
+
+macro_rules! float {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, e: &mut Encoder) {
+                e.0.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+                let bytes = d.bytes(std::mem::size_of::<$ty>())?;
+                Ok(<$ty>::from_le_bytes(bytes.try_into().unwrap()))
+            }
+        }
+    )*};
+}
+
+float!(f32, f64);
+
+impl Encode for str {
+    fn encode(&self, e: &mut Encoder) {
+        self.as_bytes().encode(e);
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, e: &mut Encoder) {
+        self.as_str().encode(e);
+    }
+}
+
+impl Decode for String {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+        Ok(String::from_utf8(Vec::<u8>::decode(d)?)?)
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, e: &mut Encoder) {
+        e.varint(self.len() as u64);
+        for item in self {
+            item.encode(e);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, e: &mut Encoder) {
+        self.as_slice().encode(e);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+        let len = d.varint()? as usize;
+        // Bounded by what's left, so a corrupt length can't allocate more than the recording holds.
+        let mut items = Vec::with_capacity(len.min(d.bytes.len()));
+        for _ in 0..len {
+            items.push(T::decode(d)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, e: &mut Encoder) {
+        match self {
+            None => e.0.push(0),
+            Some(value) => {
+                e.0.push(1);
+                value.encode(e);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+        Ok(match d.byte()? {
+            0 => None,
+            _ => Some(T::decode(d)?),
+        })
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, e: &mut Encoder) {
+        (**self).encode(e);
+    }
+}
+
+macro_rules! tuple {
+    ($(($($name:ident),*))*) => {$(
+        impl<$($name: Encode),*> Encode for ($($name,)*) {
+            fn encode(&self, e: &mut Encoder) {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = self;
+                $($name.encode(e);)*
+            }
+        }
+
+        impl<$($name: Decode),*> Decode for ($($name,)*) {
+            fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+                Ok(($($name::decode(d)?,)*))
+            }
+        }
+    )*};
+}
+
+tuple! {
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, E)
+    (A, B, C, D, E, F)
+}
+
+/// The lowest bit says whether the handle is owned, the rest is the rep.
+impl<T: 'static> Encode for Resource<T> {
+    fn encode(&self, e: &mut Encoder) {
+        e.varint((u64::from(self.rep()) << 1) | u64::from(self.owned()));
+    }
+}
+
+impl<T: 'static> Decode for Resource<T> {
+    fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+        let value = d.varint()?;
+        let recorded = (value >> 1) as u32;
+        let rep = *d.resources.get(&recorded).with_context(|| {
+            format!("Resource {recorded} wasn't created by a call in the recording")
+        })?;
+        Ok(match value & 1 {
+            0 => Resource::new_borrow(rep),
+            _ => Resource::new_own(rep),
+        })
+    }
+}
+
+macro_rules! records {
+    ($($name:ident { $($field:ident),* $(,)? })*) => {$(
+        impl Encode for webgpu::$name {
+            fn encode(&self, e: &mut Encoder) {
+                $(self.$field.encode(e);)*
+            }
+        }
+
+        impl Decode for webgpu::$name {
+            fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+                Ok(Self {
+                    $($field: Decode::decode(d)?,)*
+                })
+            }
+        }
+    )*};
+}
+
+/// Cases are listed in the order of the wit, which is also the order of their discriminants.
+macro_rules! enums {
+    ($($name:ident { $($case:ident),* $(,)? })*) => {$(
+        impl Encode for webgpu::$name {
+            fn encode(&self, e: &mut Encoder) {
+                e.varint(*self as u64);
+            }
+        }
+
+        impl Decode for webgpu::$name {
+            fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+                const CASES: &[webgpu::$name] = &[$(webgpu::$name::$case),*];
+                let index = d.varint()?;
+                CASES.get(index as usize).copied().with_context(|| {
+                    format!("{index} isn't a {} in the recording", stringify!($name))
+                })
+            }
+        }
+    )*};
+}
+
+macro_rules! variants {
+    ($($name:ident { $($index:literal => $case:ident),* $(,)? })*) => {$(
+        impl Encode for webgpu::$name {
+            fn encode(&self, e: &mut Encoder) {
+                match self {
+                    $(webgpu::$name::$case(payload) => {
+                        e.varint($index);
+                        payload.encode(e);
+                    })*
+                }
+            }
+        }
+
+        impl Decode for webgpu::$name {
+            fn decode(d: &mut Decoder) -> anyhow::Result<Self> {
+                Ok(match d.varint()? {
+                    $($index => webgpu::$name::$case(Decode::decode(d)?),)*
+                    index => anyhow::bail!("{index} isn't a {} in the recording", stringify!($name)),
+                })
+            }
+        }
+    )*};
+}
+
+records! {
+    GpuRequestAdapterOptions { power_preference, force_fallback_adapter }
+    GpuDeviceDescriptor { required_features, default_queue, label }
+    GpuQueueDescriptor { label }
+    GpuBufferDescriptor { label, size, usage, mapped_at_creation }
+    GpuTextureDescriptor {
+        size, mip_level_count, sample_count, dimension, format, usage, view_formats, label,
+    }
+    GpuExtent3DDict { width, height, depth_or_array_layers }
+    GpuSamplerDescriptor {
+        address_mode_u, address_mode_v, address_mode_w, mag_filter, min_filter, mipmap_filter,
+        lod_min_clamp, lod_max_clamp, compare, max_anisotropy, label,
+    }
+    GpuBindGroupLayoutDescriptor { entries, label }
+    GpuBindGroupLayoutEntry {
+        binding, visibility, buffer, sampler, texture, storage_texture, external_texture,
+    }
+    GpuBufferBindingLayout { type_, has_dynamic_offset, min_binding_size }
+    GpuSamplerBindingLayout { type_ }
+    GpuTextureBindingLayout { sample_type, view_dimension, multisampled }
+    GpuStorageTextureBindingLayout { access, format, view_dimension }
+    GpuExternalTextureBindingLayout { todo }
+    GpuPipelineLayoutDescriptor { bind_group_layouts, push_constant_ranges, label }
+    GpuPushConstantRange { stages, start, end }
+    GpuBindGroupDescriptor { layout, entries, label }
+    GpuBindGroupEntry { binding, resource }
+    GpuBufferBinding { buffer, offset, size }
+    GpuShaderModuleDescriptor { code, compilation_hints, label }
+    GpuShaderModuleCompilationHint { entry_point, layout }
+    GpuComputePipelineDescriptor { compute, layout, label }
+    GpuProgrammableStage { module, entry_point, constants }
+    GpuRenderPipelineDescriptor {
+        vertex, primitive, depth_stencil, multisample, fragment, layout, label, multiview,
+    }
+    GpuVertexState { buffers, module, entry_point, constants }
+    GpuVertexBufferLayout { array_stride, step_mode, attributes }
+    GpuVertexAttribute { format, offset, shader_location }
+    GpuPrimitiveState { topology, strip_index_format, front_face, cull_mode, unclipped_depth }
+    GpuDepthStencilState {
+        format, depth_write_enabled, depth_compare, stencil_front, stencil_back, stencil_read_mask,
+        stencil_write_mask, depth_bias, depth_bias_slope_scale, depth_bias_clamp,
+    }
+    GpuStencilFaceState { compare, fail_op, depth_fail_op, pass_op }
+    GpuMultisampleState { count, mask, alpha_to_coverage_enabled }
+    GpuFragmentState { targets, module, entry_point, constants }
+    GpuColorTargetState { format, blend, write_mask }
+    GpuBlendState { color, alpha }
+    GpuBlendComponent { operation, src_factor, dst_factor }
+    GpuCommandEncoderDescriptor { label }
+    GpuTextureViewDescriptor {
+        format, dimension, aspect, base_mip_level, mip_level_count, base_array_layer,
+        array_layer_count, label,
+    }
+    GpuRenderPassDescriptor {
+        color_attachments, depth_stencil_attachment, occlusion_query_set, timestamp_writes,
+        max_draw_count, label,
+    }
+    GpuRenderPassColorAttachment {
+        view, depth_slice, resolve_target, clear_value, load_op, store_op,
+    }
+    GpuColorDict { r, g, b, a }
+    GpuRenderPassDepthStencilAttachment {
+        view, depth_clear_value, depth_load_op, depth_store_op, depth_read_only,
+        stencil_clear_value, stencil_load_op, stencil_store_op, stencil_read_only,
+    }
+    GpuRenderPassTimestampWrites {
+        query_set, beginning_of_pass_write_index, end_of_pass_write_index,
+    }
+    GpuComputePassDescriptor { timestamp_writes, label }
+    GpuComputePassTimestampWrites {
+        query_set, beginning_of_pass_write_index, end_of_pass_write_index,
+    }
+    GpuCommandBufferDescriptor { label }
+    GpuImageCopyBuffer { buffer, offset, bytes_per_row, rows_per_image }
+    GpuImageCopyTexture { texture, mip_level, origin, aspect }
+    GpuOrigin3DDict { x, y, z }
+    GpuQuerySetDescriptor { type_, count, label }
+    GpuPipelineStatisticsQuerySetDescriptor { statistics, count, label }
+    GpuImageDataLayout { offset, bytes_per_row, rows_per_image }
+    GpuImageTextureDescriptor { data, srgb, usage, label }
+}
+
+enums! {
+    GpuPowerPreference { LowPower, HighPerformance }
+    GpuFeatureName {
+        DepthClipControl, Depth32floatStencil8, TextureCompressionBc, TextureCompressionEtc2,
+        TextureCompressionAstc, TimestampQuery, IndirectFirstInstance, ShaderF16,
+        Rg11b10ufloatRenderable, Bgra8unormStorage, Float32Filterable, PushConstants,
+        PipelineStatisticsQuery, Multiview, MultiDrawIndirectCount, DualSourceBlending,
+    }
+    GpuTextureDimension { OneD, TwoD, ThreeD }
+    GpuTextureFormat {
+        R8unorm, R8snorm, R8uint, R8sint, R16uint, R16sint, R16float, Rg8unorm, Rg8snorm, Rg8uint,
+        Rg8sint, R32uint, R32sint, R32float, Rg16uint, Rg16sint, Rg16float, Rgba8unorm,
+        Rgba8unormSrgb, Rgba8snorm, Rgba8uint, Rgba8sint, Bgra8unorm, Bgra8unormSrgb, Rgb9e5ufloat,
+        Rgb10a2uint, Rgb10a2unorm, Rg11b10ufloat, Rg32uint, Rg32sint, Rg32float, Rgba16uint,
+        Rgba16sint, Rgba16float, Rgba32uint, Rgba32sint, Rgba32float, Stencil8, Depth16unorm,
+        Depth24plus, Depth24plusStencil8, Depth32float, Depth32floatStencil8, Bc1RgbaUnorm,
+        Bc1RgbaUnormSrgb, Bc2RgbaUnorm, Bc2RgbaUnormSrgb, Bc3RgbaUnorm, Bc3RgbaUnormSrgb, Bc4RUnorm,
+        Bc4RSnorm, Bc5RgUnorm, Bc5RgSnorm, Bc6hRgbUfloat, Bc6hRgbFloat, Bc7RgbaUnorm,
+        Bc7RgbaUnormSrgb, Etc2Rgb8unorm, Etc2Rgb8unormSrgb, Etc2Rgb8a1unorm, Etc2Rgb8a1unormSrgb,
+        Etc2Rgba8unorm, Etc2Rgba8unormSrgb, EacR11unorm, EacR11snorm, EacRg11unorm, EacRg11snorm,
+        Astc4x4Unorm, Astc4x4UnormSrgb, Astc5x4Unorm, Astc5x4UnormSrgb, Astc5x5Unorm,
+        Astc5x5UnormSrgb, Astc6x5Unorm, Astc6x5UnormSrgb, Astc6x6Unorm, Astc6x6UnormSrgb,
+        Astc8x5Unorm, Astc8x5UnormSrgb, Astc8x6Unorm, Astc8x6UnormSrgb, Astc8x8Unorm,
+        Astc8x8UnormSrgb, Astc10x5Unorm, Astc10x5UnormSrgb, Astc10x6Unorm, Astc10x6UnormSrgb,
+        Astc10x8Unorm, Astc10x8UnormSrgb, Astc10x10Unorm, Astc10x10UnormSrgb, Astc12x10Unorm,
+        Astc12x10UnormSrgb, Astc12x12Unorm, Astc12x12UnormSrgb,
+    }
+    GpuAddressMode { ClampToEdge, Repeat, MirrorRepeat }
+    GpuFilterMode { Nearest, Linear }
+    GpuMipmapFilterMode { Nearest, Linear }
+    GpuCompareFunction { Never, Less, Equal, LessEqual, Greater, NotEqual, GreaterEqual, Always }
+    GpuBufferBindingType { Uniform, Storage, ReadOnlyStorage }
+    GpuSamplerBindingType { Filtering, NonFiltering, Comparison }
+    GpuTextureSampleType { Float, UnfilterableFloat, Depth, Sint, Uint }
+    GpuTextureViewDimension { OneD, TwoD, TwoDArray, Cube, CubeArray, ThreeD }
+    GpuStorageTextureAccess { WriteOnly, ReadOnly, ReadWrite }
+    GpuAutoLayoutMode { Auto }
+    GpuVertexStepMode { Vertex, Instance }
+    GpuVertexFormat {
+        Uint8x2, Uint8x4, Sint8x2, Sint8x4, Unorm8x2, Unorm8x4, Snorm8x2, Snorm8x4, Uint16x2,
+        Uint16x4, Sint16x2, Sint16x4, Unorm16x2, Unorm16x4, Snorm16x2, Snorm16x4, Float16x2,
+        Float16x4, Float32, Float32x2, Float32x3, Float32x4, Uint32, Uint32x2, Uint32x3, Uint32x4,
+        Sint32, Sint32x2, Sint32x3, Sint32x4, Unorm1010102,
+    }
+    GpuPrimitiveTopology { PointList, LineList, LineStrip, TriangleList, TriangleStrip }
+    GpuIndexFormat { Uint16, Uint32 }
+    GpuFrontFace { Ccw, Cw }
+    GpuCullMode { None, Front, Back }
+    GpuStencilOperation {
+        Keep, Zero, Replace, Invert, IncrementClamp, DecrementClamp, IncrementWrap, DecrementWrap,
+    }
+    GpuBlendOperation { Add, Subtract, ReverseSubtract, Min, Max }
+    GpuBlendFactor {
+        Zero, One, Src, OneMinusSrc, SrcAlpha, OneMinusSrcAlpha, Dst, OneMinusDst, DstAlpha,
+        OneMinusDstAlpha, SrcAlphaSaturated, Constant, OneMinusConstant, Src1, OneMinusSrc1,
+        Src1Alpha, OneMinusSrc1Alpha,
+    }
+    GpuTextureAspect { All, StencilOnly, DepthOnly }
+    GpuLoadOp { Load, Clear }
+    GpuStoreOp { Store, Discard }
+    GpuQueryType { Occlusion, Timestamp, PipelineStatistics }
+    GpuPipelineStatistic {
+        VertexShaderInvocations, ClipperInvocations, ClipperPrimitivesOut,
+        FragmentShaderInvocations, ComputeShaderInvocations,
+    }
+}
+
+variants! {
+    GpuExtent3DDictOrListGpuIntegerCoordinate {
+        0 => GpuExtent3DDict, 1 => ListGpuIntegerCoordinate,
+    }
+    GpuBufferBindingOrGpuExternalTextureOrGpuSamplerOrGpuTextureView {
+        0 => GpuBufferBinding, 1 => GpuExternalTexture, 2 => GpuSampler, 3 => GpuTextureView,
+    }
+    GpuAutoLayoutModeOrGpuPipelineLayout { 0 => GpuAutoLayoutMode, 1 => GpuPipelineLayout }
+    GpuPipelineLayoutOrGpuAutoLayoutMode { 0 => GpuPipelineLayout, 1 => GpuAutoLayoutMode }
+    GpuColorDictOrListF64 { 0 => GpuColorDict, 1 => ListF64 }
+    GpuOrigin3DDictOrListGpuIntegerCoordinate {
+        0 => GpuOrigin3DDict, 1 => ListGpuIntegerCoordinate,
+    }
+}
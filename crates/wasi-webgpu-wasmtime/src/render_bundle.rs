@@ -0,0 +1,223 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{ErrorScopes, GpuError};
+
+/// Wraps wgpu's encoder to also keep the commands in a form that can be compared,
+/// since wgpu-core doesn't expose what was recorded.
+pub struct RenderBundleEncoder {
+    pub(crate) encoder: wgpu_core::command::RenderBundleEncoder,
+    pub(crate) key: StaticLayerKey,
+    /// Whether the current pipeline has a strip topology but no strip index format.
+    pub(crate) strip_without_index_format: bool,
+    /// Finished encoders stay in the table until the guest drops them, since the guest still holds the handle.
+    pub(crate) finished: bool,
+    pub(crate) errors: ErrorScopes,
+}
+
+impl RenderBundleEncoder {
+    pub(crate) fn new(
+        descriptor: &wgpu_core::command::RenderBundleEncoderDescriptor,
+        device: wgpu_core::id::DeviceId,
+        errors: ErrorScopes,
+    ) -> Self {
+        let encoder = wgpu_core::command::RenderBundleEncoder::new(descriptor, device, None)
+            .unwrap_or_else(|error| {
+                errors.report(GpuError::from_core(&error));
+                // wgpu-core has no invalid bundle encoders. Bundles recorded without attachments
+                // can't be executed in any render pass, which is close enough.
+                empty_encoder(device)
+            });
+        Self {
+            encoder,
+            key: StaticLayerKey {
+                color_formats: descriptor.color_formats.to_vec(),
+                depth_stencil: descriptor.depth_stencil,
+                sample_count: descriptor.sample_count,
+                commands: Vec::new(),
+            },
+            strip_without_index_format: false,
+            finished: false,
+            errors,
+        }
+    }
+
+    /// `None` once the encoder is finished, after reporting the call as a validation error.
+    pub(crate) fn open(&mut self) -> Option<&mut Self> {
+        if self.finished {
+            self.errors.report(GpuError::validation(
+                "Render bundle encoder was already finished",
+            ));
+            return None;
+        }
+        Some(self)
+    }
+
+    /// Hands out wgpu-core's encoder to be finished, which consumes it, and marks this one finished.
+    pub(crate) fn take(&mut self) -> Option<wgpu_core::command::RenderBundleEncoder> {
+        let encoder = self.open()?;
+        encoder.finished = true;
+        let device = encoder.encoder.parent();
+        Some(std::mem::replace(
+            &mut encoder.encoder,
+            empty_encoder(device),
+        ))
+    }
+}
+
+fn empty_encoder(device: wgpu_core::id::DeviceId) -> wgpu_core::command::RenderBundleEncoder {
+    let descriptor = wgpu_core::command::RenderBundleEncoderDescriptor {
+        label: None,
+        color_formats: Cow::Borrowed(&[]),
+        depth_stencil: None,
+        sample_count: 1,
+        multiview: None,
+    };
+    wgpu_core::command::RenderBundleEncoder::new(&descriptor, device, None)
+        .expect("render bundle encoders without attachments are always valid")
+}
+
+/// An invalid bundle, for finishes refused before they reach wgpu-core.
+/// A draw without a pipeline always fails to finish, leaving an invalid id like any failed finish does.
+pub(crate) fn invalid_render_bundle(
+    instance: &wgpu_core::global::Global,
+    device: wgpu_core::id::DeviceId,
+) -> wgpu_core::id::RenderBundleId {
+    let mut encoder = empty_encoder(device);
+    wgpu_core::command::bundle_ffi::wgpu_render_bundle_draw(&mut encoder, 3, 1, 0, 0);
+    let (bundle, _) = instance.render_bundle_encoder_finish::<crate::Backend>(
+        encoder,
+        &wgpu_types::RenderBundleDescriptor { label: None },
+        None,
+    );
+    bundle
+}
+
+/// Everything that affects the contents of a baked static layer.
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub(crate) struct StaticLayerKey {
+    color_formats: Vec<Option<wgpu_types::TextureFormat>>,
+    depth_stencil: Option<wgpu_types::RenderBundleDepthStencil>,
+    sample_count: u32,
+    pub(crate) commands: Vec<BundleCommand>,
+}
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub(crate) enum BundleCommand {
+    SetBindGroup {
+        index: u32,
+        bind_group: wgpu_core::id::BindGroupId,
+        dynamic_offsets: Vec<u32>,
+    },
+    SetPipeline(wgpu_core::id::RenderPipelineId),
+    SetIndexBuffer {
+        buffer: wgpu_core::id::BufferId,
+        index_format: wgpu_types::IndexFormat,
+        offset: u64,
+        size: Option<u64>,
+    },
+    SetVertexBuffer {
+        slot: u32,
+        buffer: wgpu_core::id::BufferId,
+        offset: u64,
+        size: Option<u64>,
+    },
+    Draw {
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    },
+    DrawIndexed {
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        base_vertex: i32,
+        first_instance: u32,
+    },
+    DrawIndirect {
+        buffer: wgpu_core::id::BufferId,
+        offset: u64,
+    },
+    DrawIndexedIndirect {
+        buffer: wgpu_core::id::BufferId,
+        offset: u64,
+    },
+}
+
+/// Most bundles a device keeps baked. Baking another evicts the one that was reused the longest ago.
+pub(crate) const MAX_STATIC_LAYERS: usize = 256;
+
+/// Bundles baked for a device, by the commands they were recorded from.
+///
+/// Evicted bundles, and the ones left when the device is dropped, live on for as long as the guest holds static
+/// layers of them.
+#[derive(Clone, Default)]
+pub(crate) struct StaticLayerCache(Arc<Mutex<StaticLayers>>);
+
+#[derive(Default)]
+struct StaticLayers {
+    /// Each bundle with the value of `uses` when it was last baked or reused.
+    bundles: HashMap<StaticLayerKey, (Arc<BakedBundle>, u64)>,
+    uses: u64,
+}
+
+impl StaticLayerCache {
+    pub(crate) fn get(&self, key: &StaticLayerKey) -> Option<Arc<BakedBundle>> {
+        let mut layers = self.0.lock().unwrap();
+        layers.uses += 1;
+        let uses = layers.uses;
+        let (bundle, last_used) = layers.bundles.get_mut(key)?;
+        *last_used = uses;
+        Some(Arc::clone(bundle))
+    }
+
+    pub(crate) fn insert(&self, key: StaticLayerKey, bundle: Arc<BakedBundle>) {
+        let mut layers = self.0.lock().unwrap();
+        if layers.bundles.len() >= MAX_STATIC_LAYERS {
+            let oldest = layers
+                .bundles
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                layers.bundles.remove(&oldest);
+            }
+        }
+        layers.uses += 1;
+        let uses = layers.uses;
+        layers.bundles.insert(key, (bundle, uses));
+    }
+
+    /// Evicts every bundle, for when the device is dropped.
+    pub(crate) fn clear(&self) {
+        self.0.lock().unwrap().bundles.clear();
+    }
+}
+
+/// A bundle baked into a static layer, dropped once neither the cache nor any static layer holds it.
+pub(crate) struct BakedBundle {
+    pub(crate) bundle: wgpu_core::id::RenderBundleId,
+    instance: Arc<wgpu_core::global::Global>,
+}
+
+impl BakedBundle {
+    pub(crate) fn new(
+        instance: Arc<wgpu_core::global::Global>,
+        bundle: wgpu_core::id::RenderBundleId,
+    ) -> Self {
+        Self { bundle, instance }
+    }
+}
+
+impl Drop for BakedBundle {
+    fn drop(&mut self) {
+        self.instance
+            .render_bundle_drop::<crate::Backend>(self.bundle);
+    }
+}
+
+pub struct StaticLayer {
+    pub(crate) bundle: Arc<BakedBundle>,
+}
@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock, Weak},
+};
+
+use wasmtime::component::Resource;
+
+use crate::wasi::webgpu::shared_buffer::{self, SharedBufferError};
+use crate::{Buffer, GpuQuota, Queue, WasiWebGpuImpl, WasiWebGpuView};
+
+/// Largest shared buffer, wgpu's default max-buffer-size, so a whole buffer can be copied to the gpu.
+pub const MAX_SHARED_BUFFER_SIZE: u64 = 256 << 20;
+
+/// Memory of a shared buffer, written by any store that holds a writable handle to it.
+type SharedMemory = RwLock<Vec<u8>>;
+
+/// Buffers shared by name between the stores that were handed the same registry.
+///
+/// Only weak references are kept here, so a buffer goes away with its last `shared-buffer` handle.
+#[derive(Clone, Default)]
+pub struct SharedBuffers(Arc<Mutex<HashMap<String, Weak<SharedMemory>>>>);
+
+impl SharedBuffers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The `shared-buffer` resource. Every handle keeps the memory alive.
+pub struct SharedBuffer {
+    data: Arc<SharedMemory>,
+    writable: bool,
+    /// The quota of the store that created the buffer, charged until it drops the writable handle.
+    quota: Option<GpuQuota>,
+}
+
+impl SharedBuffer {
+    /// The range, if it's within the buffer.
+    fn range(&self, offset: u64, length: u64) -> Result<std::ops::Range<usize>, SharedBufferError> {
+        let len = self.data.read().unwrap().len() as u64;
+        match offset.checked_add(length) {
+            Some(end) if end <= len => Ok(offset as usize..end as usize),
+            _ => Err(SharedBufferError::OutOfBounds),
+        }
+    }
+}
+
+impl<T: WasiWebGpuView> shared_buffer::Host for WasiWebGpuImpl<T> {
+    fn copy_to_gpu_buffer(
+        &mut self,
+        queue: Resource<Queue>,
+        source: Resource<SharedBuffer>,
+        source_offset: u64,
+        destination: Resource<Buffer>,
+        destination_offset: u64,
+        size: u64,
+    ) -> Result<(), SharedBufferError> {
+        let queue = self.0.table().get(&queue).unwrap();
+        let (queue, errors) = (queue.queue, queue.errors.clone());
+        let destination = self.0.table().get(&destination).unwrap().buffer;
+        let source = self.0.table().get(&source).unwrap();
+        let range = source.range(source_offset, size)?;
+        // A clone of the handle, so the memory can't go away during the copy even if the guest's handle does.
+        let data = Arc::clone(&source.data);
+        let data = data.read().unwrap();
+        let instance = self.0.instance();
+        errors
+            .catch(|| {
+                errors.check(instance.queue_write_buffer::<crate::Backend>(
+                    queue,
+                    destination,
+                    destination_offset,
+                    &data[range],
+                ))
+            })
+            .map(|_| ())
+            .map_err(|error| SharedBufferError::Gpu(error.kind()))
+    }
+}
+
+impl<T: WasiWebGpuView> shared_buffer::HostSharedBuffer for WasiWebGpuImpl<T> {
+    fn create(
+        &mut self,
+        name: String,
+        size: u64,
+    ) -> Result<Resource<SharedBuffer>, SharedBufferError> {
+        let buffers = self
+            .0
+            .shared_buffers()
+            .ok_or(SharedBufferError::Unavailable)?;
+        let mut buffers = buffers.0.lock().unwrap();
+        if buffers
+            .get(&name)
+            .is_some_and(|buffer| buffer.strong_count() > 0)
+        {
+            return Err(SharedBufferError::NameTaken);
+        }
+        if size > MAX_SHARED_BUFFER_SIZE {
+            return Err(SharedBufferError::TooLarge);
+        }
+        let quota = self.0.quota();
+        if let Some(quota) = &quota {
+            quota
+                .check_buffer(size)
+                .map_err(|error| SharedBufferError::Gpu(error.kind()))?;
+            quota.charge_buffer_bytes(size);
+        }
+        let data = Arc::new(RwLock::new(vec![0; size as usize]));
+        buffers.insert(name, Arc::downgrade(&data));
+        Ok(self
+            .0
+            .table()
+            .push(SharedBuffer {
+                data,
+                writable: true,
+                quota,
+            })
+            .unwrap())
+    }
+
+    fn open(&mut self, name: String) -> Result<Resource<SharedBuffer>, SharedBufferError> {
+        let buffers = self
+            .0
+            .shared_buffers()
+            .ok_or(SharedBufferError::Unavailable)?;
+        let data = buffers
+            .0
+            .lock()
+            .unwrap()
+            .get(&name)
+            .and_then(Weak::upgrade)
+            .ok_or(SharedBufferError::NotFound)?;
+        Ok(self
+            .0
+            .table()
+            .push(SharedBuffer {
+                data,
+                writable: false,
+                quota: None,
+            })
+            .unwrap())
+    }
+
+    fn size(&mut self, buffer: Resource<SharedBuffer>) -> u64 {
+        let buffer = self.0.table().get(&buffer).unwrap();
+        let len = buffer.data.read().unwrap().len();
+        len as u64
+    }
+
+    fn write(
+        &mut self,
+        buffer: Resource<SharedBuffer>,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> Result<(), SharedBufferError> {
+        let buffer = self.0.table().get(&buffer).unwrap();
+        if !buffer.writable {
+            return Err(SharedBufferError::ReadOnly);
+        }
+        let range = buffer.range(offset, data.len() as u64)?;
+        buffer.data.write().unwrap()[range].copy_from_slice(&data);
+        Ok(())
+    }
+
+    fn read(
+        &mut self,
+        buffer: Resource<SharedBuffer>,
+        offset: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, SharedBufferError> {
+        let buffer = self.0.table().get(&buffer).unwrap();
+        let range = buffer.range(offset, length)?;
+        let data = buffer.data.read().unwrap()[range].to_vec();
+        Ok(data)
+    }
+
+    fn drop(&mut self, buffer: Resource<SharedBuffer>) -> wasmtime::Result<()> {
+        let buffer = self.0.table().delete(buffer)?;
+        if let Some(quota) = buffer.quota {
+            let len = buffer.data.read().unwrap().len();
+            quota.release_buffer_bytes(len as u64);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHost;
+    use crate::wasi::webgpu::shared_buffer::HostSharedBuffer;
+    use crate::wasi::webgpu::webgpu::GpuErrorKind;
+    use crate::{GpuQuotaLimits, GpuQuotaUsage};
+
+    fn host() -> WasiWebGpuImpl<TestHost> {
+        WasiWebGpuImpl(TestHost::new(Arc::new(
+            crate::WebGpuInstanceBuilder::new().build(),
+        )))
+    }
+
+    #[test]
+    fn buffers_over_the_limit_are_refused() {
+        let mut host = host();
+        let error = HostSharedBuffer::create(&mut host, "big".into(), MAX_SHARED_BUFFER_SIZE + 1);
+        assert!(matches!(error, Err(SharedBufferError::TooLarge)));
+    }
+
+    #[test]
+    fn buffers_are_charged_to_the_creator_until_it_drops_them() {
+        let mut host = host();
+        let quota = GpuQuota::new(GpuQuotaLimits {
+            max_buffer_bytes: Some(16),
+            ..Default::default()
+        });
+        host.0.quota = Some(quota.clone());
+
+        let error = HostSharedBuffer::create(&mut host, "big".into(), 32);
+        assert!(matches!(
+            error,
+            Err(SharedBufferError::Gpu(GpuErrorKind::OutOfMemory(_)))
+        ));
+        let buffer = HostSharedBuffer::create(&mut host, "small".into(), 16).unwrap();
+        assert_eq!(quota.usage().buffer_bytes, 16);
+        // Readers aren't charged.
+        let reader = HostSharedBuffer::open(&mut host, "small".into()).unwrap();
+        HostSharedBuffer::drop(&mut host, reader).unwrap();
+        assert_eq!(quota.usage().buffer_bytes, 16);
+        HostSharedBuffer::drop(&mut host, buffer).unwrap();
+        assert_eq!(quota.usage(), GpuQuotaUsage::default());
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use futures::future::{self, Either};
+
+/// Runs closures on the ui thread. See [`WasiWebGpuView::ui_thread_spawner`](crate::WasiWebGpuView::ui_thread_spawner).
+///
+/// The only thing this crate hops to the ui thread for is surface creation, when a graphics context is connected to a
+/// canvas. Everything else, including device creation, rendering and presenting, runs on the calling thread.
+///
+/// Whether surface creation actually needs the ui thread depends on the platform:
+/// - macOS and iOS: yes, the `CAMetalLayer` has to be created on the main thread.
+/// - Windows, Linux (X11 and Wayland) and Android: no, [`InlineSpawner`] is fine as long as the window outlives the surface.
+/// - Headless and offscreen canvases never create a surface, so [`InlineSpawner`] is always fine.
+pub trait MainThreadSpawner: Send + Sync + 'static {
+    fn spawn<F, T>(&self, f: F) -> impl Future<Output = T>
+    where
+        F: FnOnce() -> T + Send + Sync + 'static,
+        T: Send + Sync + 'static;
+
+    /// Like [`spawn`](Self::spawn), but gives up once `timeout` passed, e.g. because the ui thread is stuck on a long
+    /// event. `f` may still run after that, its result is dropped then.
+    ///
+    /// The default times out from a thread of its own, so it works with any executor.
+    fn spawn_with_timeout<F, T>(
+        &self,
+        f: F,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<T, SpawnError>>
+    where
+        F: FnOnce() -> T + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        let task = self.spawn(f);
+        let (sender, receiver) = futures::channel::oneshot::channel::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout);
+            let _ = sender.send(());
+        });
+        async move {
+            match future::select(pin!(task), receiver).await {
+                Either::Left((output, _)) => Ok(output),
+                Either::Right(_) => Err(SpawnError::Timeout(timeout)),
+            }
+        }
+    }
+}
+
+/// Why [`MainThreadSpawner::spawn_with_timeout`] has no result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnError {
+    /// The ui thread didn't run the closure within the timeout.
+    Timeout(Duration),
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout(timeout) => write!(f, "ui thread didn't respond within {timeout:?}"),
+        }
+    }
+}
+
+impl std::error::Error for SpawnError {}
+
+pub type MainThreadTask = Box<dyn FnOnce() + Send>;
+
+/// Object safe form of [`MainThreadSpawner`]. `Arc<dyn DynMainThreadSpawner>` implements [`MainThreadSpawner`].
+pub trait DynMainThreadSpawner: Send + Sync + 'static {
+    /// Runs `task` on the ui thread, eventually.
+    fn spawn_task(&self, task: MainThreadTask);
+}
+
+impl MainThreadSpawner for Arc<dyn DynMainThreadSpawner> {
+    fn spawn<F, T>(&self, f: F) -> impl Future<Output = T>
+    where
+        F: FnOnce() -> T + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        spawn_task(&**self, f)
+    }
+}
+
+fn spawn_task<F, T>(spawner: &dyn DynMainThreadSpawner, f: F) -> impl Future<Output = T>
+where
+    F: FnOnce() -> T + Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    spawner.spawn_task(Box::new(move || {
+        let _ = sender.send(f());
+    }));
+    async move {
+        receiver
+            .await
+            .expect("main thread task was dropped without running")
+    }
+}
+
+/// Runs closures in place, on the calling thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InlineSpawner;
+
+impl MainThreadSpawner for InlineSpawner {
+    async fn spawn<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        f()
+    }
+}
+
+impl DynMainThreadSpawner for InlineSpawner {
+    fn spawn_task(&self, task: MainThreadTask) {
+        task()
+    }
+}
+
+/// Hands closures to an event loop owned by the embedder.
+#[derive(Clone)]
+pub struct ChannelSpawner {
+    send: Arc<dyn Fn(MainThreadTask) + Send + Sync>,
+}
+
+impl ChannelSpawner {
+    /// `send` has to get the task to the ui thread and run it there, e.g. by posting a user event to the app's event loop.
+    pub fn new(send: impl Fn(MainThreadTask) + Send + Sync + 'static) -> Self {
+        Self {
+            send: Arc::new(send),
+        }
+    }
+
+    /// A spawner backed by a channel. Call [`MainThreadPump::pump`] regularly from the ui thread.
+    ///
+    /// `wake` is called after each task is queued, to get a sleeping event loop to pump.
+    pub fn channel(wake: impl Fn() + Send + Sync + 'static) -> (Self, MainThreadPump) {
+        let (sender, receiver) = mpsc::channel::<MainThreadTask>();
+        let spawner = Self::new(move |task| {
+            // Nothing to do if the pump is gone, the task's future just never resolves.
+            if sender.send(task).is_ok() {
+                wake();
+            }
+        });
+        (spawner, MainThreadPump { receiver })
+    }
+}
+
+impl MainThreadSpawner for ChannelSpawner {
+    fn spawn<F, T>(&self, f: F) -> impl Future<Output = T>
+    where
+        F: FnOnce() -> T + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        spawn_task(self, f)
+    }
+}
+
+impl DynMainThreadSpawner for ChannelSpawner {
+    fn spawn_task(&self, task: MainThreadTask) {
+        (self.send)(task)
+    }
+}
+
+/// Ui thread end of [`ChannelSpawner::channel`].
+pub struct MainThreadPump {
+    receiver: mpsc::Receiver<MainThreadTask>,
+}
+
+impl MainThreadPump {
+    /// Runs all queued tasks. Returns the number of tasks run.
+    pub fn pump(&self) -> usize {
+        let mut count = 0;
+        while let Ok(task) = self.receiver.try_recv() {
+            task();
+            count += 1;
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn spawns_time_out_while_the_ui_thread_is_blocked() {
+        // The pump never runs, like on a ui thread stuck handling a long event.
+        let (spawner, _pump) = ChannelSpawner::channel(|| {});
+        let timeout = Duration::from_millis(10);
+        assert_eq!(
+            block_on(spawner.spawn_with_timeout(|| 1, timeout)),
+            Err(SpawnError::Timeout(timeout))
+        );
+    }
+
+    #[test]
+    fn spawns_run_before_the_timeout() {
+        assert_eq!(
+            block_on(InlineSpawner.spawn_with_timeout(|| 1, Duration::from_secs(10))),
+            Ok(1)
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Makes webgpu calls the way a guest would, for tests that need a device.
+//!
+//! Tests run on whatever adapter the machine has, and skip themselves where there's none, e.g. on CI runners without
+//! a software Vulkan driver.
+
+use std::sync::Arc;
+
+use futures::executor::block_on;
+use wasmtime::component::{Resource, ResourceTable};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
+
+use crate::error::GpuError;
+use crate::wasi::webgpu::webgpu;
+use crate::webgpu::{
+    HostGpu, HostGpuAdapter, HostGpuBuffer, HostGpuCommandEncoder, HostGpuDevice, HostGpuQueue,
+    HostGpuTexture,
+};
+use crate::{
+    GpuQuota, InlineSpawner, Labels, MainThreadSpawner, SharedBuffers, WasiWebGpuImpl,
+    WasiWebGpuView,
+};
+
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+const MAP_MODE_READ: u32 = 0x0001;
+
+pub(crate) struct TestHost {
+    table: ResourceTable,
+    ctx: WasiCtx,
+    instance: Arc<wgpu_core::global::Global>,
+    labels: Labels,
+    pub quota: Option<GpuQuota>,
+    pub shared_buffers: SharedBuffers,
+}
+
+impl TestHost {
+    pub fn new(instance: Arc<wgpu_core::global::Global>) -> Self {
+        Self {
+            table: ResourceTable::new(),
+            ctx: WasiCtxBuilder::new().build(),
+            instance,
+            labels: Labels::new(),
+            quota: None,
+            shared_buffers: SharedBuffers::new(),
+        }
+    }
+}
+
+impl WasiView for TestHost {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.ctx
+    }
+}
+
+impl WasiWebGpuView for TestHost {
+    fn instance(&self) -> Arc<wgpu_core::global::Global> {
+        Arc::clone(&self.instance)
+    }
+
+    fn ui_thread_spawner(&self) -> Box<impl MainThreadSpawner + 'static> {
+        Box::new(InlineSpawner)
+    }
+
+    fn labels(&self) -> Option<Labels> {
+        Some(self.labels.clone())
+    }
+
+    fn quota(&self) -> Option<GpuQuota> {
+        self.quota.clone()
+    }
+
+    fn shared_buffers(&self) -> Option<SharedBuffers> {
+        Some(self.shared_buffers.clone())
+    }
+}
+
+/// A device and its queue, with a validation error scope pushed for [`TestGpu::assert_no_errors`].
+pub(crate) struct TestGpu {
+    pub host: WasiWebGpuImpl<TestHost>,
+    pub device: Resource<webgpu::GpuDevice>,
+    pub queue: Resource<webgpu::GpuQueue>,
+}
+
+impl TestGpu {
+    /// `None` where the adapter doesn't support `features`. Panics without an adapter, so tests using it are
+    /// `#[ignore]`d, for `cargo test -- --ignored` to run them on machines with a gpu.
+    pub fn new(features: &[webgpu::GpuFeatureName]) -> Option<Self> {
+        Self::with_instance(
+            Arc::new(crate::WebGpuInstanceBuilder::new().build()),
+            features,
+        )
+    }
+
+    pub fn with_instance(
+        instance: Arc<wgpu_core::global::Global>,
+        features: &[webgpu::GpuFeatureName],
+    ) -> Option<Self> {
+        let mut host = WasiWebGpuImpl(TestHost::new(instance));
+        let gpu = crate::webgpu::Host::get_gpu(&mut host);
+        let adapter = host
+            .request_adapter(gpu, None)
+            .unwrap()
+            .expect("No adapter, gpu tests need one");
+        let adapter_id = *host.0.table().get(&adapter).unwrap();
+        let supported = host
+            .0
+            .instance
+            .adapter_features::<crate::Backend>(adapter_id)
+            .unwrap();
+        let required = features
+            .iter()
+            .fold(wgpu_types::Features::empty(), |required, &feature| {
+                required | feature.into()
+            });
+        if !supported.contains(required) {
+            eprintln!("The adapter doesn't support {features:?}, skipping");
+            return None;
+        }
+
+        let device = host
+            .request_device(
+                borrow(&adapter),
+                Some(webgpu::GpuDeviceDescriptor {
+                    required_features: Some(features.to_vec()),
+                    default_queue: None,
+                    label: None,
+                }),
+            )
+            .unwrap();
+        let queue = HostGpuDevice::queue(&mut host, borrow(&device));
+        host.push_error_scope(borrow(&device), webgpu::GpuErrorFilter::Validation);
+        Some(Self {
+            host,
+            device,
+            queue,
+        })
+    }
+
+    /// Pops the scope pushed on creation, failing on any validation error reported since.
+    pub fn assert_no_errors(&mut self) {
+        if let Some(error) = self.pop_error() {
+            panic!("{error:?}");
+        }
+    }
+
+    /// The first validation error since the last check, if any. Later errors are caught by a new scope.
+    pub fn pop_error(&mut self) -> Option<GpuError> {
+        let errors = self
+            .host
+            .0
+            .table()
+            .get(&self.device)
+            .unwrap()
+            .errors
+            .clone();
+        let error = errors.pop().unwrap();
+        self.host
+            .push_error_scope(borrow(&self.device), webgpu::GpuErrorFilter::Validation);
+        error
+    }
+
+    pub fn texture(
+        &mut self,
+        format: webgpu::GpuTextureFormat,
+        (width, height): (u32, u32),
+        usage: wgpu_types::TextureUsages,
+    ) -> Resource<webgpu::GpuTexture> {
+        self.host.create_texture(
+            borrow(&self.device),
+            webgpu::GpuTextureDescriptor {
+                size: extent(width, height),
+                mip_level_count: None,
+                sample_count: None,
+                dimension: webgpu::GpuTextureDimension::TwoD,
+                format,
+                usage: usage.bits(),
+                view_formats: None,
+                label: None,
+            },
+        )
+    }
+
+    pub fn view(
+        &mut self,
+        texture: &Resource<webgpu::GpuTexture>,
+    ) -> Resource<webgpu::GpuTextureView> {
+        self.host.create_view(borrow(texture), None)
+    }
+
+    pub fn buffer(
+        &mut self,
+        size: u64,
+        usage: wgpu_types::BufferUsages,
+    ) -> Resource<webgpu::GpuBuffer> {
+        self.host.create_buffer(
+            borrow(&self.device),
+            webgpu::GpuBufferDescriptor {
+                label: None,
+                size,
+                usage: usage.bits(),
+                mapped_at_creation: None,
+            },
+        )
+    }
+
+    pub fn shader(&mut self, code: &str) -> Resource<webgpu::GpuShaderModule> {
+        self.host.create_shader_module(
+            borrow(&self.device),
+            webgpu::GpuShaderModuleDescriptor {
+                code: code.to_string(),
+                compilation_hints: None,
+                label: None,
+            },
+        )
+    }
+
+    /// Draws with `vs_main` and `fragment_entry_point` of `shader` into a single target, with an auto layout.
+    pub fn render_pipeline(
+        &mut self,
+        shader: &Resource<webgpu::GpuShaderModule>,
+        fragment_entry_point: &str,
+        target: webgpu::GpuColorTargetState,
+        depth_stencil: Option<webgpu::GpuDepthStencilState>,
+    ) -> Resource<webgpu::GpuRenderPipeline> {
+        self.host.create_render_pipeline(
+            borrow(&self.device),
+            webgpu::GpuRenderPipelineDescriptor {
+                vertex: webgpu::GpuVertexState {
+                    buffers: None,
+                    module: borrow(shader),
+                    entry_point: "vs_main".to_string(),
+                    constants: None,
+                },
+                primitive: None,
+                depth_stencil,
+                multisample: None,
+                fragment: Some(webgpu::GpuFragmentState {
+                    targets: vec![Some(target)],
+                    module: borrow(shader),
+                    entry_point: fragment_entry_point.to_string(),
+                    constants: None,
+                }),
+                layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+                    webgpu::GpuAutoLayoutMode::Auto,
+                ),
+                label: None,
+                multiview: None,
+            },
+        )
+    }
+
+    pub fn encoder(&mut self) -> Resource<webgpu::GpuCommandEncoder> {
+        self.host.create_command_encoder(borrow(&self.device), None)
+    }
+
+    /// Begins a pass drawing into `view`, cleared to `clear` or loaded if there's none.
+    pub fn render_pass(
+        &mut self,
+        encoder: &Resource<webgpu::GpuCommandEncoder>,
+        view: &Resource<webgpu::GpuTextureView>,
+        clear: Option<[f64; 4]>,
+        depth_stencil: Option<webgpu::GpuRenderPassDepthStencilAttachment>,
+    ) -> Resource<webgpu::GpuRenderPassEncoder> {
+        self.host.begin_render_pass(
+            borrow(encoder),
+            webgpu::GpuRenderPassDescriptor {
+                color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                    view: borrow(view),
+                    depth_slice: None,
+                    resolve_target: None,
+                    clear_value: clear.map(|[r, g, b, a]| {
+                        webgpu::GpuColor::GpuColorDict(webgpu::GpuColorDict { r, g, b, a })
+                    }),
+                    load_op: match clear {
+                        Some(_) => webgpu::GpuLoadOp::Clear,
+                        None => webgpu::GpuLoadOp::Load,
+                    },
+                    store_op: webgpu::GpuStoreOp::Store,
+                }],
+                depth_stencil_attachment: depth_stencil,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+                max_draw_count: None,
+                label: None,
+            },
+        )
+    }
+
+    pub fn submit(&mut self, encoder: Resource<webgpu::GpuCommandEncoder>) {
+        let command_buffer = HostGpuCommandEncoder::finish(&mut self.host, encoder, None);
+        self.host.submit(borrow(&self.queue), vec![command_buffer]);
+    }
+
+    /// Copies a 2d texture of 4 byte texels out of the gpu, row by row without padding.
+    pub fn read_texture(
+        &mut self,
+        texture: &Resource<webgpu::GpuTexture>,
+        (width, height): (u32, u32),
+    ) -> Vec<u8> {
+        let row_size = width * 4;
+        let bytes_per_row = row_size.next_multiple_of(wgpu_types::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = self.buffer(
+            (bytes_per_row * height) as u64,
+            wgpu_types::BufferUsages::MAP_READ | wgpu_types::BufferUsages::COPY_DST,
+        );
+        let encoder = self.encoder();
+        self.host.copy_texture_to_buffer(
+            borrow(&encoder),
+            webgpu::GpuImageCopyTexture {
+                texture: borrow(texture),
+                mip_level: None,
+                origin: None,
+                aspect: None,
+            },
+            webgpu::GpuImageCopyBuffer {
+                buffer: borrow(&buffer),
+                offset: None,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+            extent(width, height),
+        );
+        self.submit(encoder);
+        self.read_buffer(&buffer)
+            .chunks(bytes_per_row as usize)
+            .flat_map(|row| &row[..row_size as usize])
+            .copied()
+            .collect()
+    }
+
+    /// Maps a buffer with `map-read` usage and copies out its contents.
+    pub fn read_buffer(&mut self, buffer: &Resource<webgpu::GpuBuffer>) -> Vec<u8> {
+        block_on(
+            self.host
+                .map_async(borrow(buffer), MAP_MODE_READ, None, None),
+        );
+        self.host
+            .get_mapped_range(borrow(buffer), None, None)
+            .unwrap();
+        let data = self
+            .host
+            .0
+            .table()
+            .get(buffer)
+            .unwrap()
+            .mapped
+            .as_ref()
+            .unwrap()
+            .slice()
+            .to_vec();
+        self.host.unmap(borrow(buffer));
+        data
+    }
+}
+
+/// A `borrow<T>` handle to hand to the host, like the ones guests pass.
+pub(crate) fn borrow<T: 'static>(resource: &Resource<T>) -> Resource<T> {
+    Resource::new_borrow(resource.rep())
+}
+
+pub(crate) fn extent(width: u32, height: u32) -> webgpu::GpuExtent3D {
+    webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+        width,
+        height: Some(height),
+        depth_or_array_layers: None,
+    })
+}
@@ -0,0 +1,364 @@
+use std::sync::Arc;
+
+use wasmtime::component::Resource;
+
+use crate::wasi::webgpu::{texture_atlas, webgpu};
+use crate::{Device, ObservedResource, Texture, WasiWebGpuImpl, WasiWebGpuView};
+
+/// Size atlases start out with, unless their maximum is smaller.
+const INITIAL_SIZE: u32 = 256;
+
+/// wgpu-core's texture of an atlas, dropped once the atlas has moved on from it and no texture from
+/// `get-atlas-texture` is left, as using a dropped id panics instead of reporting an error.
+pub(crate) struct AtlasTexture {
+    instance: Arc<wgpu_core::global::Global>,
+    texture: wgpu_core::id::TextureId,
+}
+
+impl Drop for AtlasTexture {
+    fn drop(&mut self) {
+        self.instance
+            .texture_drop::<crate::Backend>(self.texture, false);
+    }
+}
+
+/// The `texture-atlas` resource.
+pub struct TextureAtlas {
+    texture: Texture,
+    device: Device,
+    max_size: u32,
+    packer: ShelfPacker,
+    /// x, y, width and height in pixels, in packing order.
+    regions: Vec<[u32; 4]>,
+}
+
+impl TextureAtlas {
+    fn region(&self, [x, y, width, height]: [u32; 4]) -> texture_atlas::AtlasRegion {
+        let size = self.packer.size as f32;
+        texture_atlas::AtlasRegion {
+            u: x as f32 / size,
+            v: y as f32 / size,
+            w: width as f32 / size,
+            h: height as f32 / size,
+        }
+    }
+}
+
+/// A row of images of at most `height` pixels, filled from left to right.
+struct Shelf {
+    y: u32,
+    height: u32,
+    x: u32,
+}
+
+/// Shelf packing: images go onto the lowest shelf they fit on, or onto a new shelf below the last one.
+/// Growing the square just makes room to the right of the shelves and below them, so packed images never move.
+struct ShelfPacker {
+    size: u32,
+    shelves: Vec<Shelf>,
+}
+
+impl ShelfPacker {
+    fn pack(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let size = self.size;
+        if width > size || height > size {
+            return None;
+        }
+        if let Some(shelf) = self
+            .shelves
+            .iter_mut()
+            .filter(|shelf| height <= shelf.height && shelf.x + width <= size)
+            .min_by_key(|shelf| shelf.height)
+        {
+            let position = (shelf.x, shelf.y);
+            shelf.x += width;
+            return Some(position);
+        }
+        let y = self
+            .shelves
+            .last()
+            .map_or(0, |shelf| shelf.y + shelf.height);
+        if y + height > size {
+            return None;
+        }
+        self.shelves.push(Shelf {
+            y,
+            height,
+            x: width,
+        });
+        Some((0, y))
+    }
+}
+
+fn create_atlas_texture(
+    instance: &Arc<wgpu_core::global::Global>,
+    device: &Device,
+    format: wgpu_types::TextureFormat,
+    size: u32,
+) -> Texture {
+    let size = wgpu_types::Extent3d {
+        width: size,
+        height: size,
+        depth_or_array_layers: 1,
+    };
+    let descriptor = wgpu_types::TextureDescriptor {
+        label: Some("texture atlas".into()),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu_types::TextureDimension::D2,
+        format,
+        usage: wgpu_types::TextureUsages::TEXTURE_BINDING
+            | wgpu_types::TextureUsages::COPY_SRC
+            | wgpu_types::TextureUsages::COPY_DST,
+        view_formats: vec![],
+    };
+    let texture = device
+        .errors
+        .handle(instance.device_create_texture::<crate::Backend>(device.device, &descriptor, None));
+    Texture {
+        texture,
+        size,
+        mip_level_count: descriptor.mip_level_count,
+        sample_count: descriptor.sample_count,
+        dimension: descriptor.dimension,
+        format,
+        usage: descriptor.usage,
+        device: device.device,
+        queue: device.queue,
+        errors: device.errors.clone(),
+        owned: false,
+        _atlas_texture: Some(Arc::new(AtlasTexture {
+            instance: Arc::clone(instance),
+            texture,
+        })),
+    }
+}
+
+/// Moves the atlas to a texture of `size` pixels, copying over everything packed so far.
+///
+/// The regions of packed images are in pixels and keep working, but normalized coordinates handed out before, and
+/// textures from `get-atlas-texture`, are of the old texture. Those textures are destroyed here, and wgpu-core's
+/// texture is dropped with the last of them.
+fn grow(instance: &Arc<wgpu_core::global::Global>, atlas: &mut TextureAtlas, size: u32) {
+    let texture = create_atlas_texture(instance, &atlas.device, atlas.texture.format, size);
+    let errors = &texture.errors;
+    let encoder = errors.handle(instance.device_create_command_encoder::<crate::Backend>(
+        texture.device,
+        &Default::default(),
+        None,
+    ));
+    errors.check(
+        instance.command_encoder_copy_texture_to_texture::<crate::Backend>(
+            encoder,
+            &wgpu_types::ImageCopyTexture {
+                texture: atlas.texture.texture,
+                mip_level: 0,
+                origin: wgpu_types::Origin3d::ZERO,
+                aspect: wgpu_types::TextureAspect::All,
+            },
+            &wgpu_types::ImageCopyTexture {
+                texture: texture.texture,
+                mip_level: 0,
+                origin: wgpu_types::Origin3d::ZERO,
+                aspect: wgpu_types::TextureAspect::All,
+            },
+            &atlas.texture.size,
+        ),
+    );
+    let command_buffer = errors
+        .handle(instance.command_encoder_finish::<crate::Backend>(encoder, &Default::default()));
+    errors.check(instance.queue_submit::<crate::Backend>(texture.queue, &[command_buffer]));
+    // wgpu-core waits for the copy before freeing the memory. The id stays registered until the guest drops the old
+    // textures it got from `get-atlas-texture`, so using them reports an error instead of panicking the host.
+    // Destroying only fails for a texture that failed to create, which was reported back then.
+    let _ = instance.texture_destroy::<crate::Backend>(atlas.texture.texture);
+    atlas.texture = texture;
+    atlas.packer.size = size;
+}
+
+impl<T: WasiWebGpuView> texture_atlas::Host for WasiWebGpuImpl<T> {
+    fn create_texture_atlas(
+        &mut self,
+        device: Resource<Device>,
+        max_size: u32,
+        format: webgpu::GpuTextureFormat,
+    ) -> Resource<TextureAtlas> {
+        let device = self.0.table().get(&device).unwrap().clone();
+        let instance = self.0.instance();
+        let limit = instance
+            .device_limits::<crate::Backend>(device.device)
+            .unwrap()
+            .max_texture_dimension_2d;
+        let max_size = max_size.clamp(1, limit);
+        let size = INITIAL_SIZE.min(max_size);
+        let texture = create_atlas_texture(&instance, &device, format.into(), size);
+        self.0
+            .table()
+            .push(TextureAtlas {
+                texture,
+                device,
+                max_size,
+                packer: ShelfPacker {
+                    size,
+                    shelves: vec![],
+                },
+                regions: vec![],
+            })
+            .unwrap()
+    }
+
+    fn pack_image(
+        &mut self,
+        atlas: Resource<TextureAtlas>,
+        rgba: Vec<u8>,
+        width: u32,
+        height: u32,
+    ) -> Result<texture_atlas::AtlasRegion, texture_atlas::PackError> {
+        let instance = self.0.instance();
+        let atlas = self.0.table().get_mut(&atlas).unwrap();
+        let errors = atlas.device.errors.clone();
+        let Some(bgra) = crate::read_back_bgra(atlas.texture.format) else {
+            return Err(texture_atlas::PackError::UnsupportedFormat);
+        };
+        if rgba.len() as u64 != width as u64 * height as u64 * 4 {
+            return Err(texture_atlas::PackError::InvalidData);
+        }
+
+        let (x, y) = loop {
+            if let Some(position) = atlas.packer.pack(width, height) {
+                break position;
+            }
+            if atlas.packer.size == atlas.max_size {
+                return Err(texture_atlas::PackError::AtlasFull);
+            }
+            let size = atlas.packer.size.saturating_mul(2).min(atlas.max_size);
+            errors
+                .catch(|| grow(&instance, atlas, size))
+                .map_err(|error| texture_atlas::PackError::Gpu(error.kind()))?;
+        };
+
+        let data = if bgra {
+            rgba.chunks_exact(4)
+                .flat_map(|p| [p[2], p[1], p[0], p[3]])
+                .collect()
+        } else {
+            rgba
+        };
+        let texture = &atlas.texture;
+        errors
+            .catch(|| {
+                errors.check(instance.queue_write_texture::<crate::Backend>(
+                    texture.queue,
+                    &wgpu_types::ImageCopyTexture {
+                        texture: texture.texture,
+                        mip_level: 0,
+                        origin: wgpu_types::Origin3d { x, y, z: 0 },
+                        aspect: wgpu_types::TextureAspect::All,
+                    },
+                    &data,
+                    &wgpu_types::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(width * 4),
+                        rows_per_image: None,
+                    },
+                    &wgpu_types::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                ))
+            })
+            .map_err(|error| texture_atlas::PackError::Gpu(error.kind()))?;
+        atlas.regions.push([x, y, width, height]);
+        Ok(atlas.region([x, y, width, height]))
+    }
+
+    fn get_region(
+        &mut self,
+        atlas: Resource<TextureAtlas>,
+        index: u32,
+    ) -> Option<texture_atlas::AtlasRegion> {
+        let atlas = self.0.table().get(&atlas).unwrap();
+        let region = *atlas.regions.get(index as usize)?;
+        Some(atlas.region(region))
+    }
+
+    fn get_atlas_texture(&mut self, atlas: Resource<TextureAtlas>) -> Resource<Texture> {
+        // Shares the atlas's texture, which is dropped with the last of them.
+        let texture = self.0.table().get(&atlas).unwrap().texture.clone();
+        self.observe_created(ObservedResource::Texture);
+        self.0.table().push(texture).unwrap()
+    }
+}
+
+impl<T: WasiWebGpuView> texture_atlas::HostTextureAtlas for WasiWebGpuImpl<T> {
+    fn drop(&mut self, atlas: Resource<TextureAtlas>) -> wasmtime::Result<()> {
+        // Drops wgpu-core's texture, unless the guest still holds textures from `get-atlas-texture`.
+        self.0.table().delete(atlas)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packer(size: u32) -> ShelfPacker {
+        ShelfPacker {
+            size,
+            shelves: vec![],
+        }
+    }
+
+    #[test]
+    fn images_fill_shelves_from_left_to_right() {
+        let mut packer = packer(16);
+        assert_eq!(packer.pack(4, 4), Some((0, 0)));
+        assert_eq!(packer.pack(4, 4), Some((4, 0)));
+        // Lower images still go onto the shelf.
+        assert_eq!(packer.pack(8, 2), Some((8, 0)));
+        // The shelf is full.
+        assert_eq!(packer.pack(1, 1), Some((0, 4)));
+    }
+
+    #[test]
+    fn taller_images_open_a_new_shelf_below() {
+        let mut packer = packer(16);
+        assert_eq!(packer.pack(4, 4), Some((0, 0)));
+        assert_eq!(packer.pack(4, 8), Some((0, 4)));
+        assert_eq!(packer.pack(4, 2), Some((4, 0)));
+    }
+
+    #[test]
+    fn images_go_onto_the_lowest_shelf_they_fit() {
+        let mut packer = packer(16);
+        assert_eq!(packer.pack(4, 8), Some((0, 0)));
+        // Too wide for what's left of the first shelf.
+        assert_eq!(packer.pack(13, 4), Some((0, 8)));
+        // Fits on both shelves, and the 4 pixel one wastes less.
+        assert_eq!(packer.pack(2, 3), Some((13, 8)));
+    }
+
+    #[test]
+    fn images_that_dont_fit_are_rejected() {
+        let mut packer = packer(8);
+        assert_eq!(packer.pack(9, 1), None);
+        assert_eq!(packer.pack(1, 9), None);
+        assert_eq!(packer.pack(8, 6), Some((0, 0)));
+        assert_eq!(packer.pack(1, 3), None);
+        assert_eq!(packer.pack(8, 2), Some((0, 6)));
+        assert_eq!(packer.pack(1, 1), None);
+    }
+
+    #[test]
+    fn growing_makes_room_without_moving_images() {
+        let mut packer = packer(8);
+        assert_eq!(packer.pack(8, 8), Some((0, 0)));
+        assert_eq!(packer.pack(4, 4), None);
+        packer.size = 16;
+        // To the right of the shelf, then below it.
+        assert_eq!(packer.pack(4, 4), Some((8, 0)));
+        assert_eq!(packer.pack(16, 4), Some((0, 8)));
+    }
+}
@@ -1,3 +1,5 @@
+use std::num::{NonZeroU32, NonZeroU64};
+
 use wasmtime::component::ResourceTable;
 
 use crate::wasi::webgpu::webgpu;
@@ -15,15 +17,34 @@ where
     }
 }
 
+impl ToCore<wgpu_core::id::TextureViewId> for wasmtime::component::Resource<crate::TextureView> {
+    fn to_core(self, table: &ResourceTable) -> wgpu_core::id::TextureViewId {
+        table.get(&self).unwrap().view
+    }
+}
+
+impl ToCore<wgpu_core::id::QuerySetId> for wasmtime::component::Resource<crate::QuerySet> {
+    fn to_core(self, table: &ResourceTable) -> wgpu_core::id::QuerySetId {
+        table.get(&self).unwrap().query_set
+    }
+}
+
 impl ToCore<wgpu_types::Extent3d> for webgpu::GpuExtent3D {
     fn to_core(self, _table: &ResourceTable) -> wgpu_types::Extent3d {
         match self {
+            // source: https://www.w3.org/TR/webgpu/#dictdef-gpuextent3ddict
             webgpu::GpuExtent3D::GpuExtent3DDict(extent_dict) => wgpu_types::Extent3d {
                 width: extent_dict.width,
-                height: extent_dict.height.unwrap(),
-                depth_or_array_layers: extent_dict.depth_or_array_layers.unwrap(),
+                height: extent_dict.height.unwrap_or(1),
+                depth_or_array_layers: extent_dict.depth_or_array_layers.unwrap_or(1),
+            },
+            // source: https://www.w3.org/TR/webgpu/#gpuextent3d-shape
+            // Lists without 1 to 3 coordinates are rejected with `validate_extent` before they get here.
+            webgpu::GpuExtent3D::ListGpuIntegerCoordinate(coordinates) => wgpu_types::Extent3d {
+                width: coordinates.first().copied().unwrap_or(0),
+                height: coordinates.get(1).copied().unwrap_or(1),
+                depth_or_array_layers: coordinates.get(2).copied().unwrap_or(1),
             },
-            webgpu::GpuExtent3D::ListGpuIntegerCoordinate(_coordinates) => todo!(),
         }
     }
 }
@@ -58,7 +79,9 @@ impl<'a> ToCore<wgpu_core::binding_model::BindingResource<'a>> for webgpu::GpuBi
             webgpu::GpuBindingResource::GpuBufferBinding(buffer) => {
                 wgpu_core::binding_model::BindingResource::Buffer(buffer.to_core(table))
             }
-            webgpu::GpuBindingResource::GpuExternalTexture(_external_texture) => todo!(),
+            webgpu::GpuBindingResource::GpuExternalTexture(_external_texture) => {
+                unreachable!("import-external-texture traps, so there are no external textures")
+            }
             webgpu::GpuBindingResource::GpuSampler(sampler) => {
                 wgpu_core::binding_model::BindingResource::Sampler(sampler.to_core(table))
             }
@@ -69,13 +92,14 @@ impl<'a> ToCore<wgpu_core::binding_model::BindingResource<'a>> for webgpu::GpuBi
     }
 }
 
-impl<'a> ToCore<wgpu_core::binding_model::BufferBinding> for webgpu::GpuBufferBinding {
+impl ToCore<wgpu_core::binding_model::BufferBinding> for webgpu::GpuBufferBinding {
     fn to_core(self, table: &ResourceTable) -> wgpu_core::binding_model::BufferBinding {
         let buffer = table.get(&self.buffer).unwrap();
         wgpu_core::binding_model::BufferBinding {
             buffer_id: buffer.buffer,
-            offset: self.offset.unwrap(),
-            size: self.size.map(|s| s.try_into().unwrap()),
+            offset: self.offset.unwrap_or(0),
+            // A zero size is rejected by wgpu-core when it's `Some`.
+            size: self.size.and_then(NonZeroU64::new),
         }
     }
 }
@@ -112,15 +136,14 @@ impl<'a> ToCore<wgpu_core::resource::TextureViewDescriptor<'a>>
             label: self.label.map(|l| l.into()),
             format: self.format.map(|f| f.into()),
             dimension: self.dimension.map(|d| d.into()),
-            // TODO: Don't default
-            range: Default::default(),
-            // range: wgpu_types::ImageSubresourceRange {
-            //     aspect: self.aspect,
-            //     base_mip_level: self.base_mip_level,
-            //     mip_level_count: self.mip_level_count,
-            //     base_array_layer: self.base_array_layer,
-            //     array_layer_count: self.array_layer_count,
-            // }
+            // Counts left as `None` extend to the last mip level / array layer of the texture.
+            range: wgpu_types::ImageSubresourceRange {
+                aspect: self.aspect.map(|a| a.into()).unwrap_or_default(),
+                base_mip_level: self.base_mip_level.unwrap_or(0),
+                mip_level_count: self.mip_level_count,
+                base_array_layer: self.base_array_layer.unwrap_or(0),
+                array_layer_count: self.array_layer_count,
+            },
         }
     }
 }
@@ -140,7 +163,22 @@ impl<'a> ToCore<wgpu_core::binding_model::PipelineLayoutDescriptor<'a>>
                 .map(|bind_group_layout| *table.get(&bind_group_layout).unwrap())
                 .collect::<Vec<_>>()
                 .into(),
-            push_constant_ranges: vec![].into(),
+            push_constant_ranges: self
+                .push_constant_ranges
+                .unwrap_or_default()
+                .into_iter()
+                .map(|range| range.to_core(table))
+                .collect::<Vec<_>>()
+                .into(),
+        }
+    }
+}
+
+impl ToCore<wgpu_types::PushConstantRange> for webgpu::GpuPushConstantRange {
+    fn to_core(self, _table: &ResourceTable) -> wgpu_types::PushConstantRange {
+        wgpu_types::PushConstantRange {
+            stages: wgpu_types::ShaderStages::from_bits_truncate(self.stages),
+            range: self.start..self.end,
         }
     }
 }
@@ -150,28 +188,28 @@ impl<'a> ToCore<wgpu_core::pipeline::RenderPipelineDescriptor<'a>>
 {
     fn to_core(self, table: &ResourceTable) -> wgpu_core::pipeline::RenderPipelineDescriptor<'a> {
         wgpu_core::pipeline::RenderPipelineDescriptor {
-            // TODO: remove defaults
-            label: Default::default(),
-            layout: self.layout.map(|l| l.to_core(table)),
+            label: self.label.map(|l| l.into()),
+            layout: self.layout.to_core(table),
             vertex: self.vertex.to_core(table),
-            primitive: self.primitive.map(|p| p.to_core(table)).unwrap(),
+            primitive: self.primitive.map(|p| p.to_core(table)).unwrap_or_default(),
             depth_stencil: self.depth_stencil.map(|ds| ds.to_core(table)),
             multisample: self
                 .multisample
                 .map(|ms| ms.to_core(table))
                 .unwrap_or_default(),
             fragment: self.fragment.map(|f| f.to_core(table)),
-            multiview: Default::default(),
+            multiview: self.multiview.and_then(NonZeroU32::new),
         }
     }
 }
 
 impl ToCore<wgpu_types::MultisampleState> for webgpu::GpuMultisampleState {
     fn to_core(self, _table: &ResourceTable) -> wgpu_types::MultisampleState {
+        // source: https://www.w3.org/TR/webgpu/#dictdef-gpumultisamplestate
         wgpu_types::MultisampleState {
-            count: self.count.unwrap(),
-            mask: self.mask.unwrap().into(),
-            alpha_to_coverage_enabled: self.alpha_to_coverage_enabled.unwrap(),
+            count: self.count.unwrap_or(1),
+            mask: self.mask.unwrap_or(0xFFFFFFFF).into(),
+            alpha_to_coverage_enabled: self.alpha_to_coverage_enabled.unwrap_or(false),
         }
     }
 }
@@ -180,8 +218,12 @@ impl ToCore<wgpu_types::DepthStencilState> for webgpu::GpuDepthStencilState {
     fn to_core(self, table: &ResourceTable) -> wgpu_types::DepthStencilState {
         wgpu_types::DepthStencilState {
             format: self.format.into(),
-            depth_write_enabled: self.depth_write_enabled.unwrap().into(),
-            depth_compare: self.depth_compare.unwrap().into(),
+            // The spec requires these for depth formats, wgpu-core doesn't, so they get its defaults.
+            depth_write_enabled: self.depth_write_enabled.unwrap_or(false),
+            depth_compare: self
+                .depth_compare
+                .map(|compare| compare.into())
+                .unwrap_or(wgpu_types::CompareFunction::Always),
             stencil: wgpu_types::StencilState {
                 front: self
                     .stencil_front
@@ -191,8 +233,9 @@ impl ToCore<wgpu_types::DepthStencilState> for webgpu::GpuDepthStencilState {
                     .stencil_back
                     .map(|b| b.to_core(table))
                     .unwrap_or_default(),
-                read_mask: self.stencil_read_mask.unwrap_or_default(),
-                write_mask: self.stencil_write_mask.unwrap_or_default(),
+                // source: https://www.w3.org/TR/webgpu/#dictdef-gpudepthstencilstate
+                read_mask: self.stencil_read_mask.unwrap_or(0xFFFFFFFF),
+                write_mask: self.stencil_write_mask.unwrap_or(0xFFFFFFFF),
             },
             bias: wgpu_types::DepthBiasState {
                 constant: self.depth_bias.unwrap_or_default(),
@@ -232,7 +275,7 @@ impl ToCore<wgpu_types::PrimitiveState> for webgpu::GpuPrimitiveState {
             topology: self.topology.map(|t| t.into()).unwrap_or_default(),
             strip_index_format: self.strip_index_format.map(|f| f.into()),
             front_face: self.front_face.map(|x| x.into()).unwrap_or_default(),
-            cull_mode: self.cull_mode.map(|cm| cm.into()),
+            cull_mode: self.cull_mode.and_then(|cm| cm.into()),
             unclipped_depth: self.unclipped_depth.unwrap_or_default(),
             // TODO: remove defaults
             polygon_mode: Default::default(),
@@ -247,7 +290,7 @@ impl<'a> ToCore<wgpu_core::pipeline::FragmentState<'a>> for webgpu::GpuFragmentS
             stage: wgpu_core::pipeline::ProgrammableStageDescriptor {
                 module: self.module.to_core(table),
                 entry_point: Some(self.entry_point.into()),
-                constants: Default::default(),
+                constants: pipeline_constants(self.constants),
                 zero_initialize_workgroup_memory: true,
             },
             targets: self
@@ -267,7 +310,7 @@ impl ToCore<wgpu_types::ColorTargetState> for webgpu::GpuColorTargetState {
             blend: self.blend.map(|b| b.to_core(table)),
             write_mask: self
                 .write_mask
-                .map(|wm| wgpu_types::ColorWrites::from_bits(wm).unwrap())
+                .map(wgpu_types::ColorWrites::from_bits_truncate)
                 .unwrap_or_default(),
         }
     }
@@ -308,7 +351,7 @@ impl<'a> ToCore<wgpu_core::pipeline::VertexState<'a>> for webgpu::GpuVertexState
             stage: wgpu_core::pipeline::ProgrammableStageDescriptor {
                 module: self.module.to_core(table),
                 entry_point: Some(self.entry_point.into()),
-                constants: Default::default(),
+                constants: pipeline_constants(self.constants),
                 zero_initialize_workgroup_memory: true,
             },
             buffers: self
@@ -323,7 +366,10 @@ impl<'a> ToCore<wgpu_core::pipeline::VertexBufferLayout<'a>> for webgpu::GpuVert
     fn to_core(self, table: &ResourceTable) -> wgpu_core::pipeline::VertexBufferLayout<'a> {
         wgpu_core::pipeline::VertexBufferLayout {
             array_stride: self.array_stride,
-            step_mode: self.step_mode.unwrap().into(),
+            step_mode: self
+                .step_mode
+                .map(|step_mode| step_mode.into())
+                .unwrap_or(wgpu_types::VertexStepMode::Vertex),
             attributes: self
                 .attributes
                 .into_iter()
@@ -353,11 +399,12 @@ impl<'a> ToCore<wgpu_types::TextureDescriptor<wgpu_core::Label<'a>, Vec<wgpu_typ
         wgpu_types::TextureDescriptor {
             label: self.label.map(|l| l.into()),
             size: self.size.to_core(table),
-            mip_level_count: self.mip_level_count.unwrap(),
-            sample_count: self.sample_count.unwrap(),
+            mip_level_count: self.mip_level_count.unwrap_or(1),
+            sample_count: self.sample_count.unwrap_or(1),
             dimension: self.dimension.into(),
             format: self.format.into(),
-            usage: wgpu_types::TextureUsages::from_bits(self.usage).unwrap(),
+            // Unknown bits are kept so wgpu-core reports them.
+            usage: wgpu_types::TextureUsages::from_bits_retain(self.usage),
             view_formats: self
                 .view_formats
                 .map(|view_formats| {
@@ -375,19 +422,21 @@ impl<'a> ToCore<wgpu_core::resource::SamplerDescriptor<'a>> for webgpu::GpuSampl
     fn to_core(self, _table: &ResourceTable) -> wgpu_core::resource::SamplerDescriptor<'a> {
         wgpu_core::resource::SamplerDescriptor {
             label: self.label.map(|l| l.into()),
+            // source: https://www.w3.org/TR/webgpu/#dictdef-gpusamplerdescriptor
             address_modes: [
-                self.address_mode_u.unwrap().into(),
-                self.address_mode_v.unwrap().into(),
-                self.address_mode_w.unwrap().into(),
+                self.address_mode_u.map(|m| m.into()).unwrap_or_default(),
+                self.address_mode_v.map(|m| m.into()).unwrap_or_default(),
+                self.address_mode_w.map(|m| m.into()).unwrap_or_default(),
             ],
-            mag_filter: self.mag_filter.unwrap().into(),
-            min_filter: self.min_filter.unwrap().into(),
-            mipmap_filter: self.mipmap_filter.unwrap().into(),
-            lod_min_clamp: self.lod_min_clamp.unwrap(),
-            lod_max_clamp: self.lod_max_clamp.unwrap(),
+            mag_filter: self.mag_filter.map(|f| f.into()).unwrap_or_default(),
+            min_filter: self.min_filter.map(|f| f.into()).unwrap_or_default(),
+            mipmap_filter: self.mipmap_filter.map(|f| f.into()).unwrap_or_default(),
+            lod_min_clamp: self.lod_min_clamp.unwrap_or(0.0),
+            lod_max_clamp: self.lod_max_clamp.unwrap_or(32.0),
             compare: self.compare.map(|compare| compare.into()),
-            // TODO: should this be coming from self.anisotropy_clamp?
-            anisotropy_clamp: 1,
+            // wgpu-core rejects anisotropy without linear filtering, and clamps it to 16, or to 1 on backends
+            // without anisotropic filtering.
+            anisotropy_clamp: self.max_anisotropy.unwrap_or(1),
             border_color: Default::default(),
         }
     }
@@ -426,9 +475,9 @@ impl<'a> ToCore<wgpu_core::binding_model::BindGroupLayoutDescriptor<'a>>
 impl ToCore<wgpu_types::BindGroupLayoutEntry> for webgpu::GpuBindGroupLayoutEntry {
     fn to_core(self, table: &ResourceTable) -> wgpu_types::BindGroupLayoutEntry {
         wgpu_types::BindGroupLayoutEntry {
-            binding: self.binding.into(),
+            binding: self.binding,
             // TODO:
-            visibility: wgpu_types::ShaderStages::from_bits(self.visibility).unwrap(),
+            visibility: wgpu_types::ShaderStages::from_bits_truncate(self.visibility),
             ty: match (
                 self.buffer,
                 self.sampler,
@@ -440,9 +489,9 @@ impl ToCore<wgpu_types::BindGroupLayoutEntry> for webgpu::GpuBindGroupLayoutEntr
                 (None, Some(sampler), None, None, None) => sampler.to_core(table),
                 (None, None, Some(texture), None, None) => texture.to_core(table),
                 (None, None, None, Some(storage_texture), None) => storage_texture.to_core(table),
-                (None, None, None, None, Some(external_texture)) => external_texture.to_core(table),
-                (None, None, None, None, None) => todo!(),
-                _ => panic!("Can't have multiple ..."),
+                // Entries without exactly one binding type, and external textures, are rejected with
+                // `validate_bind_group_layout` before they get here.
+                _ => unreachable!("Bind group layout entry wasn't validated"),
             },
             // TODO:
             count: Default::default(),
@@ -455,21 +504,28 @@ impl ToCore<wgpu_types::BindingType> for webgpu::GpuBufferBindingLayout {
         wgpu_types::BindingType::Buffer {
             ty: self.type_.map(|t| t.into()).unwrap_or_default(),
             has_dynamic_offset: self.has_dynamic_offset.unwrap_or_default(),
-            min_binding_size: self.min_binding_size.map(|x| x.try_into().unwrap()),
+            min_binding_size: self.min_binding_size.and_then(NonZeroU64::new),
         }
     }
 }
 
 impl ToCore<wgpu_types::BindingType> for webgpu::GpuSamplerBindingLayout {
     fn to_core(self, _table: &ResourceTable) -> wgpu_types::BindingType {
-        wgpu_types::BindingType::Sampler(self.type_.map(|t| t.into()).unwrap())
+        wgpu_types::BindingType::Sampler(
+            self.type_
+                .unwrap_or(webgpu::GpuSamplerBindingType::Filtering)
+                .into(),
+        )
     }
 }
 
 impl ToCore<wgpu_types::BindingType> for webgpu::GpuTextureBindingLayout {
     fn to_core(self, _table: &ResourceTable) -> wgpu_types::BindingType {
         wgpu_types::BindingType::Texture {
-            sample_type: self.sample_type.unwrap().into(),
+            sample_type: self
+                .sample_type
+                .unwrap_or(webgpu::GpuTextureSampleType::Float)
+                .into(),
             view_dimension: self.view_dimension.into(),
             multisampled: self.multisampled.unwrap_or_default(),
         }
@@ -478,13 +534,14 @@ impl ToCore<wgpu_types::BindingType> for webgpu::GpuTextureBindingLayout {
 
 impl ToCore<wgpu_types::BindingType> for webgpu::GpuStorageTextureBindingLayout {
     fn to_core(self, _table: &ResourceTable) -> wgpu_types::BindingType {
-        todo!()
-    }
-}
-
-impl ToCore<wgpu_types::BindingType> for webgpu::GpuExternalTextureBindingLayout {
-    fn to_core(self, _table: &ResourceTable) -> wgpu_types::BindingType {
-        todo!()
+        wgpu_types::BindingType::StorageTexture {
+            access: self
+                .access
+                .unwrap_or(webgpu::GpuStorageTextureAccess::WriteOnly)
+                .into(),
+            format: self.format.into(),
+            view_dimension: self.view_dimension.into(),
+        }
     }
 }
 
@@ -500,8 +557,6 @@ impl ToCore<wgpu_types::BindingType> for webgpu::GpuExternalTextureBindingLayout
 //                 .collect::<Vec<_>>()
 //                 .into(),
 //             // depth_stencil_attachment: self.depth_stencil_attachment.map(|d| d.to_core(table)),
-//             // timestamp_writes: self.timestamp_writes,
-//             // occlusion_query_set: self.occlusion_query_set,
 //             // TODO: self.max_draw_count not used
 //             ..Default::default()
 //         }
@@ -520,14 +575,14 @@ impl ToCore<wgpu_core::command::RenderPassDepthStencilAttachment>
             depth: pass_channel_from_options(
                 self.depth_load_op.map(|x| x.into()),
                 self.depth_store_op.map(|x| x.into()),
-                self.depth_clear_value.map(|x| x.into()),
-                self.depth_read_only.map(|x| x.into()),
+                self.depth_clear_value,
+                self.depth_read_only,
             ),
             stencil: pass_channel_from_options(
                 self.stencil_load_op.map(|x| x.into()),
                 self.stencil_store_op.map(|x| x.into()),
-                self.stencil_clear_value.map(|x| x.into()),
-                self.stencil_read_only.map(|x| x.into()),
+                self.stencil_clear_value,
+                self.stencil_read_only,
             ),
         }
     }
@@ -539,22 +594,15 @@ fn pass_channel_from_options<V: Default + Copy>(
     clear_value: Option<V>,
     read_only: Option<bool>,
 ) -> wgpu_core::command::PassChannel<V> {
-    match (load_op, store_op, clear_value) {
-        (Some(load_op), Some(store_op), Some(clear_value)) => wgpu_core::command::PassChannel {
-            load_op,
-            store_op,
-            clear_value,
-            // TODO: why default to false?
-            read_only: read_only.unwrap_or(false),
-        },
-        (None, None, None) => wgpu_core::command::PassChannel {
-            load_op: wgpu_core::command::LoadOp::Load,
-            store_op: wgpu_core::command::StoreOp::Store,
-            clear_value: V::default(),
-            // TODO: why default to false?
-            read_only: read_only.unwrap_or(false),
-        },
-        _ => todo!(),
+    wgpu_core::command::PassChannel {
+        // Ops are left out for read-only channels and formats without the aspect, where wgpu-core ignores them.
+        load_op: load_op.unwrap_or(wgpu_core::command::LoadOp::Load),
+        store_op: store_op.unwrap_or(wgpu_core::command::StoreOp::Store),
+        // Only used when clearing, and the spec clears to zero when it's missing.
+        // source: https://www.w3.org/TR/webgpu/#dictdef-gpurenderpasscolorattachment
+        clear_value: clear_value.unwrap_or_default(),
+        // source: https://www.w3.org/TR/webgpu/#dom-gpurenderpassdepthstencilattachment-depthreadonly
+        read_only: read_only.unwrap_or(false),
     }
 }
 
@@ -586,7 +634,8 @@ impl<'a> ToCore<wgpu_types::BufferDescriptor<wgpu_core::Label<'a>>>
         wgpu_types::BufferDescriptor {
             label: self.label.map(|l| l.into()),
             size: self.size,
-            usage: wgpu_types::BufferUsages::from_bits(self.usage).unwrap(),
+            // Unknown bits are kept so wgpu-core reports them.
+            usage: wgpu_types::BufferUsages::from_bits_retain(self.usage),
             mapped_at_creation: self.mapped_at_creation.unwrap_or_default(),
         }
     }
@@ -598,6 +647,12 @@ impl<'a> ToCore<wgpu_types::DeviceDescriptor<wgpu_core::Label<'a>>>
     fn to_core(self, _table: &ResourceTable) -> wgpu_types::DeviceDescriptor<wgpu_core::Label<'a>> {
         wgpu_types::DeviceDescriptor {
             label: self.label.map(|l| l.into()),
+            required_features: self
+                .required_features
+                .unwrap_or_default()
+                .into_iter()
+                .map(wgpu_types::Features::from)
+                .fold(wgpu_types::Features::empty(), |acc, f| acc | f),
             // TODO: Don't default
             ..Default::default()
         }
@@ -612,10 +667,14 @@ impl ToCore<wgpu_types::ImageCopyTexture<wgpu_core::id::TextureId>>
         table: &ResourceTable,
     ) -> wgpu_types::ImageCopyTexture<wgpu_core::id::TextureId> {
         wgpu_types::ImageCopyTexture {
-            texture: self.texture.to_core(table),
-            mip_level: self.mip_level.unwrap(),
-            origin: self.origin.unwrap().to_core(table),
-            aspect: self.aspect.unwrap().into(),
+            texture: table.get(&self.texture).unwrap().texture,
+            // source: https://www.w3.org/TR/webgpu/#dictdef-gpuimagecopytexture
+            mip_level: self.mip_level.unwrap_or(0),
+            origin: self
+                .origin
+                .map(|origin| origin.to_core(table))
+                .unwrap_or(wgpu_types::Origin3d::ZERO),
+            aspect: self.aspect.map(|aspect| aspect.into()).unwrap_or_default(),
         }
     }
 }
@@ -625,13 +684,21 @@ impl ToCore<wgpu_types::Origin3d> for webgpu::GpuOrigin3DDictOrListGpuIntegerCoo
         match self {
             webgpu::GpuOrigin3DDictOrListGpuIntegerCoordinate::GpuOrigin3DDict(dict) => {
                 wgpu_types::Origin3d {
-                    x: dict.x.unwrap(),
-                    y: dict.y.unwrap(),
-                    z: dict.z.unwrap(),
+                    x: dict.x.unwrap_or(0),
+                    y: dict.y.unwrap_or(0),
+                    z: dict.z.unwrap_or(0),
                 }
             }
-            webgpu::GpuOrigin3DDictOrListGpuIntegerCoordinate::ListGpuIntegerCoordinate(_) => {
-                todo!()
+            // source: https://www.w3.org/TR/webgpu/#gpuorigin3d-shape
+            webgpu::GpuOrigin3DDictOrListGpuIntegerCoordinate::ListGpuIntegerCoordinate(
+                coordinates,
+            ) => {
+                let coordinate = |i: usize| coordinates.get(i).copied().unwrap_or(0);
+                wgpu_types::Origin3d {
+                    x: coordinate(0),
+                    y: coordinate(1),
+                    z: coordinate(2),
+                }
             }
         }
     }
@@ -640,33 +707,70 @@ impl ToCore<wgpu_types::Origin3d> for webgpu::GpuOrigin3DDictOrListGpuIntegerCoo
 impl ToCore<wgpu_types::ImageDataLayout> for webgpu::GpuImageDataLayout {
     fn to_core(self, _table: &ResourceTable) -> wgpu_types::ImageDataLayout {
         wgpu_types::ImageDataLayout {
-            offset: self.offset.unwrap(),
+            offset: self.offset.unwrap_or(0),
             bytes_per_row: self.bytes_per_row,
             rows_per_image: self.rows_per_image,
         }
     }
 }
 
+impl<'a> ToCore<wgpu_core::command::RenderBundleEncoderDescriptor<'a>>
+    for webgpu::GpuRenderBundleEncoderDescriptor
+{
+    fn to_core(
+        self,
+        _table: &ResourceTable,
+    ) -> wgpu_core::command::RenderBundleEncoderDescriptor<'a> {
+        wgpu_core::command::RenderBundleEncoderDescriptor {
+            label: self.label.map(|l| l.into()),
+            color_formats: self
+                .color_formats
+                .into_iter()
+                .map(|f| Some(f.into()))
+                .collect::<Vec<_>>()
+                .into(),
+            depth_stencil: self.depth_stencil_format.map(|format| {
+                wgpu_types::RenderBundleDepthStencil {
+                    format: format.into(),
+                    // source: https://www.w3.org/TR/webgpu/#dictdef-gpurenderbundleencoderdescriptor
+                    depth_read_only: self.depth_read_only.unwrap_or(false),
+                    stencil_read_only: self.stencil_read_only.unwrap_or(false),
+                }
+            }),
+            sample_count: self.sample_count.unwrap_or(1),
+            multiview: self.multiview.and_then(NonZeroU32::new),
+        }
+    }
+}
+
 impl<'a> ToCore<wgpu_core::pipeline::ComputePipelineDescriptor<'a>>
     for webgpu::GpuComputePipelineDescriptor
 {
     fn to_core(self, table: &ResourceTable) -> wgpu_core::pipeline::ComputePipelineDescriptor<'a> {
         wgpu_core::pipeline::ComputePipelineDescriptor {
-            label: Default::default(), //TODO: enable: self.compute.label.map(|l| l.into()),
-            layout: match self.layout {
-                webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuPipelineLayout(layout) => {
-                    Some(layout.to_core(table))
-                }
-                webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(mode) => match mode
-                {
-                    webgpu::GpuAutoLayoutMode::Auto => None,
-                },
-            },
+            label: self.label.map(|l| l.into()),
+            layout: self.layout.to_core(table),
             stage: self.compute.to_core(table),
         }
     }
 }
 
+/// `None` means the layout is derived from the shaders.
+impl ToCore<Option<wgpu_core::id::PipelineLayoutId>>
+    for webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode
+{
+    fn to_core(self, table: &ResourceTable) -> Option<wgpu_core::id::PipelineLayoutId> {
+        match self {
+            webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuPipelineLayout(layout) => {
+                Some(layout.to_core(table))
+            }
+            webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(mode) => match mode {
+                webgpu::GpuAutoLayoutMode::Auto => None,
+            },
+        }
+    }
+}
+
 impl<'a> ToCore<wgpu_core::pipeline::ProgrammableStageDescriptor<'a>>
     for webgpu::GpuProgrammableStage
 {
@@ -677,7 +781,7 @@ impl<'a> ToCore<wgpu_core::pipeline::ProgrammableStageDescriptor<'a>>
         wgpu_core::pipeline::ProgrammableStageDescriptor {
             module: self.module.to_core(table),
             entry_point: self.entry_point.map(|ep| ep.into()),
-            constants: Default::default(),
+            constants: pipeline_constants(self.constants),
             zero_initialize_workgroup_memory: true,
         }
     }
@@ -695,6 +799,18 @@ impl ToCore<wgpu_core::command::ComputePassTimestampWrites>
     }
 }
 
+impl ToCore<wgpu_core::command::RenderPassTimestampWrites>
+    for webgpu::GpuRenderPassTimestampWrites
+{
+    fn to_core(self, table: &ResourceTable) -> wgpu_core::command::RenderPassTimestampWrites {
+        wgpu_core::command::RenderPassTimestampWrites {
+            query_set: self.query_set.to_core(table),
+            beginning_of_pass_write_index: self.beginning_of_pass_write_index,
+            end_of_pass_write_index: self.end_of_pass_write_index,
+        }
+    }
+}
+
 impl ToCore<wgpu_types::ImageCopyBuffer<wgpu_core::id::BufferId>> for webgpu::GpuImageCopyBuffer {
     fn to_core(
         self,
@@ -703,10 +819,45 @@ impl ToCore<wgpu_types::ImageCopyBuffer<wgpu_core::id::BufferId>> for webgpu::Gp
         wgpu_types::ImageCopyBuffer {
             buffer: table.get(&self.buffer).unwrap().buffer,
             layout: wgpu_types::ImageDataLayout {
-                offset: self.offset.unwrap(),
+                offset: self.offset.unwrap_or(0),
                 bytes_per_row: self.bytes_per_row,
                 rows_per_image: self.rows_per_image,
             },
         }
     }
 }
+
+fn pipeline_constants<'a>(
+    constants: Option<webgpu::RecordGpuPipelineConstantValue>,
+) -> std::borrow::Cow<'a, wgpu_core::naga::back::PipelineConstants> {
+    std::borrow::Cow::Owned(constants.unwrap_or_default().into_iter().collect())
+}
+
+impl<'a> ToCore<wgpu_core::resource::QuerySetDescriptor<'a>> for webgpu::GpuQuerySetDescriptor {
+    fn to_core(self, _table: &ResourceTable) -> wgpu_core::resource::QuerySetDescriptor<'a> {
+        wgpu_core::resource::QuerySetDescriptor {
+            label: self.label.map(|l| l.into()),
+            ty: self.type_.into(),
+            count: self.count,
+        }
+    }
+}
+
+impl<'a> ToCore<wgpu_core::resource::QuerySetDescriptor<'a>>
+    for webgpu::GpuPipelineStatisticsQuerySetDescriptor
+{
+    fn to_core(self, _table: &ResourceTable) -> wgpu_core::resource::QuerySetDescriptor<'a> {
+        wgpu_core::resource::QuerySetDescriptor {
+            label: self.label.map(|l| l.into()),
+            ty: wgpu_types::QueryType::PipelineStatistics(
+                self.statistics
+                    .into_iter()
+                    .map(wgpu_types::PipelineStatisticsTypes::from)
+                    .fold(wgpu_types::PipelineStatisticsTypes::empty(), |acc, s| {
+                        acc | s
+                    }),
+            ),
+            count: self.count,
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::sync::{Arc, Mutex};
+
+use wasmtime::component::Resource;
+
+use crate::error::ErrorScopes;
+use crate::wasi::webgpu::vertex_pulling::{self, VertexPullError};
+use crate::wasi::webgpu::webgpu;
+use crate::{Buffer, Device, ObservedResource, WasiWebGpuImpl, WasiWebGpuView};
+
+/// Binding the vertices are at, see `vertex-pulling.wit`.
+const BINDING: u32 = 0;
+
+/// Usage of the buffer the vertices are uploaded to.
+const BUFFER_USAGE: wgpu_types::BufferUsages =
+    wgpu_types::BufferUsages::STORAGE.union(wgpu_types::BufferUsages::COPY_DST);
+
+/// The `vertex-pull-buffer` resource.
+pub struct VertexPullBuffer {
+    buffer: wgpu_core::id::BufferId,
+    bind_group_layout: wgpu_core::id::BindGroupLayoutId,
+    bind_group: wgpu_core::id::BindGroupId,
+    errors: ErrorScopes,
+    size: u64,
+    stride: u32,
+    vertex_count: u32,
+}
+
+/// Uploads `data` to a new storage buffer, and creates the bind group layout and bind group for it.
+fn create_bindings(
+    instance: &wgpu_core::global::Global,
+    device: &Device,
+    data: &[u8],
+) -> (
+    wgpu_core::id::BufferId,
+    wgpu_core::id::BindGroupLayoutId,
+    wgpu_core::id::BindGroupId,
+) {
+    let errors = &device.errors;
+    let label = Some("vertex pull buffer".into());
+    let buffer = errors.handle(instance.device_create_buffer::<crate::Backend>(
+        device.device,
+        &wgpu_types::BufferDescriptor {
+            label: label.clone(),
+            size: data.len() as u64,
+            usage: BUFFER_USAGE,
+            mapped_at_creation: false,
+        },
+        None,
+    ));
+    errors.check(instance.queue_write_buffer::<crate::Backend>(device.queue, buffer, 0, data));
+    let bind_group_layout = errors.handle(
+        instance.device_create_bind_group_layout::<crate::Backend>(
+            device.device,
+            &wgpu_core::binding_model::BindGroupLayoutDescriptor {
+                label: label.clone(),
+                entries: vec![wgpu_types::BindGroupLayoutEntry {
+                    binding: BINDING,
+                    visibility: wgpu_types::ShaderStages::VERTEX,
+                    ty: wgpu_types::BindingType::Buffer {
+                        ty: wgpu_types::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }]
+                .into(),
+            },
+            None,
+        ),
+    );
+    let bind_group = errors.handle(
+        instance.device_create_bind_group::<crate::Backend>(
+            device.device,
+            &wgpu_core::binding_model::BindGroupDescriptor {
+                label,
+                layout: bind_group_layout,
+                entries: vec![wgpu_core::binding_model::BindGroupEntry {
+                    binding: BINDING,
+                    resource: wgpu_core::binding_model::BindingResource::Buffer(
+                        wgpu_core::binding_model::BufferBinding {
+                            buffer_id: buffer,
+                            offset: 0,
+                            size: None,
+                        },
+                    ),
+                }]
+                .into(),
+            },
+            None,
+        ),
+    );
+    (buffer, bind_group_layout, bind_group)
+}
+
+impl<T: WasiWebGpuView> vertex_pulling::Host for WasiWebGpuImpl<T> {
+    fn create_vertex_pull_buffer(
+        &mut self,
+        device: Resource<Device>,
+        data: Vec<u8>,
+        stride: u32,
+    ) -> Result<Resource<VertexPullBuffer>, VertexPullError> {
+        if stride == 0 || !stride.is_multiple_of(4) {
+            return Err(VertexPullError::InvalidStride);
+        }
+        if data.is_empty() || !data.len().is_multiple_of(stride as usize) {
+            return Err(VertexPullError::InvalidData);
+        }
+        let vertex_count = u32::try_from(data.len() / stride as usize)
+            .map_err(|_| VertexPullError::InvalidData)?;
+        let device = self.0.table().get(&device).unwrap().clone();
+        let size = data.len() as u64;
+        let quota = self.0.quota();
+        if let Some(quota) = &quota {
+            quota
+                .check_buffer(size)
+                .map_err(|error| VertexPullError::Gpu(error.kind()))?;
+        }
+
+        let instance = self.0.instance();
+        let (buffer, bind_group_layout, bind_group) = device
+            .errors
+            .catch(|| create_bindings(&instance, &device, &data))
+            .map_err(|error| VertexPullError::Gpu(error.kind()))?;
+        if let Some(quota) = quota {
+            quota.charge_buffer(buffer, size);
+        }
+        Ok(self
+            .0
+            .table()
+            .push(VertexPullBuffer {
+                buffer,
+                bind_group_layout,
+                bind_group,
+                errors: device.errors,
+                size,
+                stride,
+                vertex_count,
+            })
+            .unwrap())
+    }
+}
+
+impl<T: WasiWebGpuView> vertex_pulling::HostVertexPullBuffer for WasiWebGpuImpl<T> {
+    fn get_buffer(&mut self, buffer: Resource<VertexPullBuffer>) -> Resource<Buffer> {
+        let buffer = self.0.table().get(&buffer).unwrap();
+        let buffer = Buffer {
+            buffer: buffer.buffer,
+            mapped: None,
+            size: buffer.size,
+            usage: BUFFER_USAGE,
+            map_state: Arc::new(Mutex::new(webgpu::GpuBufferMapState::Unmapped)),
+            errors: buffer.errors.clone(),
+            owned: false,
+        };
+        self.observe_created(ObservedResource::Buffer { size: buffer.size });
+        self.0.table().push(buffer).unwrap()
+    }
+
+    fn get_bind_group_layout(
+        &mut self,
+        buffer: Resource<VertexPullBuffer>,
+    ) -> Resource<wgpu_core::id::BindGroupLayoutId> {
+        let bind_group_layout = self.0.table().get(&buffer).unwrap().bind_group_layout;
+        self.0.table().push(bind_group_layout).unwrap()
+    }
+
+    fn get_bind_group(
+        &mut self,
+        buffer: Resource<VertexPullBuffer>,
+    ) -> Resource<wgpu_core::id::BindGroupId> {
+        let bind_group = self.0.table().get(&buffer).unwrap().bind_group;
+        self.0.table().push(bind_group).unwrap()
+    }
+
+    fn stride(&mut self, buffer: Resource<VertexPullBuffer>) -> u32 {
+        self.0.table().get(&buffer).unwrap().stride
+    }
+
+    fn vertex_count(&mut self, buffer: Resource<VertexPullBuffer>) -> u32 {
+        self.0.table().get(&buffer).unwrap().vertex_count
+    }
+
+    fn drop(&mut self, buffer: Resource<VertexPullBuffer>) -> wasmtime::Result<()> {
+        let buffer = self.0.table().delete(buffer)?;
+        // Not destroyed, the guest may still hold the buffer or bind group from the getters.
+        if let Some(quota) = self.0.quota() {
+            quota.release_buffer(buffer.buffer);
+        }
+        Ok(())
+    }
+}
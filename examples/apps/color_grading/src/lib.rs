@@ -0,0 +1,241 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleColorGrading);
+
+struct ExampleColorGrading;
+
+impl Guest for ExampleColorGrading {
+    fn start() {
+        draw_graded();
+    }
+}
+
+use wasi::webgpu::{animation_frame, graphics_context, mini_canvas, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gputextureusageflags
+const TEXTURE_COPY_DST: u32 = 0x02;
+const TEXTURE_BINDING: u32 = 0x04;
+
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 400;
+/// Entries per side of the lookup table.
+const LUT_SIZE: u32 = 16;
+
+const SHADER_CODE: &str = r#"
+const LUT_SIZE: f32 = 16.0;
+
+@group(0) @binding(0) var lut: texture_3d<f32>;
+@group(0) @binding(1) var lut_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// A single triangle covering the canvas.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn hue(h: f32) -> vec3<f32> {
+    let k = vec3<f32>(0.0, 2.0, 4.0) / 3.0;
+    return clamp(abs(fract(h + k) * 6.0 - 3.0) - 1.0, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Hues left to right, from dark at the top to washed out at the bottom.
+    let color = mix(hue(in.uv.x) * in.uv.y * 2.0, mix(hue(in.uv.x), vec3<f32>(1.0), in.uv.y * 2.0 - 1.0), step(0.5, in.uv.y));
+    if (in.uv.x < 0.5) {
+        return vec4<f32>(color, 1.0);
+    }
+    // Sample at texel centers, so the ends of each axis map to the first and last entries.
+    let coords = color * ((LUT_SIZE - 1.0) / LUT_SIZE) + 0.5 / LUT_SIZE;
+    return vec4<f32>(textureSample(lut, lut_sampler, coords).rgb, 1.0);
+}
+"#;
+
+/// Grades the right half of a color chart through a 3D lookup table, leaving the left half as is.
+fn draw_graded() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+    let queue = device.queue();
+
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
+        height: HEIGHT,
+        width: WIDTH,
+        offscreen: false,
+        title: None,
+        resizable: Some(false),
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
+    });
+    let graphics_context = graphics_context::GraphicsContext::new();
+    canvas.connect_graphics_context(&graphics_context);
+    device
+        .connect_graphics_context(&graphics_context)
+        .expect("timed out creating a surface");
+
+    let lut_size = webgpu::GpuExtent3D::ListGpuIntegerCoordinate(vec![LUT_SIZE; 3]);
+    let lut = device.create_texture(&webgpu::GpuTextureDescriptor {
+        size: lut_size.clone(),
+        mip_level_count: None,
+        sample_count: None,
+        dimension: webgpu::GpuTextureDimension::ThreeD,
+        format: webgpu::GpuTextureFormat::Rgba8unorm,
+        usage: TEXTURE_BINDING | TEXTURE_COPY_DST,
+        view_formats: None,
+        label: Some("lut".into()),
+    });
+    queue.write_texture(
+        &webgpu::GpuImageCopyTexture {
+            texture: &lut,
+            mip_level: None,
+            origin: None,
+            aspect: None,
+        },
+        &lut_data(),
+        webgpu::GpuImageDataLayout {
+            offset: None,
+            bytes_per_row: Some(LUT_SIZE * 4),
+            rows_per_image: Some(LUT_SIZE),
+        },
+        &lut_size,
+    );
+    let lut_view = lut.create_view(Some(&webgpu::GpuTextureViewDescriptor {
+        format: None,
+        dimension: Some(webgpu::GpuTextureViewDimension::ThreeD),
+        aspect: None,
+        base_mip_level: None,
+        mip_level_count: None,
+        base_array_layer: None,
+        array_layer_count: None,
+        label: None,
+    }));
+    let sampler = device.create_sampler(Some(&webgpu::GpuSamplerDescriptor {
+        label: None,
+        address_mode_u: Some(webgpu::GpuAddressMode::ClampToEdge),
+        address_mode_v: Some(webgpu::GpuAddressMode::ClampToEdge),
+        address_mode_w: Some(webgpu::GpuAddressMode::ClampToEdge),
+        mag_filter: Some(webgpu::GpuFilterMode::Linear),
+        min_filter: Some(webgpu::GpuFilterMode::Linear),
+        mipmap_filter: None,
+        lod_min_clamp: None,
+        lod_max_clamp: None,
+        compare: None,
+        max_anisotropy: None,
+    }));
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+        label: Some("color grading".into()),
+        multiview: None,
+        vertex: webgpu::GpuVertexState {
+            module: &shader,
+            entry_point: "vs_main".to_string(),
+            constants: None,
+            buffers: None,
+        },
+        fragment: Some(webgpu::GpuFragmentState {
+            module: &shader,
+            entry_point: "fs_main".to_string(),
+            constants: None,
+            targets: vec![Some(webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
+                blend: None,
+                write_mask: None,
+            })],
+        }),
+        primitive: None,
+        depth_stencil: None,
+        multisample: None,
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+    });
+    let bind_group = device.create_bind_group(webgpu::GpuBindGroupDescriptor {
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: vec![
+            webgpu::GpuBindGroupEntry {
+                binding: 0,
+                resource: webgpu::GpuBindingResource::GpuTextureView(&lut_view),
+            },
+            webgpu::GpuBindGroupEntry {
+                binding: 1,
+                resource: webgpu::GpuBindingResource::GpuSampler(&sampler),
+            },
+        ],
+        label: None,
+    });
+
+    let frame_listener = animation_frame::listener(&canvas);
+    let frame_pollable = frame_listener.subscribe();
+    loop {
+        frame_pollable.block();
+        frame_listener.get();
+
+        let encoder = device.create_command_encoder(None);
+        let texture =
+            webgpu::GpuTexture::from_graphics_buffer(graphics_context.get_current_buffer());
+        let view = texture.create_view(None);
+        let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+            label: None,
+            color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                clear_value: None,
+                load_op: webgpu::GpuLoadOp::Clear,
+                store_op: webgpu::GpuStoreOp::Store,
+            }],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+            max_draw_count: None,
+        });
+        render_pass.set_pipeline(&pipeline);
+        render_pass.set_bind_group(0, &bind_group, None);
+        render_pass.draw(3, 1, 0, 0);
+        webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+
+        queue.submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+        graphics_context.present();
+    }
+}
+
+/// A teal and orange grade: more saturation, with highlights pushed towards orange and shadows towards teal.
+/// Red runs along x, green along y and blue along z.
+fn lut_data() -> Vec<u8> {
+    let mut data = Vec::with_capacity((LUT_SIZE * LUT_SIZE * LUT_SIZE * 4) as usize);
+    let level = |i: u32| i as f32 / (LUT_SIZE - 1) as f32;
+    for b in 0..LUT_SIZE {
+        for g in 0..LUT_SIZE {
+            for r in 0..LUT_SIZE {
+                let color = [level(r), level(g), level(b)];
+                let luma = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+                let warmth = luma - 0.5;
+                let tint = [0.3 * warmth, 0.05 * warmth, -0.3 * warmth];
+                for (channel, tint) in color.iter().zip(tint) {
+                    let graded = luma + (channel - luma) * 1.3 + tint;
+                    data.push((graded.clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+                data.push(255);
+            }
+        }
+    }
+    data
+}
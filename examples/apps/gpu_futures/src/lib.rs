@@ -0,0 +1,91 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleGpuFutures);
+
+struct ExampleGpuFutures;
+
+impl Guest for ExampleGpuFutures {
+    fn start() {
+        map_while_waiting();
+    }
+}
+
+use wasi::clocks::monotonic_clock;
+use wasi::io::poll;
+use wasi::webgpu::{gpu_futures, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_MAP_READ: u32 = 0x0001;
+const BUFFER_COPY_DST: u32 = 0x0008;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+const MAP_MODE_READ: u32 = 0x0001;
+
+const TIMER: u64 = 10_000_000;
+const DATA: &[u8] = b"mapped while waiting";
+
+/// Maps a buffer and waits for a 10 ms timer, both in the same poll list, and checks that both finish and the mapped
+/// buffer holds what was written.
+fn map_while_waiting() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+    let queue = device.queue();
+
+    let buffer = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("mapped".into()),
+        size: DATA.len() as u64,
+        usage: BUFFER_MAP_READ | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+    queue.write_buffer(&buffer, 0, None, DATA, None);
+    // Writes go out with the next submission.
+    queue.submit(vec![]);
+
+    let map = gpu_futures::map_buffer(&buffer, MAP_MODE_READ, None, None);
+    let map_ready = map.subscribe();
+    // Taken before subscribing, so the timer can't fire less than `TIMER` after it.
+    let start = monotonic_clock::now();
+    let timer = monotonic_clock::subscribe_duration(TIMER);
+
+    let (mut mapped, mut timed_out) = (false, false);
+    while !(mapped && timed_out) {
+        let mut pollables = Vec::new();
+        if !mapped {
+            pollables.push(&map_ready);
+        }
+        if !timed_out {
+            pollables.push(&timer);
+        }
+        for index in poll::poll(&pollables) {
+            if std::ptr::eq(pollables[index as usize], &map_ready) {
+                mapped = true;
+                print(&format!(
+                    "Buffer mapped after {}us",
+                    (monotonic_clock::now() - start) / 1000
+                ));
+            } else {
+                timed_out = true;
+                let elapsed = monotonic_clock::now() - start;
+                print(&format!("Timer fired after {}us", elapsed / 1000));
+                assert!(elapsed >= TIMER, "the timer fired after only {elapsed}ns");
+            }
+        }
+    }
+
+    assert!(map.is_ready());
+    let contents = {
+        let mapped = buffer.get_mapped_range(None, None);
+        (0..mapped.length())
+            .map(|i| mapped.get(i))
+            .collect::<Vec<_>>()
+    };
+    buffer.unmap();
+    print(&format!(
+        "Read back {:?}",
+        String::from_utf8_lossy(&contents)
+    ));
+    assert_eq!(contents, DATA);
+}
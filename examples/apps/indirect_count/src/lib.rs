@@ -0,0 +1,227 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleIndirectCount);
+
+struct ExampleIndirectCount;
+
+impl Guest for ExampleIndirectCount {
+    fn start() {
+        draw_visible_instances();
+    }
+}
+
+use wasi::webgpu::{animation_frame, graphics_context, mini_canvas, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_COPY_DST: u32 = 0x0008;
+const BUFFER_STORAGE: u32 = 0x0080;
+const BUFFER_INDIRECT: u32 = 0x0100;
+
+const INSTANCES: u32 = 16;
+/// vertex-count, instance-count, first-vertex, first-instance.
+const DRAW_ARGS_SIZE: u64 = 4 * 4;
+
+/// Instances are spread along x, and only the ones inside the viewport get a draw.
+const SHADER_CODE: &str = r#"
+const INSTANCES: u32 = 16u;
+
+fn instance_x(instance: u32) -> f32 {
+    return -1.9 + f32(instance) * 0.25;
+}
+
+struct DrawArgs {
+    vertex_count: u32,
+    instance_count: u32,
+    first_vertex: u32,
+    first_instance: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> draws: array<DrawArgs>;
+@group(0) @binding(1) var<storage, read_write> draw_count: atomic<u32>;
+
+@compute @workgroup_size(16)
+fn cull(@builtin(global_invocation_id) id: vec3<u32>) {
+    let instance = id.x;
+    if instance >= INSTANCES || abs(instance_x(instance)) > 1.0 {
+        return;
+    }
+    let slot = atomicAdd(&draw_count, 1u);
+    // first-instance needs the indirect-first-instance feature, so the instance is encoded in the vertex index.
+    draws[slot] = DrawArgs(3u, 1u, instance * 3u, 0u);
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let instance = vertex_index / 3u;
+    let corner = vertex_index % 3u;
+    let x = instance_x(instance) + (f32(corner) - 1.0) * 0.1;
+    let y = f32(corner & 1u) * 0.2 - 0.1;
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.5, 0.0, 1.0);
+}
+"#;
+
+fn draw_visible_instances() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    if !adapter.features().has("multi-draw-indirect-count") {
+        print("Adapter doesn't support multi-draw-indirect-count");
+        return;
+    }
+    let device = adapter.request_device(Some(&webgpu::GpuDeviceDescriptor {
+        required_features: Some(vec![webgpu::GpuFeatureName::MultiDrawIndirectCount]),
+        default_queue: None,
+        label: None,
+    }));
+
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
+        height: 100,
+        width: 300,
+        offscreen: false,
+        title: None,
+        resizable: None,
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
+    });
+    let graphics_context = graphics_context::GraphicsContext::new();
+    canvas.connect_graphics_context(&graphics_context);
+    device
+        .connect_graphics_context(&graphics_context)
+        .expect("timed out creating a surface");
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let cull_pipeline = device.create_compute_pipeline(&webgpu::GpuComputePipelineDescriptor {
+        compute: webgpu::GpuProgrammableStage {
+            module: &shader,
+            entry_point: Some("cull".to_string()),
+            constants: None,
+        },
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+        label: Some("cull".into()),
+    });
+    let render_pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+        label: Some("instances".into()),
+        multiview: None,
+        vertex: webgpu::GpuVertexState {
+            module: &shader,
+            entry_point: "vs_main".to_string(),
+            constants: None,
+            buffers: None,
+        },
+        fragment: Some(webgpu::GpuFragmentState {
+            module: &shader,
+            entry_point: "fs_main".to_string(),
+            constants: None,
+            targets: vec![Some(webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
+                blend: None,
+                write_mask: None,
+            })],
+        }),
+        primitive: None,
+        depth_stencil: None,
+        multisample: None,
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+    });
+
+    let draws = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("draws".into()),
+        size: DRAW_ARGS_SIZE * INSTANCES as u64,
+        usage: BUFFER_STORAGE | BUFFER_INDIRECT,
+        mapped_at_creation: None,
+    });
+    let draw_count = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("draw count".into()),
+        size: 4,
+        usage: BUFFER_STORAGE | BUFFER_INDIRECT | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+    let bind_group = device.create_bind_group(webgpu::GpuBindGroupDescriptor {
+        layout: &cull_pipeline.get_bind_group_layout(0),
+        entries: vec![
+            webgpu::GpuBindGroupEntry {
+                binding: 0,
+                resource: webgpu::GpuBindingResource::GpuBufferBinding(webgpu::GpuBufferBinding {
+                    buffer: &draws,
+                    offset: None,
+                    size: None,
+                }),
+            },
+            webgpu::GpuBindGroupEntry {
+                binding: 1,
+                resource: webgpu::GpuBindingResource::GpuBufferBinding(webgpu::GpuBufferBinding {
+                    buffer: &draw_count,
+                    offset: None,
+                    size: None,
+                }),
+            },
+        ],
+        label: None,
+    });
+
+    let frame_listener = animation_frame::listener(&canvas);
+    let frame_pollable = frame_listener.subscribe();
+    loop {
+        frame_pollable.block();
+        frame_listener.get();
+
+        let encoder = device.create_command_encoder(None);
+
+        encoder.clear_buffer(&draw_count, None, None);
+        let cull_pass = encoder.begin_compute_pass(None);
+        cull_pass.set_pipeline(&cull_pipeline);
+        cull_pass.set_bind_group(0, &bind_group, None);
+        cull_pass.dispatch_workgroups(1, None, None);
+        webgpu::GpuComputePassEncoder::end(cull_pass, &encoder);
+
+        let texture =
+            webgpu::GpuTexture::from_graphics_buffer(graphics_context.get_current_buffer());
+        let view = texture.create_view(None);
+        let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+            label: None,
+            color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                clear_value: Some(webgpu::GpuColorDictOrListF64::GpuColorDict(
+                    webgpu::GpuColorDict {
+                        r: 0.0,
+                        g: 0.0,
+                        b: 0.1,
+                        a: 0.0,
+                    },
+                )),
+                load_op: webgpu::GpuLoadOp::Clear,
+                store_op: webgpu::GpuStoreOp::Store,
+            }],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+            max_draw_count: None,
+        });
+        render_pass.set_pipeline(&render_pipeline);
+        render_pass.draw_indirect_count(&draws, 0, &draw_count, 0, INSTANCES);
+        webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+
+        device
+            .queue()
+            .submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+        graphics_context.present();
+    }
+}
@@ -0,0 +1,232 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleInstancedQuads);
+
+struct ExampleInstancedQuads;
+
+impl Guest for ExampleInstancedQuads {
+    fn start() {
+        draw_instanced();
+    }
+}
+
+use wasi::webgpu::{animation_frame, graphics_context, mini_canvas, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_COPY_DST: u32 = 0x0008;
+const BUFFER_VERTEX: u32 = 0x0020;
+
+const WIDTH: u32 = 400;
+const HEIGHT: u32 = 250;
+const COLUMNS: u32 = 40;
+const ROWS: u32 = 25;
+
+/// Two triangles, shared by every quad.
+const CORNERS: [[f32; 2]; 6] = [
+    [-1.0, -1.0],
+    [1.0, -1.0],
+    [1.0, 1.0],
+    [-1.0, -1.0],
+    [1.0, 1.0],
+    [-1.0, 1.0],
+];
+/// Per quad: a float32x2 center, a float32 half size and an unorm8x4 color.
+const INSTANCE_STRIDE: u64 = 16;
+
+const SHADER_CODE: &str = r#"
+// Keeps quads square on the wide canvas.
+const ASPECT: f32 = 1.6;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    @location(0) corner: vec2<f32>,
+    @location(1) center: vec2<f32>,
+    @location(2) half_size: f32,
+    @location(3) color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(center + corner * vec2<f32>(half_size, half_size * ASPECT), 0.0, 1.0);
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
+"#;
+
+/// Draws a grid of 1,000 quads with a single draw call: the corners come from a per-vertex buffer,
+/// position, size and color from a per-instance buffer.
+fn draw_instanced() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+    let queue = device.queue();
+
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
+        height: HEIGHT,
+        width: WIDTH,
+        offscreen: false,
+        title: None,
+        resizable: Some(false),
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
+    });
+    let graphics_context = graphics_context::GraphicsContext::new();
+    canvas.connect_graphics_context(&graphics_context);
+    device
+        .connect_graphics_context(&graphics_context)
+        .expect("timed out creating a surface");
+
+    let corners: Vec<u8> = CORNERS
+        .iter()
+        .flatten()
+        .flat_map(|f| f.to_le_bytes())
+        .collect();
+    let instances = instance_data();
+    let vertex_buffer = |label: &str, data: &[u8]| {
+        let buffer = device.create_buffer(&webgpu::GpuBufferDescriptor {
+            label: Some(label.into()),
+            size: data.len() as u64,
+            usage: BUFFER_VERTEX | BUFFER_COPY_DST,
+            mapped_at_creation: None,
+        });
+        queue.write_buffer(&buffer, 0, None, data, None);
+        buffer
+    };
+    let corner_buffer = vertex_buffer("corners", &corners);
+    let instance_buffer = vertex_buffer("instances", &instances);
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+        label: Some("instanced quads".into()),
+        multiview: None,
+        vertex: webgpu::GpuVertexState {
+            module: &shader,
+            entry_point: "vs_main".to_string(),
+            constants: None,
+            buffers: Some(vec![
+                webgpu::GpuVertexBufferLayout {
+                    array_stride: 8,
+                    step_mode: Some(webgpu::GpuVertexStepMode::Vertex),
+                    attributes: vec![webgpu::GpuVertexAttribute {
+                        format: webgpu::GpuVertexFormat::Float32x2,
+                        offset: 0,
+                        shader_location: 0,
+                    }],
+                },
+                webgpu::GpuVertexBufferLayout {
+                    array_stride: INSTANCE_STRIDE,
+                    step_mode: Some(webgpu::GpuVertexStepMode::Instance),
+                    attributes: vec![
+                        webgpu::GpuVertexAttribute {
+                            format: webgpu::GpuVertexFormat::Float32x2,
+                            offset: 0,
+                            shader_location: 1,
+                        },
+                        webgpu::GpuVertexAttribute {
+                            format: webgpu::GpuVertexFormat::Float32,
+                            offset: 8,
+                            shader_location: 2,
+                        },
+                        webgpu::GpuVertexAttribute {
+                            format: webgpu::GpuVertexFormat::Unorm8x4,
+                            offset: 12,
+                            shader_location: 3,
+                        },
+                    ],
+                },
+            ]),
+        },
+        fragment: Some(webgpu::GpuFragmentState {
+            module: &shader,
+            entry_point: "fs_main".to_string(),
+            constants: None,
+            targets: vec![Some(webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
+                blend: None,
+                write_mask: None,
+            })],
+        }),
+        primitive: None,
+        depth_stencil: None,
+        multisample: None,
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+    });
+
+    let frame_listener = animation_frame::listener(&canvas);
+    let frame_pollable = frame_listener.subscribe();
+    loop {
+        frame_pollable.block();
+        frame_listener.get();
+
+        let encoder = device.create_command_encoder(None);
+        let texture =
+            webgpu::GpuTexture::from_graphics_buffer(graphics_context.get_current_buffer());
+        let view = texture.create_view(None);
+        let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+            label: None,
+            color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                clear_value: Some(webgpu::GpuColorDictOrListF64::GpuColorDict(
+                    webgpu::GpuColorDict {
+                        r: 0.0,
+                        g: 0.0,
+                        b: 0.0,
+                        a: 1.0,
+                    },
+                )),
+                load_op: webgpu::GpuLoadOp::Clear,
+                store_op: webgpu::GpuStoreOp::Store,
+            }],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+            max_draw_count: None,
+        });
+        render_pass.set_pipeline(&pipeline);
+        render_pass.set_vertex_buffer(0, &corner_buffer, 0, corners.len() as u64);
+        render_pass.set_vertex_buffer(1, &instance_buffer, 0, instances.len() as u64);
+        render_pass.draw(CORNERS.len() as u32, COLUMNS * ROWS, 0, 0);
+        webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+
+        queue.submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+        graphics_context.present();
+    }
+}
+
+/// One quad per grid cell, shrinking towards the bottom and shading from red on the left to blue on the right.
+fn instance_data() -> Vec<u8> {
+    let mut data = Vec::with_capacity((COLUMNS * ROWS) as usize * INSTANCE_STRIDE as usize);
+    for row in 0..ROWS {
+        for column in 0..COLUMNS {
+            let x = (column as f32 + 0.5) / COLUMNS as f32 * 2.0 - 1.0;
+            let y = 1.0 - (row as f32 + 0.5) / ROWS as f32 * 2.0;
+            let half_size = 0.8 / COLUMNS as f32 * (1.0 - row as f32 / ROWS as f32 * 0.6);
+            let shade = (column * 255 / (COLUMNS - 1)) as u8;
+            data.extend_from_slice(&x.to_le_bytes());
+            data.extend_from_slice(&y.to_le_bytes());
+            data.extend_from_slice(&half_size.to_le_bytes());
+            data.extend_from_slice(&[255 - shade, 96, shade, 255]);
+        }
+    }
+    data
+}
@@ -0,0 +1,129 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleInvalidCalls);
+
+struct ExampleInvalidCalls;
+
+impl Guest for ExampleInvalidCalls {
+    fn start() {
+        invalid_calls();
+    }
+}
+
+use wasi::webgpu::webgpu;
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_MAP_READ: u32 = 0x0001;
+const BUFFER_MAP_WRITE: u32 = 0x0002;
+const BUFFER_COPY_DST: u32 = 0x0008;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gputextureusageflags
+const TEXTURE_COPY_DST: u32 = 0x02;
+
+/// Makes calls the host has to reject, and prints the error each of them is reported as.
+fn invalid_calls() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+    let queue = device.queue();
+
+    expect_error(&device, "buffer that's both map-read and map-write", || {
+        device.create_buffer(&webgpu::GpuBufferDescriptor {
+            label: None,
+            size: 4,
+            usage: BUFFER_MAP_READ | BUFFER_MAP_WRITE,
+            mapped_at_creation: None,
+        });
+    });
+
+    let texture = device.create_texture(&webgpu::GpuTextureDescriptor {
+        size: webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+            width: 4,
+            height: None,
+            depth_or_array_layers: None,
+        }),
+        mip_level_count: None,
+        sample_count: None,
+        dimension: webgpu::GpuTextureDimension::TwoD,
+        format: webgpu::GpuTextureFormat::Rgba8unorm,
+        usage: TEXTURE_COPY_DST,
+        view_formats: None,
+        label: None,
+    });
+    expect_error(&device, "write-texture with too little data", || {
+        queue.write_texture(
+            &webgpu::GpuImageCopyTexture {
+                texture: &texture,
+                mip_level: None,
+                origin: None,
+                aspect: None,
+            },
+            &[0; 4],
+            webgpu::GpuImageDataLayout {
+                offset: None,
+                bytes_per_row: None,
+                rows_per_image: None,
+            },
+            &webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+                width: 4,
+                height: None,
+                depth_or_array_layers: None,
+            }),
+        );
+    });
+
+    let buffer = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: None,
+        size: 16,
+        usage: BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+    expect_error(&device, "clear-buffer at an unaligned offset", || {
+        let encoder = device.create_command_encoder(None);
+        encoder.clear_buffer(&buffer, Some(3), None);
+        queue.submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+    });
+    expect_error(&device, "unmapping a buffer that isn't mapped", || {
+        buffer.unmap();
+    });
+
+    expect_error(&device, "create-texture with an empty extent list", || {
+        device.create_texture(&webgpu::GpuTextureDescriptor {
+            size: webgpu::GpuExtent3D::ListGpuIntegerCoordinate(vec![]),
+            mip_level_count: None,
+            sample_count: None,
+            dimension: webgpu::GpuTextureDimension::TwoD,
+            format: webgpu::GpuTextureFormat::Rgba8unorm,
+            usage: TEXTURE_COPY_DST,
+            view_formats: None,
+            label: None,
+        });
+    });
+    expect_error(&device, "binding without a type", || {
+        device.create_bind_group_layout(&webgpu::GpuBindGroupLayoutDescriptor {
+            entries: vec![webgpu::GpuBindGroupLayoutEntry {
+                binding: 0,
+                visibility: 0,
+                buffer: None,
+                sampler: None,
+                texture: None,
+                storage_texture: None,
+                external_texture: None,
+            }],
+            label: None,
+        });
+    });
+
+    print("Host is still running");
+}
+
+fn expect_error(device: &webgpu::GpuDevice, name: &str, f: impl FnOnce()) {
+    device.push_error_scope(webgpu::GpuErrorFilter::Validation);
+    f();
+    match device.pop_error_scope() {
+        Some(error) => print(&format!("{name}: {}", error.message())),
+        None => print(&format!("{name}: no error")),
+    }
+}
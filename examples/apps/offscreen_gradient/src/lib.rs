@@ -0,0 +1,168 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleOffscreenGradient);
+
+struct ExampleOffscreenGradient;
+
+impl Guest for ExampleOffscreenGradient {
+    fn start() {
+        render_offscreen();
+    }
+}
+
+use wasi::webgpu::webgpu;
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_MAP_READ: u32 = 0x0001;
+const BUFFER_COPY_DST: u32 = 0x0008;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gputextureusageflags
+const TEXTURE_COPY_SRC: u32 = 0x01;
+const TEXTURE_RENDER_ATTACHMENT: u32 = 0x10;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+const MAP_MODE_READ: u32 = 0x0001;
+
+const SIZE: u32 = 64;
+// Rows of 64 rgba8 pixels are 256 bytes, which is already the alignment copies need.
+const BYTES_PER_ROW: u32 = SIZE * 4;
+
+const SHADER_CODE: &str = r#"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> VertexOutput {
+    // A triangle covering the whole target.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.uv.x, in.uv.y, 0.5, 1.0);
+}
+"#;
+
+/// Renders a gradient into a texture of its own and reads it back, without ever creating a canvas.
+fn render_offscreen() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+    let queue = device.queue();
+
+    let extent = || {
+        webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+            width: SIZE,
+            height: Some(SIZE),
+            depth_or_array_layers: None,
+        })
+    };
+    let texture = device.create_texture(&webgpu::GpuTextureDescriptor {
+        size: extent(),
+        mip_level_count: None,
+        sample_count: None,
+        dimension: webgpu::GpuTextureDimension::TwoD,
+        format: webgpu::GpuTextureFormat::Rgba8unorm,
+        usage: TEXTURE_RENDER_ATTACHMENT | TEXTURE_COPY_SRC,
+        view_formats: None,
+        label: Some("gradient".into()),
+    });
+    let view = texture.create_view(None);
+    let read_back = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("gradient read back".into()),
+        size: (BYTES_PER_ROW * SIZE) as u64,
+        usage: BUFFER_MAP_READ | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+        label: Some("gradient".into()),
+        multiview: None,
+        vertex: webgpu::GpuVertexState {
+            module: &shader,
+            entry_point: "vs_main".to_string(),
+            constants: None,
+            buffers: None,
+        },
+        fragment: Some(webgpu::GpuFragmentState {
+            module: &shader,
+            entry_point: "fs_main".to_string(),
+            constants: None,
+            targets: vec![Some(webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Rgba8unorm,
+                blend: None,
+                write_mask: None,
+            })],
+        }),
+        primitive: None,
+        depth_stencil: None,
+        multisample: None,
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+    });
+
+    let encoder = device.create_command_encoder(None);
+    let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+        label: None,
+        color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+            view: &view,
+            depth_slice: None,
+            resolve_target: None,
+            clear_value: None,
+            load_op: webgpu::GpuLoadOp::Clear,
+            store_op: webgpu::GpuStoreOp::Store,
+        }],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+        max_draw_count: None,
+    });
+    render_pass.set_pipeline(&pipeline);
+    render_pass.draw(3, 1, 0, 0);
+    webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+    encoder.copy_texture_to_buffer(
+        &webgpu::GpuImageCopyTexture {
+            texture: &texture,
+            mip_level: None,
+            origin: None,
+            aspect: None,
+        },
+        &webgpu::GpuImageCopyBuffer {
+            buffer: &read_back,
+            offset: None,
+            bytes_per_row: Some(BYTES_PER_ROW),
+            rows_per_image: None,
+        },
+        &extent(),
+    );
+    queue.submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+
+    read_back.map_async(MAP_MODE_READ, None, None);
+    // FNV-1a, so the same image always prints the same checksum.
+    let mut checksum: u32 = 0x811c9dc5;
+    {
+        let mapped = read_back.get_mapped_range(None, None);
+        for i in 0..mapped.length() {
+            checksum ^= mapped.get(i) as u32;
+            checksum = checksum.wrapping_mul(0x01000193);
+        }
+    }
+    read_back.unmap();
+
+    print(&format!(
+        "Rendered a {SIZE}x{SIZE} gradient offscreen, checksum {checksum:08x}"
+    ));
+}
@@ -0,0 +1,131 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleOverrideConstants);
+
+struct ExampleOverrideConstants;
+
+impl Guest for ExampleOverrideConstants {
+    fn start() {
+        run_with_constants();
+    }
+}
+
+use wasi::webgpu::{gpu_futures, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_MAP_READ: u32 = 0x0001;
+const BUFFER_COPY_SRC: u32 = 0x0004;
+const BUFFER_COPY_DST: u32 = 0x0008;
+const BUFFER_STORAGE: u32 = 0x0080;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+const MAP_MODE_READ: u32 = 0x0001;
+
+const VALUES: u32 = 8;
+
+/// `scale` is overridden by name, `offset` by its id.
+const SHADER_CODE: &str = r#"
+override scale: f32 = 1.0;
+@id(1) override offset: u32 = 0u;
+
+@group(0) @binding(0) var<storage, read_write> values: array<f32>;
+
+@compute @workgroup_size(8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    values[id.x] = f32(id.x + offset) * scale;
+}
+"#;
+
+/// Creates two pipelines from the same shader module, one with the shader's defaults and one with overridden
+/// constants, and prints what each of them computed.
+fn run_with_constants() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let defaults = run(&device, &shader, None);
+    print(&format!("Defaults: {defaults:?}"));
+    let overridden = run(
+        &device,
+        &shader,
+        Some(vec![("scale".to_string(), 0.5), ("1".to_string(), 10.0)]),
+    );
+    print(&format!("scale = 0.5, offset = 10: {overridden:?}"));
+}
+
+fn run(
+    device: &webgpu::GpuDevice,
+    shader: &webgpu::GpuShaderModule,
+    constants: Option<Vec<(String, f64)>>,
+) -> Vec<f32> {
+    let pipeline = device.create_compute_pipeline(&webgpu::GpuComputePipelineDescriptor {
+        compute: webgpu::GpuProgrammableStage {
+            module: shader,
+            entry_point: Some("main".to_string()),
+            constants,
+        },
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+        label: None,
+    });
+
+    let size = VALUES as u64 * 4;
+    let values = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("values".into()),
+        size,
+        usage: BUFFER_STORAGE | BUFFER_COPY_SRC,
+        mapped_at_creation: None,
+    });
+    let read_back = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("read back".into()),
+        size,
+        usage: BUFFER_MAP_READ | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+    let bind_group = device.create_bind_group(webgpu::GpuBindGroupDescriptor {
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: vec![webgpu::GpuBindGroupEntry {
+            binding: 0,
+            resource: webgpu::GpuBindingResource::GpuBufferBinding(webgpu::GpuBufferBinding {
+                buffer: &values,
+                offset: None,
+                size: None,
+            }),
+        }],
+        label: None,
+    });
+
+    let encoder = device.create_command_encoder(None);
+    let pass = encoder.begin_compute_pass(None);
+    pass.set_pipeline(&pipeline);
+    pass.set_bind_group(0, &bind_group, None);
+    pass.dispatch_workgroups(1, None, None);
+    webgpu::GpuComputePassEncoder::end(pass, &encoder);
+    encoder.copy_buffer_to_buffer(&values, 0, &read_back, 0, size);
+    device
+        .queue()
+        .submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+
+    gpu_futures::map_buffer(&read_back, MAP_MODE_READ, None, None)
+        .subscribe()
+        .block();
+    let bytes = {
+        let mapped = read_back.get_mapped_range(None, None);
+        (0..mapped.length())
+            .map(|i| mapped.get(i))
+            .collect::<Vec<_>>()
+    };
+    read_back.unmap();
+    bytes
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
@@ -17,10 +17,15 @@ use std::cmp::min;
 use wasi::webgpu::{animation_frame, frame_buffer, graphics_context, mini_canvas, pointer_events};
 
 fn draw_rectangle() {
-    let canvas = mini_canvas::MiniCanvas::new(mini_canvas::CreateDesc {
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
         height: 100,
         width: 100,
         offscreen: false,
+        title: None,
+        resizable: None,
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
     });
     let graphics_context = graphics_context::GraphicsContext::new();
     canvas.connect_graphics_context(&graphics_context);
@@ -56,7 +61,7 @@ fn draw_rectangle() {
 
         if pollables_res.contains(&2) {
             frame_listener.get();
-            print(&format!("frame event"));
+            print("frame event");
 
             let graphics_buffer = graphics_context.get_current_buffer();
 
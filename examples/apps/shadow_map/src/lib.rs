@@ -0,0 +1,457 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleShadowMap);
+
+struct ExampleShadowMap;
+
+impl Guest for ExampleShadowMap {
+    fn start() {
+        draw_shadowed();
+    }
+}
+
+use wasi::webgpu::{animation_frame, graphics_context, mini_canvas, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_COPY_DST: u32 = 0x0008;
+const BUFFER_VERTEX: u32 = 0x0020;
+const BUFFER_UNIFORM: u32 = 0x0040;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gputextureusageflags
+const TEXTURE_BINDING: u32 = 0x04;
+const TEXTURE_RENDER_ATTACHMENT: u32 = 0x10;
+
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 400;
+const SHADOW_MAP_SIZE: u32 = 1024;
+/// Per vertex: a float32x3 position, a float32x3 normal and a float32x3 color.
+const VERTEX_STRIDE: u64 = 36;
+
+const SHADER_CODE: &str = r#"
+const ASPECT: f32 = 1.5;
+
+struct Uniforms {
+    // Points from the scene towards the light.
+    light_dir: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var shadow_map: texture_depth_2d;
+@group(0) @binding(2) var shadow_sampler: sampler_comparison;
+
+fn look_at(eye: vec3<f32>, center: vec3<f32>, up: vec3<f32>) -> mat4x4<f32> {
+    let f = normalize(center - eye);
+    let s = normalize(cross(f, up));
+    let u = cross(s, f);
+    return mat4x4<f32>(
+        vec4<f32>(s.x, u.x, -f.x, 0.0),
+        vec4<f32>(s.y, u.y, -f.y, 0.0),
+        vec4<f32>(s.z, u.z, -f.z, 0.0),
+        vec4<f32>(-dot(s, eye), -dot(u, eye), dot(f, eye), 1.0),
+    );
+}
+
+fn orthographic(extent: f32, near: f32, far: f32) -> mat4x4<f32> {
+    return mat4x4<f32>(
+        vec4<f32>(1.0 / extent, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, 1.0 / extent, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, 1.0 / (near - far), 0.0),
+        vec4<f32>(0.0, 0.0, near / (near - far), 1.0),
+    );
+}
+
+fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> mat4x4<f32> {
+    let f = 1.0 / tan(fov_y * 0.5);
+    let r = far / (near - far);
+    return mat4x4<f32>(
+        vec4<f32>(f / aspect, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, f, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, r, -1.0),
+        vec4<f32>(0.0, 0.0, r * near, 0.0),
+    );
+}
+
+fn light_view_proj() -> mat4x4<f32> {
+    let eye = normalize(uniforms.light_dir.xyz) * 8.0;
+    return orthographic(5.0, 1.0, 16.0) * look_at(eye, vec3<f32>(0.0), vec3<f32>(0.0, 1.0, 0.0));
+}
+
+@vertex
+fn vs_shadow(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return light_view_proj() * vec4<f32>(position, 1.0);
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+}
+
+@vertex
+fn vs_main(
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+) -> VertexOutput {
+    let view = look_at(vec3<f32>(4.0, 4.0, 6.0), vec3<f32>(0.0, 0.5, 0.0), vec3<f32>(0.0, 1.0, 0.0));
+    var out: VertexOutput;
+    out.position = perspective(0.8, ASPECT, 0.1, 50.0) * view * vec4<f32>(position, 1.0);
+    out.world_position = position;
+    out.normal = normal;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let light = light_view_proj() * vec4<f32>(in.world_position, 1.0);
+    let uv = light.xy * vec2<f32>(0.5, -0.5) + 0.5;
+    // The shadow pass' depth bias is what keeps lit surfaces from shadowing themselves here.
+    let lit = textureSampleCompareLevel(shadow_map, shadow_sampler, uv, light.z);
+    let diffuse = max(dot(normalize(in.normal), normalize(uniforms.light_dir.xyz)), 0.0);
+    return vec4<f32>(in.color * (0.2 + 0.8 * diffuse * lit), 1.0);
+}
+"#;
+
+/// Draws two cubes on a ground plane, lit by a circling light that casts shadows through a shadow map.
+///
+/// The shadow pass renders depth only, offset with a constant and a slope scaled depth bias, so the surfaces that
+/// produced the shadow map don't come out as striped ("shadow acne") when compared against it.
+fn draw_shadowed() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+    let queue = device.queue();
+
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
+        height: HEIGHT,
+        width: WIDTH,
+        offscreen: false,
+        title: None,
+        resizable: Some(false),
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
+    });
+    let graphics_context = graphics_context::GraphicsContext::new();
+    canvas.connect_graphics_context(&graphics_context);
+    device
+        .connect_graphics_context(&graphics_context)
+        .expect("timed out creating a surface");
+
+    let vertices = vertex_data();
+    let vertex_count = (vertices.len() as u64 / VERTEX_STRIDE) as u32;
+    let vertex_buffer = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("vertices".into()),
+        size: vertices.len() as u64,
+        usage: BUFFER_VERTEX | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+    queue.write_buffer(&vertex_buffer, 0, None, &vertices, None);
+    let uniform_buffer = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("uniforms".into()),
+        size: 16,
+        usage: BUFFER_UNIFORM | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+
+    let depth_texture =
+        |label: &str, size: [u32; 2], format: webgpu::GpuTextureFormat, usage: u32| {
+            device
+                .create_texture(&webgpu::GpuTextureDescriptor {
+                    size: webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+                        width: size[0],
+                        height: Some(size[1]),
+                        depth_or_array_layers: None,
+                    }),
+                    mip_level_count: None,
+                    sample_count: None,
+                    dimension: webgpu::GpuTextureDimension::TwoD,
+                    format,
+                    usage,
+                    view_formats: None,
+                    label: Some(label.into()),
+                })
+                .create_view(None)
+        };
+    let shadow_view = depth_texture(
+        "shadow map",
+        [SHADOW_MAP_SIZE; 2],
+        webgpu::GpuTextureFormat::Depth32float,
+        TEXTURE_RENDER_ATTACHMENT | TEXTURE_BINDING,
+    );
+    let depth_view = depth_texture(
+        "depth",
+        [WIDTH, HEIGHT],
+        webgpu::GpuTextureFormat::Depth24plus,
+        TEXTURE_RENDER_ATTACHMENT,
+    );
+    let shadow_sampler = device.create_sampler(Some(&webgpu::GpuSamplerDescriptor {
+        label: None,
+        address_mode_u: Some(webgpu::GpuAddressMode::ClampToEdge),
+        address_mode_v: Some(webgpu::GpuAddressMode::ClampToEdge),
+        address_mode_w: None,
+        mag_filter: Some(webgpu::GpuFilterMode::Linear),
+        min_filter: Some(webgpu::GpuFilterMode::Linear),
+        mipmap_filter: None,
+        lod_min_clamp: None,
+        lod_max_clamp: None,
+        compare: Some(webgpu::GpuCompareFunction::LessEqual),
+        max_anisotropy: None,
+    }));
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let attribute = |shader_location: u32| webgpu::GpuVertexAttribute {
+        format: webgpu::GpuVertexFormat::Float32x3,
+        offset: shader_location as u64 * 12,
+        shader_location,
+    };
+    let shadow_pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+        label: Some("shadow".into()),
+        multiview: None,
+        vertex: webgpu::GpuVertexState {
+            module: &shader,
+            entry_point: "vs_shadow".to_string(),
+            constants: None,
+            buffers: Some(vec![webgpu::GpuVertexBufferLayout {
+                array_stride: VERTEX_STRIDE,
+                step_mode: None,
+                attributes: vec![attribute(0)],
+            }]),
+        },
+        fragment: None,
+        primitive: None,
+        depth_stencil: Some(webgpu::GpuDepthStencilState {
+            format: webgpu::GpuTextureFormat::Depth32float,
+            depth_write_enabled: Some(true),
+            depth_compare: Some(webgpu::GpuCompareFunction::Less),
+            stencil_front: None,
+            stencil_back: None,
+            stencil_read_mask: None,
+            stencil_write_mask: None,
+            // The constant bias is in units of the format's smallest depth step, which for a float format depends on
+            // the depth being offset; the slope scale covers surfaces at grazing angles to the light. The clamp keeps
+            // steep slopes from pushing the shadow too far off its caster.
+            depth_bias: Some(2),
+            depth_bias_slope_scale: Some(2.0),
+            depth_bias_clamp: Some(0.01),
+        }),
+        multisample: None,
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+    });
+    let pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+        label: Some("shadowed".into()),
+        multiview: None,
+        vertex: webgpu::GpuVertexState {
+            module: &shader,
+            entry_point: "vs_main".to_string(),
+            constants: None,
+            buffers: Some(vec![webgpu::GpuVertexBufferLayout {
+                array_stride: VERTEX_STRIDE,
+                step_mode: None,
+                attributes: vec![attribute(0), attribute(1), attribute(2)],
+            }]),
+        },
+        fragment: Some(webgpu::GpuFragmentState {
+            module: &shader,
+            entry_point: "fs_main".to_string(),
+            constants: None,
+            targets: vec![Some(webgpu::GpuColorTargetState {
+                format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
+                blend: None,
+                write_mask: None,
+            })],
+        }),
+        primitive: None,
+        depth_stencil: Some(webgpu::GpuDepthStencilState {
+            format: webgpu::GpuTextureFormat::Depth24plus,
+            depth_write_enabled: Some(true),
+            depth_compare: Some(webgpu::GpuCompareFunction::Less),
+            stencil_front: None,
+            stencil_back: None,
+            stencil_read_mask: None,
+            stencil_write_mask: None,
+            depth_bias: None,
+            depth_bias_slope_scale: None,
+            depth_bias_clamp: None,
+        }),
+        multisample: None,
+        layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+    });
+
+    let uniform_entry = || webgpu::GpuBindGroupEntry {
+        binding: 0,
+        resource: webgpu::GpuBindingResource::GpuBufferBinding(webgpu::GpuBufferBinding {
+            buffer: &uniform_buffer,
+            offset: None,
+            size: None,
+        }),
+    };
+    let shadow_bind_group = device.create_bind_group(webgpu::GpuBindGroupDescriptor {
+        layout: &shadow_pipeline.get_bind_group_layout(0),
+        entries: vec![uniform_entry()],
+        label: None,
+    });
+    let bind_group = device.create_bind_group(webgpu::GpuBindGroupDescriptor {
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: vec![
+            uniform_entry(),
+            webgpu::GpuBindGroupEntry {
+                binding: 1,
+                resource: webgpu::GpuBindingResource::GpuTextureView(&shadow_view),
+            },
+            webgpu::GpuBindGroupEntry {
+                binding: 2,
+                resource: webgpu::GpuBindingResource::GpuSampler(&shadow_sampler),
+            },
+        ],
+        label: None,
+    });
+
+    let frame_listener = animation_frame::listener(&canvas);
+    let frame_pollable = frame_listener.subscribe();
+    let mut frame = 0u32;
+    loop {
+        frame_pollable.block();
+        frame_listener.get();
+
+        let angle = frame as f32 * 0.01;
+        frame = frame.wrapping_add(1);
+        let light_dir: Vec<u8> = [angle.cos() * 2.0, 3.0, angle.sin() * 2.0, 0.0]
+            .iter()
+            .flat_map(|f| f.to_le_bytes())
+            .collect();
+        queue.write_buffer(&uniform_buffer, 0, None, &light_dir, None);
+
+        let encoder = device.create_command_encoder(None);
+        let shadow_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+            label: Some("shadow".into()),
+            color_attachments: vec![],
+            depth_stencil_attachment: Some(webgpu::GpuRenderPassDepthStencilAttachment {
+                view: &shadow_view,
+                depth_clear_value: Some(1.0),
+                depth_load_op: Some(webgpu::GpuLoadOp::Clear),
+                depth_store_op: Some(webgpu::GpuStoreOp::Store),
+                depth_read_only: None,
+                stencil_clear_value: None,
+                stencil_load_op: None,
+                stencil_store_op: None,
+                stencil_read_only: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+            max_draw_count: None,
+        });
+        shadow_pass.set_pipeline(&shadow_pipeline);
+        shadow_pass.set_bind_group(0, &shadow_bind_group, None);
+        shadow_pass.set_vertex_buffer(0, &vertex_buffer, 0, vertices.len() as u64);
+        shadow_pass.draw(vertex_count, 1, 0, 0);
+        webgpu::GpuRenderPassEncoder::end(shadow_pass, &encoder);
+
+        let texture =
+            webgpu::GpuTexture::from_graphics_buffer(graphics_context.get_current_buffer());
+        let view = texture.create_view(None);
+        let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+            label: None,
+            color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                clear_value: Some(webgpu::GpuColorDictOrListF64::GpuColorDict(
+                    webgpu::GpuColorDict {
+                        r: 0.4,
+                        g: 0.6,
+                        b: 0.8,
+                        a: 1.0,
+                    },
+                )),
+                load_op: webgpu::GpuLoadOp::Clear,
+                store_op: webgpu::GpuStoreOp::Store,
+            }],
+            depth_stencil_attachment: Some(webgpu::GpuRenderPassDepthStencilAttachment {
+                view: &depth_view,
+                depth_clear_value: Some(1.0),
+                depth_load_op: Some(webgpu::GpuLoadOp::Clear),
+                depth_store_op: Some(webgpu::GpuStoreOp::Discard),
+                depth_read_only: None,
+                stencil_clear_value: None,
+                stencil_load_op: None,
+                stencil_store_op: None,
+                stencil_read_only: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+            max_draw_count: None,
+        });
+        render_pass.set_pipeline(&pipeline);
+        render_pass.set_bind_group(0, &bind_group, None);
+        render_pass.set_vertex_buffer(0, &vertex_buffer, 0, vertices.len() as u64);
+        render_pass.draw(vertex_count, 1, 0, 0);
+        webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+
+        queue.submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+        graphics_context.present();
+    }
+}
+
+/// A ground plane and two cubes resting on it, as a triangle list.
+fn vertex_data() -> Vec<u8> {
+    let mut vertices = Vec::new();
+    // A quad spanning `center` ± `u` ± `v`.
+    let mut quad = |center: [f32; 3], normal: [f32; 3], u: [f32; 3], v: [f32; 3], color| {
+        for (a, b) in [
+            (-1.0, -1.0),
+            (1.0, -1.0),
+            (1.0, 1.0),
+            (-1.0, -1.0),
+            (1.0, 1.0),
+            (-1.0, 1.0),
+        ] {
+            let corner = [0, 1, 2].map(|i| center[i] + u[i] * a + v[i] * b);
+            vertices.push([corner, normal, color]);
+        }
+    };
+    quad(
+        [0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 4.0],
+        [4.0, 0.0, 0.0],
+        [0.6, 0.6, 0.6],
+    );
+    for (center, size, color) in [
+        ([-0.8, 0.5, 0.0], 0.5, [0.9, 0.5, 0.2]),
+        ([1.2, 0.35, 1.0], 0.35, [0.2, 0.6, 0.9]),
+    ] {
+        let axis = |i: usize, length: f32| [0, 1, 2].map(|j| if j == i % 3 { length } else { 0.0 });
+        for i in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let normal = axis(i, sign);
+                let face_center = [0, 1, 2].map(|j| center[j] + normal[j] * size);
+                quad(
+                    face_center,
+                    normal,
+                    axis(i + 1, size),
+                    axis(i + 2, size),
+                    color,
+                );
+            }
+        }
+    }
+    vertices
+        .iter()
+        .flatten()
+        .flatten()
+        .flat_map(|f: &f32| f.to_le_bytes())
+        .collect()
+}
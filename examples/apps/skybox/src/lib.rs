@@ -76,7 +76,7 @@ impl Guest for ExampleSkybox {
 
             if pollables_res.contains(&5) {
                 frame_listener.get();
-                print(&format!("frame event"));
+                print("frame event");
                 example.render();
             }
         }
@@ -190,15 +190,25 @@ impl Example {
     }
 
     fn init(width: u32, height: u32) -> Self {
-        let device = webgpu::get_gpu().request_adapter(None).request_device(None);
-        let canvas = mini_canvas::MiniCanvas::new(mini_canvas::CreateDesc {
+        let device = webgpu::get_gpu()
+            .request_adapter(None)
+            .expect("no gpu adapter")
+            .request_device(None);
+        let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
             height,
             width,
             offscreen: false,
+            title: None,
+            resizable: None,
+            decorations: None,
+            fullscreen: None,
+            transparent: None,
         });
         let graphics_context = graphics_context::GraphicsContext::new();
         canvas.connect_graphics_context(&graphics_context);
-        device.connect_graphics_context(&graphics_context);
+        device
+            .connect_graphics_context(&graphics_context)
+            .expect("timed out creating a surface");
 
         let height = canvas.height();
         let width = canvas.width();
@@ -310,20 +320,26 @@ impl Example {
         let pipeline_layout = device.create_pipeline_layout(&webgpu::GpuPipelineLayoutDescriptor {
             label: None,
             bind_group_layouts: vec![&bind_group_layout],
+            push_constant_ranges: None,
         });
 
         let sky_pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
-            // label: Some("Sky"),
-            layout: Some(&pipeline_layout),
+            label: Some("Sky".into()),
+            multiview: None,
+            layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuPipelineLayout(
+                &pipeline_layout,
+            ),
             vertex: webgpu::GpuVertexState {
                 module: &shader,
                 entry_point: "vs_sky".into(),
+                constants: None,
                 // buffers: None,
                 buffers: Some(vec![]),
             },
             fragment: Some(webgpu::GpuFragmentState {
                 module: &shader,
                 entry_point: "fs_sky".into(),
+                constants: None,
                 targets: vec![Some(webgpu::GpuColorTargetState {
                     format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
                     blend: None,
@@ -357,12 +373,15 @@ impl Example {
         });
 
         let entity_pipeline = device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
-            // label: Some("Entity"),
-            // layout: None,
-            layout: Some(&pipeline_layout),
+            label: Some("Entity".into()),
+            multiview: None,
+            layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuPipelineLayout(
+                &pipeline_layout,
+            ),
             vertex: webgpu::GpuVertexState {
                 module: &shader,
                 entry_point: "vs_entity".into(),
+                constants: None,
                 buffers: Some(vec![webgpu::GpuVertexBufferLayout {
                     array_stride: std::mem::size_of::<Vertex>() as u64,
                     step_mode: Some(webgpu::GpuVertexStepMode::Vertex),
@@ -384,6 +403,7 @@ impl Example {
             fragment: Some(webgpu::GpuFragmentState {
                 module: &shader,
                 entry_point: "fs_entity".into(),
+                constants: None,
                 targets: vec![Some(webgpu::GpuColorTargetState {
                     format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
                     blend: None,
@@ -605,8 +625,8 @@ impl Example {
                     depth_store_op: Some(webgpu::GpuStoreOp::Store),
                     depth_clear_value: Some(1.0),
                     depth_read_only: Some(false),
-                    stencil_load_op: Some(webgpu::GpuLoadOp::Load),
-                    stencil_store_op: Some(webgpu::GpuStoreOp::Store),
+                    stencil_load_op: None,
+                    stencil_store_op: None,
                     stencil_clear_value: Some(0),
                     stencil_read_only: Some(true),
                 }),
@@ -782,8 +802,8 @@ fn physical_size(extend: &webgpu::GpuExtent3DDict) -> webgpu::GpuExtent3DDict {
     // let (block_width, block_height) = format.block_dimensions();
     let (block_width, block_height) = (1, 1);
 
-    let width = ((extend.width + block_width - 1) / block_width) * block_width;
-    let height = ((extend.height.unwrap() + block_height - 1) / block_height) * block_height;
+    let width = extend.width.div_ceil(block_width) * block_width;
+    let height = extend.height.unwrap().div_ceil(block_height) * block_height;
 
     webgpu::GpuExtent3DDict {
         width,
@@ -0,0 +1,148 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleSplitScreen);
+
+struct ExampleSplitScreen;
+
+impl Guest for ExampleSplitScreen {
+    fn start() {
+        draw_split_screen();
+    }
+}
+
+use wasi::webgpu::{animation_frame, graphics_context, mini_canvas, webgpu};
+
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 300;
+
+/// The same triangle for both players, in a color of their own.
+const SHADER_CODE: &str = r#"
+@vertex
+fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(in_vertex_index) - 1);
+    let y = f32(i32(in_vertex_index & 1u) * 2 - 1);
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_left() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.2, 0.0, 1.0);
+}
+
+@fragment
+fn fs_right() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, 0.2, 1.0, 1.0);
+}
+"#;
+
+fn draw_split_screen() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
+        height: HEIGHT,
+        width: WIDTH,
+        offscreen: false,
+        title: None,
+        resizable: Some(false),
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
+    });
+    let graphics_context = graphics_context::GraphicsContext::new();
+    canvas.connect_graphics_context(&graphics_context);
+    device
+        .connect_graphics_context(&graphics_context)
+        .expect("timed out creating a surface");
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let pipeline = |label: &str, fragment_entry_point: &str| {
+        device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+            label: Some(label.into()),
+            multiview: None,
+            vertex: webgpu::GpuVertexState {
+                module: &shader,
+                entry_point: "vs_main".to_string(),
+                constants: None,
+                buffers: None,
+            },
+            fragment: Some(webgpu::GpuFragmentState {
+                module: &shader,
+                entry_point: fragment_entry_point.to_string(),
+                constants: None,
+                targets: vec![Some(webgpu::GpuColorTargetState {
+                    format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
+                    blend: None,
+                    write_mask: None,
+                })],
+            }),
+            primitive: None,
+            depth_stencil: None,
+            multisample: None,
+            layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+                webgpu::GpuAutoLayoutMode::Auto,
+            ),
+        })
+    };
+    let players = [
+        (pipeline("left player", "fs_left"), 0),
+        (pipeline("right player", "fs_right"), WIDTH / 2),
+    ];
+
+    let frame_listener = animation_frame::listener(&canvas);
+    let frame_pollable = frame_listener.subscribe();
+    loop {
+        frame_pollable.block();
+        frame_listener.get();
+
+        let encoder = device.create_command_encoder(None);
+        let texture =
+            webgpu::GpuTexture::from_graphics_buffer(graphics_context.get_current_buffer());
+        let view = texture.create_view(None);
+
+        let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+            label: None,
+            color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                clear_value: Some(webgpu::GpuColorDictOrListF64::GpuColorDict(
+                    webgpu::GpuColorDict {
+                        r: 0.0,
+                        g: 0.0,
+                        b: 0.0,
+                        a: 1.0,
+                    },
+                )),
+                load_op: webgpu::GpuLoadOp::Clear,
+                store_op: webgpu::GpuStoreOp::Store,
+            }],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+            max_draw_count: None,
+        });
+        // Each half of the canvas gets a viewport of its own, so the triangle is drawn once per player, squeezed
+        // into that half. The scissor rect keeps each half's drawing out of the other.
+        for (pipeline, x) in &players {
+            render_pass.set_viewport(*x as f32, 0.0, (WIDTH / 2) as f32, HEIGHT as f32, 0.0, 1.0);
+            render_pass.set_scissor_rect(*x, 0, WIDTH / 2, HEIGHT);
+            render_pass.set_pipeline(pipeline);
+            render_pass.draw(3, 1, 0, 0);
+        }
+        webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+
+        device
+            .queue()
+            .submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+        graphics_context.present();
+    }
+}
@@ -0,0 +1,211 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleStencilMask);
+
+struct ExampleStencilMask;
+
+impl Guest for ExampleStencilMask {
+    fn start() {
+        draw_masked();
+    }
+}
+
+use wasi::webgpu::{animation_frame, graphics_context, mini_canvas, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gputextureusageflags
+const TEXTURE_RENDER_ATTACHMENT: u32 = 0x10;
+
+const WIDTH: u32 = 300;
+const HEIGHT: u32 = 300;
+const STENCIL_FORMAT: webgpu::GpuTextureFormat = webgpu::GpuTextureFormat::Stencil8;
+
+/// The mask is a rectangle in the middle, the color is a triangle covering the whole canvas.
+/// Only the part of the triangle inside the rectangle should end up on screen.
+const SHADER_CODE: &str = r#"
+@vertex
+fn vs_mask(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    var corners = array<vec2<f32>, 6>(
+        vec2(-0.5, -0.5), vec2(0.5, -0.5), vec2(0.5, 0.5),
+        vec2(-0.5, -0.5), vec2(0.5, 0.5), vec2(-0.5, 0.5),
+    );
+    return vec4<f32>(corners[vertex_index], 0.0, 1.0);
+}
+
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(vertex_index & 1u) * 4.0 - 1.0;
+    let y = f32(vertex_index >> 1u) * 4.0 - 1.0;
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.2, 0.8, 0.4, 1.0);
+}
+"#;
+
+fn draw_masked() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
+        height: HEIGHT,
+        width: WIDTH,
+        offscreen: false,
+        title: None,
+        resizable: Some(false),
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
+    });
+    let graphics_context = graphics_context::GraphicsContext::new();
+    canvas.connect_graphics_context(&graphics_context);
+    device
+        .connect_graphics_context(&graphics_context)
+        .expect("timed out creating a surface");
+
+    let stencil_texture = device.create_texture(&webgpu::GpuTextureDescriptor {
+        size: webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+            width: WIDTH,
+            height: Some(HEIGHT),
+            depth_or_array_layers: Some(1),
+        }),
+        mip_level_count: Some(1),
+        sample_count: Some(1),
+        dimension: webgpu::GpuTextureDimension::TwoD,
+        format: STENCIL_FORMAT,
+        usage: TEXTURE_RENDER_ATTACHMENT,
+        label: Some("stencil".into()),
+        view_formats: None,
+    });
+    let stencil_view = stencil_texture.create_view(None);
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let pipeline = |label: &str, vertex_entry_point: &str, stencil: webgpu::GpuStencilFaceState| {
+        device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+            label: Some(label.into()),
+            multiview: None,
+            vertex: webgpu::GpuVertexState {
+                module: &shader,
+                entry_point: vertex_entry_point.to_string(),
+                constants: None,
+                buffers: None,
+            },
+            fragment: Some(webgpu::GpuFragmentState {
+                module: &shader,
+                entry_point: "fs_main".to_string(),
+                constants: None,
+                targets: vec![Some(webgpu::GpuColorTargetState {
+                    format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
+                    blend: None,
+                    write_mask: None,
+                })],
+            }),
+            primitive: None,
+            depth_stencil: Some(webgpu::GpuDepthStencilState {
+                format: STENCIL_FORMAT,
+                depth_write_enabled: None,
+                depth_compare: None,
+                stencil_front: Some(stencil),
+                stencil_back: Some(stencil),
+                stencil_read_mask: None,
+                stencil_write_mask: None,
+                depth_bias: None,
+                depth_bias_slope_scale: None,
+                depth_bias_clamp: None,
+            }),
+            multisample: None,
+            layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+                webgpu::GpuAutoLayoutMode::Auto,
+            ),
+        })
+    };
+    // Writes the stencil reference wherever the rectangle covers. Its color is drawn over by the second pipeline.
+    let mask_pipeline = pipeline(
+        "mask",
+        "vs_mask",
+        webgpu::GpuStencilFaceState {
+            compare: Some(webgpu::GpuCompareFunction::Always),
+            fail_op: None,
+            depth_fail_op: None,
+            pass_op: Some(webgpu::GpuStencilOperation::Replace),
+        },
+    );
+    let masked_pipeline = pipeline(
+        "masked",
+        "vs_fullscreen",
+        webgpu::GpuStencilFaceState {
+            compare: Some(webgpu::GpuCompareFunction::Equal),
+            fail_op: None,
+            depth_fail_op: None,
+            pass_op: None,
+        },
+    );
+
+    let frame_listener = animation_frame::listener(&canvas);
+    let frame_pollable = frame_listener.subscribe();
+    loop {
+        frame_pollable.block();
+        frame_listener.get();
+
+        let encoder = device.create_command_encoder(None);
+        let texture =
+            webgpu::GpuTexture::from_graphics_buffer(graphics_context.get_current_buffer());
+        let view = texture.create_view(None);
+
+        for (pipeline, load_op, vertex_count) in [
+            (&mask_pipeline, webgpu::GpuLoadOp::Clear, 6),
+            (&masked_pipeline, webgpu::GpuLoadOp::Load, 3),
+        ] {
+            let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+                label: None,
+                color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    clear_value: Some(webgpu::GpuColorDictOrListF64::GpuColorDict(
+                        webgpu::GpuColorDict {
+                            r: 0.0,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 1.0,
+                        },
+                    )),
+                    load_op,
+                    store_op: webgpu::GpuStoreOp::Store,
+                }],
+                depth_stencil_attachment: Some(webgpu::GpuRenderPassDepthStencilAttachment {
+                    view: &stencil_view,
+                    depth_load_op: None,
+                    depth_store_op: None,
+                    depth_clear_value: None,
+                    depth_read_only: None,
+                    stencil_load_op: Some(load_op),
+                    stencil_store_op: Some(webgpu::GpuStoreOp::Store),
+                    stencil_clear_value: Some(0),
+                    stencil_read_only: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+                max_draw_count: None,
+            });
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_stencil_reference(1);
+            render_pass.draw(vertex_count, 1, 0, 0);
+            webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+        }
+
+        device
+            .queue()
+            .submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+        graphics_context.present();
+    }
+}
@@ -37,17 +37,24 @@ fn fs_green() -> @location(0) vec4<f32> {
 
 fn draw_triangle() {
     let gpu = webgpu::get_gpu();
-    let adapter = gpu.request_adapter(None);
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
     let device = adapter.request_device(None);
 
-    let canvas = mini_canvas::MiniCanvas::new(mini_canvas::CreateDesc {
+    let canvas = mini_canvas::MiniCanvas::new(&mini_canvas::CreateDesc {
         height: 100,
         width: 100,
         offscreen: false,
+        title: None,
+        resizable: None,
+        decorations: None,
+        fullscreen: None,
+        transparent: None,
     });
     let graphics_context = graphics_context::GraphicsContext::new();
     canvas.connect_graphics_context(&graphics_context);
-    device.connect_graphics_context(&graphics_context);
+    device
+        .connect_graphics_context(&graphics_context)
+        .expect("timed out creating a surface");
 
     let pointer_up_listener = pointer_events::up_listener(&canvas);
     let pointer_up_pollable = pointer_up_listener.subscribe();
@@ -81,6 +88,7 @@ fn draw_triangle() {
                 compilation_hints: None,
             }),
             entry_point: "vs_main".to_string(),
+            constants: None,
             buffers: None,
         };
         let fragment = webgpu::GpuFragmentState {
@@ -97,6 +105,7 @@ fn draw_triangle() {
                 }
             }
             .to_string(),
+            constants: None,
             targets: vec![Some(webgpu::GpuColorTargetState {
                 format: webgpu::GpuTextureFormat::Bgra8unormSrgb,
                 blend: None,
@@ -104,6 +113,8 @@ fn draw_triangle() {
             })],
         };
         let pipeline_description = webgpu::GpuRenderPipelineDescriptor {
+            label: Some("triangle".into()),
+            multiview: None,
             vertex,
             fragment: Some(fragment),
             primitive: Some(webgpu::GpuPrimitiveState {
@@ -115,7 +126,9 @@ fn draw_triangle() {
             }),
             depth_stencil: None,
             multisample: None,
-            layout: None,
+            layout: webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+                webgpu::GpuAutoLayoutMode::Auto,
+            ),
         };
         let render_pipeline = device.create_render_pipeline(&pipeline_description);
         let pollables_res = wasi::io::poll::poll(&pollables);
@@ -148,7 +161,7 @@ fn draw_triangle() {
 
         if pollables_res.contains(&6) {
             frame_listener.get();
-            print(&format!("frame event"));
+            print("frame event");
 
             let graphics_buffer = graphics_context.get_current_buffer();
             let texture = webgpu::GpuTexture::from_graphics_buffer(graphics_buffer);
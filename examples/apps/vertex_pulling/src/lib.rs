@@ -0,0 +1,250 @@
+wit_bindgen::generate!({
+    path: "../../../wit",
+    world: "example:example/example",
+});
+
+export!(ExampleVertexPulling);
+
+struct ExampleVertexPulling;
+
+impl Guest for ExampleVertexPulling {
+    fn start() {
+        compare_vertex_pulling();
+    }
+}
+
+use wasi::webgpu::{vertex_pulling, webgpu};
+
+// source: https://www.w3.org/TR/webgpu/#buffer-usage
+const BUFFER_MAP_READ: u32 = 0x0001;
+const BUFFER_COPY_DST: u32 = 0x0008;
+const BUFFER_VERTEX: u32 = 0x0020;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gputextureusageflags
+const TEXTURE_COPY_SRC: u32 = 0x01;
+const TEXTURE_RENDER_ATTACHMENT: u32 = 0x10;
+// source: https://www.w3.org/TR/webgpu/#typedefdef-gpumapmodeflags
+const MAP_MODE_READ: u32 = 0x0001;
+
+const SIZE: u32 = 64;
+// Rows of 64 rgba8 pixels are 256 bytes, which is already the alignment copies need.
+const BYTES_PER_ROW: u32 = SIZE * 4;
+
+/// Per vertex: a float32x4 position and a float32x4 color, laid out the way `Vertex` is in the shader.
+const VERTICES: [[f32; 8]; 3] = [
+    [0.0, 0.8, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0],
+    [-0.8, -0.8, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0],
+    [0.8, -0.8, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0],
+];
+const STRIDE: u32 = 32;
+
+const SHADER_CODE: &str = r#"
+struct Vertex {
+    position: vec4<f32>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0) var<storage, read> vertices: array<Vertex>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_buffers(@location(0) position: vec4<f32>, @location(1) color: vec4<f32>) -> VertexOutput {
+    return VertexOutput(position, color);
+}
+
+@vertex
+fn vs_pulled(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let vertex = vertices[index];
+    return VertexOutput(vertex.position, vertex.color);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
+"#;
+
+/// Draws the same triangle from a vertex buffer and from a vertex pull buffer, and checks that both come out the same.
+fn compare_vertex_pulling() {
+    let gpu = webgpu::get_gpu();
+    let adapter = gpu.request_adapter(None).expect("no gpu adapter");
+    let device = adapter.request_device(None);
+    let queue = device.queue();
+
+    let data: Vec<u8> = VERTICES
+        .iter()
+        .flatten()
+        .flat_map(|f| f.to_le_bytes())
+        .collect();
+    let vertex_buffer = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("vertices".into()),
+        size: data.len() as u64,
+        usage: BUFFER_VERTEX | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+    queue.write_buffer(&vertex_buffer, 0, None, &data, None);
+    let pull_buffer = vertex_pulling::create_vertex_pull_buffer(&device, &data, STRIDE)
+        .expect("failed to create the vertex pull buffer");
+    let bind_group_layout = pull_buffer.get_bind_group_layout();
+    let bind_group = pull_buffer.get_bind_group();
+
+    let shader = device.create_shader_module(webgpu::GpuShaderModuleDescriptor {
+        code: SHADER_CODE.to_string(),
+        label: None,
+        compilation_hints: None,
+    });
+    let pipeline = |entry_point: &str, buffers, layout| {
+        device.create_render_pipeline(&webgpu::GpuRenderPipelineDescriptor {
+            label: Some(entry_point.into()),
+            multiview: None,
+            vertex: webgpu::GpuVertexState {
+                module: &shader,
+                entry_point: entry_point.to_string(),
+                constants: None,
+                buffers,
+            },
+            fragment: Some(webgpu::GpuFragmentState {
+                module: &shader,
+                entry_point: "fs_main".to_string(),
+                constants: None,
+                targets: vec![Some(webgpu::GpuColorTargetState {
+                    format: webgpu::GpuTextureFormat::Rgba8unorm,
+                    blend: None,
+                    write_mask: None,
+                })],
+            }),
+            primitive: None,
+            depth_stencil: None,
+            multisample: None,
+            layout,
+        })
+    };
+    let attribute = |shader_location: u32| webgpu::GpuVertexAttribute {
+        format: webgpu::GpuVertexFormat::Float32x4,
+        offset: shader_location as u64 * 16,
+        shader_location,
+    };
+    let buffers_pipeline = pipeline(
+        "vs_buffers",
+        Some(vec![webgpu::GpuVertexBufferLayout {
+            array_stride: STRIDE as u64,
+            step_mode: None,
+            attributes: vec![attribute(0), attribute(1)],
+        }]),
+        webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuAutoLayoutMode(
+            webgpu::GpuAutoLayoutMode::Auto,
+        ),
+    );
+    // Auto layouts aren't compatible with the pull buffer's bind group, so this one needs an explicit layout.
+    let pulled_layout = device.create_pipeline_layout(&webgpu::GpuPipelineLayoutDescriptor {
+        bind_group_layouts: vec![&bind_group_layout],
+        push_constant_ranges: None,
+        label: None,
+    });
+    let pulled_pipeline = pipeline(
+        "vs_pulled",
+        None,
+        webgpu::GpuPipelineLayoutOrGpuAutoLayoutMode::GpuPipelineLayout(&pulled_layout),
+    );
+
+    let from_buffers = render(&device, |pass| {
+        pass.set_pipeline(&buffers_pipeline);
+        pass.set_vertex_buffer(0, &vertex_buffer, 0, data.len() as u64);
+        pass.draw(VERTICES.len() as u32, 1, 0, 0);
+    });
+    let pulled = render(&device, |pass| {
+        pass.set_pipeline(&pulled_pipeline);
+        pass.set_bind_group(0, &bind_group, None);
+        pass.draw(pull_buffer.vertex_count(), 1, 0, 0);
+    });
+
+    let differing = from_buffers
+        .chunks_exact(4)
+        .zip(pulled.chunks_exact(4))
+        .filter(|(a, b)| a != b)
+        .count();
+    if differing == 0 {
+        print("Vertex buffers and vertex pulling drew identical triangles");
+    } else {
+        print(&format!(
+            "Vertex buffers and vertex pulling differ in {differing} of {} pixels",
+            SIZE * SIZE
+        ));
+    }
+}
+
+/// Renders into a texture of its own with `draw`, and reads back the pixels.
+fn render(device: &webgpu::GpuDevice, draw: impl FnOnce(&webgpu::GpuRenderPassEncoder)) -> Vec<u8> {
+    let extent = || {
+        webgpu::GpuExtent3D::GpuExtent3DDict(webgpu::GpuExtent3DDict {
+            width: SIZE,
+            height: Some(SIZE),
+            depth_or_array_layers: None,
+        })
+    };
+    let texture = device.create_texture(&webgpu::GpuTextureDescriptor {
+        size: extent(),
+        mip_level_count: None,
+        sample_count: None,
+        dimension: webgpu::GpuTextureDimension::TwoD,
+        format: webgpu::GpuTextureFormat::Rgba8unorm,
+        usage: TEXTURE_RENDER_ATTACHMENT | TEXTURE_COPY_SRC,
+        view_formats: None,
+        label: None,
+    });
+    let view = texture.create_view(None);
+    let read_back = device.create_buffer(&webgpu::GpuBufferDescriptor {
+        label: Some("read back".into()),
+        size: (BYTES_PER_ROW * SIZE) as u64,
+        usage: BUFFER_MAP_READ | BUFFER_COPY_DST,
+        mapped_at_creation: None,
+    });
+
+    let encoder = device.create_command_encoder(None);
+    let render_pass = encoder.begin_render_pass(&webgpu::GpuRenderPassDescriptor {
+        label: None,
+        color_attachments: vec![webgpu::GpuRenderPassColorAttachment {
+            view: &view,
+            depth_slice: None,
+            resolve_target: None,
+            clear_value: None,
+            load_op: webgpu::GpuLoadOp::Clear,
+            store_op: webgpu::GpuStoreOp::Store,
+        }],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+        max_draw_count: None,
+    });
+    draw(&render_pass);
+    webgpu::GpuRenderPassEncoder::end(render_pass, &encoder);
+    encoder.copy_texture_to_buffer(
+        &webgpu::GpuImageCopyTexture {
+            texture: &texture,
+            mip_level: None,
+            origin: None,
+            aspect: None,
+        },
+        &webgpu::GpuImageCopyBuffer {
+            buffer: &read_back,
+            offset: None,
+            bytes_per_row: Some(BYTES_PER_ROW),
+            rows_per_image: None,
+        },
+        &extent(),
+    );
+    device
+        .queue()
+        .submit(vec![webgpu::GpuCommandEncoder::finish(encoder, None)]);
+
+    read_back.map_async(MAP_MODE_READ, None, None);
+    let pixels = {
+        let mapped = read_back.get_mapped_range(None, None);
+        (0..mapped.length()).map(|i| mapped.get(i)).collect()
+    };
+    read_back.unmap();
+    pixels
+}
@@ -0,0 +1,60 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+use wasmtime_wasi::{HostMonotonicClock, HostWallClock, WasiCtxBuilder};
+
+/// Time that passes for the guest between two animation frames.
+pub const FRAME_STEP: Duration = Duration::from_nanos(16_666_667);
+
+/// Guest clock that only moves when the runtime advances it, one [`FRAME_STEP`] per frame.
+///
+/// Both the monotonic and the wall clock read from it, the wall clock starting at the unix epoch.
+#[derive(Clone, Default)]
+pub struct FrameClock {
+    frame: Arc<AtomicU64>,
+}
+
+impl FrameClock {
+    pub fn advance(&self) {
+        self.frame.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(FRAME_STEP.as_nanos() as u64 * self.frame.load(Ordering::Relaxed))
+    }
+}
+
+impl HostMonotonicClock for FrameClock {
+    fn resolution(&self) -> u64 {
+        FRAME_STEP.as_nanos() as u64
+    }
+
+    fn now(&self) -> u64 {
+        self.elapsed().as_nanos() as u64
+    }
+}
+
+impl HostWallClock for FrameClock {
+    fn resolution(&self) -> Duration {
+        FRAME_STEP
+    }
+
+    fn now(&self) -> Duration {
+        self.elapsed()
+    }
+}
+
+/// Replaces every source of nondeterminism in `wasi` with `clock` and fixed seed randomness.
+pub fn pin_wasi(wasi: &mut WasiCtxBuilder, clock: &FrameClock) {
+    wasi.monotonic_clock(clock.clone())
+        .wall_clock(clock.clone())
+        .secure_random(StdRng::seed_from_u64(0))
+        .insecure_random(StdRng::seed_from_u64(0))
+        .insecure_random_seed(0);
+}
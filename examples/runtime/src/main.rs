@@ -1,111 +1,509 @@
-use std::sync::Arc;
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use clap::Parser;
-use futures::executor::block_on;
+use tokio::task::JoinHandle;
 use wasi_frame_buffer_wasmtime::WasiFrameBufferView;
-use wasi_graphics_context_wasmtime::WasiGraphicsContextView;
-use wasi_mini_canvas_wasmtime::{MiniCanvas, MiniCanvasDesc, WasiMiniCanvasView};
-use wasi_webgpu_wasmtime::WasiWebGpuView;
+use wasi_gamepad_wasmtime::{Gamepads, WasiGamepadView};
+use wasi_gfx_wasmtime::{CanvasBackend, DevicePoller, WasiGfxCtx};
+use wasi_graphics_context_wasmtime::{
+    DisplayApi, FrameStats, PresentObserver, WasiGraphicsContextView,
+};
+use wasi_mini_canvas_wasmtime::{
+    CanvasQuota, Clipboard, DropPolicy, FrameEvent, HeadlessCanvases, MiniCanvas, MiniCanvasDesc,
+    Monitors, WasiMiniCanvasView, WasiWinitEventLoopProxy,
+};
+use wasi_webgpu_wasmtime::{
+    AdapterSelector, GpuQuota, GpuQuotaLimits, Labels, LiveDevices, Recorder, SharedBuffers,
+    WasiWebGpuObserver, WasiWebGpuView, WebGpuInstanceBuilder,
+};
 use wasmtime::{
-    component::{Component, Linker},
-    Config, Engine, Store,
+    component::{InstancePre, Linker},
+    Config, Engine, Store, Trap,
+};
+
+use wasmtime_wasi::{
+    DirPerms, FilePerms, HostWallClock, ResourceTable, WasiCtx, WasiCtxBuilder, WasiView,
 };
 
-use wasmtime_wasi::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
+use crate::deterministic::FrameClock;
+use crate::memory_limit::MemoryLimit;
+use crate::plugin::{Extensions, RuntimePlugin};
+use crate::screenshot::Screenshot;
+
+mod deterministic;
+mod memory_limit;
+mod plugin;
+mod preview1;
+mod screenshot;
+mod watch;
 
 #[derive(clap::Parser, Debug)]
-struct RuntimeArgs {
-    /// The example name
+pub struct RuntimeArgs {
+    /// The example name. Can be repeated, each example runs as its own component, sharing the event loop and gpu instance.
+    /// Every component gets its own canvases and gpu resources, they can only hand data to each other through shared buffers
+    #[arg(long, required_unless_present_any = ["wasm", "replay"])]
+    example: Vec<String>,
+
+    /// Path to a component to run. Can be repeated, and combined with --example, e.g. to run a renderer next to a ui
+    #[arg(long)]
+    wasm: Vec<PathBuf>,
+
+    /// Adapter for running preview 1 modules, e.g. ones built for wasm32-wasip1, which are otherwise rejected.
+    /// Use the wasi_snapshot_preview1.reactor.wasm from wasmtime's releases
+    #[arg(long, value_name = "FILE")]
+    wasi_adapter: Option<PathBuf>,
+
+    /// Render into offscreen canvases instead of windows
+    #[arg(long)]
+    headless: bool,
+
+    /// Exit once every headless canvas has presented this many frames
+    #[arg(long, requires = "headless")]
+    frames: Option<u64>,
+
+    /// Save the first presented frame as a png, or the last one with --deterministic
+    #[arg(long)]
+    screenshot: Option<PathBuf>,
+
+    /// Render reproducibly, for golden-image tests. Guest clocks advance one fixed step per frame,
+    /// frames are driven in lock-step with the guest's presents, and the fallback adapter is used unless --adapter is given
+    #[arg(long, requires = "frames")]
+    deterministic: bool,
+
+    /// Only hand the guest the first adapter whose name contains this
+    #[arg(long)]
+    adapter: Option<String>,
+
+    /// Only use this graphics backend. Host calls go through the platform's native backend, so it's the only one
+    /// offered: Vulkan on Linux and Android, DX12 on Windows, Metal on macOS and iOS, and GL elsewhere
+    #[arg(long, value_enum)]
+    backend: Option<Backend>,
+
+    /// Render on the cpu, for machines without a usable gpu, e.g. CI runners and VMs. Uses the backends with a
+    /// software adapter: lavapipe for Vulkan, WARP for DX12 and llvmpipe for GL. Guests get no adapter where there's none
+    #[arg(long, conflicts_with_all = ["adapter", "backend"])]
+    software: bool,
+
+    /// Shader compiler used by the DX12 backend
+    #[cfg(target_os = "windows")]
+    #[arg(long, value_enum, default_value_t = Dx12Compiler::Fxc)]
+    dx12_compiler: Dx12Compiler,
+
+    /// OpenGL ES 3 minor version to request from the GL backend
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "ios"
+    )))]
+    #[arg(long, value_enum, default_value_t = GlesMinorVersion::Automatic)]
+    gles_minor_version: GlesMinorVersion,
+
+    /// Turn on wgpu's validation, including the Vulkan validation layers and the DX12 debug layer where they're installed.
+    /// Catches more mistakes with better messages, but is too slow to leave on. Always on in debug builds of the runtime
+    #[arg(long)]
+    wgpu_validation: bool,
+
+    /// Have wgpu label its gpu objects and add debug markers, for graphics debuggers like RenderDoc. Always on in debug builds
+    #[arg(long)]
+    wgpu_debug: bool,
+
+    /// Also offer adapters that don't fully comply with WebGPU, e.g. Vulkan drivers missing required features
+    #[arg(long)]
+    wgpu_allow_noncompliant: bool,
+
+    /// Trap when the guest grows its linear memory beyond this many megabytes
+    #[arg(long, default_value_t = 256)]
+    max_memory_mb: usize,
+
+    /// Fail buffer creation once the guest's live buffers add up to this many megabytes
+    #[arg(long, value_name = "MB")]
+    max_gpu_buffer_mb: Option<u64>,
+
+    /// Fail texture creation once the guest's live textures add up to this many megabytes, estimated from their formats and sizes
+    #[arg(long, value_name = "MB")]
+    max_gpu_texture_mb: Option<u64>,
+
+    /// Fail buffer and texture creation once the guest has this many of them alive
+    #[arg(long, value_name = "COUNT")]
+    max_gpu_resources: Option<u64>,
+
+    /// Trap when the guest opens more than this many canvases at once
+    #[arg(long, value_name = "COUNT")]
+    max_canvases: Option<usize>,
+
+    /// Abort the guest if it's still running after this many seconds
+    #[arg(long)]
+    timeout: Option<u64>,
+
+    /// Argument passed to the guest, after the example name. Can be repeated
+    #[arg(long = "arg")]
+    guest_args: Vec<String>,
+
+    /// Environment variable for the guest, as KEY=VALUE. Can be repeated
+    #[arg(long = "env", value_parser = parse_env)]
+    guest_env: Vec<(String, String)>,
+
+    /// Host directory made available to the guest, as HOST::GUEST. Can be repeated
+    #[arg(long = "dir", value_parser = parse_dir)]
+    guest_dirs: Vec<(PathBuf, String)>,
+
+    /// Run each guest this many times in a row, restarting it whenever it returns. The component is compiled and linked only once
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+
+    /// Record a wgpu api trace of every device into this directory, for replaying with wgpu's player.
+    /// Traces include the guest's shaders. Needs the `wgpu-trace` feature
+    #[arg(long, alias = "trace", value_name = "DIR")]
+    wgpu_trace: Option<PathBuf>,
+
+    /// Record the guest's webgpu calls into this file, for --replay. Only works with a single component,
+    /// and every run overwrites the file
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Make the webgpu calls of a --record file again, without a guest. Canvases are replaced with offscreen textures
+    #[arg(long, value_name = "FILE")]
+    replay: Option<PathBuf>,
+
+    /// Reload and restart a guest whenever its component file changes, in the same window. Keeps running until the runtime is killed
+    #[arg(long, conflicts_with = "headless")]
+    watch: bool,
+
+    /// Send canvases animation frames continuously, for guests that don't call `request-redraw` but expect frames to keep coming.
+    /// Otherwise canvases only get frames when they ask for one, or when input arrives
+    #[arg(long, conflicts_with = "headless")]
+    continuous_frames: bool,
+
+    /// Log each guest's frame times every few seconds and when it returns
     #[arg(long)]
-    example: String,
+    stats: bool,
+
+    /// Poll the gpu in the background every this many milliseconds, 1 if no value is given. Otherwise buffer maps can
+    /// stall until the guest's next submit
+    #[arg(
+        long,
+        value_name = "MS",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    poll_gpu_interval_ms: Option<u64>,
+}
+
+/// The build's defaults, plus whatever was turned on from the command line.
+fn instance_flags(args: &RuntimeArgs) -> wgpu_types::InstanceFlags {
+    let mut flags = wgpu_types::InstanceFlags::from_build_config();
+    for (on, flag) in [
+        (args.wgpu_validation, wgpu_types::InstanceFlags::VALIDATION),
+        (args.wgpu_debug, wgpu_types::InstanceFlags::DEBUG),
+        (
+            args.wgpu_allow_noncompliant,
+            wgpu_types::InstanceFlags::ALLOW_UNDERLYING_NONCOMPLIANT_ADAPTER,
+        ),
+    ] {
+        if on {
+            flags |= flag;
+        }
+    }
+    if flags.contains(wgpu_types::InstanceFlags::VALIDATION) {
+        log::info!("wgpu validation is on, expect gpu work to be slower");
+    }
+    flags
+}
+
+fn parse_env(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{s}`")),
+    }
+}
+
+fn parse_dir(s: &str) -> Result<(PathBuf, String), String> {
+    let (host, guest) = s
+        .split_once("::")
+        .ok_or_else(|| format!("expected HOST::GUEST, got `{s}`"))?;
+    let host = PathBuf::from(host);
+    if !host.is_dir() {
+        return Err(format!(
+            "`{}` doesn't exist or isn't a directory",
+            host.display()
+        ));
+    }
+    Ok((host, guest.to_string()))
+}
+
+/// The backend `wasi-webgpu-wasmtime` is built for on this platform.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Backend {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Vulkan,
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    Metal,
+    #[cfg(target_os = "windows")]
+    Dx12,
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "ios"
+    )))]
+    Gl,
+}
+
+impl From<Backend> for wgpu_types::Backends {
+    fn from(value: Backend) -> Self {
+        match value {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Backend::Vulkan => wgpu_types::Backends::VULKAN,
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            Backend::Metal => wgpu_types::Backends::METAL,
+            #[cfg(target_os = "windows")]
+            Backend::Dx12 => wgpu_types::Backends::DX12,
+            #[cfg(not(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "windows",
+                target_os = "macos",
+                target_os = "ios"
+            )))]
+            Backend::Gl => wgpu_types::Backends::GL,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Dx12Compiler {
+    Fxc,
+    Dxc,
+}
+
+#[cfg(target_os = "windows")]
+impl From<Dx12Compiler> for wgpu_types::Dx12Compiler {
+    fn from(value: Dx12Compiler) -> Self {
+        match value {
+            Dx12Compiler::Fxc => wgpu_types::Dx12Compiler::Fxc,
+            Dx12Compiler::Dxc => wgpu_types::Dx12Compiler::Dxc {
+                dxil_path: None,
+                dxc_path: None,
+            },
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum GlesMinorVersion {
+    Automatic,
+    #[value(name = "0")]
+    Version0,
+    #[value(name = "1")]
+    Version1,
+    #[value(name = "2")]
+    Version2,
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+impl From<GlesMinorVersion> for wgpu_types::Gles3MinorVersion {
+    fn from(value: GlesMinorVersion) -> Self {
+        match value {
+            GlesMinorVersion::Automatic => wgpu_types::Gles3MinorVersion::Automatic,
+            GlesMinorVersion::Version0 => wgpu_types::Gles3MinorVersion::Version0,
+            GlesMinorVersion::Version1 => wgpu_types::Gles3MinorVersion::Version1,
+            GlesMinorVersion::Version2 => wgpu_types::Gles3MinorVersion::Version2,
+        }
+    }
 }
 
 wasmtime::component::bindgen!({
     path: "../../wit/",
     world: "example",
     async: {
-        only_imports: [],
+        only_imports: ["sleep-ms"],
     },
     with: {
         "wasi:webgpu/graphics-context": wasi_graphics_context_wasmtime,
         "wasi:webgpu/mini-canvas": wasi_mini_canvas_wasmtime,
         "wasi:webgpu/frame-buffer": wasi_frame_buffer_wasmtime,
         "wasi:webgpu/webgpu": wasi_webgpu_wasmtime,
+        "wasi:webgpu/texture-atlas": wasi_webgpu_wasmtime::wasi::webgpu::texture_atlas,
+        "wasi:webgpu/shared-buffer": wasi_webgpu_wasmtime::wasi::webgpu::shared_buffer,
+        "wasi:webgpu/query-set-resolve-to-buffer": wasi_webgpu_wasmtime::wasi::webgpu::query_set_resolve_to_buffer,
+        "wasi:webgpu/mipmap-generation": wasi_webgpu_wasmtime::wasi::webgpu::mipmap_generation,
+        "wasi:webgpu/vertex-pulling": wasi_webgpu_wasmtime::wasi::webgpu::vertex_pulling,
+        "wasi:webgpu/gpu-futures": wasi_webgpu_wasmtime::wasi::webgpu::gpu_futures,
+        "wasi:webgpu/gamepad": wasi_gamepad_wasmtime,
     },
 });
 
-struct HostState {
-    pub table: ResourceTable,
-    pub ctx: WasiCtx,
-    pub instance: Arc<wgpu_core::global::Global>,
-    pub main_thread_proxy: wasi_mini_canvas_wasmtime::WasiWinitEventLoopProxy,
-}
-
-impl HostState {
-    fn new(main_thread_proxy: wasi_mini_canvas_wasmtime::WasiWinitEventLoopProxy) -> Self {
-        Self {
-            table: ResourceTable::new(),
-            ctx: WasiCtxBuilder::new().inherit_stdio().build(),
-            instance: Arc::new(wgpu_core::global::Global::new(
-                "webgpu",
-                wgpu_types::InstanceDescriptor {
-                    backends: wgpu_types::Backends::all(),
-                    flags: wgpu_types::InstanceFlags::from_build_config(),
-                    dx12_shader_compiler: wgpu_types::Dx12Compiler::Fxc,
-                    gles_minor_version: wgpu_types::Gles3MinorVersion::default(),
-                },
-            )),
-            main_thread_proxy,
-        }
-    }
+pub struct HostState {
+    pub gfx: WasiGfxCtx,
+    pub memory_limit: MemoryLimit,
+    pub extensions: Extensions,
 }
 
 impl WasiView for HostState {
     fn table(&mut self) -> &mut ResourceTable {
-        &mut self.table
+        self.gfx.table()
     }
 
     fn ctx(&mut self) -> &mut WasiCtx {
-        &mut self.ctx
+        self.gfx.ctx()
     }
 }
 
-impl WasiGraphicsContextView for HostState {}
-impl WasiFrameBufferView for HostState {}
+impl WasiGraphicsContextView for HostState {
+    fn present_observer(&self) -> Option<Arc<dyn PresentObserver>> {
+        self.gfx.present_observer()
+    }
 
-struct UiThreadSpawner(wasi_mini_canvas_wasmtime::WasiWinitEventLoopProxy);
+    fn frame_stats(&self) -> Option<FrameStats> {
+        self.gfx.frame_stats()
+    }
 
-impl wasi_webgpu_wasmtime::MainThreadSpawner for UiThreadSpawner {
-    async fn spawn<F, T>(&self, f: F) -> T
-    where
-        F: FnOnce() -> T + Send + Sync + 'static,
-        T: Send + Sync + 'static,
-    {
-        self.0.spawn(f).await
+    fn default_display(&self) -> Option<Box<dyn DisplayApi + Send + Sync>> {
+        self.gfx.default_display()
     }
 }
 
+impl WasiFrameBufferView for HostState {}
+
 impl WasiWebGpuView for HostState {
     fn instance(&self) -> Arc<wgpu_core::global::Global> {
-        Arc::clone(&self.instance)
+        self.gfx.instance()
     }
 
     fn ui_thread_spawner(&self) -> Box<impl wasi_webgpu_wasmtime::MainThreadSpawner + 'static> {
-        Box::new(UiThreadSpawner(self.main_thread_proxy.clone()))
+        self.gfx.ui_thread_spawner()
+    }
+
+    fn adapter_selector(&self) -> Option<AdapterSelector> {
+        self.gfx.adapter_selector()
+    }
+
+    fn live_devices(&self) -> Option<LiveDevices> {
+        self.gfx.live_devices()
+    }
+
+    fn shared_buffers(&self) -> Option<SharedBuffers> {
+        self.gfx.shared_buffers()
+    }
+
+    fn labels(&self) -> Option<Labels> {
+        self.gfx.labels()
+    }
+
+    fn trace_path(&self) -> Option<PathBuf> {
+        self.gfx.trace_path()
+    }
+
+    fn observer(&self) -> Option<Arc<dyn WasiWebGpuObserver>> {
+        self.gfx.observer()
+    }
+
+    fn quota(&self) -> Option<GpuQuota> {
+        self.gfx.quota()
+    }
+
+    fn recorder(&self) -> Option<Recorder> {
+        self.gfx.recorder()
     }
 }
 
+#[async_trait::async_trait]
 impl WasiMiniCanvasView for HostState {
-    fn create_canvas(&self, desc: MiniCanvasDesc) -> MiniCanvas {
-        block_on(self.main_thread_proxy.create_window(desc))
+    async fn create_canvas(&mut self, desc: MiniCanvasDesc) -> MiniCanvas {
+        self.gfx.create_canvas(desc).await
+    }
+
+    fn drop_policy(&self) -> DropPolicy {
+        self.gfx.drop_policy()
+    }
+
+    fn clipboard(&self) -> Option<Arc<dyn Clipboard>> {
+        self.gfx.clipboard()
+    }
+
+    fn monitors(&self) -> Option<Arc<dyn Monitors>> {
+        self.gfx.monitors()
+    }
+
+    fn canvas_quota(&self) -> Option<CanvasQuota> {
+        self.gfx.canvas_quota()
+    }
+
+    fn wall_clock(&self) -> Option<Arc<dyn HostWallClock + Send + Sync>> {
+        self.gfx.wall_clock()
+    }
+}
+
+struct GamepadPlugin;
+
+impl RuntimePlugin for GamepadPlugin {
+    fn add_to_linker(&self, linker: &mut Linker<HostState>) -> anyhow::Result<()> {
+        wasi_gamepad_wasmtime::add_only_gamepad_to_linker(linker)
+    }
+
+    fn extend_host_state(&self, state: &mut HostState) -> anyhow::Result<()> {
+        // Guests still run, they just never see a gamepad connect.
+        let gamepads = Gamepads::new().unwrap_or_else(|e| {
+            log::warn!("Gamepads aren't available: {e:#}");
+            Gamepads::unavailable()
+        });
+        state.extensions.insert(Arc::new(gamepads));
+        Ok(())
+    }
+}
+
+impl WasiGamepadView for HostState {
+    fn gamepads(&self) -> wasmtime::Result<Arc<Gamepads>> {
+        self.extensions
+            .get::<Arc<Gamepads>>()
+            .cloned()
+            .context("The runtime was started without the gamepad plugin")
     }
 }
 
+#[async_trait::async_trait]
 impl ExampleImports for HostState {
     fn print(&mut self, s: String) {
         println!("{s}");
     }
+
+    // Both read from the guest's wasi context, so --deterministic pins them along with the wasi clocks and random.
+    fn now_nanos(&mut self) -> u64 {
+        wasmtime_wasi::bindings::clocks::monotonic_clock::Host::now(self).unwrap()
+    }
+
+    fn random_f64(&mut self) -> f64 {
+        let random = wasmtime_wasi::bindings::random::random::Host::get_random_u64(self).unwrap();
+        // The top 53 bits fill an f64's mantissa exactly, so every value in [0, 1) is equally likely.
+        (random >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    async fn sleep_ms(&mut self, ms: u32) {
+        tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+    }
 }
 
 #[tokio::main]
@@ -116,16 +514,22 @@ async fn main() -> anyhow::Result<()> {
 
     let args = RuntimeArgs::parse();
 
+    run(args, vec![Box::new(GamepadPlugin)]).await
+}
+
+/// Runs the components `args` name, with the host interfaces of `plugins` on top of the wasi-gfx ones.
+pub async fn run(args: RuntimeArgs, plugins: Vec<Box<dyn RuntimePlugin>>) -> anyhow::Result<()> {
     let mut config = Config::default();
     config.wasm_component_model(true);
     config.async_support(true);
+    config.epoch_interruption(args.timeout.is_some());
     let engine = Engine::new(&config)?;
     let mut linker: Linker<HostState> = Linker::new(&engine);
 
-    wasi_webgpu_wasmtime::add_to_linker(&mut linker)?;
-    wasi_frame_buffer_wasmtime::add_to_linker(&mut linker)?;
-    wasi_graphics_context_wasmtime::add_to_linker(&mut linker)?;
-    wasi_mini_canvas_wasmtime::add_to_linker(&mut linker)?;
+    wasi_gfx_wasmtime::add_to_linker_with_wasi(&mut linker)?;
+    for plugin in &plugins {
+        plugin.add_to_linker(&mut linker)?;
+    }
 
     fn type_annotate<F>(val: F) -> F
     where
@@ -135,27 +539,452 @@ async fn main() -> anyhow::Result<()> {
     }
     let closure = type_annotate::<_>(|t| t);
     Example::add_to_linker_imports_get_host(&mut linker, closure)?;
+    // Guests reload components with it when watching.
+    let linker = Arc::new(linker);
 
-    let (main_thread_loop, main_thread_proxy) =
-        wasi_mini_canvas_wasmtime::create_wasi_winit_event_loop();
-    let host_state = HostState::new(main_thread_proxy);
+    let instance = WebGpuInstanceBuilder::new()
+        .backends(match (args.backend, args.software) {
+            (Some(backend), _) => backend.into(),
+            (None, true) => {
+                wgpu_types::Backends::VULKAN | wgpu_types::Backends::DX12 | wgpu_types::Backends::GL
+            }
+            (None, false) => wgpu_types::Backends::all(),
+        })
+        .flags(instance_flags(&args));
+    #[cfg(target_os = "windows")]
+    let instance = instance.dx12_shader_compiler(args.dx12_compiler.into());
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "ios"
+    )))]
+    let instance = instance.gles_minor_version(args.gles_minor_version.into());
+    let instance = instance.build();
+    // Every component gets its own store, but they all share the instance and the canvas backend.
+    // Canvases stay isolated anyway: each `mini-canvas` is its own window (or headless canvas), owned by the store that created it,
+    // and gpu resources live in the store's table, so one guest can't draw into another's canvas.
+    let instance = Arc::new(instance);
+    // A single poller for the shared instance, rather than one per store.
+    let poller = args
+        .poll_gpu_interval_ms
+        .map(|ms| DevicePoller::spawn(Arc::clone(&instance), Duration::from_millis(ms)));
+    let wgpu_trace = args.wgpu_trace.clone().filter(|dir| {
+        // Without the feature the guests still run, just without a trace.
+        if !cfg!(feature = "wgpu-trace") {
+            log::warn!(
+                "Not recording a wgpu trace to {}, the runtime was built without the `wgpu-trace` feature",
+                dir.display()
+            );
+        }
+        cfg!(feature = "wgpu-trace")
+    });
+    let clock = args.deterministic.then(FrameClock::default);
+    let mut main_thread_loop = None;
+    // Replays don't present anything, so there's no window to open.
+    let canvas_backend = if args.headless || args.replay.is_some() {
+        CanvasBackend::Headless(HeadlessCanvases::new())
+    } else {
+        let (event_loop, proxy) = wasi_mini_canvas_wasmtime::create_wasi_winit_event_loop();
+        // Reloaded guests draw into the window of the instance they replace.
+        main_thread_loop = Some(
+            event_loop
+                .reuse_windows(args.watch)
+                .continuous_frames(args.continuous_frames),
+        );
+        CanvasBackend::Winit(proxy)
+    };
+    let adapter = match (args.adapter.clone(), args.deterministic || args.software) {
+        (Some(name), _) => Some(AdapterSelector::Named(name)),
+        (None, true) => Some(AdapterSelector::Fallback),
+        (None, false) => None,
+    };
+    let screenshot: Option<Arc<dyn PresentObserver>> = args.screenshot.clone().map(|path| {
+        let screenshot = match (args.deterministic, args.frames) {
+            (true, Some(frames)) => Screenshot::at_frame(path, frames.saturating_sub(1)),
+            _ => Screenshot::new(path),
+        };
+        Arc::new(screenshot) as _
+    });
 
-    let mut store = Store::new(&engine, host_state);
+    if let Some(timeout) = args.timeout {
+        // The epoch is only used for the timeout, so a single tick once it's up is enough for every store.
+        let engine = engine.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(timeout));
+            engine.increment_epoch();
+        });
+    }
 
-    let wasm_path = format!("./target/example-{}.wasm", args.example);
+    let examples = args.example.iter().map(|name| {
+        let path = PathBuf::from(format!("./target/example-{name}.wasm"));
+        (name.clone(), path)
+    });
+    let wasm = args.wasm.iter().map(|path| {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        (name, path.clone())
+    });
+    let components: Vec<(String, PathBuf)> = examples.chain(wasm).collect();
+    if args.record.is_some() && components.len() > 1 {
+        anyhow::bail!("--record only works with a single component");
+    }
 
-    let component =
-        Component::from_file(&engine, &wasm_path).context("Component file not found")?;
+    // Also stops the event loop if the host panics while running a guest. Shared by the guests, so it's dropped after the last one is done.
+    let exit = Arc::new(ExitOnDrop(match &canvas_backend {
+        CanvasBackend::Winit(proxy) => Some(proxy.clone()),
+        CanvasBackend::Headless(_) => None,
+    }));
+    let stores = Arc::new(StoreFactory {
+        engine: engine.clone(),
+        guest_args: args.guest_args.clone(),
+        guest_env: args.guest_env.clone(),
+        guest_dirs: args.guest_dirs.clone(),
+        max_memory_mb: args.max_memory_mb,
+        gpu_limits: GpuQuotaLimits {
+            max_buffer_bytes: args.max_gpu_buffer_mb.map(|mb| mb * 1024 * 1024),
+            max_texture_bytes: args.max_gpu_texture_mb.map(|mb| mb * 1024 * 1024),
+            max_resources: args.max_gpu_resources,
+        },
+        max_canvases: args.max_canvases,
+        stats: args.stats,
+        timeout: args.timeout,
+        clock: clock.clone(),
+        instance: Arc::clone(&instance),
+        canvas_backend: canvas_backend.clone(),
+        adapter,
+        screenshot,
+        // One registry for all guests, so they can hand buffers to each other.
+        shared_buffers: SharedBuffers::new(),
+        wgpu_trace,
+        record: args.record.clone(),
+        plugins,
+    });
+    if let Some(path) = &args.replay {
+        let mut store = stores.new_store("replay")?;
+        let calls = wasi_webgpu_wasmtime::replay(store.data_mut(), path).await?;
+        log::info!("Replayed {calls} calls from {}", path.display());
+        if let Some(poller) = poller {
+            poller.join().await;
+        }
+        return Ok(());
+    }
+    let mut guests = Vec::with_capacity(components.len());
+    for (name, wasm_path) in components {
+        let component =
+            preview1::load_component(&engine, &wasm_path, args.wasi_adapter.as_deref())?;
+        // Type-checked against the linker once, every run only instantiates it.
+        let instance_pre = linker
+            .instantiate_pre(&component)
+            .with_context(|| format!("Failed to link {name}"))?;
 
-    let (instance, _) = Example::instantiate_async(&mut store, &component, &linker)
-        .await
-        .unwrap();
+        let runs = args.runs;
+        let watch = args.watch;
+        let wasi_adapter = args.wasi_adapter.clone();
+        let linker = Arc::clone(&linker);
+        let stores = Arc::clone(&stores);
+        let exit = Arc::clone(&exit);
+        guests.push(tokio::spawn(async move {
+            let _exit = exit;
+            if watch {
+                watch::run_watched(
+                    &name,
+                    &wasm_path,
+                    wasi_adapter.as_deref(),
+                    instance_pre,
+                    &linker,
+                    &stores,
+                )
+                .await;
+                return Ok(());
+            }
+            for _ in 0..runs {
+                stores.run(&name, &instance_pre).await?;
+            }
+            Ok(())
+        }));
+    }
+    drop(exit);
 
-    tokio::spawn(async move {
-        instance.call_start(&mut store).await.unwrap();
-    });
+    match (main_thread_loop, canvas_backend) {
+        (Some(main_thread_loop), _) => main_thread_loop.run(),
+        (None, CanvasBackend::Headless(canvases)) => {
+            match clock {
+                Some(clock) => {
+                    run_deterministic(canvases, args.frames.unwrap(), clock, &guests).await
+                }
+                None => run_headless(canvases, args.frames, &guests).await,
+            }
+            // Still running if they presented enough frames.
+            for guest in &guests {
+                guest.abort();
+            }
+        }
+        (None, CanvasBackend::Winit(_)) => unreachable!(),
+    }
+
+    let mut result = Ok(());
+    for guest in guests {
+        let guest_result = match guest.await {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => Ok(()),
+            Err(e) => Err(anyhow::anyhow!("Guest task panicked: {e}")),
+        };
+        // Later failures are still logged, only the first one is returned.
+        if let Err(e) = guest_result {
+            if result.is_ok() {
+                result = Err(e);
+            } else {
+                log::error!("{e:#}");
+            }
+        }
+    }
+    if let Some(poller) = poller {
+        poller.join().await;
+    }
+    result
+}
+
+/// Creates a store with fresh host state for each run of a guest.
+struct StoreFactory {
+    engine: Engine,
+    guest_args: Vec<String>,
+    guest_env: Vec<(String, String)>,
+    guest_dirs: Vec<(PathBuf, String)>,
+    max_memory_mb: usize,
+    gpu_limits: GpuQuotaLimits,
+    max_canvases: Option<usize>,
+    stats: bool,
+    timeout: Option<u64>,
+    clock: Option<FrameClock>,
+    instance: Arc<wgpu_core::global::Global>,
+    canvas_backend: CanvasBackend,
+    adapter: Option<AdapterSelector>,
+    screenshot: Option<Arc<dyn PresentObserver>>,
+    shared_buffers: SharedBuffers,
+    wgpu_trace: Option<PathBuf>,
+    record: Option<PathBuf>,
+    plugins: Vec<Box<dyn RuntimePlugin>>,
+}
+
+impl StoreFactory {
+    fn new_store(&self, name: &str) -> anyhow::Result<Store<HostState>> {
+        let mut wasi = WasiCtxBuilder::new();
+        wasi.inherit_stdio()
+            .arg(name)
+            .args(&self.guest_args)
+            .envs(&self.guest_env);
+        if let Some(clock) = &self.clock {
+            deterministic::pin_wasi(&mut wasi, clock);
+        }
+        for (host, guest) in &self.guest_dirs {
+            wasi.preopened_dir(host, guest, DirPerms::all(), FilePerms::all())
+                .with_context(|| format!("Failed to open {} for the guest", host.display()))?;
+        }
+        let mut gfx = WasiGfxCtx::builder()
+            .wasi(wasi.build())
+            .shared_instance(Arc::clone(&self.instance))
+            .shared_buffers(self.shared_buffers.clone());
+        gfx = match &self.canvas_backend {
+            CanvasBackend::Winit(proxy) => gfx.winit(proxy.clone()),
+            CanvasBackend::Headless(canvases) => gfx.headless(canvases.clone()),
+        };
+        if let Some(adapter) = &self.adapter {
+            gfx = gfx.adapter(adapter.clone());
+        }
+        if let Some(screenshot) = &self.screenshot {
+            gfx = gfx.present_observer(Arc::clone(screenshot));
+        }
+        if let Some(wgpu_trace) = &self.wgpu_trace {
+            gfx = gfx.trace_path(wgpu_trace);
+        }
+        if let Some(path) = &self.record {
+            let recorder = Recorder::create(path)
+                .with_context(|| format!("Failed to create recording {}", path.display()))?;
+            gfx = gfx.recorder(recorder);
+        }
+        if let Some(clock) = &self.clock {
+            // Swap chain timing reports present times by the same clock the guest reads.
+            gfx = gfx.wall_clock(Arc::new(clock.clone()));
+        }
+        if self.stats {
+            // Timed with the guest's own clock, so deterministic runs report the fixed frame step.
+            gfx = gfx.frame_stats(match &self.clock {
+                Some(clock) => FrameStats::with_clock(Arc::new(clock.clone())),
+                None => FrameStats::new(),
+            });
+        }
+        // Quotas are per store, so each guest and each run gets the full limits.
+        if self.gpu_limits != GpuQuotaLimits::default() {
+            gfx = gfx.gpu_quota(GpuQuota::new(self.gpu_limits));
+        }
+        if self.max_canvases.is_some() {
+            gfx = gfx.canvas_quota(CanvasQuota::new(self.max_canvases));
+        }
+        let mut host_state = HostState {
+            gfx: gfx.build(),
+            memory_limit: MemoryLimit::from_mb(self.max_memory_mb),
+            extensions: Extensions::default(),
+        };
+        for plugin in &self.plugins {
+            plugin.extend_host_state(&mut host_state)?;
+        }
+
+        let mut store = Store::new(&self.engine, host_state);
+        store.limiter(|state| &mut state.memory_limit);
+        if self.timeout.is_some() {
+            store.set_epoch_deadline(1);
+        }
+        Ok(store)
+    }
 
-    main_thread_loop.run();
+    /// Runs the guest once, in a fresh store so nothing a previous run created is left behind.
+    async fn run(&self, name: &str, instance_pre: &InstancePre<HostState>) -> anyhow::Result<()> {
+        let mut store = self.new_store(name)?;
+        let frame_stats = store.data().frame_stats();
+        let (instance, _) = Example::instantiate_pre(&mut store, instance_pre)
+            .await
+            .with_context(|| format!("Failed to instantiate {name}"))?;
+        let stats_logger = frame_stats.clone().map(|frame_stats| {
+            let name = name.to_string();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(STATS_INTERVAL);
+                // The first tick completes right away.
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    log_frame_stats(&name, &frame_stats);
+                }
+            })
+        });
+        let result = instance.call_start(&mut store).await;
+        // Dropping the store drops the canvases, which closes the guest's windows while other guests keep running.
+        drop(store);
+        if let Some(stats_logger) = stats_logger {
+            stats_logger.abort();
+        }
+        if let Some(frame_stats) = &frame_stats {
+            log_frame_stats(name, frame_stats);
+        }
+        result.map_err(|e| match e.downcast_ref::<Trap>() {
+            Some(Trap::Interrupt) => {
+                let timeout = self.timeout.unwrap();
+                e.context(format!(
+                    "Guest {name} didn't finish within {timeout}s, aborted"
+                ))
+            }
+            _ => e.context(format!("Guest {name} failed")),
+        })
+    }
+}
+
+/// How often `--stats` logs the frame times of running guests.
+const STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+fn log_frame_stats(name: &str, frame_stats: &FrameStats) {
+    let Some(times) = frame_stats.frame_times() else {
+        log::info!("{name}: not enough frames presented for stats");
+        return;
+    };
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    log::info!(
+        "{name}: {} frames, {:.1} fps, frame times avg {:.2}ms p50 {:.2}ms p95 {:.2}ms p99 {:.2}ms max {:.2}ms",
+        times.frames,
+        times.fps(),
+        ms(times.average),
+        ms(times.p50),
+        ms(times.p95),
+        ms(times.p99),
+        ms(times.max),
+    );
+}
+
+/// Stops the winit event loop once the guests are done, so `run` returns instead of leaving a frozen window behind.
+struct ExitOnDrop(Option<WasiWinitEventLoopProxy>);
+
+impl Drop for ExitOnDrop {
+    fn drop(&mut self) {
+        if let Some(proxy) = &self.0 {
+            proxy.shutdown();
+        }
+    }
+}
+
+/// Drives animation frames for headless canvases until the guests return, or `frames` frames were presented to each canvas.
+async fn run_headless(
+    canvases: HeadlessCanvases,
+    frames: Option<u64>,
+    guests: &[JoinHandle<anyhow::Result<()>>],
+) {
+    const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+    let start = Instant::now();
+    while !all_finished(guests) {
+        tokio::time::sleep(FRAME_INTERVAL).await;
+        let canvases = canvases.canvases();
+        for canvas in &canvases {
+            canvas.animation_frame(FrameEvent {
+                timestamp: start.elapsed().as_nanos() as u64,
+            });
+        }
+        if let Some(frames) = frames {
+            if !canvases.is_empty()
+                && canvases
+                    .iter()
+                    .all(|canvas| canvas.target().frame_count() >= frames)
+            {
+                log::info!("Presented {frames} frames, exiting");
+                break;
+            }
+        }
+    }
+}
+
+/// Like [`run_headless`], but only sends the next animation frame once every canvas presented the previous one,
+/// advancing `clock` by one step in between. Wall clock time has no influence on what the guest sees.
+async fn run_deterministic(
+    canvases: HeadlessCanvases,
+    frames: u64,
+    clock: FrameClock,
+    guests: &[JoinHandle<anyhow::Result<()>>],
+) {
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+    while !all_finished(guests) {
+        let canvases = canvases.canvases();
+        if canvases.is_empty() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        }
+        if canvases
+            .iter()
+            .all(|canvas| canvas.target().frame_count() >= frames)
+        {
+            log::info!("Presented {frames} frames, exiting");
+            break;
+        }
+        let presented: Vec<u64> = canvases
+            .iter()
+            .map(|canvas| canvas.target().frame_count())
+            .collect();
+        for canvas in &canvases {
+            canvas.animation_frame(FrameEvent {
+                timestamp: clock.elapsed().as_nanos() as u64,
+            });
+        }
+        while !all_finished(guests)
+            && canvases
+                .iter()
+                .zip(&presented)
+                .any(|(canvas, &presented)| canvas.target().frame_count() <= presented)
+        {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        clock.advance();
+    }
+}
 
-    Ok(())
+fn all_finished(guests: &[JoinHandle<anyhow::Result<()>>]) -> bool {
+    guests.iter().all(|guest| guest.is_finished())
 }
@@ -0,0 +1,40 @@
+use wasmtime::ResourceLimiter;
+
+/// Caps the linear memory of the guest, so a runaway guest traps instead of exhausting host memory.
+pub struct MemoryLimit {
+    max_bytes: usize,
+}
+
+impl MemoryLimit {
+    pub fn from_mb(max_mb: usize) -> Self {
+        Self {
+            max_bytes: max_mb.saturating_mul(1024 * 1024),
+        }
+    }
+}
+
+impl ResourceLimiter for MemoryLimit {
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> anyhow::Result<bool> {
+        if desired > self.max_bytes {
+            anyhow::bail!(
+                "memory limit exceeded: guest tried to grow its memory from {current} to {desired} bytes, the limit is {} bytes",
+                self.max_bytes
+            );
+        }
+        Ok(true)
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: u32,
+        _desired: u32,
+        _maximum: Option<u32>,
+    ) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+}
@@ -0,0 +1,33 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use wasmtime::component::Linker;
+
+use crate::HostState;
+
+/// Adds host interfaces to the runtime without having to change `HostState`.
+pub trait RuntimePlugin: Send + Sync {
+    fn add_to_linker(&self, linker: &mut Linker<HostState>) -> anyhow::Result<()>;
+
+    /// Called before each run of the component is instantiated. Plugins keep their state in
+    /// [`HostState::extensions`], and get it back from there in their host implementations.
+    fn extend_host_state(&self, _state: &mut HostState) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Plugin state, stored by type.
+#[derive(Default)]
+pub struct Extensions(HashMap<TypeId, Box<dyn Any + Send + Sync>>);
+
+impl Extensions {
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.0.insert(TypeId::of::<T>(), Box::new(value));
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.0.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use anyhow::Context;
+use wasmtime::{component::Component, Engine};
+
+/// Loads the component at `path`.
+///
+/// Preview 1 modules, e.g. built for `wasm32-wasip1`, are turned into components first, with `adapter` implementing
+/// their `wasi_snapshot_preview1` imports on top of preview 2. Guests export `start` rather than `_start`, so this is the
+/// reactor flavor of wasmtime's adapter.
+pub fn load_component(
+    engine: &Engine,
+    path: &Path,
+    adapter: Option<&Path>,
+) -> anyhow::Result<Component> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Component file {} not found", path.display()))?;
+    if !is_core_module(&bytes) {
+        return Component::new(engine, &bytes);
+    }
+
+    let adapter = adapter.with_context(|| {
+        format!(
+            "{} is a preview 1 module, pass --wasi-adapter with wasmtime's wasi_snapshot_preview1.reactor.wasm to run it",
+            path.display()
+        )
+    })?;
+    let adapter = std::fs::read(adapter)
+        .with_context(|| format!("Adapter file {} not found", adapter.display()))?;
+    let component = wit_component::ComponentEncoder::default()
+        .module(&bytes)
+        .and_then(|encoder| encoder.adapter("wasi_snapshot_preview1", &adapter))
+        .and_then(|encoder| encoder.validate(true).encode())
+        .with_context(|| format!("Failed to adapt preview 1 module {}", path.display()))?;
+    log::info!("Adapted preview 1 module {}", path.display());
+    Component::new(engine, component)
+}
+
+/// Modules and components start with the same magic number, the version after it tells them apart.
+/// source: https://github.com/WebAssembly/component-model/blob/main/design/mvp/Binary.md#component-definitions
+fn is_core_module(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\0asm\x01\0\0\0")
+}
@@ -0,0 +1,62 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use wasi_graphics_context_wasmtime::{Frame, PresentObserver};
+
+/// Writes the next presented frame to a png file.
+pub struct Screenshot {
+    path: PathBuf,
+    pending: AtomicBool,
+    /// Presents, across all canvases, to let through before capturing.
+    skip: u64,
+    presents: AtomicU64,
+}
+
+impl Screenshot {
+    pub fn new(path: PathBuf) -> Self {
+        Self::at_frame(path, 0)
+    }
+
+    /// Captures the `frame`th present (counting from zero) instead of the first one.
+    pub fn at_frame(path: PathBuf, frame: u64) -> Self {
+        Self {
+            path,
+            pending: AtomicBool::new(true),
+            skip: frame,
+            presents: AtomicU64::new(0),
+        }
+    }
+
+    fn save(&self, frame: &Frame) -> anyhow::Result<()> {
+        let file = BufWriter::new(File::create(&self.path)?);
+        let mut encoder = png::Encoder::new(file, frame.width, frame.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&frame.data)?;
+        Ok(())
+    }
+}
+
+impl PresentObserver for Screenshot {
+    fn wants_frame(&self) -> bool {
+        // Called once per present, so this doubles as the present counter.
+        let presents = self.presents.fetch_add(1, Ordering::Relaxed);
+        self.pending.load(Ordering::Relaxed) && presents >= self.skip
+    }
+
+    fn frame_presented(&self, frame: Option<Frame>) {
+        let Some(frame) = frame else {
+            log::warn!("Nothing to capture for the screenshot, trying again next frame");
+            return;
+        };
+        self.pending.store(false, Ordering::Relaxed);
+        match self.save(&frame) {
+            Ok(()) => log::info!("Saved screenshot to {}", self.path.display()),
+            Err(e) => log::error!("Failed to save screenshot to {}: {e}", self.path.display()),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use wasmtime::component::{InstancePre, Linker};
+
+use crate::{preview1, HostState, StoreFactory};
+
+/// How often the component file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Time given to whatever writes the component to finish, before it's read.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Runs the guest, and restarts it with the component at `path` reloaded whenever that file changes.
+///
+/// The running instance is torn down by dropping its store, which releases its devices. Its windows stay open for
+/// the reloaded instance, since the runtime's event loop reuses windows when watching.
+/// Failures are logged rather than returned, so a broken build only lasts until the next one.
+pub async fn run_watched(
+    name: &str,
+    path: &Path,
+    wasi_adapter: Option<&Path>,
+    instance_pre: InstancePre<HostState>,
+    linker: &Linker<HostState>,
+    stores: &StoreFactory,
+) {
+    let mut modified = modified_time(path);
+    let mut instance_pre = Some(instance_pre);
+    loop {
+        match &instance_pre {
+            Some(instance_pre) => {
+                tokio::select! {
+                    result = stores.run(name, instance_pre) => {
+                        match result {
+                            Ok(()) => log::info!("Guest {name} returned, waiting for {} to change", path.display()),
+                            Err(e) => log::error!("{e:#}"),
+                        }
+                        modified = changed(path, modified).await;
+                    }
+                    new_modified = changed(path, modified) => modified = new_modified,
+                }
+            }
+            None => modified = changed(path, modified).await,
+        }
+
+        log::info!("{} changed, reloading {name}", path.display());
+        instance_pre = match load(path, wasi_adapter, linker) {
+            Ok(instance_pre) => Some(instance_pre),
+            Err(e) => {
+                log::error!("{e:#}");
+                None
+            }
+        };
+    }
+}
+
+fn load(
+    path: &Path,
+    wasi_adapter: Option<&Path>,
+    linker: &Linker<HostState>,
+) -> anyhow::Result<InstancePre<HostState>> {
+    let component = preview1::load_component(linker.engine(), path, wasi_adapter)
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+    linker
+        .instantiate_pre(&component)
+        .with_context(|| format!("Failed to link {}", path.display()))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Waits until the file at `path` was modified after `since`, and returns its new modification time.
+async fn changed(path: &Path, since: Option<SystemTime>) -> Option<SystemTime> {
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let modified = modified_time(path);
+        // Missing while it's being rewritten, the next poll picks it up.
+        if modified.is_some() && modified != since {
+            tokio::time::sleep(SETTLE_TIME).await;
+            return modified_time(path);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Runs examples through the runtime binary, rendering headless on the cpu.
+//!
+//! Examples are built by `cargo xtask run-demo --name <example>`, which needs the wasm32-unknown-unknown target, and
+//! rendering needs a software adapter. So the tests are `#[ignore]`d, run them with `cargo test -- --ignored` once
+//! their examples are built.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+/// Renders `frames` frames of `example` and decodes the screenshot of the last one, as rgba rows.
+fn screenshot(example: &str, frames: u64) -> (u32, u32, Vec<u8>) {
+    let root = workspace_root();
+    assert!(
+        root.join(format!("target/example-{example}.wasm")).exists(),
+        "example-{example}.wasm isn't built, build it with `cargo xtask run-demo --name {example}`"
+    );
+    let path =
+        std::env::temp_dir().join(format!("runtime-test-{example}-{}.png", std::process::id()));
+    let status = Command::new(env!("CARGO_BIN_EXE_runtime"))
+        .current_dir(&root)
+        .args([
+            "--example",
+            example,
+            "--headless",
+            "--software",
+            "--deterministic",
+        ])
+        .args(["--frames", &frames.to_string()])
+        .arg("--screenshot")
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert!(status.success(), "runtime exited with {status}");
+    // Guests get no adapter on machines without a software one.
+    assert!(path.exists(), "{example} presented nothing");
+
+    let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    data.truncate(info.buffer_size());
+    (info.width, info.height, data)
+}
+
+fn pixel((width, _, data): &(u32, u32, Vec<u8>), x: u32, y: u32) -> [u8; 4] {
+    let i = ((y * width + x) * 4) as usize;
+    data[i..i + 4].try_into().unwrap()
+}
+
+#[test]
+#[ignore = "needs the triangle example built and a software adapter, run with --ignored"]
+fn triangle_draws_red_over_the_clear_color() {
+    let image = screenshot("triangle", 3);
+    let (width, height, _) = image;
+    let [r, g, b, _] = pixel(&image, width / 2, height / 2);
+    assert!(r > 200 && g < 50 && b < 50, "center is {r}, {g}, {b}");
+    // The triangle doesn't reach the top corners.
+    let [r, g, b, _] = pixel(&image, 0, 0);
+    assert!(r < 50 && g < 50 && b > 0, "corner is {r}, {g}, {b}");
+}
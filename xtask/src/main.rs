@@ -19,6 +19,15 @@ Commands:
       --name <DEMO_NAME>  Name of the demo to run (required). Available demos are:
                           - skybox
                           - triangle
+                          - invalid_calls
+                          - indirect_count
+                          - offscreen_gradient
+                          - gpu_futures
+                          - instanced_quads
+                          - color_grading
+                          - override_constants
+                          - shadow_map
+                          - vertex_pulling
                           - fb-rectangle
       -h, --help          Print help
 
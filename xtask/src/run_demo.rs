@@ -9,6 +9,17 @@ lazy_static::lazy_static! {
         let mut s = HashSet::new();
         s.insert("skybox");
         s.insert("triangle");
+        s.insert("invalid_calls");
+        s.insert("indirect_count");
+        s.insert("stencil_mask");
+        s.insert("split_screen");
+        s.insert("offscreen_gradient");
+        s.insert("gpu_futures");
+        s.insert("instanced_quads");
+        s.insert("color_grading");
+        s.insert("override_constants");
+        s.insert("shadow_map");
+        s.insert("vertex_pulling");
         s.insert("rectangle_simple_buffer");
         s
     };